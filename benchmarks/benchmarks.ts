@@ -31,6 +31,27 @@ bench({
   },
 });
 
+bench({
+  name: "deno_lint (per-rule traversal)",
+  runs: RUN_COUNT,
+  async func(b: BenchmarkTimer): Promise<void> {
+    b.start();
+    const proc = Deno.run({
+      cmd: [
+        "./target/release/examples/dlint",
+        "run",
+        "--no-single-traversal",
+        ...files,
+      ],
+      stdout: "null",
+      stderr: "null",
+    });
+
+    await proc.status();
+    b.stop();
+  },
+});
+
 bench({
   name: "eslint",
   runs: RUN_COUNT,
@@ -2,8 +2,11 @@
 
 use anyhow::bail;
 use anyhow::Error as AnyError;
-use deno_lint::rules::{get_all_rules, LintRule};
+use deno_lint::diagnostic::Severity;
+use deno_lint::rules::{configure_rules, get_all_rules, LintRule};
 use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -13,6 +16,10 @@ pub struct RulesConfig {
   pub tags: Vec<String>,
   pub include: Vec<String>,
   pub exclude: Vec<String>,
+  /// Options of rules, keyed by rule code.
+  pub options: HashMap<String, Value>,
+  /// Severity overrides, keyed by rule code.
+  pub severity: HashMap<String, Severity>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -30,7 +37,7 @@ pub struct Config {
 }
 
 impl Config {
-  pub fn get_rules(&self) -> Vec<Box<dyn LintRule>> {
+  pub fn get_rules(&self) -> Result<Vec<Box<dyn LintRule>>, AnyError> {
     let mut rules = get_all_rules();
 
     if !self.rules.tags.is_empty() {
@@ -65,7 +72,8 @@ impl Config {
       }
     }
 
-    rules
+    configure_rules(&mut rules, &self.rules.options)?;
+    Ok(rules)
   }
 
   pub fn get_files(&self) -> Result<Vec<PathBuf>, AnyError> {
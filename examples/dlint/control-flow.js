@@ -1,21 +1,40 @@
 class ControlFlow {
+  static query(stmt) {
+    return Deno.core.jsonOpSync("op_query_control_flow_by_span", {
+      span: stmt.span,
+    });
+  }
+
   static isReachable(stmt) {
-    const { isReachable } = Deno.core.jsonOpSync(
-      "op_query_control_flow_by_span",
-      {
-        span: stmt.span,
-      }
-    );
-    return isReachable;
+    return ControlFlow.query(stmt).isReachable;
   }
 
   static stopsExecution(stmt) {
-    const { stopsExectuion } = Deno.core.jsonOpSync(
-      "op_query_control_flow_by_span",
-      {
-        span: stmt.span,
-      }
-    );
-    return stopsExectuion;
+    return ControlFlow.query(stmt).stopsExecution;
+  }
+
+  /**
+   * How the execution ends at `stmt`, e.g.
+   * `{ kind: "forced", return: true, throw: false, infiniteLoop: false }`,
+   * `{ kind: "break" }` or `{ kind: "continue" }`.
+   */
+  static endReason(stmt) {
+    return ControlFlow.query(stmt).end;
+  }
+
+  /**
+   * Kind of the block `stmt` is directly in, e.g. `{ kind: "loop" }` or
+   * `{ kind: "label", label: "outer" }`.
+   */
+  static blockKind(stmt) {
+    return ControlFlow.query(stmt).blockKind;
+  }
+
+  /**
+   * `lo` of the span of the statement a `break` or `continue` statement
+   * jumps to.
+   */
+  static jumpTarget(stmt) {
+    return ControlFlow.query(stmt).jumpTarget;
   }
 }
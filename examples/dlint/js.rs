@@ -6,7 +6,7 @@ use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use deno_core::RuntimeOptions;
 use deno_core::ZeroCopyBuf;
-use deno_lint::control_flow::ControlFlow;
+use deno_lint::control_flow::{BlockKind, ControlFlow, End};
 use deno_lint::linter::{Context, Plugin};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -85,23 +85,59 @@ fn op_query_control_flow_by_span(
 
   let is_reachable = meta.map(|m| !m.unreachable);
   let stops_execution = meta.map(|m| m.stops_execution());
+  let end = meta.and_then(|m| m.end()).map(|end| match end {
+    End::Forced {
+      ret,
+      throw,
+      infinite_loop,
+    } => serde_json::json!({
+      "kind": "forced",
+      "return": ret,
+      "throw": throw,
+      "infiniteLoop": infinite_loop,
+    }),
+    End::Break => serde_json::json!({ "kind": "break" }),
+    End::Continue => serde_json::json!({ "kind": "continue" }),
+  });
+  let block_kind = meta.and_then(|m| m.block_kind()).map(|kind| {
+    let (kind, label) = match kind {
+      BlockKind::Program => ("program", None),
+      BlockKind::Function => ("function", None),
+      BlockKind::Case => ("case", None),
+      BlockKind::If => ("if", None),
+      BlockKind::Loop => ("loop", None),
+      BlockKind::Label(id) => ("label", Some(id.0.to_string())),
+      BlockKind::Catch => ("catch", None),
+      BlockKind::Finally => ("finally", None),
+    };
+    serde_json::json!({ "kind": kind, "label": label })
+  });
+  let jump_target = meta.and_then(|m| m.jump_target()).map(|lo| lo.0);
 
   #[derive(Serialize)]
   #[serde(rename_all = "camelCase")]
   struct ReturnValue {
     is_reachable: Option<bool>,
     stops_execution: Option<bool>,
+    end: Option<Value>,
+    block_kind: Option<Value>,
+    /// `lo` of the span of the statement a `break` or `continue` jumps to.
+    jump_target: Option<u32>,
   }
   serde_json::to_value(ReturnValue {
     is_reachable,
     stops_execution,
+    end,
+    block_kind,
+    jump_target,
   })
   .map_err(Into::into)
 }
 
 pub struct JsRuleRunner {
   runtime: JsRuntime,
-  module_id: i32,
+  /// Codes of the rules registered by the plugin module.
+  codes: Codes,
 }
 
 impl JsRuleRunner {
@@ -138,7 +174,18 @@ impl JsRuleRunner {
       ))
       .unwrap();
 
-    Box::new(Self { runtime, module_id })
+    // The runner is reused for many files, so the module that registers
+    // rules is evaluated only once.
+    deno_core::futures::executor::block_on(runtime.mod_evaluate(module_id))
+      .unwrap();
+
+    let codes = runtime
+      .op_state()
+      .borrow_mut()
+      .try_take::<Codes>()
+      .unwrap_or_else(HashSet::new);
+
+    Box::new(Self { runtime, codes })
   }
 }
 
@@ -146,7 +193,7 @@ impl Plugin for JsRuleRunner {
   fn run(
     &mut self,
     context: &mut Context,
-    program: Program,
+    program: &Program,
   ) -> Result<(), AnyError> {
     self
       .runtime
@@ -154,25 +201,14 @@ impl Plugin for JsRuleRunner {
       .borrow_mut()
       .put(context.control_flow.clone());
 
-    deno_core::futures::executor::block_on(
-      self.runtime.mod_evaluate(self.module_id),
-    )?;
-
-    let codes = self
-      .runtime
-      .op_state()
-      .borrow_mut()
-      .try_take::<Codes>()
-      .unwrap_or_else(HashSet::new);
-
-    context.set_plugin_codes(codes.clone());
+    context.set_plugin_codes(self.codes.clone());
 
     self.runtime.execute(
       "runPlugins",
       &format!(
         "runPlugins({ast}, {rule_codes});",
-        ast = serde_json::to_string(&program).unwrap(),
-        rule_codes = serde_json::to_string(&codes).unwrap()
+        ast = serde_json::to_string(program).unwrap(),
+        rule_codes = serde_json::to_string(&self.codes).unwrap()
       ),
     )?;
 
@@ -8,14 +8,17 @@ use clap::App;
 use clap::AppSettings;
 use clap::Arg;
 use clap::SubCommand;
+use deno_lint::autofix::lint_and_fix;
 use deno_lint::diagnostic::LintDiagnostic;
 use deno_lint::diagnostic::Range;
+use deno_lint::diagnostic::Severity;
 use deno_lint::linter::LinterBuilder;
 use deno_lint::linter::SourceFile;
 use deno_lint::rules::{get_all_rules, get_recommended_rules, LintRule};
 use log::debug;
 use rayon::prelude::*;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -61,6 +64,18 @@ fn create_cli_app<'a, 'b>() -> App<'a, 'b> {
             .help("Specify plugin paths")
             .multiple(true)
             .takes_value(true),
+        )
+        .arg(
+          Arg::with_name("FIX")
+            .long("fix")
+            .help("Fix problems automatically where possible"),
+        )
+        .arg(
+          Arg::with_name("NO_SINGLE_TRAVERSAL")
+            .long("no-single-traversal")
+            .help(
+              "Let every rule traverse the AST on its own (for benchmarks)",
+            ),
         ),
     )
 }
@@ -105,6 +120,11 @@ fn display_diagnostics(
       source_code,
       &diagnostic.range,
     );
+    let annotation_type = match diagnostic.severity {
+      Severity::Warning => snippet::AnnotationType::Warning,
+      Severity::Info => snippet::AnnotationType::Info,
+      _ => snippet::AnnotationType::Error,
+    };
     let footer = if let Some(hint) = &diagnostic.hint {
       vec![snippet::Annotation {
         label: Some(hint),
@@ -119,7 +139,7 @@ fn display_diagnostics(
       title: Some(snippet::Annotation {
         label: Some(&diagnostic.message),
         id: Some(&diagnostic.code),
-        annotation_type: snippet::AnnotationType::Error,
+        annotation_type,
       }),
       footer,
       slices: vec![snippet::Slice {
@@ -130,7 +150,7 @@ fn display_diagnostics(
         annotations: vec![snippet::SourceAnnotation {
           range,
           label: "",
-          annotation_type: snippet::AnnotationType::Error,
+          annotation_type,
         }],
       }],
       opt: display_list::FormatOptions {
@@ -149,6 +169,8 @@ fn run_linter(
   filter_rule_name: Option<&str>,
   maybe_config: Option<Arc<config::Config>>,
   plugin_paths: Vec<&str>,
+  fix: bool,
+  single_traversal: bool,
 ) -> Result<(), AnyError> {
   let mut paths: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
 
@@ -157,52 +179,90 @@ fn run_linter(
   }
 
   let error_counts = Arc::new(AtomicUsize::new(0));
+  let warning_counts = Arc::new(AtomicUsize::new(0));
+  let info_counts = Arc::new(AtomicUsize::new(0));
   let output_lock = Arc::new(Mutex::new(())); // prevent threads outputting at the same time
 
-  paths.par_iter().for_each(|file_path| {
-    let source_code =
-      std::fs::read_to_string(&file_path).expect("Failed to load file");
-
-    let mut rules = if let Some(config) = maybe_config.clone() {
-      config.get_rules()
-    } else {
-      get_recommended_rules()
-    };
-
-    if let Some(rule_name) = filter_rule_name {
-      rules = rules
-        .into_iter()
-        .filter(|r| r.code() == rule_name)
-        .collect()
-    };
-
-    debug!("Configured rules: {}", rules.len());
-
-    let mut linter_builder = LinterBuilder::default()
-      .rules(rules)
-      .lint_unknown_rules(true)
-      .lint_unused_ignore_directives(true);
+  let (mut rules, severities) = if let Some(config) = maybe_config {
+    (config.get_rules()?, config.rules.severity.clone())
+  } else {
+    (get_recommended_rules(), HashMap::new())
+  };
 
-    for plugin_path in &plugin_paths {
-      let js_runner = js::JsRuleRunner::new(plugin_path);
-      linter_builder = linter_builder.add_plugin(js_runner);
-    }
+  if let Some(rule_name) = filter_rule_name {
+    rules = rules
+      .into_iter()
+      .filter(|r| r.code() == rule_name)
+      .collect()
+  };
 
-    let mut linter = linter_builder.build();
+  debug!("Configured rules: {}", rules.len());
+
+  // Rules are shared by all threads, while every thread gets its own linter
+  // (and plugin runtimes) which is reused for all files it lints.
+  let rules = Arc::new(rules);
+
+  paths.par_iter().for_each_init(
+    || {
+      let mut linter_builder = LinterBuilder::default()
+        .shared_rules(rules.clone())
+        .severities(severities.clone())
+        .detect_syntax(true)
+        .single_traversal(single_traversal)
+        .lint_unknown_rules(true)
+        .lint_unused_ignore_directives(true);
+
+      for plugin_path in &plugin_paths {
+        let js_runner = js::JsRuleRunner::new(plugin_path);
+        linter_builder = linter_builder.add_plugin(js_runner);
+      }
 
-    let (source_file, file_diagnostics) = linter
-      .lint(file_path.to_string_lossy().to_string(), source_code)
-      .expect("Failed to lint");
+      linter_builder.build()
+    },
+    |linter, file_path| {
+      let source_code =
+        std::fs::read_to_string(&file_path).expect("Failed to load file");
+      let file_name = file_path.to_string_lossy().to_string();
+
+      let (source_file, file_diagnostics) = if fix {
+        let result = lint_and_fix(linter, &file_name, source_code);
+        if result.fixed_count > 0 {
+          std::fs::write(file_path, &result.source_code)
+            .expect("Failed to write fixed file");
+        }
+        (result.source_file, result.diagnostics)
+      } else {
+        linter.lint(file_name, source_code)
+      };
 
-    error_counts.fetch_add(file_diagnostics.len(), Ordering::Relaxed);
-    let _g = output_lock.lock().unwrap();
+      for diagnostic in &file_diagnostics {
+        let counts = match diagnostic.severity {
+          Severity::Warning => &warning_counts,
+          Severity::Info => &info_counts,
+          _ => &error_counts,
+        };
+        counts.fetch_add(1, Ordering::Relaxed);
+      }
+      let _g = output_lock.lock().unwrap();
 
-    display_diagnostics(&file_diagnostics, source_file);
-  });
+      display_diagnostics(&file_diagnostics, source_file);
+    },
+  );
 
   let err_count = error_counts.load(Ordering::Relaxed);
+  let warning_count = warning_counts.load(Ordering::Relaxed);
+  let info_count = info_counts.load(Ordering::Relaxed);
+  let problem_count = err_count + warning_count + info_count;
+  if problem_count > 0 {
+    eprintln!(
+      "Found {} problems ({} errors, {} warnings, {} infos)",
+      problem_count, err_count, warning_count, info_count
+    );
+  }
+
+  // Only errors make the run fail, so that new rules can be rolled out as
+  // warnings first.
   if err_count > 0 {
-    eprintln!("Found {} problems", err_count);
     std::process::exit(1);
   }
 
@@ -337,6 +397,8 @@ fn main() -> Result<(), AnyError> {
         run_matches.value_of("RULE_CODE"),
         maybe_config,
         plugins,
+        run_matches.is_present("FIX"),
+        !run_matches.is_present("NO_SINGLE_TRAVERSAL"),
       )?;
     }
     ("rules", Some(rules_matches)) => {
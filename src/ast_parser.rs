@@ -1,20 +1,16 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
-use std::cell::RefCell;
-use std::error::Error;
-use std::fmt;
+use crate::diagnostic::{LintDiagnostic, Range, Severity};
+use std::path::Path;
 use std::rc::Rc;
 use swc_common::comments::SingleThreadedComments;
-use swc_common::errors::Diagnostic;
-use swc_common::errors::DiagnosticBuilder;
-use swc_common::errors::Emitter;
-use swc_common::errors::Handler;
-use swc_common::errors::HandlerFlags;
 use swc_common::FileName;
 use swc_common::Globals;
 use swc_common::Mark;
+use swc_common::SourceFile;
 use swc_common::SourceMap;
-use swc_common::Span;
+use swc_common::Spanned;
 use swc_ecmascript::ast;
+use swc_ecmascript::parser::error::Error;
 use swc_ecmascript::parser::lexer::Lexer;
 use swc_ecmascript::parser::EsConfig;
 use swc_ecmascript::parser::JscTarget;
@@ -25,9 +21,8 @@ use swc_ecmascript::parser::TsConfig;
 use swc_ecmascript::transforms::resolver::ts_resolver;
 use swc_ecmascript::visit::FoldWith;
 
-#[allow(unused)]
-pub fn get_default_es_config() -> Syntax {
-  let config = EsConfig {
+fn default_es_config() -> EsConfig {
+  EsConfig {
     num_sep: true,
     class_private_props: false,
     class_private_methods: false,
@@ -40,89 +35,72 @@ pub fn get_default_es_config() -> Syntax {
     import_meta: true,
     top_level_await: true,
     ..Default::default()
-  };
-  Syntax::Es(config)
+  }
 }
 
-pub fn get_default_ts_config() -> Syntax {
-  let ts_config = TsConfig {
+fn default_ts_config() -> TsConfig {
+  TsConfig {
     dynamic_import: true,
     decorators: true,
     ..Default::default()
-  };
-  Syntax::Typescript(ts_config)
+  }
 }
 
-#[derive(Clone, Debug)]
-pub struct SwcDiagnosticBuffer {
-  pub diagnostics: Vec<String>,
+pub fn get_default_es_config() -> Syntax {
+  Syntax::Es(default_es_config())
 }
 
-impl Error for SwcDiagnosticBuffer {}
-
-impl fmt::Display for SwcDiagnosticBuffer {
-  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    let msg = self.diagnostics.join(",");
-
-    f.pad(&msg)
-  }
+pub fn get_default_ts_config() -> Syntax {
+  Syntax::Typescript(default_ts_config())
 }
 
-impl SwcDiagnosticBuffer {
-  pub(crate) fn from_swc_error(
-    error_buffer: SwcErrorBuffer,
-    parser: &AstParser,
-  ) -> Self {
-    let s = error_buffer.0.borrow().clone();
-
-    let diagnostics = s
-      .iter()
-      .map(|d| {
-        let mut msg = d.message();
-
-        if let Some(span) = d.span.primary_span() {
-          let location = parser.get_span_location(span);
-          let filename = match &location.file.name {
-            FileName::Custom(n) => n,
-            _ => unreachable!(),
-          };
-          msg = format!(
-            "{} at {}:{}:{}",
-            msg, filename, location.line, location.col_display
-          );
-        }
-
-        msg
-      })
-      .collect::<Vec<String>>();
-
-    Self { diagnostics }
+/// Picks the syntax for a file based on the extension of `file_name`.
+/// JSX is only enabled for `.jsx` and `.tsx` files and `.d.ts` files are
+/// parsed as declaration files.
+///
+/// Returns `None` if the extension is not known.
+pub fn get_syntax_for_file(file_name: &str) -> Option<Syntax> {
+  let file_name = file_name.to_lowercase();
+
+  if file_name.ends_with(".d.ts") {
+    return Some(Syntax::Typescript(TsConfig {
+      dts: true,
+      ..default_ts_config()
+    }));
   }
-}
 
-#[derive(Clone)]
-pub(crate) struct SwcErrorBuffer(Rc<RefCell<Vec<Diagnostic>>>);
-
-impl SwcErrorBuffer {
-  pub(crate) fn default() -> Self {
-    Self(Rc::new(RefCell::new(vec![])))
-  }
+  let extension = Path::new(&file_name).extension()?.to_str()?;
+  let syntax = match extension {
+    "ts" | "mts" | "cts" => get_default_ts_config(),
+    "tsx" => Syntax::Typescript(TsConfig {
+      tsx: true,
+      ..default_ts_config()
+    }),
+    "js" | "mjs" | "cjs" => get_default_es_config(),
+    "jsx" => Syntax::Es(EsConfig {
+      jsx: true,
+      ..default_es_config()
+    }),
+    _ => return None,
+  };
+  Some(syntax)
 }
 
-impl Emitter for SwcErrorBuffer {
-  fn emit(&mut self, db: &DiagnosticBuilder) {
-    self.0.borrow_mut().push((**db).clone());
-  }
-}
+/// Code of diagnostics created from syntax errors.
+pub const PARSE_ERROR_CODE: &str = "parse-error";
 
-/// Low-level utility structure with common AST parsing functions.
-///
-/// Allows to build more complicated parser by providing a callback
-/// to `parse_module`.
-pub(crate) struct AstParser {
-  pub(crate) buffered_error: SwcErrorBuffer,
+/// Result of parsing a single file.
+pub(crate) struct ParsedSource {
+  /// Source map holding only this file, so that nothing is retained across
+  /// files and positions start over for each of them.
   pub(crate) source_map: Rc<SourceMap>,
-  pub(crate) handler: Handler,
+  pub(crate) source_file: Rc<SourceFile>,
+  /// `None` if the parser could not recover from a syntax error.
+  pub(crate) program: Option<(ast::Program, SingleThreadedComments)>,
+  /// All syntax errors, including the ones the parser recovered from.
+  pub(crate) errors: Vec<LintDiagnostic>,
+  /// Globals holding the hygiene data of `program`. Like the source map, they
+  /// are created for each file so that marks don't pile up across files.
   pub(crate) globals: Globals,
   /// The marker passed to the resolver (from swc).
   ///
@@ -130,72 +108,89 @@ pub(crate) struct AstParser {
   pub(crate) top_level_mark: Mark,
 }
 
+/// Low-level utility structure with common AST parsing functions.
+pub(crate) struct AstParser;
+
 impl AstParser {
   pub(crate) fn new() -> Self {
-    let buffered_error = SwcErrorBuffer::default();
-
-    let handler = Handler::with_emitter_and_flags(
-      Box::new(buffered_error.clone()),
-      HandlerFlags {
-        dont_buffer_diagnostics: true,
-        can_emit_warnings: true,
-        ..Default::default()
-      },
-    );
-
-    let globals = Globals::new();
-    let top_level_mark =
-      swc_common::GLOBALS.set(&globals, || Mark::fresh(Mark::root()));
-
-    AstParser {
-      buffered_error,
-      source_map: Rc::new(SourceMap::default()),
-      handler,
-      globals,
-      top_level_mark,
-    }
+    AstParser
   }
 
+  /// Parses `source_code` as a new file in a source map and globals of its
+  /// own.
   pub(crate) fn parse_program(
     &self,
     file_name: &str,
     syntax: Syntax,
-    source_code: &str,
-  ) -> Result<(ast::Program, SingleThreadedComments), SwcDiagnosticBuffer> {
-    let swc_source_file = self.source_map.new_source_file(
-      FileName::Custom(file_name.to_string()),
-      source_code.to_string(),
-    );
-
-    let buffered_err = self.buffered_error.clone();
+    source_code: String,
+  ) -> ParsedSource {
+    let source_map = Rc::new(SourceMap::default());
+    let source_file = source_map
+      .new_source_file(FileName::Custom(file_name.to_string()), source_code);
 
     let comments = SingleThreadedComments::default();
     let lexer = Lexer::new(
       syntax,
       JscTarget::Es2019,
-      StringInput::from(&*swc_source_file),
+      StringInput::from(&*source_file),
       Some(&comments),
     );
 
     let mut parser = Parser::new_from(lexer);
+    let parse_result = parser.parse_program();
 
-    let parse_result = parser.parse_program().map_err(move |err| {
-      let mut diagnostic_builder = err.into_diagnostic(&self.handler);
-      diagnostic_builder.emit();
-      SwcDiagnosticBuffer::from_swc_error(buffered_err, self)
-    });
+    let globals = Globals::new();
+    let top_level_mark =
+      swc_common::GLOBALS.set(&globals, || Mark::fresh(Mark::root()));
 
-    let parse_result = parse_result.map(|program| {
-      swc_common::GLOBALS.set(&self.globals, || {
-        program.fold_with(&mut ts_resolver(self.top_level_mark))
-      })
-    });
+    let mut errors = vec![];
+    let program = match parse_result {
+      Ok(program) => Some(swc_common::GLOBALS.set(&globals, || {
+        program.fold_with(&mut ts_resolver(top_level_mark))
+      })),
+      Err(err) => {
+        errors.push(create_error_diagnostic(&source_map, &source_file, err));
+        None
+      }
+    };
+
+    errors.extend(
+      parser
+        .take_errors()
+        .into_iter()
+        .map(|err| create_error_diagnostic(&source_map, &source_file, err)),
+    );
+    errors.sort_by_key(|d| d.range.start.byte_pos);
 
-    parse_result.map(|program| (program, comments))
+    ParsedSource {
+      source_map,
+      source_file,
+      program: program.map(|program| (program, comments)),
+      errors,
+      globals,
+      top_level_mark,
+    }
   }
+}
+
+fn create_error_diagnostic(
+  source_map: &SourceMap,
+  source_file: &SourceFile,
+  err: Error,
+) -> LintDiagnostic {
+  let filename = match &source_file.name {
+    FileName::Custom(n) => n.to_string(),
+    name => name.to_string(),
+  };
 
-  pub(crate) fn get_span_location(&self, span: Span) -> swc_common::Loc {
-    self.source_map.lookup_char_pos(span.lo())
+  LintDiagnostic {
+    range: Range::new(source_map, err.span()),
+    filename,
+    message: err.kind().msg().to_string(),
+    code: PARSE_ERROR_CODE.to_string(),
+    severity: Severity::Error,
+    hint: None,
+    fixes: vec![],
   }
 }
 
@@ -204,3 +199,55 @@ impl Default for AstParser {
     Self::new()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use swc_ecmascript::utils::{find_ids, Id};
+
+  #[test]
+  fn syntax_for_file() {
+    let cases = [
+      ("mod.ts", Some((true, false, false))),
+      ("mod.mts", Some((true, false, false))),
+      ("component.tsx", Some((true, true, false))),
+      ("lib.d.ts", Some((true, false, true))),
+      ("LIB.D.TS", Some((true, false, true))),
+      ("mod.js", Some((false, false, false))),
+      ("mod.mjs", Some((false, false, false))),
+      ("mod.cjs", Some((false, false, false))),
+      ("component.jsx", Some((false, true, false))),
+      ("data.json", None),
+      ("Makefile", None),
+      ("scripts/ts", None),
+      ("js", None),
+    ];
+
+    for (file_name, expected) in cases.iter() {
+      let actual = get_syntax_for_file(file_name).map(|syntax| match syntax {
+        Syntax::Typescript(ts_config) => (true, ts_config.tsx, ts_config.dts),
+        Syntax::Es(es_config) => (false, es_config.jsx, false),
+      });
+      assert_eq!(&actual, expected, "{}", file_name);
+    }
+  }
+
+  #[test]
+  fn marks_start_over_for_each_file() {
+    let parser = AstParser::new();
+    let binding_ctxts = || {
+      let parsed = parser.parse_program(
+        "mod.ts",
+        get_default_ts_config(),
+        "function f() { const a = 1; { const b = a; } }".to_string(),
+      );
+      let (program, _) = parsed.program.unwrap();
+      let ids: Vec<Id> = find_ids(&program);
+      ids.into_iter().map(|(_, ctxt)| ctxt).collect::<Vec<_>>()
+    };
+
+    // The same source gets the same marks, however many files were parsed
+    // before.
+    assert_eq!(binding_ctxts(), binding_ctxts());
+  }
+}
@@ -0,0 +1,337 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use crate::diagnostic::{LintDiagnostic, LintFixChange};
+use crate::linter::Linter;
+use std::rc::Rc;
+use std::time::Instant;
+use swc_common::SourceFile;
+
+/// Maximum number of lint passes done by `lint_and_fix`. Fixes can
+/// create new problems (or unblock fixes that overlapped in the previous
+/// pass), but a buggy rule must not make us loop forever.
+const MAX_FIX_PASSES: usize = 10;
+
+#[derive(Debug)]
+pub struct FixResult {
+  /// Source file of the last lint pass, which contains the fixed code.
+  pub source_file: Rc<SourceFile>,
+  /// Source code with all applicable fixes applied.
+  pub source_code: String,
+  /// Diagnostics that remain after fixing.
+  pub diagnostics: Vec<LintDiagnostic>,
+  /// Number of fixes that were applied across all passes.
+  pub fixed_count: usize,
+}
+
+/// Applies the preferred fix of each diagnostic to `source_code`.
+///
+/// A fix whose changes overlap with changes of an already accepted fix is
+/// skipped; it will usually be reported again by the next lint pass.
+/// Returns `None` if there was nothing to apply.
+pub fn apply_lint_fixes(
+  source_code: &str,
+  diagnostics: &[LintDiagnostic],
+) -> Option<(String, usize)> {
+  let mut accepted: Vec<&LintFixChange> = vec![];
+  let mut fixed_count = 0;
+
+  for diagnostic in diagnostics {
+    let fix = match diagnostic.fixes.first() {
+      Some(fix) => fix,
+      None => continue,
+    };
+
+    let overlaps = fix.changes.iter().enumerate().any(|(i, change)| {
+      accepted.iter().any(|a| changes_overlap(a, change))
+        || fix.changes[i + 1..]
+          .iter()
+          .any(|other| changes_overlap(other, change))
+    });
+    if overlaps {
+      continue;
+    }
+
+    accepted.extend(fix.changes.iter());
+    fixed_count += 1;
+  }
+
+  if fixed_count == 0 {
+    return None;
+  }
+
+  // Apply from the end of the file so that byte positions of the remaining
+  // changes stay valid.
+  accepted.sort_by_key(|c| c.range.start.byte_pos);
+  let mut fixed = source_code.to_string();
+  for change in accepted.into_iter().rev() {
+    fixed.replace_range(
+      change.range.start.byte_pos..change.range.end.byte_pos,
+      &change.new_text,
+    );
+  }
+
+  Some((fixed, fixed_count))
+}
+
+fn changes_overlap(a: &LintFixChange, b: &LintFixChange) -> bool {
+  let (a_start, a_end) = (a.range.start.byte_pos, a.range.end.byte_pos);
+  let (b_start, b_end) = (b.range.start.byte_pos, b.range.end.byte_pos);
+  // Two insertions at the same position would produce an ambiguous order.
+  a_start == b_start || (a_start < b_end && b_start < a_end)
+}
+
+/// Lints `source_code` and applies fixes repeatedly until no more fixes can
+/// be applied.
+pub fn lint_and_fix(
+  linter: &mut Linter,
+  file_name: &str,
+  source_code: String,
+) -> FixResult {
+  let start = Instant::now();
+  let (mut source_file, mut diagnostics) =
+    linter.lint(file_name.to_string(), source_code.clone());
+  // Recovered syntax errors are not necessarily reported as diagnostics,
+  // so ask the linter how many the parser found.
+  let mut parse_errors = linter.parse_error_count();
+  let mut source_code = source_code;
+  let mut fixed_count = 0;
+
+  for _ in 0..MAX_FIX_PASSES {
+    let (fixed, count) = match apply_lint_fixes(&source_code, &diagnostics) {
+      Some(r) => r,
+      None => break,
+    };
+
+    // A fix that breaks the syntax is a bug in a rule; keep the last source
+    // instead of making things worse.
+    let (f, d) = linter.lint(file_name.to_string(), fixed.clone());
+    if linter.parse_error_count() > parse_errors {
+      warn!(
+        "Discarding fixes that produce invalid code in {}",
+        file_name
+      );
+      break;
+    }
+    parse_errors = linter.parse_error_count();
+    source_file = f;
+    diagnostics = d;
+    source_code = fixed;
+    fixed_count += count;
+  }
+
+  let end = Instant::now();
+  debug!("lint_and_fix took {:#?}", end - start);
+
+  FixResult {
+    source_file,
+    source_code,
+    diagnostics,
+    fixed_count,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::diagnostic::{LintFix, Position, Range, Severity};
+  use crate::linter::LinterBuilder;
+  use crate::rules::no_empty::NoEmpty;
+  use crate::rules::no_explicit_any::NoExplicitAny;
+  use crate::rules::no_extra_semi::NoExtraSemi;
+  use crate::rules::no_var::NoVar;
+  use crate::rules::prefer_const::PreferConst;
+  use crate::rules::LintRule;
+
+  fn fix(source: &str) -> FixResult {
+    let mut linter = LinterBuilder::default()
+      .rules(vec![
+        NoExtraSemi::new() as Box<dyn LintRule>,
+        NoExplicitAny::new(),
+        NoVar::new(),
+        PreferConst::new(),
+      ])
+      .build();
+    lint_and_fix(&mut linter, "fix_test.ts", source.to_string())
+  }
+
+  #[test]
+  fn fixes_until_stable() {
+    // `no-var` turns `var` into `let` and then `prefer-const` turns it into
+    // `const` in the next pass.
+    let result = fix("var a = 1;;\nconsole.log(a);");
+    assert_eq!(result.source_code, "const a = 1;\nconsole.log(a);");
+    assert_eq!(result.fixed_count, 3);
+    assert!(result.diagnostics.is_empty());
+  }
+
+  #[test]
+  fn keeps_unfixable_diagnostics() {
+    // `no-explicit-any` has no fix, so its diagnostic outlives the passes.
+    let result = fix("var a: any = 1;;\nconsole.log(a);");
+    assert_eq!(result.source_code, "const a: any = 1;\nconsole.log(a);");
+    assert_eq!(result.fixed_count, 3);
+    assert_eq!(result.diagnostics.len(), 1);
+    assert_eq!(result.diagnostics[0].code, "no-explicit-any");
+    assert_eq!(result.diagnostics[0].range.start.col, 9);
+  }
+
+  #[test]
+  fn stops_at_reassigned_variables() {
+    let result = fix("var a = 1;\na = 2;;");
+    assert_eq!(result.source_code, "let a = 1;\na = 2;");
+    assert!(result.diagnostics.is_empty());
+
+    let result = fix("var a, b = 1;\nb = 2;");
+    assert_eq!(result.source_code, "let a, b = 1;\nb = 2;");
+    assert!(result.diagnostics.is_empty());
+  }
+
+  #[test]
+  fn removes_unused_and_unknown_ignore_codes() {
+    let result = fix(
+      "// deno-lint-ignore no-var, some-rule no-extra-semi\nvar a = 1;\nconsole.log(a);",
+    );
+    assert_eq!(
+      result.source_code,
+      "// deno-lint-ignore no-var\nvar a = 1;\nconsole.log(a);"
+    );
+    assert!(result.diagnostics.is_empty());
+
+    let result = fix(
+      "// deno-lint-ignore-file no-var -- legacy\nconst a = 1; // deno-lint-ignore no-extra-semi\nconsole.log(a);\n",
+    );
+    assert_eq!(result.source_code, "const a = 1;\nconsole.log(a);\n");
+    assert!(result.diagnostics.is_empty());
+  }
+
+  #[test]
+  fn removes_unused_ranges() {
+    let result = fix(
+      "const a = 1;\n// deno-lint-disable no-var\n  console.log(a);\n// deno-lint-enable\n",
+    );
+    assert_eq!(result.source_code, "const a = 1;\n  console.log(a);\n");
+    assert!(result.diagnostics.is_empty());
+  }
+
+  #[test]
+  fn removes_mapped_eslint_codes() {
+    let mut linter = LinterBuilder::default()
+      .eslint_directives(true)
+      .rules(vec![NoEmpty::new(), NoExplicitAny::new()])
+      .build();
+    let result = lint_and_fix(
+      &mut linter,
+      "fix_test.ts",
+      "// eslint-disable-next-line @typescript-eslint/no-explicit-any, no-empty\nif (a) {}\n"
+        .to_string(),
+    );
+    assert_eq!(
+      result.source_code,
+      "// eslint-disable-next-line no-empty\nif (a) {}\n"
+    );
+    assert!(result.diagnostics.is_empty());
+  }
+
+  fn diagnostic_with_fix(changes: &[(usize, usize, &str)]) -> LintDiagnostic {
+    let position = |byte_pos| Position {
+      line: 1,
+      col: byte_pos,
+      byte_pos,
+    };
+    LintDiagnostic {
+      range: Range {
+        start: position(0),
+        end: position(0),
+      },
+      filename: "fix_test.ts".to_string(),
+      message: "".to_string(),
+      code: "fix-test".to_string(),
+      severity: Severity::Error,
+      hint: None,
+      fixes: vec![LintFix {
+        description: "".to_string(),
+        changes: changes
+          .iter()
+          .map(|&(start, end, new_text)| LintFixChange {
+            range: Range {
+              start: position(start),
+              end: position(end),
+            },
+            new_text: new_text.to_string(),
+          })
+          .collect(),
+      }],
+    }
+  }
+
+  #[test]
+  fn skips_overlapping_fixes() {
+    let diagnostics = vec![
+      diagnostic_with_fix(&[(0, 3, "let"), (10, 11, "")]),
+      diagnostic_with_fix(&[(2, 5, "xyz")]),
+      diagnostic_with_fix(&[(10, 10, "!")]),
+      diagnostic_with_fix(&[(4, 5, "b")]),
+    ];
+    let (fixed, count) = apply_lint_fixes("var a = 1;;", &diagnostics).unwrap();
+    assert_eq!(fixed, "let b = 1;");
+    assert_eq!(count, 2);
+
+    assert!(apply_lint_fixes("var a = 1;", &[]).is_none());
+  }
+
+  /// Replaces `debugger` statements with code the parser can only recover
+  /// from.
+  struct BreakingFix;
+
+  impl LintRule for BreakingFix {
+    fn new() -> Box<Self> {
+      Box::new(BreakingFix)
+    }
+
+    fn code(&self) -> &'static str {
+      "breaking-fix"
+    }
+
+    fn lint_program(
+      &self,
+      context: &mut crate::linter::Context,
+      program: &swc_ecmascript::ast::Program,
+    ) {
+      use swc_ecmascript::ast::{DebuggerStmt, Program};
+      use swc_ecmascript::visit::{Node, Visit, VisitWith};
+
+      struct Visitor<'c>(&'c mut crate::linter::Context);
+      impl<'c> Visit for Visitor<'c> {
+        fn visit_debugger_stmt(&mut self, n: &DebuggerStmt, _: &dyn Node) {
+          let fix = LintFix {
+            description: "".to_string(),
+            changes: vec![self.0.create_fix_change(n.span, "let a = 07;")],
+          };
+          self
+            .0
+            .add_diagnostic_with_fix(n.span, "breaking-fix", "", fix);
+        }
+      }
+      program.visit_with(program as &Program, &mut Visitor(context));
+    }
+  }
+
+  #[test]
+  fn discards_fixes_with_recovered_parse_errors() {
+    let mut linter = LinterBuilder::default()
+      .rules(vec![BreakingFix::new() as Box<dyn LintRule>])
+      .build();
+    let result =
+      lint_and_fix(&mut linter, "fix_test.ts", "debugger;".to_string());
+    assert_eq!(result.source_code, "debugger;");
+    assert_eq!(result.fixed_count, 0);
+    assert_eq!(result.diagnostics.len(), 1);
+  }
+
+  #[test]
+  fn nothing_to_fix() {
+    let source = "const a = 1;\nconsole.log(a);";
+    let result = fix(source);
+    assert_eq!(result.source_code, source);
+    assert_eq!(result.fixed_count, 0);
+  }
+}
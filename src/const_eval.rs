@@ -0,0 +1,630 @@
+use crate::scopes::Scope;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use swc_ecmascript::ast::*;
+use swc_ecmascript::utils::{ident::IdentLike, ExprExt, Id, Type, Value};
+use swc_ecmascript::visit::{noop_visit_type, Node, Visit, VisitWith};
+
+macro_rules! unwrap_or_unknown {
+  ($e:expr) => {
+    match $e {
+      Some(value) => value,
+      None => return Value::Unknown,
+    }
+  };
+}
+
+/// Value of an expression which is known without running the program.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Constant {
+  Undefined,
+  Null,
+  Bool(bool),
+  Number(f64),
+  String(String),
+  /// An object, e.g. an array or a regex, whose contents aren't tracked.
+  Object,
+  /// A function or a class.
+  Function,
+}
+
+impl Constant {
+  /// Converts the value like `Boolean(value)`.
+  pub fn to_bool(&self) -> bool {
+    match self {
+      Constant::Undefined | Constant::Null => false,
+      Constant::Bool(b) => *b,
+      Constant::Number(n) => *n != 0.0 && !n.is_nan(),
+      Constant::String(s) => !s.is_empty(),
+      Constant::Object | Constant::Function => true,
+    }
+  }
+
+  /// Converts the value like `Number(value)`, if it's a primitive.
+  pub fn to_number(&self) -> Option<f64> {
+    match self {
+      Constant::Undefined => Some(f64::NAN),
+      Constant::Null => Some(0.0),
+      Constant::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+      Constant::Number(n) => Some(*n),
+      Constant::String(s) => Some(string_to_number(s)),
+      Constant::Object | Constant::Function => None,
+    }
+  }
+
+  /// Converts the value like `String(value)`, if it's a primitive.
+  pub fn to_js_string(&self) -> Option<String> {
+    match self {
+      Constant::Undefined => Some("undefined".to_string()),
+      Constant::Null => Some("null".to_string()),
+      Constant::Bool(b) => Some(b.to_string()),
+      Constant::Number(n) => number_to_string(*n),
+      Constant::String(s) => Some(s.clone()),
+      Constant::Object | Constant::Function => None,
+    }
+  }
+
+  /// Result of `typeof value`.
+  pub fn type_of(&self) -> &'static str {
+    match self {
+      Constant::Undefined => "undefined",
+      Constant::Null | Constant::Object => "object",
+      Constant::Bool(_) => "boolean",
+      Constant::Number(_) => "number",
+      Constant::String(_) => "string",
+      Constant::Function => "function",
+    }
+  }
+
+  fn is_primitive(&self) -> bool {
+    !matches!(self, Constant::Object | Constant::Function)
+  }
+
+  /// `a === b`, unknown for two objects which may be the same one.
+  fn strict_equals(&self, other: &Constant) -> Option<bool> {
+    if !self.is_primitive() && !other.is_primitive() {
+      return None;
+    }
+    Some(self == other)
+  }
+
+  /// `a == b`
+  fn loose_equals(&self, other: &Constant) -> Option<bool> {
+    use Constant::*;
+    match (self, other) {
+      (Undefined, Null) | (Null, Undefined) => Some(true),
+      (Undefined, _) | (Null, _) | (_, Undefined) | (_, Null) => {
+        Some(self == other)
+      }
+      (Number(_), String(_)) | (String(_), Number(_)) | (Bool(_), _) => {
+        Some(self.to_number()? == other.to_number()?)
+      }
+      (_, Bool(_)) => other.loose_equals(self),
+      (String(a), String(b)) => Some(a == b),
+      (Number(a), Number(b)) => Some(a == b),
+      // The object is converted to a primitive, e.g. `1 == [1]` is true.
+      _ if self.is_primitive() != other.is_primitive() => None,
+      _ => self.strict_equals(other),
+    }
+  }
+
+  /// Order used by `<`, `>`, `<=` and `>=`, or `Some(None)` if the values
+  /// aren't comparable, e.g. with `NaN`.
+  fn compare(&self, other: &Constant) -> Option<Option<Ordering>> {
+    if let (Constant::String(a), Constant::String(b)) = (self, other) {
+      return Some(Some(a.encode_utf16().cmp(b.encode_utf16())));
+    }
+    Some(self.to_number()?.partial_cmp(&other.to_number()?))
+  }
+}
+
+/// Converts a string like `Number(string)`.
+fn string_to_number(s: &str) -> f64 {
+  let s = s.trim();
+  if s.is_empty() {
+    return 0.0;
+  }
+  let radix = match s.get(..2) {
+    Some("0x") | Some("0X") => 16,
+    Some("0o") | Some("0O") => 8,
+    Some("0b") | Some("0B") => 2,
+    _ => 10,
+  };
+  if radix != 10 {
+    return u64::from_str_radix(&s[2..], radix).map_or(f64::NAN, |n| n as f64);
+  }
+  let unsigned = s
+    .strip_prefix('+')
+    .or_else(|| s.strip_prefix('-'))
+    .unwrap_or(s);
+  match unsigned {
+    "Infinity" if s.starts_with('-') => f64::NEG_INFINITY,
+    "Infinity" => f64::INFINITY,
+    // Rust also accepts e.g. `inf` and `NaN`.
+    _ if unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.') => {
+      s.parse().unwrap_or(f64::NAN)
+    }
+    _ => f64::NAN,
+  }
+}
+
+/// Converts a number like `String(number)`, unless JavaScript would use the
+/// exponential notation.
+fn number_to_string(n: f64) -> Option<String> {
+  if n.is_nan() {
+    Some("NaN".to_string())
+  } else if n.is_infinite() {
+    Some(if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string())
+  } else if n == 0.0 {
+    Some("0".to_string())
+  } else if n.abs() >= 1e21 || n.abs() < 1e-6 {
+    None
+  } else {
+    Some(n.to_string())
+  }
+}
+
+fn to_int32(n: f64) -> i32 {
+  if !n.is_finite() {
+    return 0;
+  }
+  (n.trunc() % 4_294_967_296.0) as i64 as u32 as i32
+}
+
+/// Values of the `const` bindings of a program which are initialized with a
+/// constant expression, like `const a = 1;`.
+#[derive(Debug, Default)]
+pub struct ConstBindings {
+  values: HashMap<Id, Constant>,
+}
+
+impl ConstBindings {
+  pub fn analyze(program: &Program, scope: &Scope) -> Self {
+    let mut collector = ConstCollector {
+      scope,
+      bindings: ConstBindings::default(),
+    };
+    program.visit_with(program, &mut collector);
+    collector.bindings
+  }
+
+  pub fn get(&self, id: &Id) -> Option<&Constant> {
+    self.values.get(id)
+  }
+}
+
+struct ConstCollector<'a> {
+  scope: &'a Scope,
+  bindings: ConstBindings,
+}
+
+impl Visit for ConstCollector<'_> {
+  noop_visit_type!();
+
+  fn visit_var_decl(&mut self, var_decl: &VarDecl, _: &dyn Node) {
+    var_decl.visit_children_with(self);
+    if var_decl.kind != VarDeclKind::Const {
+      return;
+    }
+    for decl in &var_decl.decls {
+      if let (Pat::Ident(ident), Some(init)) = (&decl.name, &decl.init) {
+        let evaluator = ConstEvaluator::new(self.scope, &self.bindings);
+        if let Value::Known(value) = evaluator.eval(init) {
+          self.bindings.values.insert(ident.to_id(), value);
+        }
+      }
+    }
+  }
+}
+
+/// Evaluates expressions which only depend on literals and `const` bindings
+/// with constant values, e.g. `` `${a}px` `` after `const a = 2 * 8;`.
+///
+/// Builds on `ExprExt` of swc, resolving identifiers through the scope
+/// analysis so that shadowed globals like `undefined` aren't mistaken for the
+/// builtin ones.
+pub struct ConstEvaluator<'a> {
+  scope: &'a Scope,
+  bindings: &'a ConstBindings,
+}
+
+impl<'a> ConstEvaluator<'a> {
+  pub fn new(scope: &'a Scope, bindings: &'a ConstBindings) -> Self {
+    Self { scope, bindings }
+  }
+
+  pub fn eval(&self, expr: &Expr) -> Value<Constant> {
+    match expr {
+      Expr::Lit(lit) => self.eval_lit(lit),
+      Expr::Tpl(tpl) => {
+        let mut string = String::new();
+        for (i, quasi) in tpl.quasis.iter().enumerate() {
+          match &quasi.cooked {
+            Some(cooked) => string.push_str(&cooked.value),
+            None => return Value::Unknown,
+          }
+          if let Some(expr) = tpl.exprs.get(i) {
+            match self.eval(expr) {
+              Value::Known(value) => match value.to_js_string() {
+                Some(s) => string.push_str(&s),
+                None => return Value::Unknown,
+              },
+              Value::Unknown => return Value::Unknown,
+            }
+          }
+        }
+        Value::Known(Constant::String(string))
+      }
+      Expr::Ident(ident) => self.eval_ident(ident),
+      Expr::Unary(unary) => self.eval_unary(unary),
+      Expr::Bin(bin) => self.eval_bin(bin),
+      Expr::Cond(cond) => match self.eval_bool(&cond.test) {
+        Value::Known(true) => self.eval(&cond.cons),
+        Value::Known(false) => self.eval(&cond.alt),
+        Value::Unknown => Value::Unknown,
+      },
+      Expr::Seq(seq) => match seq.exprs.last() {
+        Some(last) => self.eval(last),
+        None => Value::Unknown,
+      },
+      Expr::Assign(assign) if assign.op == AssignOp::Assign => {
+        self.eval(&assign.right)
+      }
+      Expr::Paren(ParenExpr { expr, .. })
+      | Expr::TsAs(TsAsExpr { expr, .. })
+      | Expr::TsNonNull(TsNonNullExpr { expr, .. })
+      | Expr::TsTypeAssertion(TsTypeAssertion { expr, .. })
+      | Expr::TsConstAssertion(TsConstAssertion { expr, .. }) => {
+        self.eval(expr)
+      }
+      Expr::Array(_) | Expr::Object(_) | Expr::New(_) => {
+        Value::Known(Constant::Object)
+      }
+      Expr::Fn(_) | Expr::Arrow(_) | Expr::Class(_) => {
+        Value::Known(Constant::Function)
+      }
+      _ => Value::Unknown,
+    }
+  }
+
+  /// Evaluates `expr` converted to a boolean, which is known in more cases
+  /// than its value, e.g. `a || true` is always truthy.
+  pub fn eval_bool(&self, expr: &Expr) -> Value<bool> {
+    match expr {
+      Expr::Paren(paren) => self.eval_bool(&paren.expr),
+      Expr::Unary(unary) if unary.op == UnaryOp::Bang => {
+        !self.eval_bool(&unary.arg)
+      }
+      // `typeof` always returns a non-empty string.
+      Expr::Unary(unary) if unary.op == UnaryOp::TypeOf => Value::Known(true),
+      Expr::Bin(bin) if bin.op == BinaryOp::LogicalAnd => {
+        self.eval_bool(&bin.left).and(self.eval_bool(&bin.right))
+      }
+      Expr::Bin(bin) if bin.op == BinaryOp::LogicalOr => {
+        self.eval_bool(&bin.left).or(self.eval_bool(&bin.right))
+      }
+      Expr::Seq(seq) => match seq.exprs.last() {
+        Some(last) => self.eval_bool(last),
+        None => Value::Unknown,
+      },
+      Expr::Assign(assign) if assign.op == AssignOp::Assign => {
+        self.eval_bool(&assign.right)
+      }
+      // A template with some text is never empty.
+      Expr::Tpl(tpl)
+        if tpl.quasis.iter().any(|quasi| {
+          quasi.cooked.as_ref().map_or(false, |s| !s.value.is_empty())
+        }) =>
+      {
+        Value::Known(true)
+      }
+      _ => match self.eval(expr) {
+        Value::Known(value) => Value::Known(value.to_bool()),
+        Value::Unknown => Value::Unknown,
+      },
+    }
+  }
+
+  /// Evaluates `expr` if its value is a number, without conversion.
+  pub fn eval_number(&self, expr: &Expr) -> Value<f64> {
+    match self.eval(expr) {
+      Value::Known(Constant::Number(n)) => Value::Known(n),
+      _ => Value::Unknown,
+    }
+  }
+
+  fn eval_lit(&self, lit: &Lit) -> Value<Constant> {
+    Value::Known(match lit {
+      Lit::Str(s) => Constant::String(s.value.to_string()),
+      Lit::Bool(b) => Constant::Bool(b.value),
+      Lit::Null(_) => Constant::Null,
+      Lit::Num(n) => Constant::Number(n.value),
+      Lit::Regex(_) => Constant::Object,
+      Lit::BigInt(_) | Lit::JSXText(_) => return Value::Unknown,
+    })
+  }
+
+  fn eval_ident(&self, ident: &Ident) -> Value<Constant> {
+    let id = ident.to_id();
+    if self.scope.var(&id).is_some() {
+      return match self.bindings.get(&id) {
+        Some(value) => Value::Known(value.clone()),
+        None => Value::Unknown,
+      };
+    }
+    match &*ident.sym {
+      "undefined" => Value::Known(Constant::Undefined),
+      "NaN" => Value::Known(Constant::Number(f64::NAN)),
+      "Infinity" => Value::Known(Constant::Number(f64::INFINITY)),
+      _ => Value::Unknown,
+    }
+  }
+
+  fn eval_unary(&self, unary: &UnaryExpr) -> Value<Constant> {
+    if unary.op == UnaryOp::Void {
+      return Value::Known(Constant::Undefined);
+    }
+    if unary.op == UnaryOp::Bang {
+      return match self.eval_bool(&unary.arg) {
+        Value::Known(b) => Value::Known(Constant::Bool(!b)),
+        Value::Unknown => Value::Unknown,
+      };
+    }
+
+    let arg = match self.eval(&unary.arg) {
+      Value::Known(arg) => arg,
+      Value::Unknown if unary.op == UnaryOp::TypeOf => {
+        return self.type_of_unknown(&unary.arg)
+      }
+      Value::Unknown => return Value::Unknown,
+    };
+    let value = match unary.op {
+      UnaryOp::TypeOf => Constant::String(arg.type_of().to_string()),
+      UnaryOp::Minus => Constant::Number(-unwrap_or_unknown!(arg.to_number())),
+      UnaryOp::Plus => Constant::Number(unwrap_or_unknown!(arg.to_number())),
+      UnaryOp::Tilde => {
+        Constant::Number(!to_int32(unwrap_or_unknown!(arg.to_number())) as f64)
+      }
+      _ => return Value::Unknown,
+    };
+    Value::Known(value)
+  }
+
+  /// `typeof` of an expression whose value is unknown, using the type
+  /// inferred by swc when it doesn't depend on identifiers.
+  fn type_of_unknown(&self, arg: &Expr) -> Value<Constant> {
+    if matches!(arg, Expr::Ident(_)) {
+      return Value::Unknown;
+    }
+    let type_of = match arg.get_type() {
+      Value::Known(Type::Undefined) => "undefined",
+      Value::Known(Type::Null) => "object",
+      Value::Known(Type::Bool) => "boolean",
+      Value::Known(Type::Str) => "string",
+      Value::Known(Type::Symbol) => "symbol",
+      Value::Known(Type::Num) => "number",
+      // Objects may be functions.
+      Value::Known(Type::Obj) | Value::Unknown => return Value::Unknown,
+    };
+    Value::Known(Constant::String(type_of.to_string()))
+  }
+
+  fn eval_bin(&self, bin: &BinExpr) -> Value<Constant> {
+    let left = match self.eval(&bin.left) {
+      Value::Known(left) => left,
+      Value::Unknown => return Value::Unknown,
+    };
+    match bin.op {
+      BinaryOp::LogicalAnd if !left.to_bool() => return Value::Known(left),
+      BinaryOp::LogicalOr if left.to_bool() => return Value::Known(left),
+      BinaryOp::NullishCoalescing
+        if !matches!(left, Constant::Undefined | Constant::Null) =>
+      {
+        return Value::Known(left)
+      }
+      BinaryOp::LogicalAnd
+      | BinaryOp::LogicalOr
+      | BinaryOp::NullishCoalescing => return self.eval(&bin.right),
+      _ => {}
+    }
+
+    let right = match self.eval(&bin.right) {
+      Value::Known(right) => right,
+      Value::Unknown => return Value::Unknown,
+    };
+    let value = match bin.op {
+      BinaryOp::EqEqEq => {
+        Constant::Bool(unwrap_or_unknown!(left.strict_equals(&right)))
+      }
+      BinaryOp::NotEqEq => {
+        Constant::Bool(!unwrap_or_unknown!(left.strict_equals(&right)))
+      }
+      BinaryOp::EqEq => {
+        Constant::Bool(unwrap_or_unknown!(left.loose_equals(&right)))
+      }
+      BinaryOp::NotEq => {
+        Constant::Bool(!unwrap_or_unknown!(left.loose_equals(&right)))
+      }
+      BinaryOp::Lt | BinaryOp::Gt | BinaryOp::LtEq | BinaryOp::GtEq => {
+        let ordering = unwrap_or_unknown!(left.compare(&right));
+        Constant::Bool(match bin.op {
+          BinaryOp::Lt => ordering == Some(Ordering::Less),
+          BinaryOp::Gt => ordering == Some(Ordering::Greater),
+          BinaryOp::LtEq => ordering.map_or(false, |o| o != Ordering::Greater),
+          _ => ordering.map_or(false, |o| o != Ordering::Less),
+        })
+      }
+      BinaryOp::Add => match (&left, &right) {
+        (Constant::String(_), _) | (_, Constant::String(_)) => {
+          let mut string = unwrap_or_unknown!(left.to_js_string());
+          string.push_str(&unwrap_or_unknown!(right.to_js_string()));
+          Constant::String(string)
+        }
+        _ => Constant::Number(
+          unwrap_or_unknown!(left.to_number())
+            + unwrap_or_unknown!(right.to_number()),
+        ),
+      },
+      _ => {
+        let a = unwrap_or_unknown!(left.to_number());
+        let b = unwrap_or_unknown!(right.to_number());
+        Constant::Number(match bin.op {
+          BinaryOp::Sub => a - b,
+          BinaryOp::Mul => a * b,
+          BinaryOp::Div => a / b,
+          BinaryOp::Mod => a % b,
+          // Unlike `powf`, `**` returns `NaN` for `1 ** NaN` and
+          // `1 ** Infinity`.
+          BinaryOp::Exp if a.abs() == 1.0 && !b.is_finite() => f64::NAN,
+          BinaryOp::Exp => a.powf(b),
+          BinaryOp::BitAnd => (to_int32(a) & to_int32(b)) as f64,
+          BinaryOp::BitOr => (to_int32(a) | to_int32(b)) as f64,
+          BinaryOp::BitXor => (to_int32(a) ^ to_int32(b)) as f64,
+          BinaryOp::LShift => {
+            to_int32(a).wrapping_shl(to_int32(b) as u32 & 31) as f64
+          }
+          BinaryOp::RShift => (to_int32(a) >> (to_int32(b) as u32 & 31)) as f64,
+          BinaryOp::ZeroFillRShift => {
+            ((to_int32(a) as u32) >> (to_int32(b) as u32 & 31)) as f64
+          }
+          _ => return Value::Unknown,
+        })
+      }
+    };
+    Value::Known(value)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util;
+
+  /// Runs `f` on the expression statement at the end of `src`.
+  fn with_last_expr<T>(
+    src: &str,
+    f: impl FnOnce(&ConstEvaluator, &Expr) -> T,
+  ) -> T {
+    let program = test_util::parse(src);
+    let scope = Scope::analyze(&program);
+    let bindings = ConstBindings::analyze(&program, &scope);
+    let expr = match &program {
+      Program::Module(module) => match module.body.last() {
+        Some(ModuleItem::Stmt(Stmt::Expr(stmt))) => &stmt.expr,
+        _ => unreachable!(),
+      },
+      Program::Script(script) => match script.body.last() {
+        Some(Stmt::Expr(stmt)) => &stmt.expr,
+        _ => unreachable!(),
+      },
+    };
+    f(&ConstEvaluator::new(&scope, &bindings), expr)
+  }
+
+  fn eval(src: &str) -> Option<Constant> {
+    with_last_expr(src, |evaluator, expr| {
+      evaluator.eval(expr).into_result().ok()
+    })
+  }
+
+  fn eval_bool(src: &str) -> Option<bool> {
+    with_last_expr(src, |evaluator, expr| {
+      evaluator.eval_bool(expr).into_result().ok()
+    })
+  }
+
+  fn number(n: f64) -> Option<Constant> {
+    Some(Constant::Number(n))
+  }
+
+  fn string(s: &str) -> Option<Constant> {
+    Some(Constant::String(s.to_string()))
+  }
+
+  #[test]
+  fn literals() {
+    assert_eq!(eval("1;"), number(1.0));
+    assert_eq!(eval("'a';"), string("a"));
+    assert_eq!(eval("null;"), Some(Constant::Null));
+    assert_eq!(eval("undefined;"), Some(Constant::Undefined));
+    assert_eq!(eval("[];"), Some(Constant::Object));
+    assert_eq!(eval("/a/;"), Some(Constant::Object));
+    assert_eq!(eval("() => {};"), Some(Constant::Function));
+    assert_eq!(eval("1n;"), None);
+    assert_eq!(eval("foo;"), None);
+  }
+
+  #[test]
+  fn templates() {
+    assert_eq!(eval("`a${1 + 1}b${null}`;"), string("a2bnull"));
+    assert_eq!(eval("`\\u0041`;"), string("A"));
+    assert_eq!(eval("`a${foo}`;"), None);
+    assert_eq!(eval("`${[]}`;"), None);
+  }
+
+  #[test]
+  fn unary() {
+    assert_eq!(eval("-0;"), number(-0.0));
+    assert_eq!(eval("+'0x10';"), number(16.0));
+    assert_eq!(eval("+' 1.5 ';"), number(1.5));
+    assert_eq!(eval("~5;"), number(-6.0));
+    assert_eq!(eval("!'';"), Some(Constant::Bool(true)));
+    assert_eq!(eval("void foo();"), Some(Constant::Undefined));
+    assert_eq!(eval("typeof 1;"), string("number"));
+    assert_eq!(eval("typeof (() => {});"), string("function"));
+    assert_eq!(eval("typeof `${foo}`;"), string("string"));
+    assert_eq!(eval("typeof foo;"), None);
+    assert_eq!(eval("typeof {};"), string("object"));
+  }
+
+  #[test]
+  fn binary() {
+    assert_eq!(eval("1 + 2 * 3;"), number(7.0));
+    assert_eq!(eval("'a' + 1;"), string("a1"));
+    assert_eq!(eval("1 + true;"), number(2.0));
+    assert_eq!(eval("2 ** 10;"), number(1024.0));
+    assert!(
+      matches!(eval("1 ** Infinity;"), Some(Constant::Number(n)) if n.is_nan())
+    );
+    assert!(matches!(eval("0 / 0;"), Some(Constant::Number(n)) if n.is_nan()));
+    assert_eq!(eval("-1 >>> 28;"), number(15.0));
+    assert_eq!(eval("1 << 33;"), number(2.0));
+    assert_eq!(eval("'1' == 1;"), Some(Constant::Bool(true)));
+    assert_eq!(eval("'1' === 1;"), Some(Constant::Bool(false)));
+    assert_eq!(eval("null == undefined;"), Some(Constant::Bool(true)));
+    assert_eq!(eval("null == 0;"), Some(Constant::Bool(false)));
+    assert_eq!(eval("null <= 0;"), Some(Constant::Bool(true)));
+    assert_eq!(eval("NaN <= NaN;"), Some(Constant::Bool(false)));
+    assert_eq!(eval("'b' > 'a';"), Some(Constant::Bool(true)));
+    assert_eq!(eval("[] === [];"), None);
+    assert_eq!(eval("1 == [1];"), None);
+    assert_eq!(eval("'' == [];"), None);
+    assert_eq!(eval("null == [];"), Some(Constant::Bool(false)));
+    assert_eq!(eval("foo + 1;"), None);
+  }
+
+  #[test]
+  fn logical() {
+    assert_eq!(eval("0 || 'a';"), string("a"));
+    assert_eq!(eval("0 && foo;"), number(0.0));
+    assert_eq!(eval("0 ?? foo;"), number(0.0));
+    assert_eq!(eval("null ?? 'a';"), string("a"));
+    assert_eq!(eval("1 && foo;"), None);
+    assert_eq!(eval_bool("foo || 'a';"), Some(true));
+    assert_eq!(eval_bool("foo && 0;"), Some(false));
+    assert_eq!(eval_bool("foo && 1;"), None);
+    assert_eq!(eval_bool("typeof foo;"), Some(true));
+    assert_eq!(eval_bool("`a${foo}`;"), Some(true));
+  }
+
+  #[test]
+  fn bindings() {
+    assert_eq!(eval("const a = 1; const b = a * 2; b;"), number(2.0));
+    assert_eq!(eval("const a = 'px'; `1${a}`;"), string("1px"));
+    assert_eq!(eval("let a = 1; a;"), None);
+    assert_eq!(eval("const a = foo(); a;"), None);
+    assert_eq!(eval("const a = 1; function f(a) { a; } a;"), number(1.0));
+    assert_eq!(eval("const undefined = 1; undefined;"), number(1.0));
+    assert_eq!(eval("import { a } from 'a'; a;"), None);
+  }
+}
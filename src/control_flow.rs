@@ -1,6 +1,6 @@
 use std::{
   collections::{BTreeMap, HashSet},
-  mem::take,
+  mem::{replace, take},
 };
 use swc_common::{BytePos, Spanned, DUMMY_SP};
 use swc_ecmascript::ast::*;
@@ -9,6 +9,12 @@ use swc_ecmascript::{
   visit::{noop_visit_type, Node, Visit, VisitWith},
 };
 
+mod cfg;
+
+pub use cfg::{
+  BasicBlock, BlockId, Cfg, Edge, EdgeKind, FunctionCfg, FunctionId,
+};
+
 #[derive(Debug, Clone)]
 pub struct ControlFlow {
   meta: BTreeMap<BytePos, Metadata>,
@@ -19,6 +25,7 @@ impl ControlFlow {
     let mut v = Analyzer {
       scope: Scope::new(None, BlockKind::Program),
       info: Default::default(),
+      jump_targets: vec![],
     };
     program.visit_with(&Invalid { span: DUMMY_SP }, &mut v);
     ControlFlow { meta: v.info }
@@ -57,6 +64,8 @@ pub enum BlockKind {
 pub struct Metadata {
   pub unreachable: bool,
   end: Option<End>,
+  block_kind: Option<BlockKind>,
+  jump_target: Option<BytePos>,
 }
 
 impl Metadata {
@@ -71,12 +80,39 @@ impl Metadata {
   pub fn continues_execution(&self) -> bool {
     self.end.map_or(true, |d| d == End::Continue)
   }
+
+  /// How the execution ends at a node, if it was analyzed.
+  pub fn end(&self) -> Option<End> {
+    self.end
+  }
+
+  /// Kind of the block a statement is directly in.
+  pub fn block_kind(&self) -> Option<&BlockKind> {
+    self.block_kind.as_ref()
+  }
+
+  /// Start of the statement a `break` or `continue` statement jumps to.
+  ///
+  /// This is the labeled statement for a jump with a label, and the
+  /// innermost loop or switch statement otherwise.
+  pub fn jump_target(&self) -> Option<BytePos> {
+    self.jump_target
+  }
 }
 
 #[derive(Debug)]
 struct Analyzer<'a> {
   scope: Scope<'a>,
   info: BTreeMap<BytePos, Metadata>,
+  /// Statements which `break` and `continue` can jump to, innermost last.
+  jump_targets: Vec<JumpTarget>,
+}
+
+#[derive(Debug)]
+struct JumpTarget {
+  label: Option<Id>,
+  lo: BytePos,
+  is_loop: bool,
 }
 
 #[derive(Debug)]
@@ -87,7 +123,7 @@ struct Scope<'a> {
   /// `function foo() { return bar(); function bar() { return 1; } }`
   used_hoistable_ids: HashSet<Id>,
 
-  _kind: BlockKind,
+  kind: BlockKind,
 
   /// Unconditionally ends with return, throw
   end: Option<End>,
@@ -102,8 +138,9 @@ struct Scope<'a> {
   found_continue: bool,
 }
 
+/// How the execution ends at a node.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum End {
+pub enum End {
   /// Contains something that stops execution at that point.
   /// This is represented as product of three elements (ret, throw, infinite_loop)
   /// because sometimes these conditions are satisfied _simultaneously_.
@@ -190,13 +227,37 @@ impl End {
   fn is_forced(&self) -> bool {
     matches!(self, End::Forced { .. })
   }
+
+  fn stops(&self) -> bool {
+    matches!(self, End::Forced { .. } | End::Break)
+  }
+
+  /// Merges two ends which stop execution.
+  fn merge_stops(self, other: Self) -> Self {
+    self.merge_forced(other).unwrap_or(End::Break)
+  }
+
+  /// Removes unconditional throw, which is handled by a catch clause.
+  fn without_throw(self) -> Option<Self> {
+    match self {
+      End::Forced {
+        ret, infinite_loop, ..
+      } if ret || infinite_loop => Some(End::Forced {
+        ret,
+        throw: false,
+        infinite_loop,
+      }),
+      End::Forced { .. } => None,
+      e => Some(e),
+    }
+  }
 }
 
 impl<'a> Scope<'a> {
   pub fn new(parent: Option<&'a Scope<'a>>, kind: BlockKind) -> Self {
     Self {
       _parent: parent,
-      _kind: kind,
+      kind,
       used_hoistable_ids: Default::default(),
       end: None,
       may_throw: false,
@@ -217,9 +278,22 @@ impl Analyzer<'_> {
     F: for<'any> FnOnce(&mut Analyzer<'any>),
   {
     let prev_end = self.scope.end;
-    let (info, end, hoist, found_break, found_continue, may_throw) = {
+    let (
+      info,
+      jump_targets,
+      end,
+      hoist,
+      found_break,
+      found_continue,
+      may_throw,
+    ) = {
       let mut child = Analyzer {
         info: take(&mut self.info),
+        // `break` and `continue` can't jump out of a function.
+        jump_targets: match kind {
+          BlockKind::Function => vec![],
+          _ => take(&mut self.jump_targets),
+        },
         scope: Scope::new(Some(&self.scope), kind.clone()),
       };
       match kind {
@@ -236,6 +310,7 @@ impl Analyzer<'_> {
 
       (
         take(&mut child.info),
+        take(&mut child.jump_targets),
         child.scope.end,
         child.scope.used_hoistable_ids,
         child.scope.found_break,
@@ -245,10 +320,16 @@ impl Analyzer<'_> {
     };
 
     self.info = info;
+    if !matches!(kind, BlockKind::Function) {
+      self.jump_targets = jump_targets;
+    }
     self.scope.used_hoistable_ids.extend(hoist);
 
-    // Preserve information about visited ast nodes.
-    self.scope.may_throw |= may_throw;
+    // Preserve information about visited ast nodes. Exceptions thrown in a
+    // nested function don't propagate to where it's defined.
+    if !matches!(kind, BlockKind::Function) {
+      self.scope.may_throw |= may_throw;
+    }
     if self.scope.found_break.is_none() {
       self.scope.found_break = found_break;
     }
@@ -260,7 +341,8 @@ impl Analyzer<'_> {
         BlockKind::Function => {
           match end {
             End::Forced { .. } | End::Continue => self.mark_as_end(lo, end),
-            _ => unreachable!(),
+            // `break` outside of a loop is a syntax error
+            End::Break => {}
           }
           self.scope.end = prev_end;
         }
@@ -324,6 +406,32 @@ impl Analyzer<'_> {
     self.info.entry(lo).or_default().end = new_end;
   }
 
+  /// Finds the statement which a `break` or `continue` jumps to.
+  fn find_jump_target(
+    &self,
+    label: Option<&Ident>,
+    is_continue: bool,
+  ) -> Option<BytePos> {
+    let label = label.map(|l| l.to_id());
+    self
+      .jump_targets
+      .iter()
+      .rev()
+      .find(|t| match &label {
+        Some(label) => t.label.as_ref() == Some(label),
+        None => t.label.is_none() && (t.is_loop || !is_continue),
+      })
+      .map(|t| t.lo)
+  }
+
+  /// Records that an exception may be thrown at this point, unless the
+  /// execution has already stopped.
+  fn mark_may_throw(&mut self) {
+    if !self.scope.end.map_or(false, |e| e.stops()) {
+      self.scope.may_throw = true;
+    }
+  }
+
   /// Visits statement or block. This method handles break and continue.
   ///
   /// This cannot be done in visit_stmt of Visit because
@@ -351,10 +459,14 @@ impl Visit for Analyzer<'_> {
 
   fn visit_throw_stmt(&mut self, n: &ThrowStmt, _: &dyn Node) {
     n.visit_children_with(self);
+    self.mark_may_throw();
     self.mark_as_end(n.span().lo, End::forced_throw());
   }
 
   fn visit_break_stmt(&mut self, n: &BreakStmt, _: &dyn Node) {
+    let target = self.find_jump_target(n.label.as_ref(), false);
+    self.info.entry(n.span.lo).or_default().jump_target = target;
+
     if let Some(label) = &n.label {
       let label = label.to_id();
       self.scope.found_break = Some(Some(label));
@@ -363,7 +475,10 @@ impl Visit for Analyzer<'_> {
     }
   }
 
-  fn visit_continue_stmt(&mut self, _: &ContinueStmt, _: &dyn Node) {
+  fn visit_continue_stmt(&mut self, n: &ContinueStmt, _: &dyn Node) {
+    let target = self.find_jump_target(n.label.as_ref(), true);
+    self.info.entry(n.span.lo).or_default().jump_target = target;
+
     self.scope.found_continue = true;
   }
 
@@ -387,16 +502,23 @@ impl Visit for Analyzer<'_> {
     n.visit_children_with(self);
 
     if self.scope.end.is_none() {
-      match n {
-        Expr::Ident(i) => {
-          self.scope.used_hoistable_ids.insert(i.to_id());
-        }
-        Expr::This(..) => {}
-        _ => {
-          self.scope.may_throw = true;
-        }
+      if let Expr::Ident(i) = n {
+        self.scope.used_hoistable_ids.insert(i.to_id());
       }
     }
+
+    if may_throw(n) {
+      self.mark_may_throw();
+    }
+  }
+
+  fn visit_pat(&mut self, n: &Pat, _: &dyn Node) {
+    n.visit_children_with(self);
+
+    // Destructuring `null` or `undefined` throws.
+    if matches!(n, Pat::Object(..) | Pat::Array(..)) {
+      self.mark_may_throw();
+    }
   }
 
   fn visit_member_expr(&mut self, n: &MemberExpr, _: &dyn Node) {
@@ -576,9 +698,40 @@ impl Visit for Analyzer<'_> {
       false
     };
 
-    self.info.entry(n.span().lo).or_default().unreachable = unreachable;
+    let meta = self.info.entry(n.span().lo).or_default();
+    meta.unreachable = unreachable;
+    meta.block_kind = Some(self.scope.kind.clone());
+
+    let target = match n {
+      Stmt::For(..)
+      | Stmt::ForIn(..)
+      | Stmt::ForOf(..)
+      | Stmt::While(..)
+      | Stmt::DoWhile(..) => Some(JumpTarget {
+        label: None,
+        lo: n.span().lo,
+        is_loop: true,
+      }),
+      Stmt::Switch(..) => Some(JumpTarget {
+        label: None,
+        lo: n.span().lo,
+        is_loop: false,
+      }),
+      Stmt::Labeled(LabeledStmt { label, .. }) => Some(JumpTarget {
+        label: Some(label.to_id()),
+        lo: n.span().lo,
+        is_loop: false,
+      }),
+      _ => None,
+    };
+    let pushed = target.is_some();
+    self.jump_targets.extend(target);
 
     n.visit_children_with(self);
+
+    if pushed {
+      self.jump_targets.pop();
+    }
   }
 
   // loops
@@ -627,6 +780,8 @@ impl Visit for Analyzer<'_> {
     let body_lo = n.body.span().lo;
 
     n.right.visit_with(n, self);
+    // Iterating over non-iterable value throws.
+    self.mark_may_throw();
 
     self.with_child_scope(BlockKind::Loop, body_lo, |a| {
       n.body.visit_with(n, a);
@@ -717,62 +872,68 @@ impl Visit for Analyzer<'_> {
   }
 
   fn visit_try_stmt(&mut self, n: &TryStmt, _: &dyn Node) {
-    if let Some(finalizer) = &n.finalizer {
-      self.with_child_scope(BlockKind::Finally, finalizer.span.lo, |a| {
-        n.finalizer.visit_with(n, a);
-      });
-    }
-    let old_throw = self.scope.may_throw;
-
     let prev_end = self.scope.end;
+    let prev_throw = replace(&mut self.scope.may_throw, false);
 
-    self.scope.may_throw = false;
     n.block.visit_with(n, self);
+    let try_end = self.get_end_reason(n.block.span.lo).filter(End::stops);
+    let try_throws = self.scope.may_throw;
 
-    let mut try_block_end = None;
-
-    if self.scope.may_throw {
-      if let Some(end) = self.scope.end {
-        try_block_end = Some(end);
+    // `end` describes every way the statement can finish while `outer` is
+    // the part of it which isn't handled by the catch clause.
+    let (mut end, mut outer, mut may_throw) = (try_end, try_end, try_throws);
+    if let Some(handler) = &n.handler {
+      // The catch clause is reachable only if the try block may throw or
+      // completes normally. In the latter case we are being conservative.
+      if try_throws || try_end.is_none() {
         self.scope.end = prev_end;
       }
-    } else if let Some(end) = self.scope.end {
-      try_block_end = Some(end);
-      self.mark_as_end(n.span.lo, end);
-    }
-
-    if let Some(handler) = &n.handler {
       self.scope.may_throw = false;
       handler.visit_with(n, self);
-
-      match (try_block_end, self.scope.end) {
-        (Some(x), Some(y)) if x.is_forced() && y.is_forced() => {
-          // This `unwrap` is safe; `x` and `y` are surely `Some(End::Forced { .. })`
-          self.mark_as_end(n.span.lo, x.merge_forced(y).unwrap());
-        }
-        (Some(x), Some(End::Break)) if x.is_forced() => {
-          self.mark_as_end(n.span.lo, End::Break);
-        }
-        _ => {
-          self.mark_as_end(n.span.lo, End::Continue);
-          self.scope.end = prev_end;
+      let catch_end = self.get_end_reason(handler.span.lo).filter(End::stops);
+
+      if try_throws {
+        may_throw = self.scope.may_throw;
+        match (try_end, catch_end) {
+          (Some(x), Some(y)) => {
+            end = Some(x.merge_stops(y));
+            outer = Some(x.without_throw().map_or(y, |x| x.merge_stops(y)));
+          }
+          _ => {
+            end = None;
+            outer = None;
+          }
         }
       }
-    } else if matches!(try_block_end, Some(End::Forced { .. }) | Some(End::Break))
-    {
-      // This `unwrap` is safe; `try_block_end` is surely wrapped in `Some`
-      self.mark_as_end(n.span.lo, try_block_end.unwrap());
-    } else if let Some(finalizer) = &n.finalizer {
-      self.mark_as_end(
-        n.span.lo,
-        self
-          .get_end_reason(finalizer.span.lo)
-          .unwrap_or(End::Continue),
-      );
-      self.scope.end = prev_end;
-      self.scope.may_throw = old_throw;
-    } else {
+    }
+
+    if let Some(finalizer) = &n.finalizer {
+      // `finally` runs whatever happens in the try block or the catch clause.
       self.scope.end = prev_end;
+      self.scope.may_throw = false;
+      self.with_child_scope(BlockKind::Finally, finalizer.span.lo, |a| {
+        n.finalizer.visit_with(n, a);
+      });
+      let finally_end =
+        self.get_end_reason(finalizer.span.lo).filter(End::stops);
+      let finally_throws = self.scope.may_throw;
+
+      if finally_end.is_some() {
+        // Return, throw or break in `finally` overrides the completion of
+        // the try block and the catch clause.
+        end = finally_end;
+        outer = finally_end;
+        may_throw = finally_throws;
+      } else {
+        may_throw |= finally_throws;
+      }
+    }
+
+    self.scope.end = prev_end;
+    self.scope.may_throw = prev_throw || may_throw;
+    self.mark_as_end(n.span.lo, end.unwrap_or(End::Continue));
+    if outer.is_some() {
+      self.scope.end = outer;
     }
   }
 
@@ -783,6 +944,22 @@ impl Visit for Analyzer<'_> {
   }
 }
 
+/// Returns true if evaluating `expr` itself, not counting its children, may
+/// throw an exception.
+fn may_throw(expr: &Expr) -> bool {
+  !matches!(
+    expr,
+    Expr::Ident(..)
+      | Expr::This(..)
+      | Expr::Lit(..)
+      | Expr::Paren(..)
+      | Expr::Fn(..)
+      | Expr::Arrow(..)
+      | Expr::Class(..)
+      | Expr::Invalid(..)
+  )
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -795,13 +972,8 @@ mod tests {
 
   macro_rules! assert_flow {
     ($flow:ident, $lo:expr, $unreachable:expr, $end:expr) => {
-      assert_eq!(
-        $flow.meta(BytePos($lo)).unwrap(),
-        &Metadata {
-          unreachable: $unreachable,
-          end: $end,
-        }
-      );
+      let meta = $flow.meta(BytePos($lo)).unwrap();
+      assert_eq!((meta.unreachable, meta.end()), ($unreachable, $end));
     };
   }
 
@@ -1385,6 +1557,142 @@ bar();
     assert_flow!(flow, 74, false, None); // `bar();`
   }
 
+  #[test]
+  fn try_11() {
+    let src = r#"
+function foo() {
+  try {
+    throw 1;
+  } finally {
+    return 2;
+  }
+  bar();
+}
+"#;
+    let flow = analyze_flow(src);
+    assert_flow!(flow, 16, false, Some(End::forced_return())); // BlockStmt of `foo`
+    assert_flow!(flow, 20, false, Some(End::forced_return())); // TryStmt
+    assert_flow!(flow, 30, false, Some(End::forced_throw())); // throw stmt
+    assert_flow!(flow, 51, false, Some(End::forced_return())); // BlockStmt of finally
+    assert_flow!(flow, 57, false, Some(End::forced_return())); // return stmt
+    assert_flow!(flow, 73, true, None); // `bar();`
+  }
+
+  #[test]
+  fn try_12() {
+    let src = r#"
+function foo() {
+  try {
+    a();
+  } finally {
+    return 1;
+  }
+}
+"#;
+    let flow = analyze_flow(src);
+    assert_flow!(flow, 20, false, Some(End::forced_return())); // TryStmt
+    assert_flow!(flow, 30, false, None); // `a();`
+    assert_flow!(flow, 53, false, Some(End::forced_return())); // return stmt
+  }
+
+  #[test]
+  fn try_13() {
+    let src = r#"
+function foo() {
+  try {
+    return;
+  } catch (e) {
+    bar();
+  }
+}
+"#;
+    let flow = analyze_flow(src);
+    assert_flow!(flow, 20, false, Some(End::forced_return())); // TryStmt
+    assert_flow!(flow, 58, true, None); // `bar();`
+  }
+
+  #[test]
+  fn try_14() {
+    let src = r#"
+function foo() {
+  try {
+    throw e;
+  } catch {
+    return 1;
+  }
+  bar();
+}
+"#;
+    let flow = analyze_flow(src);
+    assert_flow!(
+      flow,
+      20,
+      false,
+      Some(End::Forced {
+        ret: true,
+        throw: true,
+        infinite_loop: false
+      })
+    ); // TryStmt
+    assert_flow!(flow, 43, false, Some(End::forced_return())); // catch
+    assert_flow!(flow, 55, false, Some(End::forced_return())); // return stmt
+    assert_flow!(flow, 71, true, None); // `bar();`
+  }
+
+  #[test]
+  fn try_15() {
+    let src = r#"
+function foo() {
+  try {
+    const f = () => g();
+    return f;
+  } catch (e) {
+    bar();
+  }
+}
+"#;
+    let flow = analyze_flow(src);
+    assert_flow!(flow, 20, false, Some(End::forced_return())); // TryStmt
+    assert_flow!(flow, 85, true, None); // `bar();`
+  }
+
+  #[test]
+  fn try_16() {
+    let src = r#"
+function foo() {
+  try {
+    const { a } = b;
+    return a;
+  } catch (e) {
+    bar();
+  }
+}
+"#;
+    let flow = analyze_flow(src);
+    assert_flow!(flow, 20, false, Some(End::Continue)); // TryStmt
+    assert_flow!(flow, 81, false, None); // `bar();`
+  }
+
+  #[test]
+  fn try_17() {
+    let src = r#"
+function foo() {
+  try {
+    if (a) {}
+    b();
+    return;
+  } catch (e) {
+    c();
+  }
+  d();
+}
+"#;
+    let flow = analyze_flow(src);
+    assert_flow!(flow, 20, false, Some(End::Continue)); // TryStmt
+    assert_flow!(flow, 81, false, None); // `c();`
+    assert_flow!(flow, 92, false, None); // `d();`
+  }
+
   #[test]
   fn if_1() {
     let src = r#"
@@ -1527,4 +1835,40 @@ throw err;
     assert_flow!(flow, 54, false, Some(End::forced_return())); // return stmt
     assert_flow!(flow, 70, false, Some(End::forced_throw())); // throw stmt
   }
+
+  #[test]
+  fn jump_target_and_block_kind() {
+    let src = r#"
+outer: for (;;) {
+  switch (a) {
+    case 1:
+      break;
+    case 2:
+      continue;
+    default:
+      break outer;
+  }
+  function f() {
+    while (b) {
+      if (c) continue;
+    }
+  }
+}
+"#;
+    let flow = analyze_flow(src);
+    let target = |lo| flow.meta(BytePos(lo)).unwrap().jump_target();
+    assert_eq!(target(52), Some(BytePos(21))); // `break` in switch
+    assert_eq!(target(77), Some(BytePos(8))); // `continue` in switch
+    assert_eq!(target(106), Some(BytePos(1))); // `break outer`
+    assert_eq!(target(169), Some(BytePos(144))); // `continue` in function
+    assert_eq!(target(21), None);
+
+    let kind = |lo| flow.meta(BytePos(lo)).unwrap().block_kind().cloned();
+    assert_eq!(kind(1), Some(BlockKind::Program));
+    assert!(matches!(kind(8), Some(BlockKind::Label(..))));
+    assert_eq!(kind(21), Some(BlockKind::Loop));
+    assert_eq!(kind(52), Some(BlockKind::Case));
+    assert_eq!(kind(144), Some(BlockKind::Function));
+    assert_eq!(kind(162), Some(BlockKind::Loop));
+  }
 }
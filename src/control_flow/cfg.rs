@@ -0,0 +1,1320 @@
+use super::may_throw;
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::mem::{replace, take};
+use swc_atoms::JsWord;
+use swc_common::{BytePos, SourceMap, Span, Spanned};
+use swc_ecmascript::ast::*;
+use swc_ecmascript::{
+  utils::{ExprExt, Value},
+  visit::{noop_visit_type, Node, Visit, VisitWith},
+};
+
+/// Control flow graph of a program.
+///
+/// Every function (and the program itself) has its own entry and exit
+/// blocks. Statements are placed in the block that is executing when the
+/// statement is reached; a compound statement like `while` lives in the block
+/// evaluating its test while its body gets blocks of its own.
+///
+/// Only statements split blocks, so short-circuiting expressions like `a && b`
+/// don't create edges.
+#[derive(Debug, Clone)]
+pub struct Cfg {
+  blocks: Vec<BasicBlock>,
+  functions: Vec<FunctionCfg>,
+  stmt_blocks: HashMap<BytePos, BlockId>,
+  /// Spans of statements and of other code evaluated in a block of its own,
+  /// like the update of a `for` loop, sorted by position.
+  ranges: Vec<Range>,
+}
+
+#[derive(Debug, Clone)]
+struct Range {
+  span: Span,
+  block: BlockId,
+  /// Index of the innermost range containing this one
+  parent: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct BlockId(usize);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct FunctionId(usize);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum EdgeKind {
+  Normal,
+  /// Test of a branch or a loop evaluated to truthy value, or a `case` matched
+  True,
+  /// Test of a branch or a loop evaluated to falsy value, or no `case` matched
+  False,
+  /// Exception thrown to a `catch` or `finally` clause, or out of the function
+  Exception,
+  Break,
+  Continue,
+  Return,
+}
+
+impl EdgeKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      EdgeKind::Normal => "normal",
+      EdgeKind::True => "true",
+      EdgeKind::False => "false",
+      EdgeKind::Exception => "exception",
+      EdgeKind::Break => "break",
+      EdgeKind::Continue => "continue",
+      EdgeKind::Return => "return",
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Edge {
+  pub from: BlockId,
+  pub to: BlockId,
+  pub kind: EdgeKind,
+}
+
+#[derive(Debug, Clone)]
+pub struct BasicBlock {
+  function: FunctionId,
+  stmts: Vec<Span>,
+  succs: Vec<Edge>,
+  preds: Vec<Edge>,
+  reachable: bool,
+}
+
+impl BasicBlock {
+  pub fn function(&self) -> FunctionId {
+    self.function
+  }
+
+  /// Spans of statements in this block, in execution order.
+  pub fn stmts(&self) -> &[Span] {
+    &self.stmts
+  }
+
+  pub fn succs(&self) -> &[Edge] {
+    &self.succs
+  }
+
+  pub fn preds(&self) -> &[Edge] {
+    &self.preds
+  }
+
+  /// Returns false if no path from the entry of the function leads here.
+  pub fn is_reachable(&self) -> bool {
+    self.reachable
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct FunctionCfg {
+  span: Span,
+  entry: BlockId,
+  exit: BlockId,
+}
+
+impl FunctionCfg {
+  pub fn span(&self) -> Span {
+    self.span
+  }
+
+  pub fn entry(&self) -> BlockId {
+    self.entry
+  }
+
+  pub fn exit(&self) -> BlockId {
+    self.exit
+  }
+}
+
+impl Cfg {
+  pub fn build(program: &Program) -> Self {
+    let mut builder = Builder {
+      cfg: Cfg {
+        blocks: vec![],
+        functions: vec![],
+        stmt_blocks: Default::default(),
+        ranges: vec![],
+      },
+      function: FunctionId(0),
+      current: None,
+      exit: BlockId(0),
+      jumps: vec![],
+      handlers: vec![],
+      finalizers: vec![],
+      label: None,
+    };
+
+    builder.function(program.span(), |b| match program {
+      Program::Module(m) => {
+        for item in &m.body {
+          match item {
+            ModuleItem::Stmt(s) => b.stmt(s),
+            ModuleItem::ModuleDecl(d) => {
+              b.record(d.span());
+              d.visit_children_with(b);
+            }
+          }
+        }
+      }
+      Program::Script(s) => b.stmts(&s.body),
+    });
+
+    let mut cfg = builder.cfg;
+    cfg.mark_reachable();
+    cfg.link_ranges();
+    cfg
+  }
+
+  pub fn block(&self, id: BlockId) -> &BasicBlock {
+    &self.blocks[id.0]
+  }
+
+  pub fn blocks(&self) -> impl Iterator<Item = (BlockId, &BasicBlock)> {
+    self.blocks.iter().enumerate().map(|(i, b)| (BlockId(i), b))
+  }
+
+  /// All functions, the first one being the program itself.
+  pub fn functions(&self) -> &[FunctionCfg] {
+    &self.functions
+  }
+
+  pub fn function(&self, id: FunctionId) -> &FunctionCfg {
+    &self.functions[id.0]
+  }
+
+  /// Block containing the statement starting at `lo`.
+  pub fn block_of(&self, lo: BytePos) -> Option<BlockId> {
+    self.stmt_blocks.get(&lo).copied()
+  }
+
+  /// Block where the code at `pos` is evaluated, which can be any expression
+  /// or pattern, not just the start of a statement.
+  pub fn block_at(&self, pos: BytePos) -> Option<BlockId> {
+    // The last range starting before `pos` is the innermost one containing
+    // it or its descendant, because ranges nest.
+    let end = self
+      .ranges
+      .binary_search_by(|r| {
+        if r.span.lo <= pos {
+          Ordering::Less
+        } else {
+          Ordering::Greater
+        }
+      })
+      .unwrap_or_else(|i| i);
+    let mut i = end.checked_sub(1)?;
+    loop {
+      let range = &self.ranges[i];
+      if pos < range.span.hi || pos == range.span.lo {
+        return Some(range.block);
+      }
+      i = range.parent?;
+    }
+  }
+
+  /// Renders the graph in Graphviz DOT format. Each function is drawn as a
+  /// cluster and unreachable blocks are dashed.
+  pub fn to_dot(&self, source_map: &SourceMap) -> String {
+    let first_line = |span: Span| {
+      let snippet = source_map.span_to_snippet(span).unwrap_or_default();
+      let line = snippet
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+      line.replace('\\', "\\\\").replace('"', "\\\"")
+    };
+
+    let mut dot = String::new();
+    writeln!(dot, "digraph cfg {{").unwrap();
+    writeln!(dot, "  node [shape=box, fontname=monospace];").unwrap();
+
+    for (i, function) in self.functions.iter().enumerate() {
+      writeln!(dot, "  subgraph cluster_{} {{", i).unwrap();
+      writeln!(dot, "    label=\"{}\";", first_line(function.span)).unwrap();
+      for (id, block) in self.blocks().filter(|(_, b)| b.function.0 == i) {
+        let label = if id == function.entry {
+          "entry".to_string()
+        } else if id == function.exit {
+          "exit".to_string()
+        } else if block.stmts.is_empty() {
+          format!("B{}", id.0)
+        } else {
+          let mut label = String::new();
+          for span in &block.stmts {
+            label.push_str(&first_line(*span));
+            label.push_str("\\l");
+          }
+          label
+        };
+        let style = if block.reachable {
+          ""
+        } else {
+          ", style=dashed"
+        };
+        writeln!(dot, "    b{} [label=\"{}\"{}];", id.0, label, style).unwrap();
+      }
+      writeln!(dot, "  }}").unwrap();
+    }
+
+    for edge in self.blocks.iter().flat_map(|b| b.succs.iter()) {
+      match edge.kind {
+        EdgeKind::Normal => {
+          writeln!(dot, "  b{} -> b{};", edge.from.0, edge.to.0).unwrap()
+        }
+        EdgeKind::Exception => writeln!(
+          dot,
+          "  b{} -> b{} [label=\"{}\", style=dashed];",
+          edge.from.0,
+          edge.to.0,
+          edge.kind.as_str()
+        )
+        .unwrap(),
+        _ => writeln!(
+          dot,
+          "  b{} -> b{} [label=\"{}\"];",
+          edge.from.0,
+          edge.to.0,
+          edge.kind.as_str()
+        )
+        .unwrap(),
+      }
+    }
+
+    writeln!(dot, "}}").unwrap();
+    dot
+  }
+
+  fn link_ranges(&mut self) {
+    // Stable sort keeps outer ranges with the same span first.
+    self.ranges.sort_by_key(|r| (r.span.lo, Reverse(r.span.hi)));
+    let mut stack: Vec<usize> = vec![];
+    for i in 0..self.ranges.len() {
+      let span = self.ranges[i].span;
+      while let Some(&top) = stack.last() {
+        if self.ranges[top].span.hi > span.lo {
+          break;
+        }
+        stack.pop();
+      }
+      self.ranges[i].parent = stack.last().copied();
+      stack.push(i);
+    }
+  }
+
+  fn mark_reachable(&mut self) {
+    let mut stack: Vec<BlockId> =
+      self.functions.iter().map(|f| f.entry).collect();
+    while let Some(id) = stack.pop() {
+      let block = &mut self.blocks[id.0];
+      if block.reachable {
+        continue;
+      }
+      block.reachable = true;
+      stack.extend(block.succs.iter().map(|e| e.to));
+    }
+  }
+}
+
+/// Where `break` and `continue` statements jump to.
+struct JumpTarget {
+  label: Option<JsWord>,
+  /// `false` for labeled statements other than loops and switches, which
+  /// can only be exited with a labeled break.
+  unlabeled_break: bool,
+  continue_to: Option<BlockId>,
+  /// Blocks ending with a break statement to this target
+  breaks: Vec<BlockId>,
+}
+
+/// Abrupt completion which has to run `finally` before leaving a try
+/// statement. Break and continue hold the index of their jump target.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Completion {
+  Return,
+  Throw,
+  Break(usize),
+  Continue(usize),
+}
+
+/// `finally` of a try statement being visited.
+struct Finalizer {
+  block: BlockId,
+  /// Number of jump targets outside of the try statement
+  jumps: usize,
+  /// Completions to resume after `finally` finishes normally
+  pending: Vec<Completion>,
+}
+
+struct Builder {
+  cfg: Cfg,
+  function: FunctionId,
+  /// `None` if the code being visited is unreachable.
+  current: Option<BlockId>,
+  exit: BlockId,
+  jumps: Vec<JumpTarget>,
+  /// Blocks where exceptions thrown at this point are caught
+  handlers: Vec<BlockId>,
+  finalizers: Vec<Finalizer>,
+  /// Label of the loop or switch statement about to be visited
+  label: Option<JsWord>,
+}
+
+impl Builder {
+  fn new_block(&mut self) -> BlockId {
+    let id = BlockId(self.cfg.blocks.len());
+    self.cfg.blocks.push(BasicBlock {
+      function: self.function,
+      stmts: vec![],
+      succs: vec![],
+      preds: vec![],
+      reachable: false,
+    });
+    id
+  }
+
+  fn add_edge(&mut self, from: BlockId, to: BlockId, kind: EdgeKind) {
+    let edge = Edge { from, to, kind };
+    if self.cfg.blocks[from.0].succs.contains(&edge) {
+      return;
+    }
+    self.cfg.blocks[from.0].succs.push(edge);
+    self.cfg.blocks[to.0].preds.push(edge);
+  }
+
+  /// Current block, which is created if the code is unreachable.
+  fn current(&mut self) -> BlockId {
+    match self.current {
+      Some(id) => id,
+      None => {
+        let id = self.new_block();
+        self.current = Some(id);
+        id
+      }
+    }
+  }
+
+  /// Adds an edge from the current block to `to`, if reachable.
+  fn jump(&mut self, to: BlockId, kind: EdgeKind) {
+    if let Some(from) = self.current {
+      self.add_edge(from, to, kind);
+    }
+  }
+
+  /// Starts a new block with the given incoming edges.
+  fn join(&mut self, edges: Vec<(BlockId, EdgeKind)>) -> Option<BlockId> {
+    if edges.is_empty() {
+      return None;
+    }
+    let id = self.new_block();
+    for (from, kind) in edges {
+      self.add_edge(from, id, kind);
+    }
+    Some(id)
+  }
+
+  /// Leaves the current block abruptly, running enclosing `finally` blocks
+  /// first.
+  fn complete(&mut self, completion: Completion) {
+    let from = match self.current {
+      Some(from) => from,
+      None => return,
+    };
+    if completion == Completion::Throw {
+      return self.throw();
+    }
+
+    let finalizer =
+      self.finalizers.iter_mut().rev().find(|f| match completion {
+        Completion::Break(target) | Completion::Continue(target) => {
+          target < f.jumps
+        }
+        _ => true,
+      });
+    let kind = match completion {
+      Completion::Return => EdgeKind::Return,
+      Completion::Break(_) => EdgeKind::Break,
+      _ => EdgeKind::Continue,
+    };
+    if let Some(finalizer) = finalizer {
+      if !finalizer.pending.contains(&completion) {
+        finalizer.pending.push(completion);
+      }
+      let to = finalizer.block;
+      return self.add_edge(from, to, kind);
+    }
+
+    match completion {
+      Completion::Break(target) => self.jumps[target].breaks.push(from),
+      Completion::Continue(target) => {
+        if let Some(to) = self.jumps[target].continue_to {
+          self.add_edge(from, to, kind);
+        }
+      }
+      _ => self.add_edge(from, self.exit, kind),
+    }
+  }
+
+  /// Adds an exception edge from the current block to the innermost catch
+  /// clause or `finally`, or out of the function.
+  fn throw(&mut self) {
+    let from = match self.current {
+      Some(from) => from,
+      None => return,
+    };
+    let to = match self.handlers.last() {
+      Some(&handler) => {
+        if let Some(finalizer) =
+          self.finalizers.iter_mut().find(|f| f.block == handler)
+        {
+          if !finalizer.pending.contains(&Completion::Throw) {
+            finalizer.pending.push(Completion::Throw);
+          }
+        }
+        handler
+      }
+      None => self.exit,
+    };
+    self.add_edge(from, to, EdgeKind::Exception);
+  }
+
+  fn record(&mut self, span: Span) {
+    let id = self.current();
+    self.cfg.blocks[id.0].stmts.push(span);
+    self.cfg.stmt_blocks.insert(span.lo, id);
+    self.range(span);
+  }
+
+  /// Records that code in `span` is evaluated in the current block.
+  fn range(&mut self, span: Span) {
+    let block = self.current();
+    self.cfg.ranges.push(Range {
+      span,
+      block,
+      parent: None,
+    });
+  }
+
+  fn function<F>(&mut self, span: Span, op: F)
+  where
+    F: FnOnce(&mut Builder),
+  {
+    let id = FunctionId(self.cfg.functions.len());
+    let prev_function = replace(&mut self.function, id);
+    let entry = self.new_block();
+    let exit = self.new_block();
+    self.cfg.functions.push(FunctionCfg { span, entry, exit });
+    let body = self.new_block();
+    self.add_edge(entry, body, EdgeKind::Normal);
+
+    let prev_current = self.current.replace(body);
+    self.range(span);
+    let prev_exit = replace(&mut self.exit, exit);
+    let prev_jumps = take(&mut self.jumps);
+    let prev_handlers = take(&mut self.handlers);
+    let prev_finalizers = take(&mut self.finalizers);
+    let prev_label = self.label.take();
+
+    op(self);
+    self.jump(exit, EdgeKind::Normal);
+
+    self.function = prev_function;
+    self.current = prev_current;
+    self.exit = prev_exit;
+    self.jumps = prev_jumps;
+    self.handlers = prev_handlers;
+    self.finalizers = prev_finalizers;
+    self.label = prev_label;
+  }
+
+  /// Visits the body of a loop, returning blocks ending with a break.
+  fn loop_body(
+    &mut self,
+    body: &Stmt,
+    continue_to: BlockId,
+    label: Option<JsWord>,
+  ) -> Vec<BlockId> {
+    self.jumps.push(JumpTarget {
+      label,
+      unlabeled_break: true,
+      continue_to: Some(continue_to),
+      breaks: vec![],
+    });
+    self.stmt(body);
+    self.jumps.pop().unwrap().breaks
+  }
+
+  fn stmts(&mut self, stmts: &[Stmt]) {
+    for stmt in stmts {
+      self.stmt(stmt);
+    }
+  }
+
+  fn stmt(&mut self, s: &Stmt) {
+    match s {
+      Stmt::Block(n) => {
+        self.record(n.span);
+        self.stmts(&n.stmts);
+      }
+      Stmt::If(n) => {
+        self.record(n.span);
+        n.test.visit_with(n, self);
+        let cond = self.current();
+        let test = known_bool(&n.test);
+
+        let then_block = self.new_block();
+        if test != Some(false) {
+          self.add_edge(cond, then_block, EdgeKind::True);
+        }
+        self.current = Some(then_block);
+        self.stmt(&n.cons);
+
+        let mut ends: Vec<_> = self
+          .current
+          .map(|c| (c, EdgeKind::Normal))
+          .into_iter()
+          .collect();
+        if let Some(alt) = &n.alt {
+          let else_block = self.new_block();
+          if test != Some(true) {
+            self.add_edge(cond, else_block, EdgeKind::False);
+          }
+          self.current = Some(else_block);
+          self.stmt(alt);
+          ends.extend(self.current.map(|c| (c, EdgeKind::Normal)));
+        } else if test != Some(true) {
+          ends.push((cond, EdgeKind::False));
+        }
+        self.current = self.join(ends);
+      }
+      Stmt::While(n) => {
+        let label = self.label.take();
+        let head = self.new_block();
+        self.jump(head, EdgeKind::Normal);
+        self.current = Some(head);
+        self.record(n.span);
+        n.test.visit_with(n, self);
+        let cond = self.current();
+        let test = known_bool(&n.test);
+
+        let body = self.new_block();
+        if test != Some(false) {
+          self.add_edge(cond, body, EdgeKind::True);
+        }
+        self.current = Some(body);
+        let breaks = self.loop_body(&n.body, head, label);
+        self.jump(head, EdgeKind::Normal);
+
+        let mut exits = breaks_to_edges(breaks);
+        if test != Some(true) {
+          exits.push((cond, EdgeKind::False));
+        }
+        self.current = self.join(exits);
+      }
+      Stmt::DoWhile(n) => {
+        let label = self.label.take();
+        let body = self.new_block();
+        self.jump(body, EdgeKind::Normal);
+        self.current = Some(body);
+        self.record(n.span);
+
+        let test_block = self.new_block();
+        let breaks = self.loop_body(&n.body, test_block, label);
+        self.jump(test_block, EdgeKind::Normal);
+
+        self.current = Some(test_block);
+        self.range(n.test.span());
+        n.test.visit_with(n, self);
+        let cond = self.current();
+        let test = known_bool(&n.test);
+        if test != Some(false) {
+          self.add_edge(cond, body, EdgeKind::True);
+        }
+
+        let mut exits = breaks_to_edges(breaks);
+        if test != Some(true) {
+          exits.push((cond, EdgeKind::False));
+        }
+        self.current = self.join(exits);
+      }
+      Stmt::For(n) => {
+        let label = self.label.take();
+        self.record(n.span);
+        n.init.visit_with(n, self);
+
+        let head = self.new_block();
+        self.jump(head, EdgeKind::Normal);
+        self.current = Some(head);
+        if let Some(test) = &n.test {
+          self.range(test.span());
+          test.visit_with(n, self);
+        }
+        let cond = self.current();
+        let test = n.test.as_ref().map_or(Some(true), |t| known_bool(t));
+
+        let body = self.new_block();
+        if test != Some(false) {
+          let kind = if n.test.is_some() {
+            EdgeKind::True
+          } else {
+            EdgeKind::Normal
+          };
+          self.add_edge(cond, body, kind);
+        }
+        let update = self.new_block();
+        self.current = Some(body);
+        let breaks = self.loop_body(&n.body, update, label);
+        self.jump(update, EdgeKind::Normal);
+
+        self.current = Some(update);
+        if let Some(update) = &n.update {
+          self.range(update.span());
+          update.visit_with(n, self);
+        }
+        self.jump(head, EdgeKind::Normal);
+
+        let mut exits = breaks_to_edges(breaks);
+        if test != Some(true) {
+          exits.push((cond, EdgeKind::False));
+        }
+        self.current = self.join(exits);
+      }
+      Stmt::ForIn(ForInStmt {
+        span,
+        left,
+        right,
+        body,
+      })
+      | Stmt::ForOf(ForOfStmt {
+        span,
+        left,
+        right,
+        body,
+        ..
+      }) => {
+        let label = self.label.take();
+        self.record(*span);
+        right.visit_with(s, self);
+
+        let head = self.new_block();
+        self.jump(head, EdgeKind::Normal);
+
+        let body_block = self.new_block();
+        self.add_edge(head, body_block, EdgeKind::True);
+        self.current = Some(body_block);
+        self.range(left.span());
+        left.visit_with(s, self);
+        let breaks = self.loop_body(body, head, label);
+        self.jump(head, EdgeKind::Normal);
+
+        let mut exits = breaks_to_edges(breaks);
+        exits.push((head, EdgeKind::False));
+        self.current = self.join(exits);
+      }
+      Stmt::Switch(n) => {
+        let label = self.label.take();
+        self.record(n.span);
+        n.discriminant.visit_with(n, self);
+        let discriminant = self.current();
+        self.current = None;
+
+        self.jumps.push(JumpTarget {
+          label,
+          unlabeled_break: true,
+          continue_to: None,
+          breaks: vec![],
+        });
+        let mut has_default = false;
+        for case in &n.cases {
+          let case_block = self.new_block();
+          let kind = if case.test.is_some() {
+            EdgeKind::True
+          } else {
+            has_default = true;
+            EdgeKind::False
+          };
+          self.add_edge(discriminant, case_block, kind);
+          // Fall through from the previous case
+          self.jump(case_block, EdgeKind::Normal);
+          self.current = Some(case_block);
+          self.record(case.span);
+          case.test.visit_with(case, self);
+          self.stmts(&case.cons);
+        }
+        let breaks = self.jumps.pop().unwrap().breaks;
+
+        let mut exits: Vec<_> = self
+          .current
+          .map(|c| (c, EdgeKind::Normal))
+          .into_iter()
+          .collect();
+        exits.extend(breaks_to_edges(breaks));
+        if !has_default {
+          exits.push((discriminant, EdgeKind::False));
+        }
+        self.current = self.join(exits);
+      }
+      Stmt::Labeled(n) => {
+        self.record(n.span);
+        match &*n.body {
+          Stmt::While(_)
+          | Stmt::DoWhile(_)
+          | Stmt::For(_)
+          | Stmt::ForIn(_)
+          | Stmt::ForOf(_)
+          | Stmt::Switch(_) => {
+            self.label = Some(n.label.sym.clone());
+            self.stmt(&n.body);
+          }
+          _ => {
+            self.jumps.push(JumpTarget {
+              label: Some(n.label.sym.clone()),
+              unlabeled_break: false,
+              continue_to: None,
+              breaks: vec![],
+            });
+            self.stmt(&n.body);
+            let breaks = self.jumps.pop().unwrap().breaks;
+
+            let mut exits: Vec<_> = self
+              .current
+              .map(|c| (c, EdgeKind::Normal))
+              .into_iter()
+              .collect();
+            exits.extend(breaks_to_edges(breaks));
+            self.current = self.join(exits);
+          }
+        }
+      }
+      Stmt::Break(n) => {
+        self.record(n.span);
+        let target = self.jumps.iter().rposition(|t| match &n.label {
+          Some(label) => t.label.as_ref() == Some(&label.sym),
+          None => t.unlabeled_break,
+        });
+        if let Some(target) = target {
+          self.complete(Completion::Break(target));
+        }
+        self.current = None;
+      }
+      Stmt::Continue(n) => {
+        self.record(n.span);
+        let target = self.jumps.iter().rposition(|t| {
+          t.continue_to.is_some()
+            && match &n.label {
+              Some(label) => t.label.as_ref() == Some(&label.sym),
+              None => true,
+            }
+        });
+        if let Some(target) = target {
+          self.complete(Completion::Continue(target));
+        }
+        self.current = None;
+      }
+      Stmt::Return(n) => {
+        self.record(n.span);
+        n.arg.visit_with(n, self);
+        self.complete(Completion::Return);
+        self.current = None;
+      }
+      Stmt::Throw(n) => {
+        self.record(n.span);
+        n.arg.visit_with(n, self);
+        self.throw();
+        self.current = None;
+      }
+      Stmt::Try(n) => {
+        self.record(n.span);
+        let handler = n.handler.as_ref().map(|_| self.new_block());
+        let finalizer = n.finalizer.as_ref().map(|_| self.new_block());
+        if let Some(block) = finalizer {
+          self.finalizers.push(Finalizer {
+            block,
+            jumps: self.jumps.len(),
+            pending: vec![],
+          });
+        }
+
+        let try_block = self.new_block();
+        self.jump(try_block, EdgeKind::Normal);
+        self.current = Some(try_block);
+        let try_handler = handler.or(finalizer);
+        self.handlers.extend(try_handler);
+        self.stmts(&n.block.stmts);
+        if try_handler.is_some() {
+          self.handlers.pop();
+        }
+        let mut ends: Vec<_> = self
+          .current
+          .map(|c| (c, EdgeKind::Normal))
+          .into_iter()
+          .collect();
+
+        if let (Some(handler), Some(clause)) = (handler, &n.handler) {
+          self.handlers.extend(finalizer);
+          self.current = Some(handler);
+          self.range(clause.span);
+          clause.param.visit_with(clause, self);
+          self.stmts(&clause.body.stmts);
+          if finalizer.is_some() {
+            self.handlers.pop();
+          }
+          ends.extend(self.current.map(|c| (c, EdgeKind::Normal)));
+        }
+
+        match (finalizer, &n.finalizer) {
+          (Some(block), Some(body)) => {
+            let Finalizer { pending, .. } = self.finalizers.pop().unwrap();
+            let completes_normally = !ends.is_empty();
+            for (from, kind) in ends {
+              self.add_edge(from, block, kind);
+            }
+            self.current = Some(block);
+            self.stmts(&body.stmts);
+
+            // Resume what was interrupted by `finally`. If `finally` itself
+            // returns or throws, the original completion is discarded.
+            if let Some(end) = self.current {
+              for completion in pending {
+                self.current = Some(end);
+                self.complete(completion);
+              }
+              self.current = if completes_normally { Some(end) } else { None };
+            }
+          }
+          _ => self.current = self.join(ends),
+        }
+      }
+      _ => {
+        self.record(s.span());
+        s.visit_children_with(self);
+      }
+    }
+  }
+}
+
+/// Nested functions get graphs of their own and statements reached by
+/// visiting, like the bodies of namespaces, are added to the current flow.
+impl Visit for Builder {
+  noop_visit_type!();
+
+  fn visit_stmt(&mut self, n: &Stmt, _: &dyn Node) {
+    self.stmt(n);
+  }
+
+  fn visit_expr(&mut self, n: &Expr, _: &dyn Node) {
+    n.visit_children_with(self);
+
+    // Any expression in a try statement may jump to the handler. Outside of
+    // it, only throw statements get exception edges to keep graphs small.
+    if !self.handlers.is_empty() && may_throw(n) {
+      self.throw();
+    }
+  }
+
+  fn visit_pat(&mut self, n: &Pat, _: &dyn Node) {
+    n.visit_children_with(self);
+
+    if !self.handlers.is_empty()
+      && matches!(n, Pat::Object(..) | Pat::Array(..))
+    {
+      self.throw();
+    }
+  }
+
+  fn visit_function(&mut self, n: &Function, _: &dyn Node) {
+    n.decorators.visit_with(n, self);
+    self.function(n.span, |b| {
+      n.params.visit_with(n, b);
+      if let Some(body) = &n.body {
+        b.stmts(&body.stmts);
+      }
+    });
+  }
+
+  fn visit_arrow_expr(&mut self, n: &ArrowExpr, _: &dyn Node) {
+    self.function(n.span, |b| {
+      n.params.visit_with(n, b);
+      match &n.body {
+        BlockStmtOrExpr::BlockStmt(body) => b.stmts(&body.stmts),
+        BlockStmtOrExpr::Expr(expr) => expr.visit_with(n, b),
+      }
+    });
+  }
+
+  fn visit_constructor(&mut self, n: &Constructor, _: &dyn Node) {
+    self.function(n.span, |b| {
+      n.params.visit_with(n, b);
+      if let Some(body) = &n.body {
+        b.stmts(&body.stmts);
+      }
+    });
+  }
+
+  fn visit_getter_prop(&mut self, n: &GetterProp, _: &dyn Node) {
+    n.key.visit_with(n, self);
+    self.function(n.span, |b| {
+      if let Some(body) = &n.body {
+        b.stmts(&body.stmts);
+      }
+    });
+  }
+
+  fn visit_setter_prop(&mut self, n: &SetterProp, _: &dyn Node) {
+    n.key.visit_with(n, self);
+    self.function(n.span, |b| {
+      n.param.visit_with(n, b);
+      if let Some(body) = &n.body {
+        b.stmts(&body.stmts);
+      }
+    });
+  }
+}
+
+fn known_bool(expr: &Expr) -> Option<bool> {
+  match expr.as_bool() {
+    (_, Value::Known(b)) => Some(b),
+    _ => None,
+  }
+}
+
+fn breaks_to_edges(breaks: Vec<BlockId>) -> Vec<(BlockId, EdgeKind)> {
+  breaks.into_iter().map(|b| (b, EdgeKind::Break)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util;
+  use swc_common::FileName;
+
+  fn build(src: &str) -> Cfg {
+    let program = test_util::parse(src);
+    Cfg::build(&program)
+  }
+
+  /// Position of the statement starting with `needle`.
+  fn pos(src: &str, needle: &str) -> BytePos {
+    BytePos(src.find(needle).unwrap() as u32)
+  }
+
+  fn block_of(cfg: &Cfg, src: &str, needle: &str) -> BlockId {
+    cfg.block_of(pos(src, needle)).unwrap()
+  }
+
+  fn succs(cfg: &Cfg, id: BlockId) -> Vec<(BlockId, EdgeKind)> {
+    cfg
+      .block(id)
+      .succs()
+      .iter()
+      .map(|e| (e.to, e.kind))
+      .collect()
+  }
+
+  #[test]
+  fn if_else() {
+    let src = r#"
+function foo() {
+  if (a) {
+    b();
+  } else {
+    c();
+  }
+  d();
+}
+"#;
+    let cfg = build(src);
+    assert_eq!(cfg.functions().len(), 2);
+
+    let cond = block_of(&cfg, src, "if (a)");
+    let then_block = block_of(&cfg, src, "b();");
+    let else_block = block_of(&cfg, src, "c();");
+    let after = block_of(&cfg, src, "d();");
+    assert_eq!(
+      succs(&cfg, cond),
+      vec![(then_block, EdgeKind::True), (else_block, EdgeKind::False)]
+    );
+    assert_eq!(succs(&cfg, then_block), vec![(after, EdgeKind::Normal)]);
+    assert_eq!(succs(&cfg, else_block), vec![(after, EdgeKind::Normal)]);
+
+    let foo = &cfg.functions()[1];
+    assert_eq!(succs(&cfg, after), vec![(foo.exit(), EdgeKind::Normal)]);
+    assert_eq!(cfg.block(cond).function(), FunctionId(1));
+  }
+
+  #[test]
+  fn loops() {
+    let src = r#"
+function foo() {
+  outer: for (let i = 0; i < 10; i++) {
+    while (a) {
+      if (b) continue outer;
+      if (c) break;
+      d();
+    }
+  }
+  e();
+}
+"#;
+    let cfg = build(src);
+    let head = block_of(&cfg, src, "while (a)");
+    let continue_block = block_of(&cfg, src, "continue outer");
+    let break_block = block_of(&cfg, src, "break;");
+
+    assert!(succs(&cfg, continue_block)
+      .iter()
+      .any(|(_, k)| *k == EdgeKind::Continue));
+    let (break_target, kind) = succs(&cfg, break_block)[0];
+    assert_eq!(kind, EdgeKind::Break);
+    assert!(cfg
+      .block(head)
+      .succs()
+      .iter()
+      .any(|e| e.kind == EdgeKind::False && e.to == break_target));
+
+    let d = block_of(&cfg, src, "d();");
+    assert_eq!(succs(&cfg, d), vec![(head, EdgeKind::Normal)]);
+  }
+
+  #[test]
+  fn return_and_unreachable() {
+    let src = r#"
+function foo() {
+  return 1;
+  bar();
+}
+"#;
+    let cfg = build(src);
+    let foo = &cfg.functions()[1];
+    let ret = block_of(&cfg, src, "return 1");
+    assert_eq!(succs(&cfg, ret), vec![(foo.exit(), EdgeKind::Return)]);
+
+    let bar = block_of(&cfg, src, "bar();");
+    assert_ne!(ret, bar);
+    assert!(cfg.block(ret).is_reachable());
+    assert!(!cfg.block(bar).is_reachable());
+  }
+
+  #[test]
+  fn infinite_loop() {
+    let src = r#"
+while (true) {
+  foo();
+}
+bar();
+"#;
+    let cfg = build(src);
+    assert!(!cfg.block(block_of(&cfg, src, "bar();")).is_reachable());
+  }
+
+  #[test]
+  fn try_catch() {
+    let src = r#"
+try {
+  throw new Error();
+} catch (e) {
+  handle(e);
+} finally {
+  cleanup();
+}
+after();
+"#;
+    let cfg = build(src);
+    let throw_block = block_of(&cfg, src, "throw");
+    let catch_block = block_of(&cfg, src, "handle(e)");
+    let finally_block = block_of(&cfg, src, "cleanup()");
+    assert_eq!(
+      succs(&cfg, throw_block),
+      vec![(catch_block, EdgeKind::Exception)]
+    );
+    // `handle(e)` may throw as well
+    assert_eq!(
+      succs(&cfg, catch_block),
+      vec![
+        (finally_block, EdgeKind::Exception),
+        (finally_block, EdgeKind::Normal)
+      ]
+    );
+    assert_eq!(block_of(&cfg, src, "after()"), finally_block);
+  }
+
+  #[test]
+  fn return_through_finally() {
+    let src = r#"
+function foo() {
+  try {
+    return bar();
+  } finally {
+    cleanup();
+  }
+  baz();
+}
+"#;
+    let cfg = build(src);
+    let exit = cfg.functions()[1].exit();
+    let ret = block_of(&cfg, src, "return bar()");
+    let finally_block = block_of(&cfg, src, "cleanup()");
+    assert_eq!(
+      succs(&cfg, ret),
+      vec![
+        (finally_block, EdgeKind::Exception),
+        (finally_block, EdgeKind::Return)
+      ]
+    );
+    assert_eq!(
+      succs(&cfg, finally_block),
+      vec![(exit, EdgeKind::Exception), (exit, EdgeKind::Return)]
+    );
+    assert!(!cfg.block(block_of(&cfg, src, "baz()")).is_reachable());
+  }
+
+  #[test]
+  fn finally_overrides_completion() {
+    let src = r#"
+function foo() {
+  try {
+    bar();
+  } catch (e) {
+    return 1;
+  } finally {
+    return 2;
+  }
+}
+"#;
+    let cfg = build(src);
+    let exit = cfg.functions()[1].exit();
+    let try_block = block_of(&cfg, src, "bar()");
+    let catch_block = block_of(&cfg, src, "return 1");
+    let finally_block = block_of(&cfg, src, "return 2");
+    assert_eq!(
+      succs(&cfg, try_block),
+      vec![
+        (catch_block, EdgeKind::Exception),
+        (finally_block, EdgeKind::Normal)
+      ]
+    );
+    assert_eq!(
+      succs(&cfg, catch_block),
+      vec![(finally_block, EdgeKind::Return)]
+    );
+    assert_eq!(succs(&cfg, finally_block), vec![(exit, EdgeKind::Return)]);
+  }
+
+  #[test]
+  fn break_through_finally() {
+    let src = r#"
+while (a) {
+  try {
+    break;
+  } finally {
+    cleanup();
+  }
+}
+after();
+"#;
+    let cfg = build(src);
+    let brk = block_of(&cfg, src, "break");
+    let finally_block = block_of(&cfg, src, "cleanup()");
+    let after = block_of(&cfg, src, "after()");
+    assert_eq!(succs(&cfg, brk), vec![(finally_block, EdgeKind::Break)]);
+    assert_eq!(succs(&cfg, finally_block), vec![(after, EdgeKind::Break)]);
+    assert!(cfg.block(after).is_reachable());
+  }
+
+  #[test]
+  fn switch() {
+    let src = r#"
+switch (a) {
+  case 1:
+    foo();
+  case 2:
+    bar();
+    break;
+  default:
+    baz();
+}
+"#;
+    let cfg = build(src);
+    let discriminant = block_of(&cfg, src, "switch");
+    let case1 = block_of(&cfg, src, "foo()");
+    let case2 = block_of(&cfg, src, "bar()");
+    let default = block_of(&cfg, src, "baz()");
+    assert_eq!(
+      succs(&cfg, discriminant),
+      vec![
+        (case1, EdgeKind::True),
+        (case2, EdgeKind::True),
+        (default, EdgeKind::False)
+      ]
+    );
+    assert_eq!(succs(&cfg, case1), vec![(case2, EdgeKind::Normal)]);
+    assert_eq!(succs(&cfg, case2)[0].1, EdgeKind::Break);
+  }
+
+  #[test]
+  fn nested_functions() {
+    let src = r#"
+const f = () => {
+  return 1;
+};
+class A {
+  constructor() {}
+  method() {}
+  get prop() { return 1; }
+}
+"#;
+    let cfg = build(src);
+    assert_eq!(cfg.functions().len(), 5);
+    let ret = block_of(&cfg, src, "return 1;\n};");
+    assert_eq!(cfg.block(ret).function(), FunctionId(1));
+  }
+
+  #[test]
+  fn block_at() {
+    let src = r#"
+for (let i = 0; i < n; i++) {
+  foo(i);
+}
+const f = () => bar;
+"#;
+    let cfg = build(src);
+    let init = block_of(&cfg, src, "for");
+    let body = block_of(&cfg, src, "foo(i)");
+    assert_eq!(cfg.block_at(pos(src, "0;")), Some(init));
+    assert_eq!(cfg.block_at(pos(src, "i)")), Some(body));
+
+    let test = cfg.block_at(pos(src, "i < n")).unwrap();
+    let update = cfg.block_at(pos(src, "i++")).unwrap();
+    assert_ne!(test, init);
+    assert_ne!(update, body);
+    assert_eq!(succs(&cfg, update), vec![(test, EdgeKind::Normal)]);
+
+    let bar = cfg.block_at(pos(src, "bar")).unwrap();
+    assert_eq!(cfg.block(bar).function(), FunctionId(1));
+  }
+
+  #[test]
+  fn dot() {
+    let src = r#"
+if (a) {
+  b();
+}
+"#;
+    let source_map = SourceMap::default();
+    source_map.new_source_file(FileName::Anon, src.to_string());
+    let dot = build(src).to_dot(&source_map);
+    assert!(dot.starts_with("digraph cfg {"));
+    assert!(dot.contains("label=\"entry\""));
+    assert!(dot.contains("[label=\"true\"]"));
+    assert!(dot.contains("[label=\"false\"]"));
+    assert!(dot.contains("b();"));
+  }
+}
@@ -0,0 +1,470 @@
+use crate::control_flow::{BlockId, Cfg, EdgeKind, FunctionId};
+use crate::scopes::Scope;
+use std::collections::{HashMap, VecDeque};
+use swc_common::{BytePos, Span};
+use swc_ecmascript::ast::*;
+use swc_ecmascript::utils::{ident::IdentLike, Id};
+use swc_ecmascript::visit::{noop_visit_type, Node, Visit, VisitWith};
+
+/// Forward data-flow analysis over the control flow graph.
+///
+/// The program is reduced to accesses to bindings known to the scope
+/// analysis, grouped by the basic block they happen in. An [Analysis]
+/// describes how each access changes its state, and [DataFlow::solve] finds
+/// the state at the start of every reachable block.
+pub struct DataFlow<'a> {
+  cfg: &'a Cfg,
+  accesses: HashMap<BlockId, Vec<Access>>,
+}
+
+/// Read or write of a binding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Access {
+  pub id: Id,
+  pub span: Span,
+  pub kind: AccessKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessKind {
+  /// Declaration without an initializer, like `let a;`
+  Declare,
+  /// Declaration with a value, like `let a = 1;`, a class declaration or the
+  /// variable of a `for-of` loop
+  Init,
+  Write,
+  Read,
+}
+
+pub trait Analysis {
+  type State: Clone + PartialEq;
+
+  /// State at the entry of `function`.
+  fn initial(&self, function: FunctionId) -> Self::State;
+
+  /// Merges the state flowing from another predecessor into `state`.
+  fn join(&self, state: &mut Self::State, other: &Self::State);
+
+  /// Applies an access to the state.
+  fn transfer(&self, state: &mut Self::State, access: &Access);
+}
+
+/// States at the start of reachable blocks.
+pub struct Solution<S> {
+  entries: HashMap<BlockId, S>,
+}
+
+impl<S> Solution<S> {
+  pub fn entry(&self, block: BlockId) -> Option<&S> {
+    self.entries.get(&block)
+  }
+}
+
+impl<'a> DataFlow<'a> {
+  pub fn new(program: &Program, cfg: &'a Cfg, scope: &Scope) -> Self {
+    let mut collector = Collector {
+      cfg,
+      scope,
+      accesses: Default::default(),
+    };
+    program.visit_with(program, &mut collector);
+    DataFlow {
+      cfg,
+      accesses: collector.accesses,
+    }
+  }
+
+  /// Accesses in the block, in evaluation order.
+  pub fn accesses(&self, block: BlockId) -> &[Access] {
+    self.accesses.get(&block).map_or(&[], |a| a)
+  }
+
+  /// Function which the code at `pos` belongs to.
+  pub fn function_at(&self, pos: BytePos) -> Option<FunctionId> {
+    self.cfg.block_at(pos).map(|b| self.cfg.block(b).function())
+  }
+
+  pub fn solve<A: Analysis>(&self, analysis: &A) -> Solution<A::State> {
+    let mut entries = HashMap::new();
+    let mut worklist = VecDeque::new();
+    for function in self.cfg.functions() {
+      let id = self.cfg.block(function.entry()).function();
+      entries.insert(function.entry(), analysis.initial(id));
+      worklist.push_back(function.entry());
+    }
+
+    while let Some(block) = worklist.pop_front() {
+      let entry: A::State = entries[&block].clone();
+      let mut exit = entry.clone();
+      for access in self.accesses(block) {
+        analysis.transfer(&mut exit, access);
+      }
+
+      for edge in self.cfg.block(block).succs() {
+        // An exception may be thrown anywhere in the block.
+        let state = if edge.kind == EdgeKind::Exception {
+          &entry
+        } else {
+          &exit
+        };
+        let changed = match entries.get_mut(&edge.to) {
+          Some(to) => {
+            let prev = to.clone();
+            analysis.join(to, state);
+            *to != prev
+          }
+          None => {
+            entries.insert(edge.to, state.clone());
+            true
+          }
+        };
+        if changed && !worklist.contains(&edge.to) {
+          worklist.push_back(edge.to);
+        }
+      }
+    }
+
+    Solution { entries }
+  }
+
+  /// Calls `op` for every access in reachable blocks with the state right
+  /// before the access.
+  pub fn walk<A, F>(
+    &self,
+    analysis: &A,
+    solution: &Solution<A::State>,
+    mut op: F,
+  ) where
+    A: Analysis,
+    F: FnMut(&A::State, &Access),
+  {
+    for (block, _) in self.cfg.blocks() {
+      if let Some(entry) = solution.entry(block) {
+        let mut state = entry.clone();
+        for access in self.accesses(block) {
+          op(&state, access);
+          analysis.transfer(&mut state, access);
+        }
+      }
+    }
+  }
+}
+
+struct Collector<'a> {
+  cfg: &'a Cfg,
+  scope: &'a Scope,
+  accesses: HashMap<BlockId, Vec<Access>>,
+}
+
+impl Collector<'_> {
+  fn access(&mut self, ident: &Ident, kind: AccessKind) {
+    let id = ident.to_id();
+    if self.scope.var(&id).is_none() {
+      return;
+    }
+    if let Some(block) = self.cfg.block_at(ident.span.lo) {
+      self.accesses.entry(block).or_default().push(Access {
+        id,
+        span: ident.span,
+        kind,
+      });
+    }
+  }
+
+  fn assign_to_ident(&mut self, ident: &Ident, n: &AssignExpr) {
+    // Compound assignments like `a += 1` read the old value first.
+    if n.op != AssignOp::Assign {
+      self.access(ident, AccessKind::Read);
+    }
+    n.right.visit_with(n, self);
+    self.access(ident, AccessKind::Write);
+  }
+
+  fn write_loop_var(&mut self, left: &VarDeclOrPat) {
+    match left {
+      VarDeclOrPat::VarDecl(decl) => {
+        for declarator in &decl.decls {
+          self.write_pat(&declarator.name, AccessKind::Init);
+        }
+      }
+      VarDeclOrPat::Pat(pat) => self.write_pat(pat, AccessKind::Write),
+    }
+  }
+
+  /// Records writes to bindings in `pat`, after default values and computed
+  /// keys in it are evaluated.
+  fn write_pat(&mut self, pat: &Pat, kind: AccessKind) {
+    match pat {
+      Pat::Ident(i) => self.access(i, kind),
+      Pat::Array(a) => {
+        for elem in a.elems.iter().flatten() {
+          self.write_pat(elem, kind);
+        }
+      }
+      Pat::Object(o) => {
+        for prop in &o.props {
+          match prop {
+            ObjectPatProp::KeyValue(kv) => {
+              kv.key.visit_with(kv, self);
+              self.write_pat(&kv.value, kind);
+            }
+            ObjectPatProp::Assign(a) => {
+              a.value.visit_with(a, self);
+              self.access(&a.key, kind);
+            }
+            ObjectPatProp::Rest(r) => self.write_pat(&r.arg, kind),
+          }
+        }
+      }
+      Pat::Rest(r) => self.write_pat(&r.arg, kind),
+      Pat::Assign(a) => {
+        a.right.visit_with(a, self);
+        self.write_pat(&a.left, kind);
+      }
+      Pat::Expr(e) => e.visit_with(pat, self),
+      Pat::Invalid(_) => {}
+    }
+  }
+}
+
+impl Visit for Collector<'_> {
+  noop_visit_type!();
+
+  fn visit_expr(&mut self, n: &Expr, _: &dyn Node) {
+    match n {
+      Expr::Ident(i) => self.access(i, AccessKind::Read),
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  fn visit_member_expr(&mut self, n: &MemberExpr, _: &dyn Node) {
+    n.obj.visit_with(n, self);
+    if n.computed {
+      n.prop.visit_with(n, self);
+    }
+  }
+
+  fn visit_prop(&mut self, n: &Prop, _: &dyn Node) {
+    match n {
+      Prop::Shorthand(i) => self.access(i, AccessKind::Read),
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  fn visit_assign_expr(&mut self, n: &AssignExpr, _: &dyn Node) {
+    let target = match &n.left {
+      PatOrExpr::Expr(e) => &**e,
+      PatOrExpr::Pat(p) => match &**p {
+        Pat::Expr(e) => &**e,
+        Pat::Ident(i) => return self.assign_to_ident(i, n),
+        pat => {
+          n.right.visit_with(n, self);
+          return self.write_pat(pat, AccessKind::Write);
+        }
+      },
+    };
+
+    match target {
+      Expr::Ident(i) => self.assign_to_ident(i, n),
+      _ => {
+        target.visit_with(n, self);
+        n.right.visit_with(n, self);
+      }
+    }
+  }
+
+  fn visit_update_expr(&mut self, n: &UpdateExpr, _: &dyn Node) {
+    match &*n.arg {
+      Expr::Ident(i) => {
+        self.access(i, AccessKind::Read);
+        self.access(i, AccessKind::Write);
+      }
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  fn visit_var_declarator(&mut self, n: &VarDeclarator, _: &dyn Node) {
+    n.init.visit_with(n, self);
+    let kind = if n.init.is_some() {
+      AccessKind::Init
+    } else {
+      AccessKind::Declare
+    };
+    self.write_pat(&n.name, kind);
+  }
+
+  fn visit_class_decl(&mut self, n: &ClassDecl, _: &dyn Node) {
+    // The class can refer to itself in its body.
+    self.access(&n.ident, AccessKind::Init);
+    n.class.visit_with(n, self);
+  }
+
+  fn visit_param(&mut self, n: &Param, _: &dyn Node) {
+    n.decorators.visit_with(n, self);
+    self.write_pat(&n.pat, AccessKind::Init);
+  }
+
+  fn visit_arrow_expr(&mut self, n: &ArrowExpr, _: &dyn Node) {
+    for param in &n.params {
+      self.write_pat(param, AccessKind::Init);
+    }
+    n.body.visit_with(n, self);
+  }
+
+  fn visit_catch_clause(&mut self, n: &CatchClause, _: &dyn Node) {
+    if let Some(param) = &n.param {
+      self.write_pat(param, AccessKind::Init);
+    }
+    n.body.visit_with(n, self);
+  }
+
+  fn visit_for_in_stmt(&mut self, n: &ForInStmt, _: &dyn Node) {
+    n.right.visit_with(n, self);
+    self.write_loop_var(&n.left);
+    n.body.visit_with(n, self);
+  }
+
+  fn visit_for_of_stmt(&mut self, n: &ForOfStmt, _: &dyn Node) {
+    n.right.visit_with(n, self);
+    self.write_loop_var(&n.left);
+    n.body.visit_with(n, self);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util;
+  use std::collections::HashSet;
+
+  /// Names of variables which are assigned on every path.
+  struct Assigned;
+
+  impl Analysis for Assigned {
+    type State = HashSet<String>;
+
+    fn initial(&self, _: FunctionId) -> Self::State {
+      HashSet::new()
+    }
+
+    fn join(&self, state: &mut Self::State, other: &Self::State) {
+      state.retain(|name| other.contains(name));
+    }
+
+    fn transfer(&self, state: &mut Self::State, access: &Access) {
+      match access.kind {
+        AccessKind::Init | AccessKind::Write => {
+          state.insert(access.id.0.to_string());
+        }
+        AccessKind::Declare | AccessKind::Read => {}
+      }
+    }
+  }
+
+  /// Returns names read by `Read` accesses, with whether they were assigned
+  /// at that point.
+  fn reads(src: &str) -> Vec<(String, bool)> {
+    let program = test_util::parse(src);
+    let cfg = Cfg::build(&program);
+    let scope = Scope::analyze(&program);
+    let data_flow = DataFlow::new(&program, &cfg, &scope);
+    let solution = data_flow.solve(&Assigned);
+
+    let mut reads = vec![];
+    data_flow.walk(&Assigned, &solution, |state, access| {
+      if access.kind == AccessKind::Read {
+        let name = access.id.0.to_string();
+        let assigned = state.contains(&name);
+        reads.push((access.span.lo, name, assigned));
+      }
+    });
+    reads.sort();
+    reads.into_iter().map(|(_, n, a)| (n, a)).collect()
+  }
+
+  fn r(name: &str, assigned: bool) -> (String, bool) {
+    (name.to_string(), assigned)
+  }
+
+  #[test]
+  fn straight_line() {
+    assert_eq!(
+      reads("let a; a; a = 1; a; let b = a; b;"),
+      vec![r("a", false), r("a", true), r("a", true), r("b", true)]
+    );
+  }
+
+  #[test]
+  fn evaluation_order() {
+    assert_eq!(
+      reads("let a; a = a; let b; b += 1; let c = c;"),
+      vec![r("a", false), r("b", false), r("c", false)]
+    );
+  }
+
+  #[test]
+  fn branches() {
+    let src = r#"
+let a, b;
+if (cond) {
+  a = 1;
+  b = 1;
+} else {
+  a = 2;
+}
+a;
+b;
+"#;
+    assert_eq!(reads(src), vec![r("a", true), r("b", false)]);
+  }
+
+  #[test]
+  fn loops() {
+    let src = r#"
+let a, b;
+for (const x of xs) {
+  a = x;
+}
+while (true) {
+  b = 1;
+  break;
+}
+a;
+b;
+"#;
+    assert_eq!(reads(src), vec![r("x", true), r("a", false), r("b", true)]);
+  }
+
+  #[test]
+  fn exceptions() {
+    let src = r#"
+let a, b;
+try {
+  a = f();
+  b = 1;
+} catch {
+  a = 0;
+}
+a;
+b;
+"#;
+    assert_eq!(reads(src), vec![r("a", true), r("b", false)]);
+  }
+
+  #[test]
+  fn nested_functions() {
+    let src = r#"
+let a;
+function f(b = a) {
+  a;
+  b;
+}
+const g = () => a;
+"#;
+    // Each function starts from its own initial state.
+    assert_eq!(
+      reads(src),
+      vec![r("a", false), r("a", false), r("b", true), r("a", false)]
+    );
+  }
+}
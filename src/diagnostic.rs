@@ -1,6 +1,10 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use serde::Deserialize;
 use serde::Serialize;
 use std::convert::TryInto;
+use std::fmt;
+use swc_common::SourceMap;
+use swc_common::Span;
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -30,11 +34,69 @@ pub struct Range {
   pub end: Position,
 }
 
+impl Range {
+  pub fn new(source_map: &SourceMap, span: Span) -> Self {
+    let start = Position::new(
+      source_map.lookup_byte_offset(span.lo()).pos,
+      source_map.lookup_char_pos(span.lo()),
+    );
+    let end = Position::new(
+      source_map.lookup_byte_offset(span.hi()).pos,
+      source_map.lookup_char_pos(span.hi()),
+    );
+    Range { start, end }
+  }
+}
+
+/// A single text replacement that is part of a `LintFix`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintFixChange {
+  pub range: Range,
+  pub new_text: String,
+}
+
+/// A suggested fix for a diagnostic. All changes of a fix must be applied
+/// together and must not overlap each other.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LintFix {
+  pub description: String,
+  pub changes: Vec<LintFixChange>,
+}
+
+/// How serious a diagnostic is. Every rule has a default severity which can
+/// be overridden per rule code with `LinterBuilder::severities`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+  Error,
+  Warning,
+  Info,
+  /// The rule is disabled and its diagnostics are never reported.
+  Off,
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let s = match self {
+      Severity::Error => "error",
+      Severity::Warning => "warning",
+      Severity::Info => "info",
+      Severity::Off => "off",
+    };
+    write!(f, "{}", s)
+  }
+}
+
 #[derive(Clone, Debug, Serialize)]
 pub struct LintDiagnostic {
   pub range: Range,
   pub filename: String,
   pub message: String,
   pub code: String,
+  pub severity: Severity,
   pub hint: Option<String>,
+  /// Alternative fixes for this diagnostic, the first one being the preferred
+  /// one. Empty if the diagnostic is not auto-fixable.
+  pub fixes: Vec<LintFix>,
 }
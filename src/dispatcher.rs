@@ -0,0 +1,255 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+//! Single traversal rule engine.
+//!
+//! Most rules only look at a few kinds of nodes, yet a rule implementing
+//! `LintRule::lint_program` has to walk the whole program to find them.
+//! Rules implementing `NodeRule` instead subscribe to the node kinds they are
+//! interested in and the linter visits the program once, handing every node
+//! to the rules subscribed to its kind.
+//!
+//! Only rules which check nodes in isolation are node rules. Rules that track
+//! state across nodes or steer the traversal still walk the program on their
+//! own, and parsing and the scope and control flow analyses are unaffected,
+//! so how much time is saved depends on how many of the enabled rules are
+//! node rules.
+use crate::linter::Context;
+use swc_ecmascript::ast;
+use swc_ecmascript::ast::Program;
+use swc_ecmascript::visit::Node;
+use swc_ecmascript::visit::{Visit, VisitWith};
+
+/// A rule that checks individual nodes.
+///
+/// Node rules don't get to control the traversal, so a rule that needs to
+/// track state across nodes (e.g. the enclosing function) should implement
+/// `LintRule::lint_program` instead.
+pub trait NodeRule {
+  /// Kinds of nodes passed to `check_node`.
+  fn node_kinds(&self) -> &'static [NodeKind];
+
+  /// Called for every node of one of the kinds returned by `node_kinds`,
+  /// in the order nodes are visited. `program` is the whole program being
+  /// linted, e.g. for `Context::const_evaluator`.
+  fn check_node(&self, context: &mut Context, program: &Program, node: NodeRef);
+
+  /// Whether `check_node` is also called for nodes inside of TypeScript
+  /// types, namespaces and enums, which are skipped by default just like
+  /// visitors using `noop_visit_type!()` skip them.
+  fn checks_types(&self) -> bool {
+    false
+  }
+}
+
+macro_rules! node_kinds {
+  ($($kind:ident => $visit:ident,)*) => {
+    /// Kinds of nodes a `NodeRule` can subscribe to.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum NodeKind {
+      $($kind,)*
+    }
+
+    impl NodeKind {
+      const COUNT: usize = [$(NodeKind::$kind,)*].len();
+    }
+
+    /// A node handed to `NodeRule::check_node`.
+    #[derive(Clone, Copy, Debug)]
+    pub enum NodeRef<'a> {
+      $($kind(&'a ast::$kind),)*
+    }
+
+    impl NodeRef<'_> {
+      pub fn kind(&self) -> NodeKind {
+        match self {
+          $(NodeRef::$kind(_) => NodeKind::$kind,)*
+        }
+      }
+    }
+
+    impl<'r, 'c> Visit for Dispatcher<'r, 'c> {
+      $(
+        fn $visit(&mut self, node: &ast::$kind, _parent: &dyn Node) {
+          self.dispatch(NodeRef::$kind(node));
+          self.visit_children(node, NodeKind::$kind.is_type());
+        }
+      )*
+
+      type_nodes! {
+        Accessibility => visit_accessibility,
+        TruePlusMinus => visit_true_plus_minus,
+        TsArrayType => visit_ts_array_type,
+        TsCallSignatureDecl => visit_ts_call_signature_decl,
+        TsConditionalType => visit_ts_conditional_type,
+        TsConstructSignatureDecl => visit_ts_construct_signature_decl,
+        TsConstructorType => visit_ts_constructor_type,
+        TsEntityName => visit_ts_entity_name,
+        TsEnumDecl => visit_ts_enum_decl,
+        TsEnumMember => visit_ts_enum_member,
+        TsEnumMemberId => visit_ts_enum_member_id,
+        TsExternalModuleRef => visit_ts_external_module_ref,
+        TsFnOrConstructorType => visit_ts_fn_or_constructor_type,
+        TsFnParam => visit_ts_fn_param,
+        TsFnType => visit_ts_fn_type,
+        TsImportEqualsDecl => visit_ts_import_equals_decl,
+        TsImportType => visit_ts_import_type,
+        TsIndexSignature => visit_ts_index_signature,
+        TsIndexedAccessType => visit_ts_indexed_access_type,
+        TsInferType => visit_ts_infer_type,
+        TsInterfaceBody => visit_ts_interface_body,
+        TsIntersectionType => visit_ts_intersection_type,
+        TsKeywordType => visit_ts_keyword_type,
+        TsKeywordTypeKind => visit_ts_keyword_type_kind,
+        TsMappedType => visit_ts_mapped_type,
+        TsMethodSignature => visit_ts_method_signature,
+        TsModuleBlock => visit_ts_module_block,
+        TsModuleName => visit_ts_module_name,
+        TsModuleRef => visit_ts_module_ref,
+        TsNamespaceBody => visit_ts_namespace_body,
+        TsNamespaceDecl => visit_ts_namespace_decl,
+        TsNamespaceExportDecl => visit_ts_namespace_export_decl,
+        TsOptionalType => visit_ts_optional_type,
+        TsParamProp => visit_ts_param_prop,
+        TsParamPropParam => visit_ts_param_prop_param,
+        TsParenthesizedType => visit_ts_parenthesized_type,
+        TsPropertySignature => visit_ts_property_signature,
+        TsQualifiedName => visit_ts_qualified_name,
+        TsRestType => visit_ts_rest_type,
+        TsSignatureDecl => visit_ts_signature_decl,
+        TsThisType => visit_ts_this_type,
+        TsThisTypeOrIdent => visit_ts_this_type_or_ident,
+        TsTupleType => visit_ts_tuple_type,
+        TsType => visit_ts_type,
+        TsTypeAliasDecl => visit_ts_type_alias_decl,
+        TsTypeAnn => visit_ts_type_ann,
+        TsTypeAssertion => visit_ts_type_assertion,
+        TsTypeCastExpr => visit_ts_type_cast_expr,
+        TsTypeElement => visit_ts_type_element,
+        TsTypeLit => visit_ts_type_lit,
+        TsTypeOperator => visit_ts_type_operator,
+        TsTypeOperatorOp => visit_ts_type_operator_op,
+        TsTypeParam => visit_ts_type_param,
+        TsTypeParamDecl => visit_ts_type_param_decl,
+        TsTypeParamInstantiation => visit_ts_type_param_instantiation,
+        TsTypePredicate => visit_ts_type_predicate,
+        TsTypeQuery => visit_ts_type_query,
+        TsTypeQueryExpr => visit_ts_type_query_expr,
+        TsTypeRef => visit_ts_type_ref,
+        TsUnionOrIntersectionType => visit_ts_union_or_intersection_type,
+        TsUnionType => visit_ts_union_type,
+      }
+    }
+  };
+}
+
+/// Visits the children of nodes that are skipped by `noop_visit_type!()`,
+/// except for the ones which are also node kinds.
+macro_rules! type_nodes {
+  ($($ty:ident => $visit:ident,)*) => {
+    $(
+      fn $visit(&mut self, node: &ast::$ty, _parent: &dyn Node) {
+        self.visit_children(node, true);
+      }
+    )*
+  };
+}
+
+node_kinds! {
+  ArrayLit => visit_array_lit,
+  ArrowExpr => visit_arrow_expr,
+  AssignExpr => visit_assign_expr,
+  BinExpr => visit_bin_expr,
+  CallExpr => visit_call_expr,
+  Class => visit_class,
+  CondExpr => visit_cond_expr,
+  DebuggerStmt => visit_debugger_stmt,
+  Function => visit_function,
+  IfStmt => visit_if_stmt,
+  MemberExpr => visit_member_expr,
+  NewExpr => visit_new_expr,
+  Number => visit_number,
+  ObjectLit => visit_object_lit,
+  Regex => visit_regex,
+  SetterProp => visit_setter_prop,
+  Str => visit_str,
+  SwitchCase => visit_switch_case,
+  SwitchStmt => visit_switch_stmt,
+  ThrowStmt => visit_throw_stmt,
+  TsInterfaceDecl => visit_ts_interface_decl,
+  TsModuleDecl => visit_ts_module_decl,
+  TsNonNullExpr => visit_ts_non_null_expr,
+  UnaryExpr => visit_unary_expr,
+  VarDecl => visit_var_decl,
+  VarDeclarator => visit_var_declarator,
+  WithStmt => visit_with_stmt,
+}
+
+impl NodeKind {
+  /// Whether the node is skipped by `noop_visit_type!()`.
+  fn is_type(self) -> bool {
+    matches!(self, NodeKind::TsInterfaceDecl | NodeKind::TsModuleDecl)
+  }
+}
+
+struct Dispatcher<'r, 'c> {
+  context: &'c mut Context,
+  program: &'c Program,
+  rules_by_kind: Vec<Vec<&'r dyn NodeRule>>,
+  /// Rules of `rules_by_kind` which implement `NodeRule::checks_types`.
+  type_rules_by_kind: Vec<Vec<&'r dyn NodeRule>>,
+  /// Number of enclosing type nodes.
+  type_depth: usize,
+}
+
+impl<'r, 'c> Dispatcher<'r, 'c> {
+  fn dispatch(&mut self, node: NodeRef) {
+    let rules_by_kind = if self.type_depth == 0 {
+      &self.rules_by_kind
+    } else {
+      &self.type_rules_by_kind
+    };
+    for rule in &rules_by_kind[node.kind() as usize] {
+      rule.check_node(self.context, self.program, node);
+    }
+  }
+
+  fn visit_children<N: VisitWith<Self>>(&mut self, node: &N, is_type: bool) {
+    if is_type {
+      self.type_depth += 1;
+    }
+    node.visit_children_with(self);
+    if is_type {
+      self.type_depth -= 1;
+    }
+  }
+}
+
+/// Runs all given node rules in a single traversal of `program`.
+pub fn run_node_rules(
+  context: &mut Context,
+  program: &Program,
+  rules: &[&dyn NodeRule],
+) {
+  if rules.is_empty() {
+    return;
+  }
+
+  let mut rules_by_kind = vec![vec![]; NodeKind::COUNT];
+  let mut type_rules_by_kind = vec![vec![]; NodeKind::COUNT];
+  for rule in rules {
+    for kind in rule.node_kinds() {
+      rules_by_kind[*kind as usize].push(*rule);
+      if rule.checks_types() {
+        type_rules_by_kind[*kind as usize].push(*rule);
+      }
+    }
+  }
+
+  let mut dispatcher = Dispatcher {
+    context,
+    program,
+    rules_by_kind,
+    type_rules_by_kind,
+    type_depth: 0,
+  };
+  program.visit_with(program, &mut dispatcher);
+}
@@ -2,15 +2,43 @@
 use crate::diagnostic::{LintDiagnostic, Position};
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use swc_common::comments::Comment;
 use swc_common::comments::CommentKind;
 use swc_common::BytePos;
 use swc_common::SourceMap;
 use swc_common::Span;
+use swc_common::Spanned;
+use swc_ecmascript::ast::{
+  ClassMember, JSXElement, JSXFragment, ModuleDecl, Program, PropOrSpread,
+  Stmt, TsTypeElement,
+};
+use swc_ecmascript::visit::Node;
+use swc_ecmascript::visit::{VisitAll, VisitAllWith};
 
 static IGNORE_COMMENT_CODE_RE: Lazy<Regex> =
   Lazy::new(|| Regex::new(r",\s*|\s").unwrap());
+/// Separates the codes of a directive from the reason for it, e.g.
+/// `// deno-lint-ignore no-explicit-any -- upstream types are wrong`.
+static IGNORE_COMMENT_REASON_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"(^|\s)--+(\s|$)").unwrap());
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IgnoreDirectiveKind {
+  /// `deno-lint-ignore`, applies to the next line.
+  Line,
+  /// `eslint-disable-line`, applies to the line of the directive.
+  SameLine,
+  /// `deno-lint-ignore` in span aware mode, applies to the statement (or
+  /// declaration, class member, ...) starting on the next line until its
+  /// `end`.
+  Node { end: Position },
+  /// `deno-lint-ignore-file`, applies to the whole file.
+  File,
+  /// `deno-lint-disable`, applies until the position of the matching
+  /// `deno-lint-enable`, or until the end of the file if it's not closed.
+  Range { end: Option<Position> },
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct IgnoreDirective {
@@ -18,7 +46,13 @@ pub struct IgnoreDirective {
   pub span: Span,
   pub codes: Vec<String>,
   pub used_codes: HashMap<String, bool>,
-  pub is_global: bool,
+  pub kind: IgnoreDirectiveKind,
+  /// Justification given after `--`, if any.
+  pub reason: Option<String>,
+  /// Whether this is an ESLint directive, see `eslint_rule_codes` of
+  /// `parse_ignore_directives`. ESLint directives without codes apply to
+  /// all rules.
+  pub is_eslint: bool,
 }
 
 impl IgnoreDirective {
@@ -28,12 +62,27 @@ impl IgnoreDirective {
     &mut self,
     diagnostic: &LintDiagnostic,
   ) -> bool {
-    // `is_global` means that diagnostic is ignored in whole file.
-    if self.is_global {
-      // pass
-    } else if self.position.line != diagnostic.range.start.line - 1 {
+    let start = &diagnostic.range.start;
+    let applies = match &self.kind {
+      IgnoreDirectiveKind::Line => self.position.line == start.line - 1,
+      IgnoreDirectiveKind::SameLine => self.position.line == start.line,
+      IgnoreDirectiveKind::File => true,
+      IgnoreDirectiveKind::Node { end } => {
+        (self.position.line, self.position.col) <= (start.line, start.col)
+          && (start.line, start.col) < (end.line, end.col)
+      }
+      IgnoreDirectiveKind::Range { end } => {
+        (self.position.line, self.position.col) <= (start.line, start.col)
+          && end
+            .map_or(true, |end| (start.line, start.col) < (end.line, end.col))
+      }
+    };
+    if !applies {
       return false;
     }
+    if self.is_eslint && self.codes.is_empty() {
+      return true;
+    }
 
     let mut should_ignore = false;
     for code in self.codes.iter() {
@@ -47,55 +96,352 @@ impl IgnoreDirective {
   }
 }
 
+/// Parses all ignore directives in the given comments.
+///
+/// If `eslint_rule_codes` is given, ESLint's suppression comments are
+/// understood as well and the rule names in them are mapped to these codes,
+/// see `map_eslint_rule_name`.
+///
+/// Returns the directives, sorted by position, and the spans of
+/// `enable_directive` comments that don't close any open range.
 pub fn parse_ignore_directives(
   ignore_diagnostic_directive: &str,
+  disable_directive: &str,
+  enable_directive: &str,
+  eslint_rule_codes: Option<&HashSet<String>>,
   source_map: &SourceMap,
   leading_comments: &HashMap<BytePos, Vec<Comment>>,
   trailing_comments: &HashMap<BytePos, Vec<Comment>>,
-) -> Vec<IgnoreDirective> {
+) -> (Vec<IgnoreDirective>, Vec<Span>) {
+  let mut comments: Vec<&Comment> = leading_comments
+    .values()
+    .chain(trailing_comments.values())
+    .flatten()
+    .collect();
+  comments.sort_by_key(|comment| comment.span.lo());
+
   let mut ignore_directives = vec![];
+  // Indexes of the ranges that are still open, innermost last.
+  let mut open_ranges: Vec<usize> = vec![];
+  let mut open_eslint_ranges: Vec<usize> = vec![];
+  let mut unmatched_enables = vec![];
 
-  leading_comments.values().for_each(|comments| {
-    for comment in comments {
-      if let Some(ignore) = parse_ignore_comment(
-        &ignore_diagnostic_directive,
-        source_map,
-        comment,
-        false,
-      ) {
-        ignore_directives.push(ignore);
+  for comment in comments {
+    if let Some(ignore) = parse_ignore_comment(
+      ignore_diagnostic_directive,
+      source_map,
+      comment,
+      IgnoreDirectiveKind::Line,
+    ) {
+      ignore_directives.push(ignore);
+    } else if let Some(disable) = parse_ignore_comment(
+      disable_directive,
+      source_map,
+      comment,
+      IgnoreDirectiveKind::Range { end: None },
+    ) {
+      open_ranges.push(ignore_directives.len());
+      ignore_directives.push(disable);
+    } else if let Some(enable) = parse_ignore_comment(
+      enable_directive,
+      source_map,
+      comment,
+      IgnoreDirectiveKind::Range { end: None },
+    ) {
+      // Ranges must be closed in reverse order of opening. An enable without
+      // codes closes the innermost range, otherwise the codes must match.
+      let innermost = open_ranges.last().copied().filter(|i| {
+        enable.codes.is_empty() || same_codes(&ignore_directives[*i], &enable)
+      });
+      match innermost {
+        Some(i) => {
+          open_ranges.pop();
+          ignore_directives[i].kind = IgnoreDirectiveKind::Range {
+            end: Some(enable.position),
+          };
+        }
+        None => unmatched_enables.push(enable.span),
       }
-    }
-  });
-
-  trailing_comments.values().for_each(|comments| {
-    for comment in comments {
-      if let Some(ignore) = parse_ignore_comment(
-        &ignore_diagnostic_directive,
-        source_map,
-        comment,
-        false,
-      ) {
-        ignore_directives.push(ignore);
+    } else if let Some((directive, eslint_directive)) = eslint_rule_codes
+      .and_then(|codes| parse_eslint_comment(source_map, comment, codes))
+    {
+      match eslint_directive {
+        EslintDirective::DisableNextLine | EslintDirective::DisableLine => {
+          ignore_directives.push(directive)
+        }
+        EslintDirective::Disable => {
+          open_eslint_ranges.push(ignore_directives.len());
+          ignore_directives.push(directive);
+        }
+        EslintDirective::Enable => {
+          // Like ESLint, an enable without codes closes all ranges. Partially
+          // enabling the rules of a range isn't supported, the innermost
+          // range with any of the codes is closed instead.
+          let closed: Vec<usize> = if directive.codes.is_empty() {
+            std::mem::take(&mut open_eslint_ranges)
+          } else {
+            let found = open_eslint_ranges.iter().rposition(|i| {
+              ignore_directives[*i]
+                .codes
+                .iter()
+                .any(|code| directive.codes.contains(code))
+            });
+            found
+              .map(|pos| open_eslint_ranges.remove(pos))
+              .into_iter()
+              .collect()
+          };
+          for i in closed {
+            ignore_directives[i].kind = IgnoreDirectiveKind::Range {
+              end: Some(directive.position),
+            };
+          }
+        }
       }
     }
-  });
+  }
+
+  (ignore_directives, unmatched_enables)
+}
+
+/// Turns line directives that are followed by a statement, declaration,
+/// class member, object property or JSX element on the next line into
+/// directives covering that whole node.
+pub fn extend_to_next_node(
+  ignore_directives: &mut [IgnoreDirective],
+  program: &Program,
+  source_map: &SourceMap,
+) {
+  let mut collector = NodeSpanCollector { spans: vec![] };
+  program.visit_all_with(program, &mut collector);
+  // Outermost node first if several start at the same position.
+  let mut spans = collector.spans;
+  spans.sort_by(|a, b| a.lo().cmp(&b.lo()).then(b.hi().cmp(&a.hi())));
+
+  for directive in ignore_directives.iter_mut() {
+    if directive.kind != IgnoreDirectiveKind::Line {
+      continue;
+    }
+    let next = spans.iter().find(|span| span.lo() >= directive.span.hi());
+    let span = match next {
+      Some(span) => span,
+      None => continue,
+    };
+    if source_map.lookup_char_pos(span.lo()).line != directive.position.line + 1
+    {
+      continue;
+    }
+    let end = Position::new(span.hi(), source_map.lookup_char_pos(span.hi()));
+    directive.kind = IgnoreDirectiveKind::Node { end };
+  }
+}
+
+struct NodeSpanCollector {
+  spans: Vec<Span>,
+}
+
+impl VisitAll for NodeSpanCollector {
+  fn visit_stmt(&mut self, stmt: &Stmt, _parent: &dyn Node) {
+    self.spans.push(stmt.span());
+  }
+
+  fn visit_module_decl(&mut self, decl: &ModuleDecl, _parent: &dyn Node) {
+    self.spans.push(decl.span());
+  }
+
+  fn visit_class_member(&mut self, member: &ClassMember, _parent: &dyn Node) {
+    self.spans.push(member.span());
+  }
+
+  fn visit_ts_type_element(
+    &mut self,
+    element: &TsTypeElement,
+    _parent: &dyn Node,
+  ) {
+    self.spans.push(element.span());
+  }
+
+  fn visit_prop_or_spread(&mut self, prop: &PropOrSpread, _parent: &dyn Node) {
+    self.spans.push(prop.span());
+  }
+
+  fn visit_jsx_element(&mut self, element: &JSXElement, _parent: &dyn Node) {
+    self.spans.push(element.span);
+  }
+
+  fn visit_jsx_fragment(&mut self, fragment: &JSXFragment, _parent: &dyn Node) {
+    self.spans.push(fragment.span);
+  }
+}
+
+/// Result of removing a code from the source text of a directive comment.
+#[derive(Debug, PartialEq)]
+pub enum CodeRemoval {
+  /// The comment text without the code and a separator next to it.
+  Text(String),
+  /// No other codes would remain, so the whole directive can be removed.
+  Directive,
+  /// The code isn't spelled out in the comment text.
+  NotFound,
+}
+
+/// Removes `code` and a separator next to it from the source text of a
+/// directive comment. For ESLint directives, `code` may be spelled as any
+/// rule name mapping to it, e.g. `@typescript-eslint/no-explicit-any`.
+pub fn remove_code_from_comment(
+  comment_text: &str,
+  directive: &IgnoreDirective,
+  code: &str,
+) -> CodeRemoval {
+  if directive.codes.iter().all(|c| c == code) {
+    return CodeRemoval::Directive;
+  }
+
+  let is_separator = |c: char| c == ',' || c.is_whitespace();
+  // Skip the comment opener and the name of the directive.
+  let name_start = comment_text[2..]
+    .find(|c: char| !c.is_whitespace())
+    .map_or(comment_text.len(), |i| i + 2);
+  let codes_start = comment_text[name_start..]
+    .find(char::is_whitespace)
+    .map_or(comment_text.len(), |i| i + name_start);
+  let codes_end = IGNORE_COMMENT_REASON_RE
+    .find(&comment_text[codes_start..])
+    .map_or(comment_text.len(), |m| m.start() + codes_start);
+
+  let code_only: HashSet<String> = std::iter::once(code.to_string()).collect();
+  let spells_code = |name: &str| {
+    name == code
+      || (directive.is_eslint && map_eslint_rule_name(name, &code_only) == code)
+  };
+  let codes = &comment_text[codes_start..codes_end];
+  let name = match codes
+    .split(is_separator)
+    .find(|name| !name.is_empty() && spells_code(name))
+  {
+    Some(name) => name,
+    None => return CodeRemoval::NotFound,
+  };
+  let start = codes_start + (name.as_ptr() as usize - codes.as_ptr() as usize);
+  let end = start + name.len();
+
+  let after = &comment_text[end..codes_end];
+  let next_code = after.trim_start_matches(is_separator);
+  if next_code.is_empty() || next_code.starts_with("*/") {
+    // The last code, remove the separator in front of it.
+    let before = comment_text[..start].trim_end_matches(is_separator);
+    CodeRemoval::Text(format!("{}{}", before, &comment_text[end..]))
+  } else {
+    let separator_len = after.len() - next_code.len();
+    CodeRemoval::Text(format!(
+      "{}{}",
+      &comment_text[..start],
+      &comment_text[end + separator_len..]
+    ))
+  }
+}
+
+fn same_codes(a: &IgnoreDirective, b: &IgnoreDirective) -> bool {
+  a.codes.len() == b.codes.len()
+    && a.codes.iter().all(|code| b.codes.contains(code))
+}
+
+#[derive(Clone, Copy)]
+enum EslintDirective {
+  DisableNextLine,
+  DisableLine,
+  Disable,
+  Enable,
+}
+
+/// Rules of typescript-eslint and ESLint plugins are prefixed with the name
+/// of the plugin.
+const ESLINT_RULE_PREFIXES: &[&str] = &["@typescript-eslint/"];
+
+/// ESLint rules that are known by a different name in deno_lint.
+const ESLINT_RULE_ALIASES: &[(&str, &str)] = &[
+  ("ban-ts-ignore", "ban-ts-comment"),
+  ("naming-convention", "camelcase"),
+];
+
+/// Maps the name of an ESLint or typescript-eslint rule to the code of the
+/// equivalent rule in `rule_codes`. Names that can't be mapped are returned
+/// unchanged, so they are reported as unknown rules.
+pub fn map_eslint_rule_name(
+  name: &str,
+  rule_codes: &HashSet<String>,
+) -> String {
+  let unprefixed = ESLINT_RULE_PREFIXES
+    .iter()
+    .find_map(|prefix| name.strip_prefix(prefix))
+    .unwrap_or(name);
+  let code = ESLINT_RULE_ALIASES
+    .iter()
+    .find(|(eslint_name, _)| *eslint_name == unprefixed)
+    .map_or(unprefixed, |(_, code)| code);
+
+  if rule_codes.contains(code) {
+    code.to_string()
+  } else {
+    name.to_string()
+  }
+}
+
+fn parse_eslint_comment(
+  source_map: &SourceMap,
+  comment: &Comment,
+  rule_codes: &HashSet<String>,
+) -> Option<(IgnoreDirective, EslintDirective)> {
+  let (mut directive, eslint_directive) = [
+    ("eslint-disable-next-line", EslintDirective::DisableNextLine),
+    ("eslint-disable-line", EslintDirective::DisableLine),
+    ("eslint-disable", EslintDirective::Disable),
+    ("eslint-enable", EslintDirective::Enable),
+  ]
+  .iter()
+  .find_map(|(name, eslint_directive)| {
+    let kind = match eslint_directive {
+      EslintDirective::DisableNextLine => IgnoreDirectiveKind::Line,
+      EslintDirective::DisableLine => IgnoreDirectiveKind::SameLine,
+      _ => IgnoreDirectiveKind::Range { end: None },
+    };
+    let directive = parse_directive(name, source_map, comment, kind)?;
+    Some((directive, *eslint_directive))
+  })?;
 
-  ignore_directives
-    .sort_by(|a, b| a.position.line.partial_cmp(&b.position.line).unwrap());
-  ignore_directives
+  directive.codes = directive
+    .codes
+    .iter()
+    .map(|name| map_eslint_rule_name(name, rule_codes))
+    .collect();
+  directive.used_codes = directive
+    .codes
+    .iter()
+    .map(|code| (code.to_string(), false))
+    .collect();
+  directive.is_eslint = true;
+  Some((directive, eslint_directive))
 }
 
 pub fn parse_ignore_comment(
   ignore_diagnostic_directive: &str,
   source_map: &SourceMap,
   comment: &Comment,
-  is_global: bool,
+  kind: IgnoreDirectiveKind,
 ) -> Option<IgnoreDirective> {
   if comment.kind != CommentKind::Line {
     return None;
   }
 
+  parse_directive(ignore_diagnostic_directive, source_map, comment, kind)
+}
+
+fn parse_directive(
+  ignore_diagnostic_directive: &str,
+  source_map: &SourceMap,
+  comment: &Comment,
+  kind: IgnoreDirectiveKind,
+) -> Option<IgnoreDirective> {
   let comment_text = comment.text.trim();
 
   if let Some(prefix) = comment_text.split_whitespace().next() {
@@ -103,6 +449,15 @@ pub fn parse_ignore_comment(
       let comment_text = comment_text
         .strip_prefix(ignore_diagnostic_directive)
         .unwrap();
+      let (comment_text, reason) =
+        match IGNORE_COMMENT_REASON_RE.find(comment_text) {
+          Some(m) => {
+            let reason = comment_text[m.end()..].trim();
+            let reason = Some(reason.to_string()).filter(|r| !r.is_empty());
+            (&comment_text[..m.start()], reason)
+          }
+          None => (comment_text, None),
+        };
       let comment_text = IGNORE_COMMENT_CODE_RE.replace_all(comment_text, ",");
       let codes = comment_text
         .split(',')
@@ -122,7 +477,9 @@ pub fn parse_ignore_comment(
         span: comment.span,
         codes,
         used_codes,
-        is_global,
+        kind,
+        reason,
+        is_eslint: false,
       });
     }
   }
@@ -137,6 +494,38 @@ mod tests {
   use crate::ast_parser::AstParser;
   use std::rc::Rc;
 
+  fn parse_directives(source_code: &str) -> (Vec<IgnoreDirective>, Vec<Span>) {
+    let eslint_rule_codes = ["no-explicit-any", "no-empty", "ban-ts-comment"]
+      .iter()
+      .map(|code| code.to_string())
+      .collect();
+    let ast_parser = AstParser::new();
+    let parsed = ast_parser.parse_program(
+      "test.ts",
+      ast_parser::get_default_ts_config(),
+      source_code.to_string(),
+    );
+    let (_program, comments) = parsed.program.expect("Failed to parse");
+    let (leading, trailing) = comments.take_all();
+    let leading_coms = Rc::try_unwrap(leading)
+      .expect("Failed to get leading comments")
+      .into_inner();
+    let trailing_coms = Rc::try_unwrap(trailing)
+      .expect("Failed to get trailing comments")
+      .into_inner();
+    let leading = leading_coms.into_iter().collect();
+    let trailing = trailing_coms.into_iter().collect();
+    parse_ignore_directives(
+      "deno-lint-ignore",
+      "deno-lint-disable",
+      "deno-lint-enable",
+      Some(&eslint_rule_codes),
+      &parsed.source_map,
+      &leading,
+      &trailing,
+    )
+  }
+
   #[test]
   fn test_parse_ignore_comments() {
     let source_code = r#"
@@ -158,29 +547,7 @@ target: Record<string, any>,
 ): // deno-lint-ignore ban-types
 object | undefined {}
   "#;
-    let ast_parser = AstParser::new();
-    let (_program, comments) = ast_parser
-      .parse_program(
-        "test.ts",
-        ast_parser::get_default_ts_config(),
-        &source_code,
-      )
-      .expect("Failed to parse");
-    let (leading, trailing) = comments.take_all();
-    let leading_coms = Rc::try_unwrap(leading)
-      .expect("Failed to get leading comments")
-      .into_inner();
-    let trailing_coms = Rc::try_unwrap(trailing)
-      .expect("Failed to get trailing comments")
-      .into_inner();
-    let leading = leading_coms.into_iter().collect();
-    let trailing = trailing_coms.into_iter().collect();
-    let directives = parse_ignore_directives(
-      "deno-lint-ignore",
-      &ast_parser.source_map,
-      &leading,
-      &trailing,
-    );
+    let (directives, _) = parse_directives(source_code);
 
     assert_eq!(directives.len(), 4);
     let d = &directives[0];
@@ -224,4 +591,197 @@ object | undefined {}
     );
     assert_eq!(d.codes, vec!["ban-types"]);
   }
+
+  #[test]
+  fn test_parse_range_directives() {
+    let source_code = r#"
+// deno-lint-disable no-explicit-any
+function foo(): any {}
+// deno-lint-disable no-empty no-debugger
+function bar() {}
+// deno-lint-enable no-debugger no-empty
+// deno-lint-ignore no-empty
+function baz() {}
+// deno-lint-enable
+// deno-lint-enable no-explicit-any
+// deno-lint-disable no-empty
+// deno-lint-disable no-debugger
+// deno-lint-enable no-empty
+"#;
+    let (directives, unmatched_enables) = parse_directives(source_code);
+
+    assert_eq!(directives.len(), 5);
+    let end_line = |d: &IgnoreDirective| match &d.kind {
+      IgnoreDirectiveKind::Range { end } => end.map(|end| end.line),
+      kind => panic!("unexpected kind {:?}", kind),
+    };
+    assert_eq!(directives[0].codes, vec!["no-explicit-any"]);
+    assert_eq!(end_line(&directives[0]), Some(9));
+    assert_eq!(directives[1].codes, vec!["no-empty", "no-debugger"]);
+    assert_eq!(end_line(&directives[1]), Some(6));
+    assert_eq!(directives[2].kind, IgnoreDirectiveKind::Line);
+    // Not closed, the enable doesn't match the innermost range.
+    assert_eq!(end_line(&directives[3]), None);
+    assert_eq!(end_line(&directives[4]), None);
+
+    // `deno-lint-enable no-explicit-any` after its range was closed and
+    // `deno-lint-enable no-empty` while `no-debugger` is innermost.
+    assert_eq!(unmatched_enables.len(), 2);
+  }
+
+  #[test]
+  fn test_parse_ignore_reasons() {
+    let source_code = r#"
+// deno-lint-ignore no-explicit-any -- upstream types are wrong
+function foo(): any {}
+// deno-lint-ignore no-explicit-any, no-empty --- see #123
+function bar(): any {}
+// deno-lint-disable no-empty --
+function baz() {}
+// deno-lint-enable -- not a directive that needs a reason
+// deno-lint-ignore -- no codes
+function qux() {}
+// deno-lint-ignore no-explicit-any
+function quux(): any {}
+"#;
+    let (directives, unmatched_enables) = parse_directives(source_code);
+
+    assert!(unmatched_enables.is_empty());
+    assert_eq!(directives.len(), 5);
+    assert_eq!(directives[0].codes, vec!["no-explicit-any"]);
+    assert_eq!(
+      directives[0].reason.as_deref(),
+      Some("upstream types are wrong")
+    );
+    assert_eq!(directives[1].codes, vec!["no-explicit-any", "no-empty"]);
+    assert_eq!(directives[1].reason.as_deref(), Some("see #123"));
+    assert_eq!(directives[2].codes, vec!["no-empty"]);
+    assert_eq!(directives[2].reason, None);
+    assert!(directives[3].codes.is_empty());
+    assert_eq!(directives[3].reason.as_deref(), Some("no codes"));
+    assert_eq!(directives[4].reason, None);
+  }
+
+  #[test]
+  fn test_map_eslint_rule_name() {
+    let rule_codes: HashSet<String> = ["no-explicit-any", "ban-ts-comment"]
+      .iter()
+      .map(|code| code.to_string())
+      .collect();
+    let map = |name| map_eslint_rule_name(name, &rule_codes);
+
+    assert_eq!(map("no-explicit-any"), "no-explicit-any");
+    assert_eq!(map("@typescript-eslint/no-explicit-any"), "no-explicit-any");
+    assert_eq!(map("@typescript-eslint/ban-ts-ignore"), "ban-ts-comment");
+    assert_eq!(
+      map("@typescript-eslint/no-floating"),
+      "@typescript-eslint/no-floating"
+    );
+    assert_eq!(map("react/jsx-key"), "react/jsx-key");
+  }
+
+  #[test]
+  fn test_parse_eslint_directives() {
+    let source_code = r#"
+/* eslint-disable @typescript-eslint/no-explicit-any, no-empty */
+function foo(): any {}
+/* eslint-enable no-empty */
+// eslint-disable-next-line @typescript-eslint/no-explicit-any -- legacy
+function bar(): any {}
+function baz(): any {} // eslint-disable-line no-explicit-any, react/jsx-key
+/* eslint-disable */
+// deno-lint-ignore no-empty
+function qux() {}
+"#;
+    let (directives, unmatched_enables) = parse_directives(source_code);
+
+    assert!(unmatched_enables.is_empty());
+    assert_eq!(directives.len(), 5);
+    assert!(directives[0].is_eslint);
+    assert_eq!(directives[0].codes, vec!["no-explicit-any", "no-empty"]);
+    assert_eq!(
+      directives[0].kind,
+      IgnoreDirectiveKind::Range {
+        end: Some(Position {
+          line: 4,
+          col: 0,
+          byte_pos: 90
+        })
+      }
+    );
+    assert_eq!(directives[1].codes, vec!["no-explicit-any"]);
+    assert_eq!(directives[1].kind, IgnoreDirectiveKind::Line);
+    assert_eq!(directives[1].reason.as_deref(), Some("legacy"));
+    assert_eq!(
+      directives[2].codes,
+      vec!["no-explicit-any", "react/jsx-key"]
+    );
+    assert_eq!(directives[2].kind, IgnoreDirectiveKind::SameLine);
+    assert!(directives[3].is_eslint);
+    assert!(directives[3].codes.is_empty());
+    assert_eq!(directives[3].kind, IgnoreDirectiveKind::Range { end: None });
+    assert!(!directives[4].is_eslint);
+  }
+
+  #[test]
+  fn test_remove_code_from_comment() {
+    let remove = |comment_text: &str, code: &str| {
+      let source_code = format!("{}\nfunction foo() {{}}", comment_text);
+      let (directives, _) = parse_directives(&source_code);
+      remove_code_from_comment(comment_text, &directives[0], code)
+    };
+
+    assert_eq!(
+      remove("// deno-lint-ignore no-empty no-explicit-any", "no-empty"),
+      CodeRemoval::Text("// deno-lint-ignore no-explicit-any".to_string())
+    );
+    assert_eq!(
+      remove(
+        "// deno-lint-ignore no-empty, no-explicit-any",
+        "no-explicit-any"
+      ),
+      CodeRemoval::Text("// deno-lint-ignore no-empty".to_string())
+    );
+    assert_eq!(
+      remove("// deno-lint-ignore a,b,c -- reason with b", "b"),
+      CodeRemoval::Text("// deno-lint-ignore a,c -- reason with b".to_string())
+    );
+    assert_eq!(
+      remove("// deno-lint-ignore a b -- reason", "b"),
+      CodeRemoval::Text("// deno-lint-ignore a -- reason".to_string())
+    );
+    assert_eq!(
+      remove(
+        "/* eslint-disable no-empty, @typescript-eslint/no-foo */",
+        "@typescript-eslint/no-foo"
+      ),
+      CodeRemoval::Text("/* eslint-disable no-empty */".to_string())
+    );
+    assert_eq!(
+      remove("// deno-lint-ignore no-empty", "no-empty"),
+      CodeRemoval::Directive
+    );
+    assert_eq!(
+      remove(
+        "// eslint-disable-next-line @typescript-eslint/no-explicit-any, no-empty",
+        "no-explicit-any"
+      ),
+      CodeRemoval::Text("// eslint-disable-next-line no-empty".to_string())
+    );
+    assert_eq!(
+      remove(
+        "// eslint-disable-line no-empty, ban-ts-ignore",
+        "ban-ts-comment"
+      ),
+      CodeRemoval::Text("// eslint-disable-line no-empty".to_string())
+    );
+    assert_eq!(
+      remove("// deno-lint-ignore no-empty a/no-empty", "no-explicit-any"),
+      CodeRemoval::NotFound
+    );
+    assert_eq!(
+      remove("// deno-lint-ignore a/no-empty no-explicit-any", "no-empty"),
+      CodeRemoval::NotFound
+    );
+  }
 }
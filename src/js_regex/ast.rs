@@ -0,0 +1,312 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+
+//! AST of EcmaScript regular expressions, modeled after
+//! [regexpp](https://github.com/mysticatea/regexpp).
+
+/// Byte range in the source of a pattern.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+  pub start: usize,
+  pub end: usize,
+}
+
+impl Span {
+  pub fn new(start: usize, end: usize) -> Self {
+    Span { start, end }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+  pub span: Span,
+  pub alternatives: Vec<Alternative>,
+}
+
+/// One of the alternatives separated by `|`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alternative {
+  pub span: Span,
+  pub terms: Vec<Term>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Term {
+  Assertion(Assertion),
+  Lookaround(Lookaround),
+  Group(Group),
+  CapturingGroup(CapturingGroup),
+  Quantifier(Quantifier),
+  CharacterClass(CharacterClass),
+  CharacterSet(CharacterSet),
+  Character(Character),
+  Backreference(Backreference),
+}
+
+impl Term {
+  pub fn span(&self) -> Span {
+    match self {
+      Term::Assertion(n) => n.span,
+      Term::Lookaround(n) => n.span,
+      Term::Group(n) => n.span,
+      Term::CapturingGroup(n) => n.span,
+      Term::Quantifier(n) => n.span,
+      Term::CharacterClass(n) => n.span,
+      Term::CharacterSet(n) => n.span,
+      Term::Character(n) => n.span,
+      Term::Backreference(n) => n.span,
+    }
+  }
+}
+
+/// `^`, `$`, `\b` or `\B`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Assertion {
+  pub span: Span,
+  pub kind: AssertionKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AssertionKind {
+  Start,
+  End,
+  WordBoundary,
+  NotWordBoundary,
+}
+
+/// `(?=...)`, `(?!...)`, `(?<=...)` or `(?<!...)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lookaround {
+  pub span: Span,
+  pub kind: LookaroundKind,
+  pub negate: bool,
+  pub alternatives: Vec<Alternative>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LookaroundKind {
+  Lookahead,
+  Lookbehind,
+}
+
+/// `(?:...)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Group {
+  pub span: Span,
+  pub alternatives: Vec<Alternative>,
+}
+
+/// `(...)` or `(?<name>...)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CapturingGroup {
+  pub span: Span,
+  pub name: Option<String>,
+  pub alternatives: Vec<Alternative>,
+}
+
+/// `*`, `+`, `?` or `{min,max}` with the term it repeats.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quantifier {
+  pub span: Span,
+  pub min: u64,
+  /// `None` if unbounded.
+  pub max: Option<u64>,
+  /// `false` if followed by `?`.
+  pub greedy: bool,
+  pub term: Box<Term>,
+}
+
+/// `[...]` or `[^...]`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CharacterClass {
+  pub span: Span,
+  pub negate: bool,
+  pub elements: Vec<ClassElement>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClassElement {
+  Character(Character),
+  CharacterSet(CharacterSet),
+  Range(ClassRange),
+  /// A class nested in a class with the `v` flag.
+  Class(CharacterClass),
+  StringDisjunction(ClassStringDisjunction),
+  SetOperation(ClassSetOperation),
+}
+
+/// `a-z` in a character class.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassRange {
+  pub span: Span,
+  pub min: Character,
+  pub max: Character,
+}
+
+/// `\q{abc|def}` in a class with the `v` flag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassStringDisjunction {
+  pub span: Span,
+  pub alternatives: Vec<ClassString>,
+}
+
+/// One of the strings of a `\q{...}`, may be empty.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassString {
+  pub span: Span,
+  pub characters: Vec<Character>,
+}
+
+/// `A&&B` or `A--B` in a class with the `v` flag. A class with an operation
+/// has no other elements.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassSetOperation {
+  pub span: Span,
+  pub kind: SetOperationKind,
+  /// Operations are left-associative, so this may be another operation of
+  /// the same kind.
+  pub left: Box<ClassElement>,
+  pub right: Box<ClassElement>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SetOperationKind {
+  Intersection,
+  Subtraction,
+}
+
+/// `.`, `\d`, `\s`, `\w`, `\p{...}` or their negations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CharacterSet {
+  pub span: Span,
+  pub kind: CharacterSetKind,
+  pub negate: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CharacterSetKind {
+  Any,
+  Digit,
+  Space,
+  Word,
+  Property {
+    key: String,
+    /// `None` for binary properties, e.g. `\p{ASCII}`.
+    value: Option<String>,
+  },
+}
+
+/// A single character, either written as is or escaped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Character {
+  pub span: Span,
+  /// Code point with the `u` flag, UTF-16 code unit otherwise.
+  pub value: u32,
+}
+
+/// `\1` or `\k<name>`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Backreference {
+  pub span: Span,
+  pub reference: BackreferenceKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BackreferenceKind {
+  Number(u32),
+  Name(String),
+}
+
+/// Flags of a regular expression.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Flags {
+  pub global: bool,
+  pub ignore_case: bool,
+  pub multiline: bool,
+  pub unicode: bool,
+  pub sticky: bool,
+  pub dot_all: bool,
+  pub has_indices: bool,
+  pub unicode_sets: bool,
+}
+
+/// Traverses the AST. Every method walks the children of the node by
+/// default, so overriding one stops the traversal below that node unless
+/// the matching `walk_*` function is called.
+pub trait Visit {
+  fn visit_alternative(&mut self, n: &Alternative) {
+    walk_alternative(self, n);
+  }
+
+  fn visit_term(&mut self, n: &Term) {
+    walk_term(self, n);
+  }
+
+  fn visit_quantifier(&mut self, n: &Quantifier) {
+    self.visit_term(&n.term);
+  }
+
+  fn visit_character_class(&mut self, n: &CharacterClass) {
+    walk_character_class(self, n);
+  }
+
+  fn visit_class_element(&mut self, n: &ClassElement) {
+    match n {
+      ClassElement::Character(c) => self.visit_character(c),
+      ClassElement::CharacterSet(s) => self.visit_character_set(s),
+      ClassElement::Range(r) => {
+        self.visit_character(&r.min);
+        self.visit_character(&r.max);
+      }
+      ClassElement::Class(c) => self.visit_character_class(c),
+      ClassElement::StringDisjunction(d) => {
+        for string in &d.alternatives {
+          for c in &string.characters {
+            self.visit_character(c);
+          }
+        }
+      }
+      ClassElement::SetOperation(o) => {
+        self.visit_class_element(&o.left);
+        self.visit_class_element(&o.right);
+      }
+    }
+  }
+
+  fn visit_character_set(&mut self, _: &CharacterSet) {}
+
+  fn visit_character(&mut self, _: &Character) {}
+}
+
+pub fn walk_pattern<V: Visit + ?Sized>(v: &mut V, n: &Pattern) {
+  for alternative in &n.alternatives {
+    v.visit_alternative(alternative);
+  }
+}
+
+pub fn walk_alternative<V: Visit + ?Sized>(v: &mut V, n: &Alternative) {
+  for term in &n.terms {
+    v.visit_term(term);
+  }
+}
+
+pub fn walk_term<V: Visit + ?Sized>(v: &mut V, n: &Term) {
+  let alternatives = match n {
+    Term::Lookaround(n) => &n.alternatives,
+    Term::Group(n) => &n.alternatives,
+    Term::CapturingGroup(n) => &n.alternatives,
+    Term::Quantifier(n) => return v.visit_quantifier(n),
+    Term::CharacterClass(n) => return v.visit_character_class(n),
+    Term::CharacterSet(n) => return v.visit_character_set(n),
+    Term::Character(n) => return v.visit_character(n),
+    Term::Assertion(..) | Term::Backreference(..) => return,
+  };
+  for alternative in alternatives {
+    v.visit_alternative(alternative);
+  }
+}
+
+pub fn walk_character_class<V: Visit + ?Sized>(v: &mut V, n: &CharacterClass) {
+  for element in &n.elements {
+    v.visit_class_element(element);
+  }
+}
@@ -1,9 +1,12 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 
+pub mod ast;
+mod parser;
 mod reader;
 mod unicode;
 mod validator;
 
+pub use parser::EcmaRegexParser;
 pub use validator::{EcmaRegexValidator, EcmaVersion};
 
 #[cfg(test)]
@@ -1249,4 +1252,100 @@ mod tests {
       Ok(())
     );
   }
+
+  #[test]
+  fn flags_2022_and_2024() {
+    let validator = EcmaRegexValidator::new(EcmaVersion::ES2021);
+    assert_eq!(
+      validator.validate_flags("d"),
+      Err("Invalid flag d".to_string())
+    );
+    let validator = EcmaRegexValidator::new(EcmaVersion::ES2022);
+    assert_eq!(validator.validate_flags("dgimsuy"), Ok(()));
+    assert_eq!(
+      validator.validate_flags("v"),
+      Err("Invalid flag v".to_string())
+    );
+    let validator = EcmaRegexValidator::new(EcmaVersion::ES2024);
+    assert_eq!(validator.validate_flags("dgimsvy"), Ok(()));
+    assert_eq!(
+      validator.validate_flags("uv"),
+      Err("Flags u and v can't be used together".to_string())
+    );
+  }
+
+  #[test]
+  fn unicode_sets_valid() {
+    let mut validator = EcmaRegexValidator::new(EcmaVersion::ES2024);
+    for source in &[
+      "[]",
+      "[^]",
+      "[abc]",
+      "[a-z0-9]",
+      "[\\p{L}--\\p{Ll}]",
+      "[\\p{L}--[a-z]--\\q{x}]",
+      "[\\w&&\\d]",
+      "[[a-z]&&[^aeiou]&&\\q{b}]",
+      "[[[a]]]",
+      "[\\q{}]",
+      "[\\q{abc|d|}]",
+      "[\\p{RGI_Emoji}]",
+      "[\\p{RGI_Emoji}--\\q{x}]",
+      "[^\\p{RGI_Emoji}&&\\p{ASCII}]",
+      "[^\\q{a|b}]",
+      "[\\&\\-\\!\\#]",
+      "[\\b\\n\\x41\\u{1F600}]",
+      "\\p{Basic_Emoji}",
+      "(?<y>\\d{4})\\k<y>",
+    ] {
+      assert_eq!(
+        validator.validate_pattern_with_flags(source, "v"),
+        Ok(()),
+        "{}",
+        source
+      );
+    }
+  }
+
+  #[test]
+  fn unicode_sets_invalid() {
+    let mut validator = EcmaRegexValidator::new(EcmaVersion::ES2024);
+    for source in &[
+      "[(]",
+      "[a-]",
+      "[z-a]",
+      "[a&&&b]",
+      "[a&&b--c]",
+      "[a--b&&c]",
+      "[ab--c]",
+      "[a-z&&b]",
+      "[&&a]",
+      "[a&&]",
+      "[a!!b]",
+      "[\\q{a]",
+      "[\\qa]",
+      "[\\z]",
+      "[^\\p{RGI_Emoji}]",
+      "[^\\q{ab}]",
+      "[^[\\q{ab}]]",
+      "\\P{RGI_Emoji}",
+      "[\\p{L}",
+      "\\1",
+    ] {
+      assert_ne!(
+        validator.validate_pattern_with_flags(source, "v"),
+        Ok(()),
+        "{}",
+        source
+      );
+    }
+    assert_ne!(validator.validate_pattern("\\p{RGI_Emoji}", true), Ok(()));
+
+    let mut validator = EcmaRegexValidator::new(EcmaVersion::ES2022);
+    // The `v` flag is ignored before ES2024.
+    assert_eq!(
+      validator.validate_pattern_with_flags("[a-z&&b]", "v"),
+      Ok(())
+    );
+  }
 }
@@ -0,0 +1,789 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+
+use super::ast::*;
+use super::validator::{EcmaRegexValidator, EcmaVersion};
+
+/// Parses EcmaScript regular expressions into [Pattern]s.
+///
+/// The grammar is implemented once in [EcmaRegexValidator], which reports
+/// the nodes it consumes to an [AstBuilder] while validating.
+pub struct EcmaRegexParser {
+  validator: EcmaRegexValidator,
+}
+
+impl EcmaRegexParser {
+  pub fn new(ecma_version: EcmaVersion) -> Self {
+    EcmaRegexParser {
+      validator: EcmaRegexValidator::new(ecma_version),
+    }
+  }
+
+  pub fn parse_flags(&self, flags: &str) -> Result<Flags, String> {
+    self.validator.validate_flags(flags)?;
+    Ok(Flags {
+      global: flags.contains('g'),
+      ignore_case: flags.contains('i'),
+      multiline: flags.contains('m'),
+      unicode: flags.contains('u'),
+      sticky: flags.contains('y'),
+      dot_all: flags.contains('s'),
+      has_indices: flags.contains('d'),
+      unicode_sets: flags.contains('v'),
+    })
+  }
+
+  /// Parses `source` in the mode selected by the `u` and `v` flags.
+  pub fn parse_pattern(
+    &mut self,
+    source: &str,
+    flags: Flags,
+  ) -> Result<Pattern, String> {
+    let unicode = flags.unicode || flags.unicode_sets;
+    self.validator.builder = Some(AstBuilder::new(source, unicode));
+    let result =
+      self
+        .validator
+        .validate(source, flags.unicode, flags.unicode_sets);
+    let builder = self.validator.builder.take().unwrap();
+    result?;
+    Ok(builder.pattern.expect("pattern should be built"))
+  }
+}
+
+/// Node whose children are still being parsed.
+#[derive(Debug)]
+enum Open {
+  Pattern(Span),
+  Alternative(Span, Vec<Term>),
+  Group(Span, Vec<Alternative>),
+  CapturingGroup(Span, Option<String>, Vec<Alternative>),
+  Lookaround(Span, LookaroundKind, bool, Vec<Alternative>),
+  CharacterClass(Span, bool, Vec<ClassElement>),
+  ClassStringDisjunction(Span, Vec<ClassString>),
+  ClassString(Span, Vec<Character>),
+}
+
+/// Builds the AST from the events reported by [EcmaRegexValidator].
+/// Positions in the events are indices of the validator's reader, which
+/// are converted to byte offsets.
+#[derive(Debug)]
+pub(super) struct AstBuilder {
+  /// Byte offset of each reader index.
+  offsets: Vec<usize>,
+  stack: Vec<Open>,
+  alternatives: Vec<Alternative>,
+  pattern: Option<Pattern>,
+}
+
+impl AstBuilder {
+  fn new(source: &str, u_flag: bool) -> Self {
+    let mut offsets = vec![];
+    for (i, c) in source.char_indices() {
+      offsets.push(i);
+      // Without the `u` flag, the reader goes through UTF-16 code units.
+      if !u_flag && c.len_utf16() == 2 {
+        offsets.push(i + c.len_utf8());
+      }
+    }
+    offsets.push(source.len());
+    AstBuilder {
+      offsets,
+      stack: vec![],
+      alternatives: vec![],
+      pattern: None,
+    }
+  }
+
+  fn span(&self, start: usize, end: usize) -> Span {
+    Span::new(self.offsets[start], self.offsets[end])
+  }
+
+  pub(super) fn on_pattern_enter(&mut self, start: usize) {
+    // The pattern may be parsed again once named groups are found.
+    self.stack.clear();
+    self.alternatives.clear();
+    self.stack.push(Open::Pattern(self.span(start, start)));
+  }
+
+  pub(super) fn on_pattern_leave(&mut self, end: usize) {
+    if let Some(Open::Pattern(span)) = self.stack.pop() {
+      self.pattern = Some(Pattern {
+        span: Span::new(span.start, self.offsets[end]),
+        alternatives: std::mem::take(&mut self.alternatives),
+      });
+    }
+  }
+
+  pub(super) fn on_alternative_enter(&mut self, start: usize) {
+    let span = self.span(start, start);
+    self.stack.push(Open::Alternative(span, vec![]));
+  }
+
+  pub(super) fn on_alternative_leave(&mut self, end: usize) {
+    if let Some(Open::Alternative(span, terms)) = self.stack.pop() {
+      let alternative = Alternative {
+        span: Span::new(span.start, self.offsets[end]),
+        terms,
+      };
+      match self.stack.last_mut() {
+        Some(Open::Group(_, alternatives))
+        | Some(Open::CapturingGroup(_, _, alternatives))
+        | Some(Open::Lookaround(_, _, _, alternatives)) => {
+          alternatives.push(alternative)
+        }
+        _ => self.alternatives.push(alternative),
+      }
+    }
+  }
+
+  pub(super) fn on_group_enter(&mut self, start: usize) {
+    let span = self.span(start, start);
+    self.stack.push(Open::Group(span, vec![]));
+  }
+
+  pub(super) fn on_capturing_group_enter(
+    &mut self,
+    start: usize,
+    name: Option<String>,
+  ) {
+    let span = self.span(start, start);
+    self.stack.push(Open::CapturingGroup(span, name, vec![]));
+  }
+
+  pub(super) fn on_lookaround_enter(
+    &mut self,
+    start: usize,
+    kind: LookaroundKind,
+    negate: bool,
+  ) {
+    let span = self.span(start, start);
+    self
+      .stack
+      .push(Open::Lookaround(span, kind, negate, vec![]));
+  }
+
+  /// Closes the innermost group, capturing group or lookaround.
+  pub(super) fn on_group_leave(&mut self, end: usize) {
+    let end = self.offsets[end];
+    let term = match self.stack.pop() {
+      Some(Open::Group(span, alternatives)) => Term::Group(Group {
+        span: Span::new(span.start, end),
+        alternatives,
+      }),
+      Some(Open::CapturingGroup(span, name, alternatives)) => {
+        Term::CapturingGroup(CapturingGroup {
+          span: Span::new(span.start, end),
+          name,
+          alternatives,
+        })
+      }
+      Some(Open::Lookaround(span, kind, negate, alternatives)) => {
+        Term::Lookaround(Lookaround {
+          span: Span::new(span.start, end),
+          kind,
+          negate,
+          alternatives,
+        })
+      }
+      _ => return,
+    };
+    self.add_term(term);
+  }
+
+  pub(super) fn on_assertion(
+    &mut self,
+    start: usize,
+    end: usize,
+    kind: AssertionKind,
+  ) {
+    let span = self.span(start, end);
+    self.add_term(Term::Assertion(Assertion { span, kind }));
+  }
+
+  pub(super) fn on_quantifier(
+    &mut self,
+    end: usize,
+    min: u64,
+    max: Option<u64>,
+    greedy: bool,
+  ) {
+    let end = self.offsets[end];
+    if let Some(Open::Alternative(_, terms)) = self.stack.last_mut() {
+      if let Some(term) = terms.pop() {
+        terms.push(Term::Quantifier(Quantifier {
+          span: Span::new(term.span().start, end),
+          min,
+          max,
+          greedy,
+          term: Box::new(term),
+        }));
+      }
+    }
+  }
+
+  pub(super) fn on_character_class_enter(
+    &mut self,
+    start: usize,
+    negate: bool,
+  ) {
+    let span = self.span(start, start);
+    self.stack.push(Open::CharacterClass(span, negate, vec![]));
+  }
+
+  pub(super) fn on_character_class_leave(&mut self, end: usize) {
+    if let Some(Open::CharacterClass(span, negate, elements)) = self.stack.pop()
+    {
+      let class = CharacterClass {
+        span: Span::new(span.start, self.offsets[end]),
+        negate,
+        elements,
+      };
+      match self.stack.last_mut() {
+        Some(Open::CharacterClass(_, _, elements)) => {
+          elements.push(ClassElement::Class(class))
+        }
+        _ => self.add_term(Term::CharacterClass(class)),
+      }
+    }
+  }
+
+  pub(super) fn on_class_string_disjunction_enter(&mut self, start: usize) {
+    let span = self.span(start, start);
+    self.stack.push(Open::ClassStringDisjunction(span, vec![]));
+  }
+
+  pub(super) fn on_class_string_disjunction_leave(&mut self, end: usize) {
+    if let Some(Open::ClassStringDisjunction(span, alternatives)) =
+      self.stack.pop()
+    {
+      let disjunction = ClassStringDisjunction {
+        span: Span::new(span.start, self.offsets[end]),
+        alternatives,
+      };
+      if let Some(Open::CharacterClass(_, _, elements)) = self.stack.last_mut()
+      {
+        elements.push(ClassElement::StringDisjunction(disjunction));
+      }
+    }
+  }
+
+  pub(super) fn on_class_string_enter(&mut self, start: usize) {
+    let span = self.span(start, start);
+    self.stack.push(Open::ClassString(span, vec![]));
+  }
+
+  pub(super) fn on_class_string_leave(&mut self, end: usize) {
+    if let Some(Open::ClassString(span, characters)) = self.stack.pop() {
+      let string = ClassString {
+        span: Span::new(span.start, self.offsets[end]),
+        characters,
+      };
+      if let Some(Open::ClassStringDisjunction(_, alternatives)) =
+        self.stack.last_mut()
+      {
+        alternatives.push(string);
+      }
+    }
+  }
+
+  /// Turns the last two elements of the class into the operands of `kind`.
+  pub(super) fn on_class_set_operation(
+    &mut self,
+    start: usize,
+    end: usize,
+    kind: SetOperationKind,
+  ) {
+    let span = self.span(start, end);
+    if let Some(Open::CharacterClass(_, _, elements)) = self.stack.last_mut() {
+      if elements.len() < 2 {
+        return;
+      }
+      let right = elements.pop().unwrap();
+      let left = elements.pop().unwrap();
+      elements.push(ClassElement::SetOperation(ClassSetOperation {
+        span,
+        kind,
+        left: Box::new(left),
+        right: Box::new(right),
+      }));
+    }
+  }
+
+  /// Turns the last three elements of the class, `min`, `-` and `max`, into
+  /// a range.
+  pub(super) fn on_class_range(&mut self, start: usize, end: usize) {
+    let span = self.span(start, end);
+    if let Some(Open::CharacterClass(_, _, elements)) = self.stack.last_mut() {
+      let len = elements.len();
+      if len < 3 {
+        return;
+      }
+      let mut tail = elements.split_off(len - 3).into_iter();
+      match (tail.next(), tail.next(), tail.next()) {
+        (
+          Some(ClassElement::Character(min)),
+          Some(_),
+          Some(ClassElement::Character(max)),
+        ) => elements.push(ClassElement::Range(ClassRange { span, min, max })),
+        (min, dash, max) => {
+          elements.extend(min.into_iter().chain(dash).chain(max))
+        }
+      }
+    }
+  }
+
+  pub(super) fn on_character_set(
+    &mut self,
+    start: usize,
+    end: usize,
+    kind: CharacterSetKind,
+    negate: bool,
+  ) {
+    let set = CharacterSet {
+      span: self.span(start, end),
+      kind,
+      negate,
+    };
+    match self.stack.last_mut() {
+      Some(Open::CharacterClass(_, _, elements)) => {
+        elements.push(ClassElement::CharacterSet(set))
+      }
+      _ => self.add_term(Term::CharacterSet(set)),
+    }
+  }
+
+  pub(super) fn on_character(&mut self, start: usize, end: usize, value: u32) {
+    let character = Character {
+      span: self.span(start, end),
+      value,
+    };
+    match self.stack.last_mut() {
+      Some(Open::CharacterClass(_, _, elements)) => {
+        elements.push(ClassElement::Character(character))
+      }
+      Some(Open::ClassString(_, characters)) => characters.push(character),
+      _ => self.add_term(Term::Character(character)),
+    }
+  }
+
+  pub(super) fn on_backreference(
+    &mut self,
+    start: usize,
+    end: usize,
+    reference: BackreferenceKind,
+  ) {
+    let span = self.span(start, end);
+    self.add_term(Term::Backreference(Backreference { span, reference }));
+  }
+
+  fn add_term(&mut self, term: Term) {
+    if let Some(Open::Alternative(_, terms)) = self.stack.last_mut() {
+      terms.push(term);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(source: &str, u_flag: bool) -> Pattern {
+    let flags = Flags {
+      unicode: u_flag,
+      ..Flags::default()
+    };
+    EcmaRegexParser::new(EcmaVersion::ES2018)
+      .parse_pattern(source, flags)
+      .unwrap()
+  }
+
+  fn parse_unicode_sets(source: &str) -> Pattern {
+    let flags = Flags {
+      unicode_sets: true,
+      ..Flags::default()
+    };
+    EcmaRegexParser::new(EcmaVersion::ES2024)
+      .parse_pattern(source, flags)
+      .unwrap()
+  }
+
+  fn terms(source: &str) -> Vec<Term> {
+    let mut pattern = parse(source, false);
+    assert_eq!(pattern.alternatives.len(), 1);
+    pattern.alternatives.remove(0).terms
+  }
+
+  fn character(start: usize, end: usize, value: char) -> Character {
+    Character {
+      span: Span::new(start, end),
+      value: value as u32,
+    }
+  }
+
+  #[test]
+  fn alternatives() {
+    let pattern = parse("a|bc|", false);
+    assert_eq!(pattern.span, Span::new(0, 5));
+    let spans: Vec<_> = pattern.alternatives.iter().map(|a| a.span).collect();
+    assert_eq!(
+      spans,
+      vec![Span::new(0, 1), Span::new(2, 4), Span::new(5, 5)]
+    );
+    assert_eq!(
+      pattern.alternatives[1].terms,
+      vec![
+        Term::Character(character(2, 3, 'b')),
+        Term::Character(character(3, 4, 'c')),
+      ]
+    );
+  }
+
+  #[test]
+  fn groups() {
+    let terms = terms("(a)(?:b)(?<name>c)(?=d)(?<!e)");
+    let spans: Vec<_> = terms.iter().map(|t| t.span()).collect();
+    assert_eq!(
+      spans,
+      vec![
+        Span::new(0, 3),
+        Span::new(3, 8),
+        Span::new(8, 18),
+        Span::new(18, 23),
+        Span::new(23, 29),
+      ]
+    );
+    assert!(matches!(&terms[0], Term::CapturingGroup(g) if g.name.is_none()));
+    assert!(matches!(&terms[1], Term::Group(..)));
+    assert!(
+      matches!(&terms[2], Term::CapturingGroup(g) if g.name.as_deref() == Some("name"))
+    );
+    match &terms[3] {
+      Term::Lookaround(l) => {
+        assert_eq!(l.kind, LookaroundKind::Lookahead);
+        assert!(!l.negate);
+        assert_eq!(
+          l.alternatives[0].terms,
+          vec![Term::Character(character(21, 22, 'd'))]
+        );
+      }
+      t => panic!("unexpected term {:?}", t),
+    }
+    assert!(matches!(
+      &terms[4],
+      Term::Lookaround(Lookaround {
+        kind: LookaroundKind::Lookbehind,
+        negate: true,
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn quantifiers() {
+    let terms = terms("a*b+?c{2}d{1,}(e){0,3}?");
+    let quantifiers: Vec<_> = terms
+      .iter()
+      .map(|t| match t {
+        Term::Quantifier(q) => (q.span, q.min, q.max, q.greedy),
+        t => panic!("unexpected term {:?}", t),
+      })
+      .collect();
+    assert_eq!(
+      quantifiers,
+      vec![
+        (Span::new(0, 2), 0, None, true),
+        (Span::new(2, 5), 1, None, false),
+        (Span::new(5, 9), 2, Some(2), true),
+        (Span::new(9, 14), 1, None, true),
+        (Span::new(14, 23), 0, Some(3), false),
+      ]
+    );
+    match &terms[4] {
+      Term::Quantifier(q) => {
+        assert!(matches!(*q.term, Term::CapturingGroup(..)))
+      }
+      t => panic!("unexpected term {:?}", t),
+    }
+  }
+
+  #[test]
+  fn assertions_and_sets() {
+    let terms = terms(r"^\b.\d\S\B$");
+    assert_eq!(
+      terms,
+      vec![
+        Term::Assertion(Assertion {
+          span: Span::new(0, 1),
+          kind: AssertionKind::Start,
+        }),
+        Term::Assertion(Assertion {
+          span: Span::new(1, 3),
+          kind: AssertionKind::WordBoundary,
+        }),
+        Term::CharacterSet(CharacterSet {
+          span: Span::new(3, 4),
+          kind: CharacterSetKind::Any,
+          negate: false,
+        }),
+        Term::CharacterSet(CharacterSet {
+          span: Span::new(4, 6),
+          kind: CharacterSetKind::Digit,
+          negate: false,
+        }),
+        Term::CharacterSet(CharacterSet {
+          span: Span::new(6, 8),
+          kind: CharacterSetKind::Space,
+          negate: true,
+        }),
+        Term::Assertion(Assertion {
+          span: Span::new(8, 10),
+          kind: AssertionKind::NotWordBoundary,
+        }),
+        Term::Assertion(Assertion {
+          span: Span::new(10, 11),
+          kind: AssertionKind::End,
+        }),
+      ]
+    );
+
+    let pattern = parse(r"\p{Script=Greek}\P{ASCII}", true);
+    let sets: Vec<_> = pattern.alternatives[0]
+      .terms
+      .iter()
+      .map(|t| match t {
+        Term::CharacterSet(s) => (s.span, s.kind.clone(), s.negate),
+        t => panic!("unexpected term {:?}", t),
+      })
+      .collect();
+    assert_eq!(
+      sets,
+      vec![
+        (
+          Span::new(0, 16),
+          CharacterSetKind::Property {
+            key: "Script".to_string(),
+            value: Some("Greek".to_string()),
+          },
+          false
+        ),
+        (
+          Span::new(16, 25),
+          CharacterSetKind::Property {
+            key: "ASCII".to_string(),
+            value: None,
+          },
+          true
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn escapes() {
+    let terms = terms(r"\x1f\u0041\n\0\cA\.\1()");
+    assert_eq!(
+      &terms[..6],
+      &[
+        Term::Character(character(0, 4, '\x1f')),
+        Term::Character(character(4, 10, 'A')),
+        Term::Character(character(10, 12, '\n')),
+        Term::Character(character(12, 14, '\0')),
+        Term::Character(character(14, 17, '\x01')),
+        Term::Character(character(17, 19, '.')),
+      ]
+    );
+    assert_eq!(
+      terms[6],
+      Term::Backreference(Backreference {
+        span: Span::new(19, 21),
+        reference: BackreferenceKind::Number(1),
+      })
+    );
+
+    let pattern = parse(r"\u{1F600}(?<a>x)\k<a>", true);
+    let terms = &pattern.alternatives[0].terms;
+    assert_eq!(terms[0], Term::Character(character(0, 9, '😀')));
+    assert_eq!(
+      terms[2],
+      Term::Backreference(Backreference {
+        span: Span::new(16, 21),
+        reference: BackreferenceKind::Name("a".to_string()),
+      })
+    );
+  }
+
+  #[test]
+  fn character_classes() {
+    let terms = terms(r"[^a-z\d-][]\b[\w-a]");
+    assert_eq!(
+      terms[0],
+      Term::CharacterClass(CharacterClass {
+        span: Span::new(0, 9),
+        negate: true,
+        elements: vec![
+          ClassElement::Range(ClassRange {
+            span: Span::new(2, 5),
+            min: character(2, 3, 'a'),
+            max: character(4, 5, 'z'),
+          }),
+          ClassElement::CharacterSet(CharacterSet {
+            span: Span::new(5, 7),
+            kind: CharacterSetKind::Digit,
+            negate: false,
+          }),
+          ClassElement::Character(character(7, 8, '-')),
+        ],
+      })
+    );
+    assert_eq!(
+      terms[1],
+      Term::CharacterClass(CharacterClass {
+        span: Span::new(9, 11),
+        negate: false,
+        elements: vec![],
+      })
+    );
+    assert!(matches!(
+      terms[2],
+      Term::Assertion(Assertion {
+        kind: AssertionKind::WordBoundary,
+        ..
+      })
+    ));
+    // A set can't be a bound of a range, so `-` is a character.
+    match &terms[3] {
+      Term::CharacterClass(class) => {
+        assert_eq!(class.elements.len(), 3);
+        assert!(matches!(class.elements[1], ClassElement::Character(..)));
+      }
+      t => panic!("unexpected term {:?}", t),
+    }
+  }
+
+  #[test]
+  fn unicode_sets() {
+    let pattern = parse_unicode_sets(r"[[a-c]&&\q{b|de}&&[^d]][\w--_]");
+    let terms = &pattern.alternatives[0].terms;
+    let nested = CharacterClass {
+      span: Span::new(1, 6),
+      negate: false,
+      elements: vec![ClassElement::Range(ClassRange {
+        span: Span::new(2, 5),
+        min: character(2, 3, 'a'),
+        max: character(4, 5, 'c'),
+      })],
+    };
+    let strings = ClassStringDisjunction {
+      span: Span::new(8, 16),
+      alternatives: vec![
+        ClassString {
+          span: Span::new(11, 12),
+          characters: vec![character(11, 12, 'b')],
+        },
+        ClassString {
+          span: Span::new(13, 15),
+          characters: vec![character(13, 14, 'd'), character(14, 15, 'e')],
+        },
+      ],
+    };
+    let negated = CharacterClass {
+      span: Span::new(18, 22),
+      negate: true,
+      elements: vec![ClassElement::Character(character(20, 21, 'd'))],
+    };
+    assert_eq!(
+      terms[0],
+      Term::CharacterClass(CharacterClass {
+        span: Span::new(0, 23),
+        negate: false,
+        elements: vec![ClassElement::SetOperation(ClassSetOperation {
+          span: Span::new(1, 22),
+          kind: SetOperationKind::Intersection,
+          left: Box::new(ClassElement::SetOperation(ClassSetOperation {
+            span: Span::new(1, 16),
+            kind: SetOperationKind::Intersection,
+            left: Box::new(ClassElement::Class(nested)),
+            right: Box::new(ClassElement::StringDisjunction(strings)),
+          })),
+          right: Box::new(ClassElement::Class(negated)),
+        })],
+      })
+    );
+    match &terms[1] {
+      Term::CharacterClass(class) => match &class.elements[..] {
+        [ClassElement::SetOperation(o)] => {
+          assert_eq!(o.kind, SetOperationKind::Subtraction);
+          assert!(matches!(*o.left, ClassElement::CharacterSet(..)));
+          assert_eq!(*o.right, ClassElement::Character(character(28, 29, '_')));
+        }
+        elements => panic!("unexpected elements {:?}", elements),
+      },
+      t => panic!("unexpected term {:?}", t),
+    }
+  }
+
+  #[test]
+  fn byte_offsets() {
+    let pattern = parse("é|😀", true);
+    assert_eq!(pattern.alternatives[0].span, Span::new(0, 2));
+    assert_eq!(pattern.alternatives[1].span, Span::new(3, 7));
+    assert_eq!(
+      pattern.alternatives[1].terms,
+      vec![Term::Character(character(3, 7, '😀'))]
+    );
+  }
+
+  #[test]
+  fn lone_surrogates() {
+    // Without the `u` flag, an astral character is a pair of code units,
+    // the first of which covers the bytes of the character.
+    let pattern = parse("[😀]a", false);
+    let terms = &pattern.alternatives[0].terms;
+    assert_eq!(
+      terms[0],
+      Term::CharacterClass(CharacterClass {
+        span: Span::new(0, 6),
+        negate: false,
+        elements: vec![
+          ClassElement::Character(Character {
+            span: Span::new(1, 5),
+            value: 0xD83D,
+          }),
+          ClassElement::Character(Character {
+            span: Span::new(5, 5),
+            value: 0xDE00,
+          }),
+        ],
+      })
+    );
+    assert_eq!(terms[1], Term::Character(character(6, 7, 'a')));
+  }
+
+  #[test]
+  fn flags() {
+    let parser = EcmaRegexParser::new(EcmaVersion::ES2018);
+    let flags = parser.parse_flags("gu").unwrap();
+    assert!(flags.global && flags.unicode);
+    assert!(!flags.ignore_case && !flags.dot_all);
+    assert!(parser.parse_flags("gg").is_err());
+    assert!(parser.parse_flags("d").is_err());
+
+    let parser = EcmaRegexParser::new(EcmaVersion::ES2024);
+    let flags = parser.parse_flags("dv").unwrap();
+    assert!(flags.has_indices && flags.unicode_sets && !flags.unicode);
+    assert!(parser.parse_flags("uv").is_err());
+  }
+
+  #[test]
+  fn invalid() {
+    let mut parser = EcmaRegexParser::new(EcmaVersion::ES2018);
+    let flags = Flags::default();
+    assert_eq!(
+      parser.parse_pattern("(a", flags),
+      Err("Unterminated group".to_string())
+    );
+    assert!(parser.parse_pattern("a**", flags).is_err());
+    // The parser can be reused after an error.
+    assert!(parser.parse_pattern("a", flags).is_ok());
+  }
+}
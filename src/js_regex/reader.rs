@@ -8,9 +8,13 @@ pub struct Reader {
   src: String,
   index: usize,
   end: usize,
-  cps: VecDeque<char>,
+  cps: VecDeque<u32>,
 }
 
+/// Stands in for lone surrogates in `Reader::code_point_with_offset`, as they
+/// are not valid `char`s. Neither is a syntax or identifier character.
+const LONE_SURROGATE: char = '\u{fffd}';
+
 impl Reader {
   pub fn new() -> Self {
     Self {
@@ -31,7 +35,18 @@ impl Reader {
     self.index
   }
 
+  /// Returns the code point at `offset` from the current position. Without the
+  /// `u` flag these are UTF-16 code units, and lone surrogates are returned as
+  /// U+FFFD; use `raw_code_point_with_offset` to get their value.
   pub fn code_point_with_offset(&self, offset: usize) -> Option<char> {
+    self
+      .raw_code_point_with_offset(offset)
+      .map(|cp| std::char::from_u32(cp).unwrap_or(LONE_SURROGATE))
+  }
+
+  /// Returns the value of the code point or code unit at `offset` from the
+  /// current position.
+  pub fn raw_code_point_with_offset(&self, offset: usize) -> Option<u32> {
     self.cps.get(offset).cloned()
   }
 
@@ -73,7 +88,7 @@ impl Reader {
 
   pub fn eat(&mut self, cp: char) -> bool {
     let opt = self.cps.get(0);
-    if opt.is_some() && *opt.unwrap() == cp {
+    if opt.is_some() && *opt.unwrap() == cp as u32 {
       self.advance();
       true
     } else {
@@ -85,8 +100,8 @@ impl Reader {
     let (opt1, opt2) = (self.cps.get(0), self.cps.get(1));
     if opt1.is_some()
       && opt2.is_some()
-      && *opt1.unwrap() == cp1
-      && *opt2.unwrap() == cp2
+      && *opt1.unwrap() == cp1 as u32
+      && *opt2.unwrap() == cp2 as u32
     {
       self.advance();
       self.advance();
@@ -102,9 +117,9 @@ impl Reader {
     if opt1.is_some()
       && opt2.is_some()
       && opt3.is_some()
-      && *opt1.unwrap() == cp1
-      && *opt2.unwrap() == cp2
-      && *opt3.unwrap() == cp3
+      && *opt1.unwrap() == cp1 as u32
+      && *opt2.unwrap() == cp2 as u32
+      && *opt3.unwrap() == cp3 as u32
     {
       self.advance();
       self.advance();
@@ -115,20 +130,13 @@ impl Reader {
     }
   }
 
-  fn at(&self, i: usize) -> Option<char> {
+  fn at(&self, i: usize) -> Option<u32> {
     if i >= self.end {
       None
     } else if self.unicode {
-      let c = self.src.chars().nth(i).unwrap();
-      Some(c)
+      self.src.chars().nth(i).map(|c| c as u32)
     } else {
-      // TODO: move the conversion out of this method and make it safe
-      unsafe {
-        let c: char = std::char::from_u32_unchecked(
-          self.src.encode_utf16().nth(i).unwrap() as u32,
-        );
-        Some(c)
-      }
+      self.src.encode_utf16().nth(i).map(u32::from)
     }
   }
 }
@@ -164,24 +172,37 @@ mod tests {
     assert_eq!(reader.eat3('b', 'c', 'd'), true);
   }
 
+  #[test]
+  fn lone_surrogate_test() {
+    let mut reader = Reader::new();
+    reader.reset("🩢a", 0, 3, false);
+    assert_eq!(reader.code_point_with_offset(0), Some('\u{fffd}'));
+    assert_eq!(reader.raw_code_point_with_offset(0), Some(0xd83e));
+    assert_eq!(reader.raw_code_point_with_offset(1), Some(0xde62));
+    assert_eq!(reader.code_point_with_offset(2), Some('a'));
+    reader.advance();
+    reader.advance();
+    assert_eq!(reader.eat('a'), true);
+  }
+
   #[test]
   fn at_test_es_compliance() {
     let mut reader = Reader::new();
     // without unicode flag
     reader.reset("Hello", 0, 5, false);
-    assert_eq!(reader.at(1).unwrap() as u32, 101);
+    assert_eq!(reader.at(1).unwrap(), 101);
     reader.reset("􀃃a🩢☃★♲", 0, 6, false);
-    assert_eq!(reader.at(0).unwrap() as u32, 56256);
+    assert_eq!(reader.at(0).unwrap(), 56256);
     reader.reset("􀃃ello", 0, 6, false);
-    assert_eq!(reader.at(0).unwrap() as u32, 56256);
+    assert_eq!(reader.at(0).unwrap(), 56256);
     reader.reset("􀃃ello", 0, 6, false);
-    assert_eq!(reader.at(1).unwrap() as u32, 56515);
+    assert_eq!(reader.at(1).unwrap(), 56515);
     // with unicode flag
     reader.reset("Hello", 0, 5, true);
-    assert_eq!(reader.at(1).unwrap() as u32, 101);
+    assert_eq!(reader.at(1).unwrap(), 101);
     reader.reset("􀃃a🩢☃★♲", 0, 6, true);
-    assert_eq!(reader.at(0).unwrap() as u32, 1048771);
+    assert_eq!(reader.at(0).unwrap(), 1048771);
     reader.reset("􀃃ello", 0, 6, true);
-    assert_eq!(reader.at(0).unwrap() as u32, 1048771);
+    assert_eq!(reader.at(0).unwrap(), 1048771);
   }
 }
@@ -579,6 +579,25 @@ pub fn is_valid_lone_unicode_property(
       && BIN_PROPERTY_PATTERNS.es2019.contains(value))
 }
 
+/// Binary properties which match sequences of code points, only available
+/// with the `v` flag.
+static BIN_PROPERTY_OF_STRINGS: &[&str] = &[
+  "Basic_Emoji",
+  "Emoji_Keycap_Sequence",
+  "RGI_Emoji_Modifier_Sequence",
+  "RGI_Emoji_Flag_Sequence",
+  "RGI_Emoji_Tag_Sequence",
+  "RGI_Emoji_ZWJ_Sequence",
+  "RGI_Emoji",
+];
+
+pub fn is_valid_lone_unicode_property_of_strings(
+  version: EcmaVersion,
+  value: &str,
+) -> bool {
+  version >= EcmaVersion::ES2024 && BIN_PROPERTY_OF_STRINGS.contains(&value)
+}
+
 pub fn is_large_id_start(cp: char) -> bool {
   is_in_range(cp as u32, &LARGE_ID_START_RANGES)
 }
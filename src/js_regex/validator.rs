@@ -3,6 +3,11 @@
 use std::collections::HashSet;
 use std::ops::{Deref, DerefMut};
 
+use super::ast::{
+  AssertionKind, BackreferenceKind, CharacterSetKind, LookaroundKind,
+  SetOperationKind,
+};
+use super::parser::AstBuilder;
 use super::reader::Reader;
 use super::unicode::*;
 
@@ -23,6 +28,22 @@ fn is_syntax_character(cp: char) -> bool {
     || cp == '|'
 }
 
+/// Characters which must be escaped in a class with the `v` flag.
+fn is_class_set_syntax_character(cp: char) -> bool {
+  "()[]{}/-\\|".contains(cp)
+}
+
+/// Characters which can't be repeated in a class with the `v` flag, as the
+/// pairs are reserved for future set operators.
+fn is_class_set_reserved_double_punctuator(cp: char) -> bool {
+  "&!#$%*+,.:;<=>?@^`~".contains(cp)
+}
+
+/// Characters which may be escaped in a class with the `v` flag.
+fn is_class_set_reserved_punctuator(cp: char) -> bool {
+  "&-!#%,:;<=>@`~".contains(cp)
+}
+
 fn is_unicode_property_name_character(cp: char) -> bool {
   cp.is_ascii_alphabetic() || cp == '_'
 }
@@ -102,6 +123,14 @@ pub enum EcmaVersion {
   ES2019,
   ES2020,
   ES2021,
+  ES2022,
+  ES2023,
+  ES2024,
+}
+
+impl EcmaVersion {
+  /// The latest version supported by the validator.
+  pub const LATEST: EcmaVersion = EcmaVersion::ES2024;
 }
 
 #[derive(Debug)]
@@ -110,6 +139,7 @@ pub struct EcmaRegexValidator {
   strict: bool,
   ecma_version: EcmaVersion,
   u_flag: bool,
+  v_flag: bool,
   n_flag: bool,
   last_int_value: i64,
   last_min_value: i64,
@@ -118,9 +148,14 @@ pub struct EcmaRegexValidator {
   last_key_value: String,
   last_val_value: String,
   last_assertion_is_quantifiable: bool,
+  /// Whether the last class set operand may match strings of more than one
+  /// character, which can't be negated.
+  last_may_contain_strings: bool,
   num_capturing_parens: u32,
   group_names: HashSet<String>,
   backreference_names: HashSet<String>,
+  /// Receives the nodes consumed while parsing.
+  pub(super) builder: Option<AstBuilder>,
 }
 
 impl Deref for EcmaRegexValidator {
@@ -144,6 +179,7 @@ impl EcmaRegexValidator {
       strict: false,
       ecma_version,
       u_flag: false,
+      v_flag: false,
       n_flag: false,
       last_int_value: 0,
       last_min_value: 0,
@@ -152,9 +188,11 @@ impl EcmaRegexValidator {
       last_key_value: "".to_string(),
       last_val_value: "".to_string(),
       last_assertion_is_quantifiable: false,
+      last_may_contain_strings: false,
       num_capturing_parens: 0,
       group_names: HashSet::new(),
       backreference_names: HashSet::new(),
+      builder: None,
     }
   }
 
@@ -174,26 +212,59 @@ impl EcmaRegexValidator {
         || (flag == 'u' && self.ecma_version >= EcmaVersion::ES2015)
         || (flag == 'y' && self.ecma_version >= EcmaVersion::ES2015)
         || (flag == 's' && self.ecma_version >= EcmaVersion::ES2018)
+        || (flag == 'd' && self.ecma_version >= EcmaVersion::ES2022)
+        || (flag == 'v' && self.ecma_version >= EcmaVersion::ES2024)
       {
         // do nothing
       } else {
         return Err(format!("Invalid flag {}", flag));
       }
     }
+    if existing_flags.contains(&'u') && existing_flags.contains(&'v') {
+      return Err("Flags u and v can't be used together".to_string());
+    }
     Ok(())
   }
 
   /// Validates the pattern of a EcmaScript regular expression.
+  #[cfg(test)]
   pub fn validate_pattern(
     &mut self,
     source: &str,
     u_flag: bool,
   ) -> Result<(), String> {
+    self.validate(source, u_flag, false)
+  }
+
+  /// Validates the pattern of a EcmaScript regular expression in the mode
+  /// selected by `flags`, which should be valid.
+  pub fn validate_pattern_with_flags(
+    &mut self,
+    source: &str,
+    flags: &str,
+  ) -> Result<(), String> {
+    self.validate(source, flags.contains('u'), flags.contains('v'))
+  }
+
+  pub(super) fn validate(
+    &mut self,
+    source: &str,
+    u_flag: bool,
+    v_flag: bool,
+  ) -> Result<(), String> {
+    self.v_flag = v_flag && self.ecma_version >= EcmaVersion::ES2024;
+    // The `v` flag is a stricter version of the `u` flag.
+    let u_flag = u_flag || self.v_flag;
     self.strict = u_flag; // TODO: allow toggling strict independently of u flag
     self.u_flag = u_flag && self.ecma_version >= EcmaVersion::ES2015;
     self.n_flag = u_flag && self.ecma_version >= EcmaVersion::ES2018;
-    //self.reset(source, 0, source.len(), u_flag);
-    self.reset(source, 0, source.chars().count(), u_flag);
+    // Without the `u` flag, the reader goes through UTF-16 code units.
+    let end = if u_flag {
+      source.chars().count()
+    } else {
+      source.encode_utf16().count()
+    };
+    self.reset(source, 0, end, u_flag);
     self.consume_pattern()?;
 
     if !self.n_flag
@@ -214,10 +285,12 @@ impl EcmaRegexValidator {
   ///     Disjunction[?U, ?N]
   /// ```
   fn consume_pattern(&mut self) -> Result<(), String> {
+    let start = self.index();
     self.num_capturing_parens = self.count_capturing_parens();
     self.group_names.clear();
     self.backreference_names.clear();
 
+    self.emit(|b| b.on_pattern_enter(start));
     self.consume_disjunction()?;
 
     if let Some(cp) = self.code_point_with_offset(0) {
@@ -238,6 +311,8 @@ impl EcmaRegexValidator {
     {
       return Err(format!("Invalid named capture referenced: {}", name));
     }
+    let end = self.index();
+    self.emit(|b| b.on_pattern_leave(end));
     Ok(())
   }
 
@@ -269,9 +344,13 @@ impl EcmaRegexValidator {
   ///      Alternative[?U, ?N] Term[?U, ?N]
   /// ```
   fn consume_alternative(&mut self) -> Result<(), String> {
+    let start = self.index();
+    self.emit(|b| b.on_alternative_enter(start));
     while self.code_point_with_offset(0).is_some() && self.consume_term()? {
       // do nothing
     }
+    let end = self.index();
+    self.emit(|b| b.on_alternative_leave(end));
     Ok(())
   }
 
@@ -337,11 +416,20 @@ impl EcmaRegexValidator {
     let start = self.index();
     self.last_assertion_is_quantifiable = false;
 
-    if self.eat('^')
-      || self.eat('$')
-      || self.eat2('\\', 'B')
-      || self.eat2('\\', 'b')
-    {
+    let kind = if self.eat('^') {
+      Some(AssertionKind::Start)
+    } else if self.eat('$') {
+      Some(AssertionKind::End)
+    } else if self.eat2('\\', 'B') {
+      Some(AssertionKind::NotWordBoundary)
+    } else if self.eat2('\\', 'b') {
+      Some(AssertionKind::WordBoundary)
+    } else {
+      None
+    };
+    if let Some(kind) = kind {
+      let end = self.index();
+      self.emit(|b| b.on_assertion(start, end, kind));
       return Ok(true);
     }
 
@@ -350,14 +438,23 @@ impl EcmaRegexValidator {
       let lookbehind =
         self.ecma_version >= EcmaVersion::ES2018 && self.eat('<');
       let mut flag = self.eat('=');
+      let negate = !flag && self.eat('!');
       if !flag {
-        flag = self.eat('!');
+        flag = negate;
       }
       if flag {
+        let kind = if lookbehind {
+          LookaroundKind::Lookbehind
+        } else {
+          LookaroundKind::Lookahead
+        };
+        self.emit(|b| b.on_lookaround_enter(start, kind, negate));
         self.consume_disjunction()?;
         if !self.eat(')') {
           return Err("Unterminated group".to_string());
         }
+        let end = self.index();
+        self.emit(|b| b.on_group_leave(end));
         self.last_assertion_is_quantifiable = !lookbehind && !self.strict;
         return Ok(true);
       }
@@ -382,16 +479,28 @@ impl EcmaRegexValidator {
   /// Returns `true` if it consumed the next characters successfully.
   fn consume_quantifier(&mut self, no_consume: bool) -> Result<bool, String> {
     // QuantifierPrefix
-    if !self.eat('*')
-      && !self.eat('+')
-      && !self.eat('?')
-      && !self.eat_braced_quantifier(no_consume)?
-    {
-      Ok(false)
+    let (min, max) = if self.eat('*') {
+      (0, None)
+    } else if self.eat('+') {
+      (1, None)
+    } else if self.eat('?') {
+      (0, Some(1))
+    } else if self.eat_braced_quantifier(no_consume)? {
+      let max = if self.last_max_value == i64::MAX {
+        None
+      } else {
+        Some(self.last_max_value as u64)
+      };
+      (self.last_min_value as u64, max)
     } else {
-      self.eat('?');
-      Ok(true)
+      return Ok(false);
+    };
+    let greedy = !self.eat('?');
+    if !no_consume {
+      let end = self.index();
+      self.emit(|b| b.on_quantifier(end, min, max, greedy));
     }
+    Ok(true)
   }
 
   /// Eats the next characters as the following alternatives if possible.
@@ -447,7 +556,7 @@ impl EcmaRegexValidator {
   fn consume_atom(&mut self) -> Result<bool, String> {
     Ok(
       self.consume_pattern_character()
-        || self.eat('.')
+        || self.consume_dot()
         || self.consume_reverse_solidus_atom_escape()?
         || self.consume_character_class()?
         || self.consume_uncapturing_group()?
@@ -477,11 +586,15 @@ impl EcmaRegexValidator {
   /// ```
   /// Returns `true` if it consumed the next characters successfully.
   fn consume_uncapturing_group(&mut self) -> Result<bool, String> {
+    let start = self.index();
     if self.eat3('(', '?', ':') {
+      self.emit(|b| b.on_group_enter(start));
       self.consume_disjunction()?;
       if !self.eat(')') {
         Err("Unterminated group".to_string())
       } else {
+        let end = self.index();
+        self.emit(|b| b.on_group_leave(end));
         Ok(true)
       }
     } else {
@@ -495,20 +608,27 @@ impl EcmaRegexValidator {
   /// ```
   /// Returns `true` if it consumed the next characters successfully.
   fn consume_capturing_group(&mut self) -> Result<bool, String> {
+    let start = self.index();
     if !self.eat('(') {
       return Ok(false);
     }
 
+    let mut name = None;
     if self.ecma_version >= EcmaVersion::ES2018 {
-      self.consume_group_specifier()?;
+      if self.consume_group_specifier()? {
+        name = Some(self.last_str_value.clone());
+      }
     } else if self.code_point_with_offset(0) == Some('?') {
       return Err("Invalid group".to_string());
     }
 
+    self.emit(|b| b.on_capturing_group_enter(start, name));
     self.consume_disjunction()?;
     if !self.eat(')') {
       return Err("Unterminated group".to_string());
     }
+    let end = self.index();
+    self.emit(|b| b.on_group_leave(end));
     Ok(true)
   }
 
@@ -527,7 +647,7 @@ impl EcmaRegexValidator {
   /// Returns `true` if it consumed the next characters successfully.
   fn consume_extended_atom(&mut self) -> Result<bool, String> {
     Ok(
-      self.eat('.')
+      self.consume_dot()
         || self.consume_reverse_solidus_atom_escape()?
         || self.consume_reverse_solidus_followed_by_c()
         || self.consume_character_class()?
@@ -547,8 +667,10 @@ impl EcmaRegexValidator {
     if self.code_point_with_offset(0) == Some('\\')
       && self.code_point_with_offset(1) == Some('c')
     {
+      let start = self.index();
       self.last_int_value = '\\' as i64;
       self.advance();
+      self.emit(|b| b.on_character(start, start + 1, '\\' as u32));
       true
     } else {
       false
@@ -582,7 +704,7 @@ impl EcmaRegexValidator {
   fn consume_pattern_character(&mut self) -> bool {
     if let Some(cp) = self.code_point_with_offset(0) {
       if !is_syntax_character(cp) {
-        self.advance();
+        self.advance_character();
         return true;
       }
     }
@@ -610,7 +732,7 @@ impl EcmaRegexValidator {
         && cp != '['
         && cp != '|'
       {
-        self.advance();
+        self.advance_character();
         return true;
       }
     }
@@ -655,11 +777,22 @@ impl EcmaRegexValidator {
   /// ```
   /// Returns `Ok(true)` if it consumed the next characters successfully.
   fn consume_atom_escape(&mut self) -> Result<bool, String> {
-    if self.consume_backreference()?
-      || self.consume_character_class_escape()?
-      || self.consume_character_escape()?
-      || (self.n_flag && self.consume_k_group_name()?)
-    {
+    // Including the preceding `\`.
+    let start = self.index() - 1;
+    if self.consume_backreference()? {
+      let reference = BackreferenceKind::Number(self.last_int_value as u32);
+      let end = self.index();
+      self.emit(|b| b.on_backreference(start, end, reference));
+      Ok(true)
+    } else if self.consume_character_class_escape()? {
+      Ok(true)
+    } else if self.consume_character_escape()? {
+      self.emit_last_character(start);
+      Ok(true)
+    } else if self.n_flag && self.consume_k_group_name()? {
+      let reference = BackreferenceKind::Name(self.last_str_value.clone());
+      let end = self.index();
+      self.emit(|b| b.on_backreference(start, end, reference));
       Ok(true)
     } else if self.strict || self.u_flag {
       Err("Invalid escape".to_string())
@@ -704,26 +837,47 @@ impl EcmaRegexValidator {
   /// ```
   /// Returns `true` if it consumed the next characters successfully.
   fn consume_character_class_escape(&mut self) -> Result<bool, String> {
-    if self.eat('d')
-      || self.eat('D')
-      || self.eat('s')
-      || self.eat('S')
-      || self.eat('w')
-      || self.eat('W')
-    {
-      self.last_int_value = -1;
-      return Ok(true);
+    // Including the preceding `\`.
+    let start = self.index() - 1;
+    self.last_may_contain_strings = false;
+    if let Some(cp) = self.code_point_with_offset(0) {
+      let kind = match cp.to_ascii_lowercase() {
+        'd' => Some(CharacterSetKind::Digit),
+        's' => Some(CharacterSetKind::Space),
+        'w' => Some(CharacterSetKind::Word),
+        _ => None,
+      };
+      if let Some(kind) = kind {
+        self.advance();
+        self.last_int_value = -1;
+        let end = self.index();
+        let negate = cp.is_ascii_uppercase();
+        self.emit(|b| b.on_character_set(start, end, kind, negate));
+        return Ok(true);
+      }
     }
 
     if self.u_flag
       && self.ecma_version >= EcmaVersion::ES2018
-      && (self.eat('p') || self.eat('P'))
+      && (self.code_point_with_offset(0) == Some('p')
+        || self.code_point_with_offset(0) == Some('P'))
     {
+      let negate = self.code_point_with_offset(0) == Some('P');
+      self.advance();
       self.last_int_value = -1;
       if self.eat('{')
         && self.eat_unicode_property_value_expression()?
         && self.eat('}')
       {
+        if negate && self.last_may_contain_strings {
+          return Err("Invalid property name".to_string());
+        }
+        let kind = CharacterSetKind::Property {
+          key: self.last_key_value.clone(),
+          value: Some(self.last_val_value.clone()).filter(|v| !v.is_empty()),
+        };
+        let end = self.index();
+        self.emit(|b| b.on_character_set(start, end, kind, negate));
         return Ok(true);
       }
       return Err("Invalid property name".to_string());
@@ -775,23 +929,267 @@ impl EcmaRegexValidator {
   }
 
   /// Validate the next characters as a RegExp `CharacterClass` production if possible.
+  /// Set `self.last_may_contain_strings` if it consumed the next characters
+  /// successfully.
   /// ```grammar
-  /// CharacterClass[U]::
-  ///      `[` [lookahead ≠ ^] ClassRanges[?U] `]`
-  ///      `[^` ClassRanges[?U] `]`
+  /// CharacterClass[U, V]::
+  ///      `[` [lookahead ≠ ^] ClassContents[?U, ?V] `]`
+  ///      `[^` ClassContents[?U, ?V] `]`
+  /// ClassContents[U, V]::
+  ///      [~V] ClassRanges[?U]
+  ///      [+V] ClassSetExpression
   /// ```
   /// Returns `true` if it consumed the next characters successfully.
   fn consume_character_class(&mut self) -> Result<bool, String> {
+    let start = self.index();
     if !self.eat('[') {
       return Ok(false);
     }
-    self.consume_class_ranges()?;
+    let negate = self.eat('^');
+    self.emit(|b| b.on_character_class_enter(start, negate));
+    if self.v_flag {
+      self.consume_class_set_expression()?;
+      if negate && self.last_may_contain_strings {
+        return Err("Negated character class may contain strings".to_string());
+      }
+    } else {
+      self.consume_class_ranges()?;
+    }
     if !self.eat(']') {
       return Err("Unterminated character class".to_string());
     }
+    let end = self.index();
+    self.emit(|b| b.on_character_class_leave(end));
+    self.last_may_contain_strings &= !negate;
+    Ok(true)
+  }
+
+  /// Validate the next characters as a RegExp `ClassSetExpression` production.
+  /// Set `self.last_may_contain_strings`.
+  /// ```grammar
+  /// ClassSetExpression::
+  ///      ClassUnion
+  ///      ClassIntersection
+  ///      ClassSubtraction
+  /// ClassUnion::
+  ///      ClassSetRange ClassUnion?
+  ///      ClassSetOperand ClassUnion?
+  /// ClassIntersection::
+  ///      ClassSetOperand `&&` [lookahead ≠ &] ClassSetOperand
+  ///      ClassIntersection `&&` [lookahead ≠ &] ClassSetOperand
+  /// ClassSubtraction::
+  ///      ClassSetOperand `--` ClassSetOperand
+  ///      ClassSubtraction `--` ClassSetOperand
+  /// ```
+  fn consume_class_set_expression(&mut self) -> Result<(), String> {
+    let start = self.index();
+    if !self.consume_class_set_operand()? {
+      self.last_may_contain_strings = false;
+      return match self.code_point_with_offset(0) {
+        Some(']') => Ok(()),
+        _ => Err("Invalid character in character class".to_string()),
+      };
+    }
+
+    let operator = if self.eat2('&', '&') {
+      Some(SetOperationKind::Intersection)
+    } else if self.eat2('-', '-') {
+      Some(SetOperationKind::Subtraction)
+    } else {
+      None
+    };
+    if let Some(kind) = operator {
+      let mut may_contain_strings = self.last_may_contain_strings;
+      loop {
+        if self.code_point_with_offset(0) == Some('&') {
+          return Err("Invalid character in character class".to_string());
+        }
+        if !self.consume_class_set_operand()? {
+          return Err("Invalid set operation in character class".to_string());
+        }
+        // Only an intersection of strings may contain strings.
+        if kind == SetOperationKind::Intersection {
+          may_contain_strings &= self.last_may_contain_strings;
+        }
+        let end = self.index();
+        self.emit(|b| b.on_class_set_operation(start, end, kind));
+        let next = match kind {
+          SetOperationKind::Intersection => self.eat2('&', '&'),
+          SetOperationKind::Subtraction => self.eat2('-', '-'),
+        };
+        if !next {
+          break;
+        }
+      }
+      if self.code_point_with_offset(0) != Some(']') {
+        return Err("Invalid set operation in character class".to_string());
+      }
+      self.last_may_contain_strings = may_contain_strings;
+      return Ok(());
+    }
+
+    let mut may_contain_strings = false;
+    let mut operand_start = start;
+    loop {
+      may_contain_strings |= self.last_may_contain_strings;
+
+      // ClassSetRange
+      let min = self.last_int_value;
+      if min != -1
+        && self.code_point_with_offset(0) == Some('-')
+        && self.code_point_with_offset(1) != Some('-')
+      {
+        let dash = self.index();
+        self.advance();
+        self.emit(|b| b.on_character(dash, dash + 1, '-' as u32));
+        if !self.consume_class_set_character()? {
+          return Err("Invalid character class".to_string());
+        }
+        if min > self.last_int_value {
+          return Err("Range out of order in character class".to_string());
+        }
+        let end = self.index();
+        self.emit(|b| b.on_class_range(operand_start, end));
+      }
+
+      match self.code_point_with_offset(0) {
+        Some(']') | None => break,
+        _ => {}
+      }
+      if self.code_point_with_offset(0) == self.code_point_with_offset(1)
+        && matches!(self.code_point_with_offset(0), Some('&') | Some('-'))
+      {
+        return Err("Invalid set operation in character class".to_string());
+      }
+      operand_start = self.index();
+      if !self.consume_class_set_operand()? {
+        return Err("Invalid character in character class".to_string());
+      }
+    }
+    self.last_may_contain_strings = may_contain_strings;
+    Ok(())
+  }
+
+  /// Validate the next characters as a RegExp `ClassSetOperand` production if possible.
+  /// Set `self.last_int_value` to the value of a single character or `-1`,
+  /// and `self.last_may_contain_strings` if it consumed the next characters
+  /// successfully.
+  /// ```grammar
+  /// ClassSetOperand::
+  ///      NestedClass
+  ///      ClassStringDisjunction
+  ///      ClassSetCharacter
+  /// NestedClass::
+  ///      `[` [lookahead ≠ ^] ClassContents[+U, +V] `]`
+  ///      `[^` ClassContents[+U, +V] `]`
+  ///      `\` CharacterClassEscape[+U]
+  /// ```
+  /// Returns `Ok(true)` if it consumed the next characters successfully.
+  fn consume_class_set_operand(&mut self) -> Result<bool, String> {
+    let start = self.index();
+    let consumed = self.consume_character_class()?
+      || self.consume_class_string_disjunction()?
+      || (self.eat('\\') && self.consume_character_class_escape()?);
+    if consumed {
+      self.last_int_value = -1;
+      return Ok(true);
+    }
+    self.rewind(start);
+    self.last_may_contain_strings = false;
+    self.consume_class_set_character()
+  }
+
+  /// Validate the next characters as a RegExp `ClassStringDisjunction` production if possible.
+  /// Set `self.last_may_contain_strings` if it consumed the next characters
+  /// successfully.
+  /// ```grammar
+  /// ClassStringDisjunction::
+  ///      `\q{` ClassStringDisjunctionContents `}`
+  /// ClassStringDisjunctionContents::
+  ///      ClassString
+  ///      ClassString `|` ClassStringDisjunctionContents
+  /// ClassString::
+  ///      [empty]
+  ///      NonEmptyClassString
+  /// NonEmptyClassString::
+  ///      ClassSetCharacter NonEmptyClassString?
+  /// ```
+  /// Returns `Ok(true)` if it consumed the next characters successfully.
+  fn consume_class_string_disjunction(&mut self) -> Result<bool, String> {
+    let start = self.index();
+    if !self.eat3('\\', 'q', '{') {
+      return Ok(false);
+    }
+    self.emit(|b| b.on_class_string_disjunction_enter(start));
+    let mut may_contain_strings = false;
+    loop {
+      let string_start = self.index();
+      self.emit(|b| b.on_class_string_enter(string_start));
+      let mut length = 0;
+      while self.consume_class_set_character()? {
+        length += 1;
+      }
+      // Strings of a single character are plain characters.
+      may_contain_strings |= length != 1;
+      let string_end = self.index();
+      self.emit(|b| b.on_class_string_leave(string_end));
+      if !self.eat('|') {
+        break;
+      }
+    }
+    if !self.eat('}') {
+      return Err("Invalid escape".to_string());
+    }
+    let end = self.index();
+    self.emit(|b| b.on_class_string_disjunction_leave(end));
+    self.last_may_contain_strings = may_contain_strings;
     Ok(true)
   }
 
+  /// Validate the next characters as a RegExp `ClassSetCharacter` production if possible.
+  /// Set `self.last_int_value` if it consumed the next characters successfully.
+  /// ```grammar
+  /// ClassSetCharacter::
+  ///      [lookahead ∉ ClassSetReservedDoublePunctuator] SourceCharacter but not ClassSetSyntaxCharacter
+  ///      `\` CharacterEscape[+U]
+  ///      `\` ClassSetReservedPunctuator
+  ///      `\b`
+  /// ```
+  /// Returns `Ok(true)` if it consumed the next characters successfully.
+  fn consume_class_set_character(&mut self) -> Result<bool, String> {
+    let start = self.index();
+    let cp = match self.code_point_with_offset(0) {
+      Some(cp) => cp,
+      None => return Ok(false),
+    };
+
+    if cp != '\\' {
+      if is_class_set_syntax_character(cp)
+        || (is_class_set_reserved_double_punctuator(cp)
+          && self.code_point_with_offset(1) == Some(cp))
+      {
+        return Ok(false);
+      }
+      self.last_int_value = self.advance_character() as i64;
+      return Ok(true);
+    }
+
+    self.advance();
+    if self.consume_character_escape()? {
+      self.emit_last_character(start);
+      return Ok(true);
+    }
+    if let Some(cp) = self.code_point_with_offset(0) {
+      if is_class_set_reserved_punctuator(cp) || cp == 'b' {
+        self.advance();
+        self.last_int_value = if cp == 'b' { 0x08 } else { cp as i64 };
+        self.emit_last_character(start);
+        return Ok(true);
+      }
+    }
+    Err("Invalid escape".to_string())
+  }
+
   /// Validate the next characters as a RegExp `ClassRanges` production.
   /// ```grammar
   /// ClassRanges[U]::
@@ -809,6 +1207,7 @@ impl EcmaRegexValidator {
   fn consume_class_ranges(&mut self) -> Result<(), String> {
     loop {
       // Consume the first ClassAtom
+      let start = self.index();
       if !self.consume_class_atom()? {
         break;
       }
@@ -818,6 +1217,8 @@ impl EcmaRegexValidator {
       if !self.eat('-') {
         continue;
       }
+      let dash = self.index() - 1;
+      self.emit(|b| b.on_character(dash, dash + 1, '-' as u32));
 
       // Consume the second ClassAtom
       if !self.consume_class_atom()? {
@@ -836,6 +1237,8 @@ impl EcmaRegexValidator {
       if min > max {
         return Err("Range out of order in character class".to_string());
       }
+      let end = self.index();
+      self.emit(|b| b.on_class_range(start, end));
     }
     Ok(())
   }
@@ -857,18 +1260,22 @@ impl EcmaRegexValidator {
 
     if let Some(cp) = self.code_point_with_offset(0) {
       if cp != '\\' && cp != ']' {
-        self.advance();
-        self.last_int_value = cp as i64;
+        self.last_int_value = self.advance_character() as i64;
         return Ok(true);
       }
     }
 
     if self.eat('\\') {
       if self.consume_class_escape()? {
+        // Character sets are reported by themselves.
+        if self.last_int_value != -1 {
+          self.emit_last_character(start);
+        }
         return Ok(true);
       }
       if !self.strict && self.code_point_with_offset(0) == Some('c') {
         self.last_int_value = '\\' as i64;
+        self.emit_last_character(start);
         return Ok(true);
       }
       if self.strict || self.u_flag {
@@ -987,17 +1394,18 @@ impl EcmaRegexValidator {
     let force_u_flag = !self.u_flag && self.ecma_version >= EcmaVersion::ES2020;
 
     if let Some(mut cp) = self.code_point_with_offset(0) {
+      let raw_cp = self.raw_code_point_with_offset(0).unwrap();
       self.advance();
-      let cp1 = self.code_point_with_offset(0);
+      let cp1 = self.raw_code_point_with_offset(0);
       if cp == '\\' && self.eat_regexp_unicode_escape_sequence(force_u_flag)? {
         cp = std::char::from_u32(self.last_int_value as u32).unwrap();
       } else if force_u_flag
-        && is_lead_surrogate(cp as i64)
+        && is_lead_surrogate(raw_cp as i64)
         && cp1.is_some()
         && is_trail_surrogate(cp1.unwrap() as i64)
       {
         cp = std::char::from_u32(combine_surrogate_pair(
-          cp as i64,
+          raw_cp as i64,
           cp1.unwrap() as i64,
         ) as u32)
         .unwrap();
@@ -1034,8 +1442,9 @@ impl EcmaRegexValidator {
     let start = self.index();
     let force_u_flag = !self.u_flag && self.ecma_version >= EcmaVersion::ES2020;
     let mut cp = self.code_point_with_offset(0);
+    let raw_cp = self.raw_code_point_with_offset(0);
     self.advance();
-    let cp1 = self.code_point_with_offset(0);
+    let cp1 = self.raw_code_point_with_offset(0);
 
     if cp == Some('\\')
       && self.eat_regexp_unicode_escape_sequence(force_u_flag)?
@@ -1043,11 +1452,11 @@ impl EcmaRegexValidator {
       // TODO: convert unicode code point to char
       cp = std::char::from_u32(self.last_int_value as u32);
     } else if force_u_flag
-      && is_lead_surrogate(cp.unwrap() as i64)
-      && is_trail_surrogate(cp1.unwrap() as i64)
+      && raw_cp.map_or(false, |cp| is_lead_surrogate(cp as i64))
+      && cp1.map_or(false, |cp1| is_trail_surrogate(cp1 as i64))
     {
       cp = std::char::from_u32(combine_surrogate_pair(
-        cp.unwrap() as i64,
+        raw_cp.unwrap() as i64,
         cp1.unwrap() as i64,
       ) as u32);
       self.advance();
@@ -1255,7 +1664,8 @@ impl EcmaRegexValidator {
   fn eat_identity_escape(&mut self) -> bool {
     if let Some(cp) = self.code_point_with_offset(0) {
       if self.is_valid_identity_escape(cp) {
-        self.last_int_value = cp as i64;
+        self.last_int_value =
+          self.raw_code_point_with_offset(0).unwrap() as i64;
         self.advance();
         return true;
       }
@@ -1284,7 +1694,7 @@ impl EcmaRegexValidator {
   fn eat_decimal_escape(&mut self) -> bool {
     self.last_int_value = 0;
     if let Some(cp) = self.code_point_with_offset(0) {
-      if cp.is_digit(10) {
+      if cp != '0' && cp.is_digit(10) {
         self.last_int_value =
           10 * self.last_int_value + cp.to_digit(10).unwrap() as i64;
         self.advance();
@@ -1348,6 +1758,17 @@ impl EcmaRegexValidator {
         self.last_val_value = "".to_string();
         return Ok(true);
       }
+      if self.v_flag
+        && is_valid_lone_unicode_property_of_strings(
+          self.ecma_version,
+          &name_or_value,
+        )
+      {
+        self.last_key_value = name_or_value;
+        self.last_val_value = "".to_string();
+        self.last_may_contain_strings = true;
+        return Ok(true);
+      }
       return Err("Invalid property name");
     }
     Ok(false)
@@ -1556,6 +1977,42 @@ impl EcmaRegexValidator {
     true
   }
 
+  /// Validate the next character as `.`.
+  /// Returns `true` if it consumed the next characters successfully.
+  fn consume_dot(&mut self) -> bool {
+    let start = self.index();
+    if self.eat('.') {
+      let kind = CharacterSetKind::Any;
+      self.emit(|b| b.on_character_set(start, start + 1, kind, false));
+      true
+    } else {
+      false
+    }
+  }
+
+  /// Advances past the next character, reports it and returns its value.
+  fn advance_character(&mut self) -> u32 {
+    let start = self.index();
+    let value = self.raw_code_point_with_offset(0).unwrap();
+    self.advance();
+    self.emit(|b| b.on_character(start, start + 1, value));
+    value
+  }
+
+  /// Reports the character escape from `start` to the current position,
+  /// whose value is `self.last_int_value`.
+  fn emit_last_character(&mut self, start: usize) {
+    let end = self.index();
+    let value = self.last_int_value as u32;
+    self.emit(|b| b.on_character(start, end, value));
+  }
+
+  fn emit<F: FnOnce(&mut AstBuilder)>(&mut self, f: F) {
+    if let Some(builder) = &mut self.builder {
+      f(builder);
+    }
+  }
+
   fn count_capturing_parens(&mut self) -> u32 {
     let start = self.index();
     let mut in_class = false;
@@ -10,20 +10,25 @@ extern crate log;
 mod test_util;
 
 pub mod ast_parser;
+pub mod autofix;
+pub mod const_eval;
 // TODO(magurotuna): Making control_flow public is just needed for implementing plugin prototype.
 // It will be likely possible to remove `pub` later.
 pub mod control_flow;
+pub mod dataflow;
 pub mod diagnostic;
+pub mod dispatcher;
 mod globals;
-mod ignore_directives;
+pub mod ignore_directives;
 mod js_regex;
 pub mod linter;
 pub mod rules;
-mod scopes;
+pub mod scopes;
 pub mod swc_util;
 
 #[cfg(test)]
 mod lint_tests {
+  use crate::ast_parser::PARSE_ERROR_CODE;
   use crate::diagnostic::LintDiagnostic;
   use crate::linter::*;
   use crate::rules::{get_recommended_rules, LintRule};
@@ -41,9 +46,8 @@ mod lint_tests {
       .rules(rules)
       .build();
 
-    let (_, diagnostics) = linter
-      .lint("lint_test.ts".to_string(), source.to_string())
-      .expect("Failed to lint");
+    let (_, diagnostics) =
+      linter.lint("lint_test.ts".to_string(), source.to_string());
     diagnostics
   }
 
@@ -209,4 +213,598 @@ const fooBar = 42;
     assert_eq!(diagnostics.len(), 1);
     assert_diagnostic(&diagnostics[0], "ban-unused-ignore", 4, 1, src);
   }
+
+  #[test]
+  fn range_directives() {
+    let src = r#"
+// deno-lint-disable no-explicit-any
+function foo(p: any): any {
+  // deno-lint-disable no-empty
+  if (p) {}
+  // deno-lint-enable
+}
+if (foo) {}
+// deno-lint-enable no-explicit-any
+function bar(p: any) {}
+"#;
+    let diagnostics = lint_recommended_rules(src, true, true);
+
+    assert_eq!(diagnostics.len(), 2);
+    assert_diagnostic(&diagnostics[0], "no-empty", 8, 9, src);
+    assert_diagnostic(&diagnostics[1], "no-explicit-any", 10, 16, src);
+  }
+
+  #[test]
+  fn range_directives_unused_and_unknown() {
+    let src = r#"
+// deno-lint-disable no-explicit-any no-empty some-rule
+function foo(p: any) {}
+// deno-lint-enable
+"#;
+    let diagnostics = lint_recommended_rules(src, true, true);
+
+    assert_eq!(diagnostics.len(), 2);
+    let codes: Vec<&str> =
+      diagnostics.iter().map(|d| d.code.as_str()).collect();
+    assert!(codes.contains(&"ban-unused-ignore"));
+    assert!(codes.contains(&"ban-unknown-rule-code"));
+    assert!(diagnostics.iter().all(|d| d.range.start.line == 2));
+  }
+
+  #[test]
+  fn missing_ignore_reasons() {
+    let src = r#"
+// deno-lint-ignore-file no-empty -- generated code
+// deno-lint-ignore no-explicit-any -- upstream types are wrong
+function foo(p: any) {}
+// deno-lint-ignore no-explicit-any
+function bar(p: any) {}
+"#;
+    let mut linter = LinterBuilder::default()
+      .rules(get_recommended_rules())
+      .lint_unused_ignore_directives(false)
+      .lint_missing_ignore_reasons(true)
+      .build();
+    let (_, diagnostics) =
+      linter.lint("lint_test.ts".to_string(), src.to_string());
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_diagnostic(&diagnostics[0], "ban-unexplained-ignore", 5, 0, src);
+
+    let reasons: Vec<(usize, Option<&str>, bool)> = linter
+      .ignore_directives()
+      .iter()
+      .map(|d| {
+        let used = d.used_codes.values().all(|used| *used);
+        (d.position.line, d.reason.as_deref(), used)
+      })
+      .collect();
+    assert_eq!(
+      reasons,
+      vec![
+        (2, Some("generated code"), false),
+        (3, Some("upstream types are wrong"), true),
+        (5, None, true),
+      ]
+    );
+  }
+
+  #[test]
+  fn ignore_directives_are_cleared_on_parse_error() {
+    let mut linter = LinterBuilder::default()
+      .rules(get_recommended_rules())
+      .build();
+    linter.lint(
+      "good.ts".to_string(),
+      "// deno-lint-ignore no-explicit-any\nfunction foo(p: any) {}\n"
+        .to_string(),
+    );
+    assert_eq!(linter.ignore_directives().len(), 1);
+
+    let (_, diagnostics) =
+      linter.lint("broken.ts".to_string(), "function (".to_string());
+    assert_eq!(diagnostics[0].code, PARSE_ERROR_CODE);
+    assert!(linter.ignore_directives().is_empty());
+  }
+
+  #[test]
+  fn missing_ignore_reason_hint_uses_configured_directive() {
+    let src = r#"
+// lint-ignore no-explicit-any
+function bar(p: any) {}
+"#;
+    let mut linter = LinterBuilder::default()
+      .rules(get_recommended_rules())
+      .ignore_diagnostic_directive("lint-ignore")
+      .lint_missing_ignore_reasons(true)
+      .build();
+    let (_, diagnostics) =
+      linter.lint("lint_test.ts".to_string(), src.to_string());
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_diagnostic(&diagnostics[0], "ban-unexplained-ignore", 2, 0, src);
+    assert_eq!(
+      diagnostics[0].hint.as_deref(),
+      Some(
+        "Explain why after the rule names, e.g. `// lint-ignore no-explicit-any -- upstream types are wrong`"
+      )
+    );
+  }
+
+  fn lint_span_aware(
+    file_name: &str,
+    src: &str,
+    span_aware: bool,
+  ) -> Vec<LintDiagnostic> {
+    let mut linter = LinterBuilder::default()
+      .rules(get_recommended_rules())
+      .detect_syntax(true)
+      .span_aware_ignore_directives(span_aware)
+      .build();
+    let (_, diagnostics) = linter.lint(file_name.to_string(), src.to_string());
+    diagnostics
+  }
+
+  #[test]
+  fn span_aware_ignore_chained_calls() {
+    let src = r#"
+export function chain(list: number[]) {
+  // deno-lint-ignore no-explicit-any
+  return list
+    .map((n) => n * 2)
+    .filter((n: any) => n > 2);
+}
+"#;
+    assert!(lint_span_aware("lint_test.ts", src, true).is_empty());
+
+    let diagnostics = lint_span_aware("lint_test.ts", src, false);
+    assert_eq!(diagnostics.len(), 2);
+    assert_diagnostic(&diagnostics[0], "ban-unused-ignore", 3, 2, src);
+    assert_diagnostic(&diagnostics[1], "no-explicit-any", 6, 16, src);
+  }
+
+  #[test]
+  fn span_aware_ignore_jsx() {
+    let src = r#"
+export function App(props: { name: string }) {
+  // deno-lint-ignore no-explicit-any
+  const title = (
+    <b>{(props.name as any).toUpperCase()}</b>
+  );
+  return (
+    // deno-lint-ignore no-explicit-any
+    <div
+      title={props.name as any}
+    >
+      {title}
+    </div>
+  );
+}
+"#;
+    assert!(lint_span_aware("lint_test.tsx", src, true).is_empty());
+
+    let diagnostics = lint_span_aware("lint_test.tsx", src, false);
+    let codes: Vec<(usize, &str)> = diagnostics
+      .iter()
+      .map(|d| (d.range.start.line, d.code.as_str()))
+      .collect();
+    assert_eq!(
+      codes,
+      vec![
+        (3, "ban-unused-ignore"),
+        (5, "no-explicit-any"),
+        (8, "ban-unused-ignore"),
+        (10, "no-explicit-any"),
+      ]
+    );
+  }
+
+  #[test]
+  fn span_aware_ignore_class_members() {
+    let src = r#"
+export class A {
+  // deno-lint-ignore no-explicit-any
+  method(
+    a: any,
+  ): void {}
+
+  other(b: any): void {}
+}
+"#;
+    let diagnostics = lint_span_aware("lint_test.ts", src, true);
+    assert_eq!(diagnostics.len(), 1);
+    assert_diagnostic(&diagnostics[0], "no-explicit-any", 8, 11, src);
+
+    let diagnostics = lint_span_aware("lint_test.ts", src, false);
+    assert_eq!(diagnostics.len(), 3);
+    assert_diagnostic(&diagnostics[0], "ban-unused-ignore", 3, 2, src);
+    assert_diagnostic(&diagnostics[1], "no-explicit-any", 5, 7, src);
+  }
+
+  #[test]
+  fn eslint_directives() {
+    let src = r#"
+// eslint-disable-next-line @typescript-eslint/no-explicit-any
+function foo(p: any) {}
+function bar(p: any) {} // eslint-disable-line no-explicit-any
+// eslint-disable-next-line @typescript-eslint/no-unsafe-call
+function baz(p: any) {}
+/* eslint-disable */
+function qux(p: any) {}
+"#;
+    let lint_with = |eslint_directives: bool| {
+      let mut linter = LinterBuilder::default()
+        .rules(get_recommended_rules())
+        .eslint_directives(eslint_directives)
+        .build();
+      let (_, diagnostics) =
+        linter.lint("lint_test.ts".to_string(), src.to_string());
+      diagnostics
+    };
+
+    let diagnostics = lint_with(true);
+    assert_eq!(diagnostics.len(), 2);
+    assert_diagnostic(&diagnostics[0], "ban-unknown-rule-code", 5, 0, src);
+    assert!(diagnostics[0]
+      .message
+      .contains("@typescript-eslint/no-unsafe-call"));
+    assert_diagnostic(&diagnostics[1], "no-explicit-any", 6, 16, src);
+
+    assert_eq!(lint_with(false).len(), 4);
+  }
+
+  #[test]
+  fn unclosed_and_unmatched_range_directives() {
+    let src = r#"
+// deno-lint-enable no-empty
+// deno-lint-disable no-explicit-any
+function foo(p: any) {}
+// deno-lint-disable no-empty
+if (foo) {}
+// deno-lint-enable no-explicit-any
+"#;
+    let diagnostics = lint_recommended_rules(src, true, true);
+
+    assert_eq!(diagnostics.len(), 4);
+    assert_diagnostic(&diagnostics[0], "ban-unmatched-enable", 2, 0, src);
+    // Unclosed ranges still disable rules until the end of the file.
+    assert_diagnostic(&diagnostics[1], "ban-unclosed-disable", 3, 0, src);
+    assert_diagnostic(&diagnostics[2], "ban-unclosed-disable", 5, 0, src);
+    assert_diagnostic(&diagnostics[3], "ban-unmatched-enable", 7, 0, src);
+  }
+
+  #[test]
+  fn linter_is_reusable() {
+    use crate::rules::no_debugger::NoDebugger;
+    use crate::rules::no_empty::NoEmpty;
+    let mut linter = LinterBuilder::default()
+      .rules(vec![NoDebugger::new(), NoEmpty::new()])
+      .build();
+
+    let src = "function foo() { debugger; }";
+    let (_, diagnostics) = linter.lint("a.ts".to_string(), src.to_string());
+    assert_eq!(diagnostics.len(), 1);
+    assert_diagnostic(&diagnostics[0], "no-debugger", 1, 17, src);
+
+    // A parse error doesn't affect subsequent files.
+    let (_, diagnostics) =
+      linter.lint("b.ts".to_string(), "function {".to_string());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, PARSE_ERROR_CODE);
+
+    let src = "\n// deno-lint-ignore no-empty\nwhile (a) {}\nif (b) {}";
+    let (source_file, diagnostics) =
+      linter.lint("a.ts".to_string(), src.to_string());
+    assert_eq!(&*source_file.src, src);
+    assert_eq!(diagnostics.len(), 1);
+    assert_diagnostic(&diagnostics[0], "no-empty", 4, 7, src);
+  }
+
+  #[test]
+  fn rules_are_shared_across_threads() {
+    use std::sync::Arc;
+
+    use crate::rules::no_debugger::NoDebugger;
+    let rules: Arc<Vec<Box<dyn LintRule>>> = Arc::new(vec![NoDebugger::new()]);
+    let handles: Vec<_> = (0..4)
+      .map(|i| {
+        let rules = rules.clone();
+        std::thread::spawn(move || {
+          let mut linter = LinterBuilder::default().shared_rules(rules).build();
+          (0..3)
+            .map(|j| {
+              let src = format!("function f{}_{}() {{ debugger; }}", i, j);
+              let (_, diagnostics) =
+                linter.lint(format!("file{}_{}.ts", i, j), src);
+              diagnostics.len()
+            })
+            .sum::<usize>()
+        })
+      })
+      .collect();
+
+    for handle in handles {
+      assert_eq!(handle.join().unwrap(), 3);
+    }
+  }
+
+  #[test]
+  fn severity_overrides() {
+    use crate::diagnostic::Severity;
+    use crate::rules::no_debugger::NoDebugger;
+    use crate::rules::no_empty::NoEmpty;
+    use std::collections::HashMap;
+
+    let src = r#"
+// deno-lint-ignore no-empty
+if (a) {}
+debugger;
+// deno-lint-ignore no-debugger
+while (b) {}
+"#;
+    let lint_with_severities = |severities: &[(&str, Severity)]| {
+      let severities: HashMap<String, Severity> = severities
+        .iter()
+        .map(|(code, severity)| (code.to_string(), *severity))
+        .collect();
+      let mut linter = LinterBuilder::default()
+        .rules(vec![NoDebugger::new(), NoEmpty::new()])
+        .severities(severities)
+        .build();
+      let (_, diagnostics) =
+        linter.lint("lint_test.ts".to_string(), src.to_string());
+      diagnostics
+    };
+
+    let diagnostics = lint_with_severities(&[]);
+    assert_eq!(diagnostics.len(), 3);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_diagnostic(&diagnostics[1], "ban-unused-ignore", 5, 0, src);
+    assert_eq!(diagnostics[1].severity, Severity::Warning);
+    assert_eq!(diagnostics[2].severity, Severity::Error);
+
+    let diagnostics = lint_with_severities(&[
+      ("no-debugger", Severity::Warning),
+      ("ban-unused-ignore", Severity::Info),
+    ]);
+    assert_eq!(diagnostics.len(), 3);
+    assert_diagnostic(&diagnostics[0], "no-debugger", 4, 0, src);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+    assert_diagnostic(&diagnostics[1], "ban-unused-ignore", 5, 0, src);
+    assert_eq!(diagnostics[1].severity, Severity::Info);
+    assert_diagnostic(&diagnostics[2], "no-empty", 6, 10, src);
+    assert_eq!(diagnostics[2].severity, Severity::Error);
+
+    // Ignore directives for disabled rules are not reported as unused.
+    let diagnostics = lint_with_severities(&[("no-debugger", Severity::Off)]);
+    assert_eq!(diagnostics.len(), 1);
+    assert_diagnostic(&diagnostics[0], "no-empty", 6, 10, src);
+  }
+
+  #[test]
+  fn rule_default_severities() {
+    use crate::diagnostic::Severity;
+    use crate::rules::prefer_named_capture_group::PreferNamedCaptureGroup;
+    use std::collections::HashMap;
+
+    let lint_with_severities = |severities: HashMap<String, Severity>| {
+      let mut linter = LinterBuilder::default()
+        .rules(vec![PreferNamedCaptureGroup::new()])
+        .severities(severities)
+        .build();
+      let (_, diagnostics) =
+        linter.lint("lint_test.ts".to_string(), "/(a)/;".to_string());
+      diagnostics
+    };
+
+    let diagnostics = lint_with_severities(HashMap::new());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Warning);
+
+    let mut severities = HashMap::new();
+    severities
+      .insert("prefer-named-capture-group".to_string(), Severity::Error);
+    let diagnostics = lint_with_severities(severities);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+  }
+
+  #[test]
+  fn parse_errors_are_diagnostics() {
+    use crate::rules::no_debugger::NoDebugger;
+    let mut linter = LinterBuilder::default()
+      .rules(vec![NoDebugger::new()])
+      .build();
+
+    // The parser can't recover, so rules are not run.
+    let src = "debugger;\nfunction foo( {";
+    let (_, diagnostics) = linter.lint("a.ts".to_string(), src.to_string());
+    assert_eq!(diagnostics.len(), 1);
+    assert_diagnostic(&diagnostics[0], PARSE_ERROR_CODE, 2, 15, src);
+    assert_eq!(diagnostics[0].filename, "a.ts");
+
+    // The parser recovers from the error, so rules are run as well.
+    let src = "debugger;\nlet a = 07;";
+    let (_, diagnostics) = linter.lint("b.ts".to_string(), src.to_string());
+    assert_eq!(diagnostics.len(), 1);
+    assert_diagnostic(&diagnostics[0], "no-debugger", 1, 0, src);
+
+    let mut linter = LinterBuilder::default()
+      .rules(vec![NoDebugger::new()])
+      .report_recovered_parse_errors(true)
+      .build();
+    let (_, diagnostics) = linter.lint("b.ts".to_string(), src.to_string());
+    assert_eq!(diagnostics.len(), 2);
+    assert_diagnostic(&diagnostics[0], "no-debugger", 1, 0, src);
+    assert_diagnostic(&diagnostics[1], PARSE_ERROR_CODE, 2, 8, src);
+  }
+
+  #[test]
+  fn syntax_from_file_name() {
+    use crate::ast_parser::get_default_es_config;
+    use crate::rules::no_debugger::NoDebugger;
+    let mut linter = LinterBuilder::default()
+      .rules(vec![NoDebugger::new()])
+      .detect_syntax(true)
+      .syntax_override(|file_name| {
+        if file_name.ends_with(".es") {
+          Some(get_default_es_config())
+        } else {
+          None
+        }
+      })
+      .build();
+
+    let mut lint = |file_name: &str, src: &str| {
+      let (_, diagnostics) =
+        linter.lint(file_name.to_string(), src.to_string());
+      diagnostics.into_iter().map(|d| d.code).collect::<Vec<_>>()
+    };
+
+    let jsx = "const a = <div>{b}</div>;\ndebugger;";
+    assert_eq!(lint("a.tsx", jsx), vec!["no-debugger"]);
+    assert_eq!(lint("a.jsx", jsx), vec!["no-debugger"]);
+    assert_eq!(lint("a.ts", jsx)[0], PARSE_ERROR_CODE);
+
+    let generic = "const a = <T>(b: T) => b;\ndebugger;";
+    assert_eq!(lint("a.ts", generic), vec!["no-debugger"]);
+    assert_eq!(lint("a.js", generic)[0], PARSE_ERROR_CODE);
+    assert_eq!(lint("a.mjs", "debugger;"), vec!["no-debugger"]);
+    assert_eq!(lint("a.cjs", "debugger;"), vec!["no-debugger"]);
+    assert_eq!(
+      lint("a.d.ts", "declare const a: number;"),
+      Vec::<String>::new()
+    );
+
+    // Unknown extensions use the configured syntax.
+    assert_eq!(lint("a.vue", generic), vec!["no-debugger"]);
+    assert_eq!(lint("a.es", generic)[0], PARSE_ERROR_CODE);
+  }
+
+  #[test]
+  fn lint_pre_parsed_program() {
+    use crate::ast_parser::{get_default_ts_config, AstParser};
+    use crate::rules::no_debugger::NoDebugger;
+    use crate::rules::no_undef::NoUndef;
+
+    // The host's parser, with its own source map, globals and mark.
+    let host = AstParser::new();
+    let src = r#"
+const a = 1;
+// deno-lint-ignore no-debugger
+debugger;
+console.log(a, b);
+"#;
+    let parsed =
+      host.parse_program("host.ts", get_default_ts_config(), src.to_string());
+    let (program, comments) = parsed.program.unwrap();
+    let source_map = parsed.source_map;
+    let top_level_mark = parsed.top_level_mark;
+
+    let mut linter = LinterBuilder::default()
+      .rules(vec![NoDebugger::new(), NoUndef::new()])
+      .build();
+    let diagnostics = swc_common::GLOBALS.set(&parsed.globals, || {
+      linter.lint_program(
+        "host.ts".to_string(),
+        &program,
+        &comments,
+        source_map.clone(),
+        top_level_mark,
+      )
+    });
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_diagnostic(&diagnostics[0], "no-undef", 5, 15, src);
+    assert_eq!(diagnostics[0].filename, "host.ts");
+
+    // The comments are still usable by the host.
+    assert!(comments.borrow_all().0.values().any(|c| !c.is_empty()));
+  }
+
+  #[test]
+  fn node_rules_skip_types_unless_they_check_types() {
+    let src = r#"
+namespace Foo {
+  debugger;
+  new Symbol();
+  namespace Bar {}
+}
+export enum E { A = 07 }
+"#;
+    let mut linter = LinterBuilder::default()
+      .rules(crate::rules::get_all_rules())
+      .build();
+    let (_, diagnostics) =
+      linter.lint("lint_test.ts".to_string(), src.to_string());
+    let diagnostics = diagnostics
+      .into_iter()
+      .map(|d| (d.range.start.line, d.code))
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      diagnostics,
+      vec![
+        (2, "no-namespace".to_string()),
+        (4, "no-new-symbol".to_string()),
+        (5, "no-namespace".to_string()),
+        (7, "no-octal".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn single_traversal_reports_node_rules() {
+    let src = r#"
+debugger;
+with (a) { debugger; }
+const arr = [1, , new Array(1, 2)];
+if (!key in obj && x === -0) {}
+new Promise(async () => { delete arr; });
+new Symbol();
+namespace Foo {}
+const n = 07;
+switch (a) { case 1: case 1: throw 1; }
+const o = { a: 1, a: Math() }, e = eval;
+"#;
+    let expected = vec![
+      (2, 0, "no-debugger"),
+      (3, 0, "no-with"),
+      (3, 11, "no-debugger"),
+      (4, 12, "no-sparse-arrays"),
+      (4, 18, "no-array-constructor"),
+      (5, 4, "no-unsafe-negation"),
+      (5, 19, "no-compare-neg-zero"),
+      (6, 0, "no-async-promise-executor"),
+      (6, 26, "no-delete-var"),
+      (7, 0, "no-new-symbol"),
+      (8, 0, "no-namespace"),
+      (9, 10, "no-octal"),
+      (10, 21, "no-duplicate-case"),
+      (10, 29, "no-throw-literal"),
+      (11, 0, "single-var-declarator"),
+      (11, 10, "no-dupe-keys"),
+      (11, 21, "no-obj-calls"),
+      (11, 31, "no-eval"),
+    ];
+
+    for single_traversal in &[true, false] {
+      let rules = crate::rules::get_all_rules()
+        .into_iter()
+        .filter(|rule| expected.iter().any(|(_, _, code)| *code == rule.code()))
+        .collect();
+      let mut linter = LinterBuilder::default()
+        .rules(rules)
+        .single_traversal(*single_traversal)
+        .build();
+      let (_, diagnostics) =
+        linter.lint("lint_test.ts".to_string(), src.to_string());
+      let mut actual = diagnostics
+        .iter()
+        .map(|d| (d.range.start.line, d.range.start.col, d.code.as_str()))
+        .collect::<Vec<_>>();
+      actual.sort();
+      assert_eq!(actual, expected, "single_traversal: {}", single_traversal);
+    }
+  }
 }
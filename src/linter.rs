@@ -1,28 +1,50 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use crate::ast_parser::get_default_ts_config;
+use crate::ast_parser::get_syntax_for_file;
 use crate::ast_parser::AstParser;
-use crate::ast_parser::SwcDiagnosticBuffer;
-use crate::control_flow::ControlFlow;
-use crate::diagnostic::{LintDiagnostic, Position, Range};
+use crate::const_eval::{ConstBindings, ConstEvaluator};
+use crate::control_flow::{Cfg, ControlFlow};
+use crate::diagnostic::{
+  LintDiagnostic, LintFix, LintFixChange, Range, Severity,
+};
+use crate::dispatcher::run_node_rules;
+use crate::ignore_directives::extend_to_next_node;
 use crate::ignore_directives::parse_ignore_comment;
 use crate::ignore_directives::parse_ignore_directives;
+use crate::ignore_directives::remove_code_from_comment;
+use crate::ignore_directives::CodeRemoval;
 use crate::ignore_directives::IgnoreDirective;
+use crate::ignore_directives::IgnoreDirectiveKind;
 use crate::rules::{get_all_rules, LintRule};
 use crate::scopes::Scope;
+use once_cell::unsync::OnceCell;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Instant;
 use swc_common::comments::SingleThreadedComments;
 use swc_common::BytePos;
+use swc_common::Mark;
 use swc_common::SourceMap;
 use swc_common::Span;
 use swc_common::Spanned;
 use swc_common::{comments::Comment, SyntaxContext};
+use swc_ecmascript::ast::Program;
 use swc_ecmascript::parser::Syntax;
 
+pub use crate::js_regex::EcmaVersion;
 pub use swc_common::SourceFile;
 
+/// Default severities of the diagnostics about ignore directives. Leftover
+/// or unexplained directives are untidy but harmless, while malformed ranges
+/// silently disable rules and stay errors.
+const DIRECTIVE_SEVERITIES: &[(&str, Severity)] = &[
+  ("ban-unused-ignore", Severity::Warning),
+  ("ban-unknown-rule-code", Severity::Warning),
+  ("ban-unexplained-ignore", Severity::Warning),
+];
+
 pub struct Context {
   pub file_name: String,
   pub diagnostics: Vec<LintDiagnostic>,
@@ -31,14 +53,50 @@ pub struct Context {
   pub(crate) leading_comments: HashMap<BytePos, Vec<Comment>>,
   pub(crate) trailing_comments: HashMap<BytePos, Vec<Comment>>,
   pub ignore_directives: RefCell<Vec<IgnoreDirective>>,
+  /// Spans of enable directives that don't close any disabled range.
+  unmatched_enable_directives: Vec<Span>,
   pub(crate) scope: Scope,
+  const_bindings: OnceCell<ConstBindings>,
   // TODO(magurotuna): Making control_flow public is just needed for implementing plugin prototype.
   // It will be likely possible to revert it to `pub(crate)` later.
   pub control_flow: ControlFlow,
+  cfg: OnceCell<Cfg>,
   pub(crate) top_level_ctxt: SyntaxContext,
+  severities: Arc<HashMap<String, Severity>>,
+  ecma_version: EcmaVersion,
 }
 
 impl Context {
+  /// Scope tree of the program being linted, with every binding and
+  /// reference in it.
+  pub fn scope(&self) -> &Scope {
+    &self.scope
+  }
+
+  /// Evaluator of constant expressions, which knows the values of the
+  /// constant `const` bindings of the program being linted. The bindings are
+  /// only collected the first time this is called, so `program` must always
+  /// be the program being linted.
+  pub fn const_evaluator(&self, program: &Program) -> ConstEvaluator<'_> {
+    let bindings = self
+      .const_bindings
+      .get_or_init(|| ConstBindings::analyze(program, &self.scope));
+    ConstEvaluator::new(&self.scope, bindings)
+  }
+
+  /// Control flow graph of the program being linted. The graph is only built
+  /// the first time this is called, so `program` must always be the program
+  /// being linted.
+  pub fn cfg(&self, program: &Program) -> &Cfg {
+    self.cfg.get_or_init(|| Cfg::build(program))
+  }
+
+  /// ECMAScript version the linted code targets, see
+  /// `LinterBuilder::ecma_version`.
+  pub fn ecma_version(&self) -> EcmaVersion {
+    self.ecma_version
+  }
+
   pub fn add_diagnostic(
     &mut self,
     span: Span,
@@ -62,6 +120,49 @@ impl Context {
     self.diagnostics.push(diagnostic);
   }
 
+  pub fn add_diagnostic_with_fix(
+    &mut self,
+    span: Span,
+    code: impl ToString,
+    message: impl ToString,
+    fix: LintFix,
+  ) {
+    self.add_diagnostic_with_fixes(span, code, message, None, vec![fix]);
+  }
+
+  /// Add a diagnostic with several alternative fixes, the first one being
+  /// the preferred one which is used when fixes are applied automatically.
+  pub fn add_diagnostic_with_fixes(
+    &mut self,
+    span: Span,
+    code: impl ToString,
+    message: impl ToString,
+    maybe_hint: Option<String>,
+    fixes: Vec<LintFix>,
+  ) {
+    let mut diagnostic =
+      self.create_diagnostic(span, code, message, maybe_hint);
+    diagnostic.fixes = fixes;
+    self.diagnostics.push(diagnostic);
+  }
+
+  /// Create a change that replaces source text covered by `span` with
+  /// `new_text`, to be used in a `LintFix`.
+  pub fn create_fix_change(
+    &self,
+    span: Span,
+    new_text: impl ToString,
+  ) -> LintFixChange {
+    LintFixChange {
+      range: self.create_range(span),
+      new_text: new_text.to_string(),
+    }
+  }
+
+  fn create_range(&self, span: Span) -> Range {
+    Range::new(&self.source_map, span)
+  }
+
   fn create_diagnostic(
     &self,
     span: Span,
@@ -70,21 +171,21 @@ impl Context {
     maybe_hint: Option<String>,
   ) -> LintDiagnostic {
     let time_start = Instant::now();
-    let start = Position::new(
-      self.source_map.lookup_byte_offset(span.lo()).pos,
-      self.source_map.lookup_char_pos(span.lo()),
-    );
-    let end = Position::new(
-      self.source_map.lookup_byte_offset(span.hi()).pos,
-      self.source_map.lookup_char_pos(span.hi()),
-    );
 
+    let code = code.to_string();
+    let severity = self
+      .severities
+      .get(&code)
+      .copied()
+      .unwrap_or(Severity::Error);
     let diagnostic = LintDiagnostic {
-      range: Range { start, end },
+      range: self.create_range(span),
       filename: self.file_name.clone(),
       message: message.to_string(),
-      code: code.to_string(),
+      code,
+      severity,
       hint: maybe_hint,
+      fixes: vec![],
     };
 
     let time_end = Instant::now();
@@ -100,13 +201,112 @@ impl Context {
   }
 }
 
+/// Creates a fix that removes `code` from an ignore directive, or the whole
+/// directive if it doesn't have other codes. There's no fix if the code can't
+/// be found in the comment.
+fn create_remove_code_fix(
+  context: &Context,
+  directive: &IgnoreDirective,
+  code: &str,
+) -> Option<LintFix> {
+  let comment_text = context.source_map.span_to_snippet(directive.span).ok()?;
+
+  match remove_code_from_comment(&comment_text, directive, code) {
+    CodeRemoval::Text(new_text) => {
+      return Some(LintFix {
+        description: format!("Remove \"{}\" from the directive", code),
+        changes: vec![context.create_fix_change(directive.span, new_text)],
+      });
+    }
+    CodeRemoval::NotFound => return None,
+    CodeRemoval::Directive => {}
+  }
+
+  let mut changes = vec![context.create_fix_change(
+    comment_removal_span(&context.source_map, directive.span),
+    "",
+  )];
+  // A range must be removed together with the enable directive closing it.
+  // ESLint directives are skipped, an `eslint-enable` can close many ranges.
+  if let IgnoreDirectiveKind::Range { end: Some(end) } = &directive.kind {
+    if !directive.is_eslint {
+      let enable_span = context
+        .leading_comments
+        .values()
+        .chain(context.trailing_comments.values())
+        .flatten()
+        .map(|comment| comment.span)
+        .find(|span| span.lo().0 as usize == end.byte_pos)?;
+      changes.push(context.create_fix_change(
+        comment_removal_span(&context.source_map, enable_span),
+        "",
+      ));
+    }
+  }
+
+  Some(LintFix {
+    description: "Remove the directive".to_string(),
+    changes,
+  })
+}
+
+/// Span to remove when deleting the comment at `span`, including the whole
+/// line if the comment is on its own line.
+fn comment_removal_span(source_map: &SourceMap, span: Span) -> Span {
+  let file = source_map.lookup_char_pos(span.lo()).file;
+  let src = file.src.as_str();
+  let lo = (span.lo() - file.start_pos).0 as usize;
+  let hi = (span.hi() - file.start_pos).0 as usize;
+
+  let is_blank = |c: char| c == ' ' || c == '\t';
+  let before = src[..lo].trim_end_matches(is_blank);
+  let after = src[hi..].trim_start_matches(is_blank);
+  let (lo, hi) = if before.is_empty() || before.ends_with('\n') {
+    if let Some(rest) = after
+      .strip_prefix("\r\n")
+      .or_else(|| after.strip_prefix('\n'))
+    {
+      (before.len(), src.len() - rest.len())
+    } else if after.is_empty() {
+      (before.len(), src.len())
+    } else {
+      // Code follows the comment on the same line, keep the indentation.
+      (lo, src.len() - after.len())
+    }
+  } else {
+    // A trailing comment, remove the whitespace in front of it.
+    (before.len(), hi)
+  };
+
+  Span::new(
+    file.start_pos + BytePos(lo as u32),
+    file.start_pos + BytePos(hi as u32),
+    span.ctxt,
+  )
+}
+
+/// Hook that picks the syntax for a file name, see
+/// `LinterBuilder::syntax_override`.
+pub type SyntaxOverride = Box<dyn Fn(&str) -> Option<Syntax>>;
+
 pub struct LinterBuilder {
   ignore_file_directive: String,
   ignore_diagnostic_directive: String,
+  disable_directive: String,
+  enable_directive: String,
   lint_unused_ignore_directives: bool,
   lint_unknown_rules: bool,
+  lint_missing_ignore_reasons: bool,
+  span_aware_ignore_directives: bool,
+  eslint_directives: bool,
+  report_recovered_parse_errors: bool,
+  single_traversal: bool,
   syntax: swc_ecmascript::parser::Syntax,
-  rules: Vec<Box<dyn LintRule>>,
+  detect_syntax: bool,
+  syntax_override: Option<SyntaxOverride>,
+  ecma_version: EcmaVersion,
+  rules: Arc<Vec<Box<dyn LintRule>>>,
+  severities: HashMap<String, Severity>,
   plugins: Vec<Box<dyn Plugin>>,
 }
 
@@ -115,10 +315,21 @@ impl LinterBuilder {
     Self {
       ignore_file_directive: "deno-lint-ignore-file".to_string(),
       ignore_diagnostic_directive: "deno-lint-ignore".to_string(),
+      disable_directive: "deno-lint-disable".to_string(),
+      enable_directive: "deno-lint-enable".to_string(),
       lint_unused_ignore_directives: true,
       lint_unknown_rules: true,
+      lint_missing_ignore_reasons: false,
+      span_aware_ignore_directives: false,
+      eslint_directives: false,
+      report_recovered_parse_errors: false,
+      single_traversal: true,
       syntax: get_default_ts_config(),
-      rules: vec![],
+      detect_syntax: false,
+      syntax_override: None,
+      ecma_version: EcmaVersion::LATEST,
+      rules: Arc::new(vec![]),
+      severities: HashMap::new(),
       plugins: vec![],
     }
   }
@@ -127,10 +338,21 @@ impl LinterBuilder {
     Linter::new(
       self.ignore_file_directive,
       self.ignore_diagnostic_directive,
+      self.disable_directive,
+      self.enable_directive,
       self.lint_unused_ignore_directives,
       self.lint_unknown_rules,
+      self.lint_missing_ignore_reasons,
+      self.span_aware_ignore_directives,
+      self.eslint_directives,
+      self.report_recovered_parse_errors,
+      self.single_traversal,
       self.syntax,
+      self.detect_syntax,
+      self.syntax_override,
+      self.ecma_version,
       self.rules,
+      self.severities,
       self.plugins,
     )
   }
@@ -145,6 +367,21 @@ impl LinterBuilder {
     self
   }
 
+  /// Directive that disables the given rules until a matching enable
+  /// directive, e.g. `// deno-lint-disable no-explicit-any`.
+  pub fn disable_directive(mut self, directive: &str) -> Self {
+    self.disable_directive = directive.to_owned();
+    self
+  }
+
+  /// Directive that closes the innermost range opened by a disable
+  /// directive, e.g. `// deno-lint-enable no-explicit-any`. Codes are
+  /// optional, but if given they must match the ones of the range.
+  pub fn enable_directive(mut self, directive: &str) -> Self {
+    self.enable_directive = directive.to_owned();
+    self
+  }
+
   pub fn lint_unused_ignore_directives(
     mut self,
     lint_unused_ignore_directives: bool,
@@ -158,92 +395,307 @@ impl LinterBuilder {
     self
   }
 
+  /// Report ignore directives that don't give a reason after `--`, e.g.
+  /// `// deno-lint-ignore no-explicit-any -- upstream types are wrong`.
+  pub fn lint_missing_ignore_reasons(
+    mut self,
+    lint_missing_ignore_reasons: bool,
+  ) -> Self {
+    self.lint_missing_ignore_reasons = lint_missing_ignore_reasons;
+    self
+  }
+
+  /// Make `deno-lint-ignore` directives suppress diagnostics anywhere in the
+  /// statement, declaration, class member, object property or JSX element
+  /// starting on the next line, instead of only on the next line itself.
+  pub fn span_aware_ignore_directives(
+    mut self,
+    span_aware_ignore_directives: bool,
+  ) -> Self {
+    self.span_aware_ignore_directives = span_aware_ignore_directives;
+    self
+  }
+
+  /// Understand ESLint's suppression comments (`eslint-disable-next-line`,
+  /// `eslint-disable-line`, `eslint-disable` and `eslint-enable`) in
+  /// addition to our own directives. ESLint and typescript-eslint rule names
+  /// are mapped to the codes of the equivalent rules, names that can't be
+  /// mapped are reported as unknown rules.
+  pub fn eslint_directives(mut self, eslint_directives: bool) -> Self {
+    self.eslint_directives = eslint_directives;
+    self
+  }
+
+  /// Report syntax errors the parser was able to recover from. They are off
+  /// by default, because many of them (e.g. legacy octal literals or `with`
+  /// statements) are covered by rules with more helpful messages.
+  pub fn report_recovered_parse_errors(
+    mut self,
+    report_recovered_parse_errors: bool,
+  ) -> Self {
+    self.report_recovered_parse_errors = report_recovered_parse_errors;
+    self
+  }
+
+  /// Run all node rules (see `dispatcher::NodeRule`) in a single traversal
+  /// of the program. Other rules always traverse the program on their own.
+  /// This is the default; turning it off makes node rules traverse the
+  /// program on their own as well, which is only useful for comparing
+  /// performance.
+  pub fn single_traversal(mut self, single_traversal: bool) -> Self {
+    self.single_traversal = single_traversal;
+    self
+  }
+
   pub fn syntax(mut self, syntax: Syntax) -> Self {
     self.syntax = syntax;
     self
   }
 
+  /// Pick the syntax from the extension of the linted file, see
+  /// `ast_parser::get_syntax_for_file`. The syntax set with `syntax` is
+  /// used for files with unknown extensions.
+  pub fn detect_syntax(mut self, detect_syntax: bool) -> Self {
+    self.detect_syntax = detect_syntax;
+    self
+  }
+
+  /// Set a hook that is asked for the syntax of every linted file first.
+  /// Returning `None` falls back to the usual syntax selection.
+  pub fn syntax_override(
+    mut self,
+    syntax_override: impl Fn(&str) -> Option<Syntax> + 'static,
+  ) -> Self {
+    self.syntax_override = Some(Box::new(syntax_override));
+    self
+  }
+
+  /// ECMAScript version the linted code targets. Rules checking regular
+  /// expressions reject flags and syntax newer than it. Defaults to
+  /// `EcmaVersion::LATEST`.
+  pub fn ecma_version(mut self, ecma_version: EcmaVersion) -> Self {
+    self.ecma_version = ecma_version;
+    self
+  }
+
   pub fn rules(mut self, rules: Vec<Box<dyn LintRule>>) -> Self {
+    self.rules = Arc::new(rules);
+    self
+  }
+
+  /// Use rule instances that are shared with other linters, e.g. when
+  /// every thread of a pool builds its own `Linter`.
+  pub fn shared_rules(mut self, rules: Arc<Vec<Box<dyn LintRule>>>) -> Self {
     self.rules = rules;
     self
   }
 
+  /// Override the severity of diagnostics by rule code. Rules set to
+  /// `Severity::Off` are not run at all.
+  pub fn severities(mut self, severities: HashMap<String, Severity>) -> Self {
+    self.severities = severities;
+    self
+  }
+
   pub fn add_plugin(mut self, plugin: Box<dyn Plugin>) -> Self {
     self.plugins.push(plugin);
     self
   }
 }
 
+/// Linter can be used to lint any number of files. Everything that is
+/// specific to a single file lives in a `Context` created for each `lint`
+/// call, while rules, plugins and the parser are reused.
 pub struct Linter {
-  has_linted: bool,
   ast_parser: AstParser,
   ignore_file_directive: String,
   ignore_diagnostic_directive: String,
+  disable_directive: String,
+  enable_directive: String,
   lint_unused_ignore_directives: bool,
   lint_unknown_rules: bool,
+  lint_missing_ignore_reasons: bool,
+  span_aware_ignore_directives: bool,
+  /// Codes ESLint rule names are mapped to, if ESLint directives are enabled.
+  eslint_rule_codes: Option<HashSet<String>>,
+  report_recovered_parse_errors: bool,
+  single_traversal: bool,
   syntax: Syntax,
-  rules: Vec<Box<dyn LintRule>>,
+  detect_syntax: bool,
+  syntax_override: Option<SyntaxOverride>,
+  ecma_version: EcmaVersion,
+  rules: Arc<Vec<Box<dyn LintRule>>>,
+  /// Severity of every known code, with overrides applied.
+  severities: Arc<HashMap<String, Severity>>,
   plugins: Vec<Box<dyn Plugin>>,
+  /// Ignore directives of the last linted file.
+  ignore_directives: Vec<IgnoreDirective>,
+  /// Number of syntax errors in the last linted file, including the ones
+  /// the parser recovered from.
+  parse_error_count: usize,
 }
 
 impl Linter {
+  #[allow(clippy::too_many_arguments)]
   fn new(
     ignore_file_directive: String,
     ignore_diagnostic_directive: String,
+    disable_directive: String,
+    enable_directive: String,
     lint_unused_ignore_directives: bool,
     lint_unknown_rules: bool,
+    lint_missing_ignore_reasons: bool,
+    span_aware_ignore_directives: bool,
+    eslint_directives: bool,
+    report_recovered_parse_errors: bool,
+    single_traversal: bool,
     syntax: Syntax,
-    rules: Vec<Box<dyn LintRule>>,
+    detect_syntax: bool,
+    syntax_override: Option<SyntaxOverride>,
+    ecma_version: EcmaVersion,
+    rules: Arc<Vec<Box<dyn LintRule>>>,
+    severity_overrides: HashMap<String, Severity>,
     plugins: Vec<Box<dyn Plugin>>,
   ) -> Self {
+    let mut severities: HashMap<String, Severity> = DIRECTIVE_SEVERITIES
+      .iter()
+      .map(|(code, severity)| (code.to_string(), *severity))
+      .chain(
+        rules
+          .iter()
+          .map(|rule| (rule.code().to_string(), rule.default_severity())),
+      )
+      .collect();
+    severities.extend(severity_overrides);
+
+    let eslint_rule_codes = if eslint_directives {
+      Some(
+        get_all_rules()
+          .iter()
+          .map(|r| r.code().to_string())
+          .collect(),
+      )
+    } else {
+      None
+    };
+
     Linter {
-      has_linted: false,
       ast_parser: AstParser::new(),
       ignore_file_directive,
       ignore_diagnostic_directive,
+      disable_directive,
+      enable_directive,
       lint_unused_ignore_directives,
       lint_unknown_rules,
+      lint_missing_ignore_reasons,
+      span_aware_ignore_directives,
+      eslint_rule_codes,
+      report_recovered_parse_errors,
+      single_traversal,
       syntax,
+      detect_syntax,
+      syntax_override,
+      ecma_version,
       rules,
+      severities: Arc::new(severities),
       plugins,
+      ignore_directives: vec![],
+      parse_error_count: 0,
     }
   }
 
+  /// Ignore directives found in the last linted file, with their reasons
+  /// and which of their codes were used. Useful for auditing suppressions.
+  pub fn ignore_directives(&self) -> &[IgnoreDirective] {
+    &self.ignore_directives
+  }
+
+  /// Number of syntax errors found in the last linted file, whether they
+  /// are reported as diagnostics or not.
+  pub(crate) fn parse_error_count(&self) -> usize {
+    self.parse_error_count
+  }
+
+  fn syntax_for_file(&self, file_name: &str) -> Syntax {
+    if let Some(syntax) =
+      self.syntax_override.as_ref().and_then(|f| f(file_name))
+    {
+      return syntax;
+    }
+    if self.detect_syntax {
+      if let Some(syntax) = get_syntax_for_file(file_name) {
+        return syntax;
+      }
+    }
+    self.syntax
+  }
+
+  fn is_enabled(&self, code: &str) -> bool {
+    self.severities.get(code) != Some(&Severity::Off)
+  }
+
+  /// Lints a single file.
+  ///
+  /// Syntax errors are returned as diagnostics with the
+  /// `ast_parser::PARSE_ERROR_CODE` code. If the parser was able to recover
+  /// from all errors, rules are run as usual and the recovered errors are
+  /// only returned if `report_recovered_parse_errors` is enabled.
   pub fn lint(
     &mut self,
     file_name: String,
     source_code: String,
-  ) -> Result<
-    (Rc<swc_common::SourceFile>, Vec<LintDiagnostic>),
-    SwcDiagnosticBuffer,
-  > {
-    assert!(
-      !self.has_linted,
-      "Linter can be used only on a single module."
-    );
-    self.has_linted = true;
+  ) -> (Rc<swc_common::SourceFile>, Vec<LintDiagnostic>) {
     let start = Instant::now();
+    // Don't leave the directives of the previous file behind if this one
+    // can't be parsed.
+    self.ignore_directives.clear();
 
-    let parse_result =
-      self
-        .ast_parser
-        .parse_program(&file_name, self.syntax, &source_code);
+    let syntax = self.syntax_for_file(&file_name);
+    let parsed = self
+      .ast_parser
+      .parse_program(&file_name, syntax, source_code);
     let end_parse_program = Instant::now();
     debug!(
       "ast_parser.parse_program took {:#?}",
       end_parse_program - start
     );
-    let (program, comments) = parse_result?;
-    let diagnostics = self.lint_program(file_name.clone(), program, comments);
 
-    let source_file = self
-      .ast_parser
-      .source_map
-      .get_source_file(&swc_common::FileName::Custom(file_name))
-      .unwrap();
+    self.parse_error_count = parsed.errors.len();
+    let mut diagnostics = parsed.errors;
+    if let Some((program, comments)) = parsed.program {
+      if !self.report_recovered_parse_errors {
+        diagnostics.clear();
+      }
+      let (leading, trailing) = comments.take_all();
+      let leading = Rc::try_unwrap(leading)
+        .expect("Failed to get leading comments")
+        .into_inner()
+        .into_iter()
+        .collect();
+      let trailing = Rc::try_unwrap(trailing)
+        .expect("Failed to get trailing comments")
+        .into_inner()
+        .into_iter()
+        .collect();
+      let top_level_mark = parsed.top_level_mark;
+      let top_level_ctxt = swc_common::GLOBALS.set(&parsed.globals, || {
+        SyntaxContext::empty().apply_mark(top_level_mark)
+      });
+
+      diagnostics.extend(self.lint_program_with_comments(
+        file_name,
+        &program,
+        leading,
+        trailing,
+        parsed.source_map,
+        top_level_ctxt,
+      ));
+      diagnostics.sort_by_key(|d| d.range.start.line);
+    }
+
     let end = Instant::now();
     debug!("Linter::lint took {:#?}", end - start);
-    Ok((source_file, diagnostics))
+    (parsed.source_file, diagnostics)
   }
 
   fn filter_diagnostics(&self, context: &mut Context) -> Vec<LintDiagnostic> {
@@ -255,6 +707,7 @@ impl Linter {
       let mut executed = context.plugin_codes.clone();
       // builtin executed rules
       executed.extend(self.rules.iter().map(|r| r.code().to_string()));
+      executed.retain(|code| self.is_enabled(code));
 
       let mut available = context.plugin_codes.clone();
       // builtin all available rules
@@ -284,27 +737,84 @@ impl Linter {
             && !used
             && executed_rule_codes.contains(code)
           {
-            let diagnostic = context.create_diagnostic(
+            let mut diagnostic = context.create_diagnostic(
               ignore_directive.span,
               "ban-unused-ignore",
               format!("Ignore for code \"{}\" was not used.", code),
               None,
             );
+            diagnostic.fixes =
+              create_remove_code_fix(context, ignore_directive, code)
+                .into_iter()
+                .collect();
             filtered_diagnostics.push(diagnostic);
           }
 
           if self.lint_unknown_rules && !available_rule_codes.contains(code) {
-            filtered_diagnostics.push(context.create_diagnostic(
+            let mut diagnostic = context.create_diagnostic(
               ignore_directive.span,
               "ban-unknown-rule-code",
               format!("Unknown rule for code \"{}\"", code),
               None,
-            ))
+            );
+            diagnostic.fixes =
+              create_remove_code_fix(context, ignore_directive, code)
+                .into_iter()
+                .collect();
+            filtered_diagnostics.push(diagnostic);
           }
         }
       }
     }
 
+    if self.lint_missing_ignore_reasons {
+      for ignore_directive in ignore_directives.borrow().iter() {
+        if ignore_directive.reason.is_none() {
+          filtered_diagnostics.push(context.create_diagnostic(
+            ignore_directive.span,
+            "ban-unexplained-ignore",
+            "Ignore directive requires a reason",
+            Some(format!(
+              "Explain why after the rule names, e.g. `// {} no-explicit-any -- upstream types are wrong`",
+              self.ignore_diagnostic_directive
+            )),
+          ));
+        }
+      }
+    }
+
+    // Malformed ranges are reported regardless of the settings above, an
+    // unclosed range silently disables rules for the rest of the file.
+    for ignore_directive in ignore_directives.borrow().iter() {
+      if !ignore_directive.is_eslint
+        && ignore_directive.kind == (IgnoreDirectiveKind::Range { end: None })
+      {
+        filtered_diagnostics.push(context.create_diagnostic(
+          ignore_directive.span,
+          "ban-unclosed-disable",
+          format!(
+            "\"{}\" is never closed by \"{}\"",
+            self.disable_directive, self.enable_directive
+          ),
+          None,
+        ));
+      }
+    }
+    for span in context.unmatched_enable_directives.iter() {
+      filtered_diagnostics.push(context.create_diagnostic(
+        *span,
+        "ban-unmatched-enable",
+        format!(
+          "\"{}\" doesn't close any \"{}\" range",
+          self.enable_directive, self.disable_directive
+        ),
+        None,
+      ));
+    }
+
+    context.ignore_directives = ignore_directives;
+
+    filtered_diagnostics.retain(|d| d.severity != Severity::Off);
     filtered_diagnostics
       .sort_by(|a, b| a.range.start.line.cmp(&b.range.start.line));
 
@@ -314,21 +824,54 @@ impl Linter {
     filtered_diagnostics
   }
 
-  fn lint_program(
+  /// Lints a program that was already parsed by the caller, e.g. a host tool
+  /// that parses modules anyway and wants to avoid parsing them twice.
+  ///
+  /// The source file of `program` must be part of `source_map`, and the
+  /// program must have been processed with swc's resolver using
+  /// `top_level_mark`. This has to be called inside of
+  /// `swc_common::GLOBALS.set()` with the globals `top_level_mark` belongs to.
+  pub fn lint_program(
     &mut self,
     file_name: String,
-    program: swc_ecmascript::ast::Program,
-    comments: SingleThreadedComments,
+    program: &Program,
+    comments: &SingleThreadedComments,
+    source_map: Rc<SourceMap>,
+    top_level_mark: Mark,
+  ) -> Vec<LintDiagnostic> {
+    let (leading, trailing) = comments.borrow_all();
+    let leading = leading.iter().map(|(k, v)| (*k, v.clone())).collect();
+    let trailing = trailing.iter().map(|(k, v)| (*k, v.clone())).collect();
+    let top_level_ctxt = SyntaxContext::empty().apply_mark(top_level_mark);
+
+    self.lint_program_with_comments(
+      file_name,
+      program,
+      leading,
+      trailing,
+      source_map,
+      top_level_ctxt,
+    )
+  }
+
+  fn lint_program_with_comments(
+    &mut self,
+    file_name: String,
+    program: &Program,
+    leading: HashMap<BytePos, Vec<Comment>>,
+    trailing: HashMap<BytePos, Vec<Comment>>,
+    source_map: Rc<SourceMap>,
+    top_level_ctxt: SyntaxContext,
   ) -> Vec<LintDiagnostic> {
     let start = Instant::now();
     let file_ignore_directive =
-      comments.with_leading(program.span().lo(), |c| {
+      leading.get(&program.span().lo()).and_then(|c| {
         c.iter().find_map(|comment| {
           parse_ignore_comment(
             &self.ignore_file_directive,
-            &*self.ast_parser.source_map,
+            &*source_map,
             comment,
-            true,
+            IgnoreDirectiveKind::File,
           )
         })
       });
@@ -337,63 +880,72 @@ impl Linter {
     // whole file and skip linting it.
     if let Some(ignore_directive) = &file_ignore_directive {
       if ignore_directive.codes.is_empty() {
+        self.ignore_directives = vec![ignore_directive.clone()];
         return vec![];
       }
     }
 
-    let (leading, trailing) = comments.take_all();
-    let leading_coms = Rc::try_unwrap(leading)
-      .expect("Failed to get leading comments")
-      .into_inner();
-    let leading = leading_coms.into_iter().collect();
-    let trailing_coms = Rc::try_unwrap(trailing)
-      .expect("Failed to get leading comments")
-      .into_inner();
-    let trailing = trailing_coms.into_iter().collect();
-
-    let mut ignore_directives = parse_ignore_directives(
-      &self.ignore_diagnostic_directive,
-      &self.ast_parser.source_map,
-      &leading,
-      &trailing,
-    );
+    let (mut ignore_directives, unmatched_enable_directives) =
+      parse_ignore_directives(
+        &self.ignore_diagnostic_directive,
+        &self.disable_directive,
+        &self.enable_directive,
+        self.eslint_rule_codes.as_ref(),
+        &source_map,
+        &leading,
+        &trailing,
+      );
+
+    if self.span_aware_ignore_directives {
+      extend_to_next_node(&mut ignore_directives, program, &source_map);
+    }
 
     if let Some(ignore_directive) = file_ignore_directive {
       ignore_directives.insert(0, ignore_directive);
     }
 
-    let scope = Scope::analyze(&program);
-    let control_flow = ControlFlow::analyze(&program);
-    let top_level_ctxt = swc_common::GLOBALS
-      .set(&self.ast_parser.globals, || {
-        SyntaxContext::empty().apply_mark(self.ast_parser.top_level_mark)
-      });
+    let scope = Scope::analyze(program);
+    let control_flow = ControlFlow::analyze(program);
 
     let mut context = Context {
       file_name,
-      source_map: self.ast_parser.source_map.clone(),
+      source_map,
       leading_comments: leading,
       trailing_comments: trailing,
       ignore_directives: RefCell::new(ignore_directives),
+      unmatched_enable_directives,
       scope,
+      const_bindings: OnceCell::new(),
       control_flow,
+      cfg: OnceCell::new(),
       top_level_ctxt,
       diagnostics: Vec::new(),
       plugin_codes: HashSet::new(),
+      severities: self.severities.clone(),
+      ecma_version: self.ecma_version,
     };
 
-    // Run builtin rules
-    for rule in &self.rules {
-      rule.lint_program(&mut context, &program);
+    // Run builtin rules, node rules all share a single traversal
+    let mut node_rules = vec![];
+    for rule in self.rules.iter() {
+      if !self.is_enabled(rule.code()) {
+        continue;
+      }
+      match rule.as_node_rule() {
+        Some(node_rule) if self.single_traversal => node_rules.push(node_rule),
+        _ => rule.lint_program(&mut context, program),
+      }
     }
+    run_node_rules(&mut context, program, &node_rules);
 
     // Run plugin rules
     for plugin in self.plugins.iter_mut() {
       // Ignore any error
-      let _ = plugin.run(&mut context, program.clone());
+      let _ = plugin.run(&mut context, program);
     }
 
     let d = self.filter_diagnostics(&mut context);
+    self.ignore_directives = context.ignore_directives.into_inner();
     let end = Instant::now();
     debug!("Linter::lint_module took {:#?}", end - start);
 
@@ -405,6 +957,6 @@ pub trait Plugin {
   fn run(
     &mut self,
     context: &mut Context,
-    program: swc_ecmascript::ast::Program,
+    program: &swc_ecmascript::ast::Program,
   ) -> anyhow::Result<()>;
 }
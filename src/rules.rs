@@ -1,5 +1,11 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use crate::diagnostic::Severity;
+use crate::dispatcher::{run_node_rules, NodeRule};
 use crate::linter::Context;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
 use swc_ecmascript::ast::Program;
 
 pub mod adjacent_overload_signatures;
@@ -43,6 +49,7 @@ pub mod no_extra_boolean_cast;
 pub mod no_extra_non_null_assertion;
 pub mod no_extra_semi;
 pub mod no_fallthrough;
+pub mod no_floating_promises;
 pub mod no_func_assign;
 pub mod no_global_assign;
 pub mod no_import_assign;
@@ -50,6 +57,7 @@ pub mod no_inferrable_types;
 pub mod no_inner_declarations;
 pub mod no_invalid_regexp;
 pub mod no_irregular_whitespace;
+pub mod no_misleading_character_class;
 pub mod no_misused_new;
 pub mod no_mixed_spaces_and_tabs;
 pub mod no_namespace;
@@ -65,19 +73,25 @@ pub mod no_self_assign;
 pub mod no_setter_return;
 pub mod no_shadow_restricted_names;
 pub mod no_sparse_arrays;
+pub mod no_super_linear_regex;
 pub mod no_this_alias;
 pub mod no_this_before_super;
 pub mod no_throw_literal;
 pub mod no_undef;
+pub mod no_uninitialized_read;
 pub mod no_unreachable;
 pub mod no_unsafe_finally;
 pub mod no_unsafe_negation;
 pub mod no_unused_labels;
 pub mod no_unused_vars;
+pub mod no_use_before_define;
+pub mod no_useless_backreference;
+pub mod no_useless_escape;
 pub mod no_var;
 pub mod no_with;
 pub mod prefer_as_const;
 pub mod prefer_const;
+pub mod prefer_named_capture_group;
 pub mod prefer_namespace_keyword;
 pub mod require_await;
 pub mod require_yield;
@@ -86,11 +100,25 @@ pub mod triple_slash_reference;
 pub mod use_isnan;
 pub mod valid_typeof;
 
-pub trait LintRule {
+pub trait LintRule: Send + Sync {
   fn new() -> Box<Self>
   where
     Self: Sized;
-  fn lint_program(&self, context: &mut Context, program: &Program);
+  /// Lints the whole program. Node rules don't need to implement this, by
+  /// default they are run in a traversal of their own.
+  fn lint_program(&self, context: &mut Context, program: &Program) {
+    if let Some(rule) = self.as_node_rule() {
+      run_node_rules(context, program, &[rule]);
+    }
+  }
+
+  /// Returns the rule as a `NodeRule` if it only checks individual nodes.
+  /// The linter runs all node rules in a single traversal of the program
+  /// instead of calling `lint_program` for each of them.
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    None
+  }
+
   fn code(&self) -> &'static str;
   fn tags(&self) -> &'static [&'static str] {
     &[]
@@ -98,6 +126,127 @@ pub trait LintRule {
   fn docs(&self) -> &'static str {
     ""
   }
+
+  /// Severity of diagnostics reported by this rule, unless overridden by
+  /// the configuration.
+  fn default_severity(&self) -> Severity {
+    Severity::Error
+  }
+
+  /// Configures the rule with the options given for it in the `rules`
+  /// section of a config. Rules without options only accept `null`.
+  fn set_options(&mut self, options: Value) -> Result<(), RuleOptionsError> {
+    if options.is_null() {
+      return Ok(());
+    }
+    Err(RuleOptionsError::new(
+      self.code(),
+      None,
+      "This rule doesn't have any options",
+    ))
+  }
+}
+
+/// Error returned when options given to a rule are invalid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleOptionsError {
+  /// Code of the rule the options were given for.
+  pub code: String,
+  /// Name of the invalid option, if the error can be attributed to one.
+  pub field: Option<String>,
+  pub message: String,
+}
+
+impl RuleOptionsError {
+  pub fn new(
+    code: impl ToString,
+    field: Option<&str>,
+    message: impl ToString,
+  ) -> Self {
+    Self {
+      code: code.to_string(),
+      field: field.map(ToString::to_string),
+      message: message.to_string(),
+    }
+  }
+}
+
+impl fmt::Display for RuleOptionsError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.field {
+      Some(field) => write!(
+        f,
+        "Invalid option \"{}\" for rule \"{}\": {}",
+        field, self.code, self.message
+      ),
+      None => {
+        write!(
+          f,
+          "Invalid options for rule \"{}\": {}",
+          self.code, self.message
+        )
+      }
+    }
+  }
+}
+
+impl std::error::Error for RuleOptionsError {}
+
+/// Deserializes the options of the rule `code` into `T`. `null` yields the
+/// default options.
+///
+/// Options structs are expected to use `#[serde(default)]`, so that the
+/// option responsible for an error can be found by deserializing every
+/// option on its own.
+pub fn parse_rule_options<T>(
+  code: &str,
+  options: Value,
+) -> Result<T, RuleOptionsError>
+where
+  T: DeserializeOwned + Default,
+{
+  if options.is_null() {
+    return Ok(T::default());
+  }
+
+  let err = match serde_json::from_value(options.clone()) {
+    Ok(parsed) => return Ok(parsed),
+    Err(err) => err,
+  };
+
+  if let Value::Object(map) = options {
+    for (field, value) in map {
+      let mut single = serde_json::Map::new();
+      single.insert(field.clone(), value);
+      if let Err(err) = serde_json::from_value::<T>(Value::Object(single)) {
+        return Err(RuleOptionsError::new(code, Some(&field), err));
+      }
+    }
+  }
+
+  Err(RuleOptionsError::new(code, None, err))
+}
+
+/// Applies options, keyed by rule code, to `rules`.
+///
+/// Options of known rules that are not part of `rules` are still validated,
+/// so a mistake doesn't go unnoticed just because the rule is disabled.
+pub fn configure_rules(
+  rules: &mut [Box<dyn LintRule>],
+  options: &HashMap<String, Value>,
+) -> Result<(), RuleOptionsError> {
+  for (code, rule_options) in options {
+    if let Some(rule) = rules.iter_mut().find(|r| r.code() == code) {
+      rule.set_options(rule_options.clone())?;
+      continue;
+    }
+
+    match get_all_rules().into_iter().find(|r| r.code() == code) {
+      Some(mut rule) => rule.set_options(rule_options.clone())?,
+      None => return Err(RuleOptionsError::new(code, None, "Unknown rule")),
+    }
+  }
+  Ok(())
 }
 
 pub fn get_all_rules() -> Vec<Box<dyn LintRule>> {
@@ -143,6 +292,7 @@ pub fn get_all_rules() -> Vec<Box<dyn LintRule>> {
     no_extra_non_null_assertion::NoExtraNonNullAssertion::new(),
     no_extra_semi::NoExtraSemi::new(),
     no_fallthrough::NoFallthrough::new(),
+    no_floating_promises::NoFloatingPromises::new(),
     no_func_assign::NoFuncAssign::new(),
     no_global_assign::NoGlobalAssign::new(),
     no_import_assign::NoImportAssign::new(),
@@ -150,6 +300,7 @@ pub fn get_all_rules() -> Vec<Box<dyn LintRule>> {
     no_inner_declarations::NoInnerDeclarations::new(),
     no_invalid_regexp::NoInvalidRegexp::new(),
     no_irregular_whitespace::NoIrregularWhitespace::new(),
+    no_misleading_character_class::NoMisleadingCharacterClass::new(),
     no_misused_new::NoMisusedNew::new(),
     no_mixed_spaces_and_tabs::NoMixedSpacesAndTabs::new(),
     no_namespace::NoNamespace::new(),
@@ -165,19 +316,25 @@ pub fn get_all_rules() -> Vec<Box<dyn LintRule>> {
     no_setter_return::NoSetterReturn::new(),
     no_shadow_restricted_names::NoShadowRestrictedNames::new(),
     no_sparse_arrays::NoSparseArrays::new(),
+    no_super_linear_regex::NoSuperLinearRegex::new(),
     no_this_alias::NoThisAlias::new(),
     no_this_before_super::NoThisBeforeSuper::new(),
     no_throw_literal::NoThrowLiteral::new(),
     no_undef::NoUndef::new(),
+    no_uninitialized_read::NoUninitializedRead::new(),
     no_unreachable::NoUnreachable::new(),
     no_unsafe_finally::NoUnsafeFinally::new(),
     no_unsafe_negation::NoUnsafeNegation::new(),
     no_unused_labels::NoUnusedLabels::new(),
     no_unused_vars::NoUnusedVars::new(),
+    no_use_before_define::NoUseBeforeDefine::new(),
+    no_useless_backreference::NoUselessBackreference::new(),
+    no_useless_escape::NoUselessEscape::new(),
     no_var::NoVar::new(),
     no_with::NoWith::new(),
     prefer_as_const::PreferAsConst::new(),
     prefer_const::PreferConst::new(),
+    prefer_named_capture_group::PreferNamedCaptureGroup::new(),
     prefer_namespace_keyword::PreferNamespaceKeyword::new(),
     require_await::RequireAwait::new(),
     require_yield::RequireYield::new(),
@@ -218,4 +375,46 @@ mod tests {
       assert_eq!(sorted.code(), unsorted.code());
     }
   }
+
+  #[test]
+  fn configure_rules_validates_options() {
+    use serde_json::json;
+
+    let mut rules = vec![
+      ban_types::BanTypes::new() as Box<dyn LintRule>,
+      no_debugger::NoDebugger::new(),
+    ];
+
+    let mut options = HashMap::new();
+    options.insert("ban-types".to_string(), json!({ "types": {} }));
+    options.insert("no-debugger".to_string(), Value::Null);
+    assert!(configure_rules(&mut rules, &options).is_ok());
+
+    let mut options = HashMap::new();
+    options.insert("no-debugger".to_string(), json!({ "foo": true }));
+    let err = configure_rules(&mut rules, &options).unwrap_err();
+    assert_eq!(err.code, "no-debugger");
+    assert_eq!(err.field, None);
+
+    // Options of rules that are not enabled are validated as well.
+    let mut options = HashMap::new();
+    options.insert(
+      "no-explicit-any".to_string(),
+      json!({ "ignoreRestArgs": "yes" }),
+    );
+    let err = configure_rules(&mut rules, &options).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Invalid option \"ignoreRestArgs\" for rule \"no-explicit-any\": \
+       invalid type: string \"yes\", expected a boolean"
+    );
+
+    let mut options = HashMap::new();
+    options.insert("no-such-rule".to_string(), json!({}));
+    let err = configure_rules(&mut rules, &options).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Invalid options for rule \"no-such-rule\": Unknown rule"
+    );
+  }
 }
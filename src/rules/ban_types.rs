@@ -1,8 +1,12 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use super::{parse_rule_options, RuleOptionsError};
 use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Value;
 use std::collections::HashMap;
+use swc_common::Span;
 use swc_ecmascript::ast::{
   TsEntityName, TsKeywordType, TsKeywordTypeKind, TsTypeLit,
   TsTypeParamInstantiation, TsTypeRef,
@@ -10,11 +14,36 @@ use swc_ecmascript::ast::{
 use swc_ecmascript::visit::Node;
 use swc_ecmascript::visit::Visit;
 
-pub struct BanTypes;
+pub struct BanTypes {
+  /// Banned types mapped to the message shown when they are used.
+  banned_types: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct BanTypesOptions {
+  /// Types to ban in addition to the default ones, mapped to the message
+  /// to show. `null` allows a type that is banned by default. Use `{}` to
+  /// refer to the empty type literal.
+  pub types: HashMap<String, Option<String>>,
+  /// Whether the default banned types are banned too.
+  pub extend_defaults: bool,
+}
+
+impl Default for BanTypesOptions {
+  fn default() -> Self {
+    Self {
+      types: HashMap::new(),
+      extend_defaults: true,
+    }
+  }
+}
 
 impl LintRule for BanTypes {
   fn new() -> Box<Self> {
-    Box::new(BanTypes)
+    Box::new(BanTypes {
+      banned_types: default_banned_types(),
+    })
   }
 
   fn tags(&self) -> &'static [&'static str] {
@@ -30,10 +59,29 @@ impl LintRule for BanTypes {
     context: &mut Context,
     program: &swc_ecmascript::ast::Program,
   ) {
-    let mut visitor = BanTypesVisitor::new(context);
+    let mut visitor = BanTypesVisitor::new(context, &self.banned_types);
     visitor.visit_program(program, program);
   }
 
+  fn set_options(&mut self, options: Value) -> Result<(), RuleOptionsError> {
+    let options: BanTypesOptions = parse_rule_options(self.code(), options)?;
+
+    let mut banned_types = if options.extend_defaults {
+      default_banned_types()
+    } else {
+      HashMap::new()
+    };
+    for (ty, message) in options.types {
+      match message {
+        Some(message) => banned_types.insert(ty, message),
+        None => banned_types.remove(&ty),
+      };
+    }
+
+    self.banned_types = banned_types;
+    Ok(())
+  }
+
   fn docs(&self) -> &'static str {
     r#"Bans the use of primitive wrapper objects (e.g. `String` the object is a 
 wrapper of `string` the primitive) in addition to the non-explicit `Function`
@@ -63,6 +111,20 @@ let g: object;
 let h: {};
 ```
 
+### Options:
+```json
+{
+  "types": {
+    "Foo": "Use `Bar` instead",
+    "Function": null
+  },
+  "extendDefaults": true
+}
+```
+`types` bans additional types with the given message, or allows a type that
+is banned by default if the message is `null`. With `extendDefaults` set to
+`false` only the types listed in `types` are banned.
+
 ### Valid:
 ```typescript
 let a: boolean;
@@ -78,11 +140,24 @@ let f: Record<string, unknown>;
 
 struct BanTypesVisitor<'c> {
   context: &'c mut Context,
+  banned_types: &'c HashMap<String, String>,
 }
 
 impl<'c> BanTypesVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+  fn new(
+    context: &'c mut Context,
+    banned_types: &'c HashMap<String, String>,
+  ) -> Self {
+    Self {
+      context,
+      banned_types,
+    }
+  }
+
+  fn check(&mut self, span: Span, ty: &str) {
+    if let Some(message) = self.banned_types.get(ty) {
+      self.context.add_diagnostic(span, "ban-types", message);
+    }
   }
 }
 
@@ -98,22 +173,24 @@ static BAN_TYPES_MESSAGE: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(
   "if you want a type meaning `any object` use `Record<string, unknown>` instead,
 or if you want a type meaning `any value`, you probably want `unknown` instead.");
     map.insert("object", "Use `Record<string, unknown>` instead");
+    map.insert("{}",
+  "if you want a type meaning `any object` use `Record<string, unknown>` instead,
+or if you want a type meaning `any value`, you probably want `unknown` instead.");
     map
   },
 );
 
-fn get_message(ident: impl AsRef<str>) -> Option<&'static str> {
-  BAN_TYPES_MESSAGE.get(ident.as_ref()).copied()
+fn default_banned_types() -> HashMap<String, String> {
+  BAN_TYPES_MESSAGE
+    .iter()
+    .map(|(ty, message)| (ty.to_string(), message.to_string()))
+    .collect()
 }
 
 impl<'c> Visit for BanTypesVisitor<'c> {
   fn visit_ts_type_ref(&mut self, ts_type_ref: &TsTypeRef, _parent: &dyn Node) {
     if let TsEntityName::Ident(ident) = &ts_type_ref.type_name {
-      if let Some(message) = get_message(&ident.sym) {
-        self
-          .context
-          .add_diagnostic(ts_type_ref.span, "ban-types", message);
-      }
+      self.check(ts_type_ref.span, &ident.sym);
     }
     if let Some(type_param) = &ts_type_ref.type_params {
       self.visit_ts_type_param_instantiation(type_param, ts_type_ref);
@@ -127,11 +204,7 @@ impl<'c> Visit for BanTypesVisitor<'c> {
       }
       return;
     }
-    self.context.add_diagnostic(
-      ts_type_lit.span,
-      "ban-types",
-      get_message("Object").unwrap(), // `BAN_TYPES_MESSAGE` absolutely has `Object` key
-    );
+    self.check(ts_type_lit.span, "{}");
   }
 
   fn visit_ts_keyword_type(
@@ -140,11 +213,7 @@ impl<'c> Visit for BanTypesVisitor<'c> {
     _parent: &dyn Node,
   ) {
     if TsKeywordTypeKind::TsObjectKeyword == ts_keyword_type.kind {
-      self.context.add_diagnostic(
-        ts_keyword_type.span,
-        "ban-types",
-        get_message("object").unwrap(), // `BAN_TYPES_MESSAGE` absolutely has `object` key
-      );
+      self.check(ts_keyword_type.span, "object");
     }
   }
 
@@ -162,6 +231,8 @@ impl<'c> Visit for BanTypesVisitor<'c> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::test_util::lint_with_options;
+  use serde_json::json;
 
   #[test]
   fn ban_types_valid() {
@@ -179,7 +250,7 @@ mod tests {
   #[test]
   fn ban_types_invalid() {
     fn message(ty: &str) -> &str {
-      get_message(ty).unwrap()
+      BAN_TYPES_MESSAGE.get(ty).unwrap()
     }
 
     assert_lint_err! {
@@ -345,4 +416,49 @@ class Foo<F = String> extends Bar<String> implements Baz<Object> {
       ]
     };
   }
+
+  #[test]
+  fn ban_types_options() {
+    let source = "let a: Foo; let b: String; let c: Number;";
+
+    let diagnostics = lint_with_options::<BanTypes>(
+      json!({ "types": { "Foo": "Use `Bar` instead", "String": null } }),
+      source,
+    );
+    let messages: Vec<_> = diagnostics.iter().map(|d| &d.message).collect();
+    assert_eq!(
+      messages,
+      vec![
+        "Use `Bar` instead",
+        BAN_TYPES_MESSAGE.get("Number").unwrap()
+      ]
+    );
+
+    let diagnostics = lint_with_options::<BanTypes>(
+      json!({ "types": { "Foo": "Use `Bar` instead" }, "extendDefaults": false }),
+      source,
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range.start.col, 7);
+
+    let diagnostics = lint_with_options::<BanTypes>(
+      json!({ "types": { "{}": null } }),
+      "let a: {};",
+    );
+    assert!(diagnostics.is_empty());
+  }
+
+  #[test]
+  fn ban_types_invalid_options() {
+    let err = BanTypes::new()
+      .set_options(json!({ "extendDefaults": true, "types": { "Foo": 1 } }))
+      .unwrap_err();
+    assert_eq!(err.code, "ban-types");
+    assert_eq!(err.field.as_deref(), Some("types"));
+
+    let err = BanTypes::new()
+      .set_options(json!({ "extend": false }))
+      .unwrap_err();
+    assert_eq!(err.field.as_deref(), Some("extend"));
+  }
 }
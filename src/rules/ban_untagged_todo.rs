@@ -1,13 +1,38 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use once_cell::sync::Lazy;
+use super::{parse_rule_options, RuleOptionsError};
+use crate::diagnostic::Severity;
 use regex::Regex;
+use serde::Deserialize;
+use serde_json::Value;
 use swc_common::comments::Comment;
 use swc_common::comments::CommentKind;
 use swc_common::Span;
 
-pub struct BanUntaggedTodo;
+pub struct BanUntaggedTodo {
+  /// Matches a properly tagged TODO.
+  todo_re: Regex,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct BanUntaggedTodoOptions {
+  /// Regular expression a tag inside of `TODO(...)` has to match.
+  pub tag_pattern: String,
+}
+
+impl Default for BanUntaggedTodoOptions {
+  fn default() -> Self {
+    Self {
+      tag_pattern: r#"(#|@)?\S+"#.to_string(),
+    }
+  }
+}
+
+fn todo_regex(tag_pattern: &str) -> Result<Regex, regex::Error> {
+  Regex::new(&format!(r#"(?i)todo\((?:{})\)"#, tag_pattern))
+}
 
 const CODE: &str = "ban-untagged-todo";
 const MESSAGE: &str = "TODO should be tagged with (@username) or (#issue)";
@@ -20,13 +45,20 @@ impl BanUntaggedTodo {
 
 impl LintRule for BanUntaggedTodo {
   fn new() -> Box<Self> {
-    Box::new(BanUntaggedTodo)
+    let options = BanUntaggedTodoOptions::default();
+    Box::new(BanUntaggedTodo {
+      todo_re: todo_regex(&options.tag_pattern).unwrap(),
+    })
   }
 
   fn code(&self) -> &'static str {
     CODE
   }
 
+  fn default_severity(&self) -> Severity {
+    Severity::Warning
+  }
+
   fn lint_program(
     &self,
     context: &mut Context,
@@ -36,7 +68,7 @@ impl LintRule for BanUntaggedTodo {
 
     violated_comment_spans.extend(
       context.leading_comments.values().flatten().filter_map(|c| {
-        if check_comment(c) {
+        if check_comment(c, &self.todo_re) {
           Some(c.span)
         } else {
           None
@@ -48,7 +80,13 @@ impl LintRule for BanUntaggedTodo {
         .trailing_comments
         .values()
         .flatten()
-        .filter_map(|c| if check_comment(c) { Some(c.span) } else { None }),
+        .filter_map(|c| {
+          if check_comment(c, &self.todo_re) {
+            Some(c.span)
+          } else {
+            None
+          }
+        }),
     );
 
     for span in violated_comment_spans {
@@ -56,6 +94,15 @@ impl LintRule for BanUntaggedTodo {
     }
   }
 
+  fn set_options(&mut self, options: Value) -> Result<(), RuleOptionsError> {
+    let options: BanUntaggedTodoOptions =
+      parse_rule_options(self.code(), options)?;
+    self.todo_re = todo_regex(&options.tag_pattern).map_err(|err| {
+      RuleOptionsError::new(self.code(), Some("tagPattern"), err)
+    })?;
+    Ok(())
+  }
+
   fn docs(&self) -> &'static str {
     r#"Requires TODOs to be annotated with either a user tag (@user) or an issue reference (#issue).
 
@@ -92,27 +139,31 @@ export function calcValue(): number { }
 // TODO(#332) Improve calc engine
 export function calcValue(): number { }
 ```
+
+### Options:
+```json
+{ "tagPattern": "[A-Z]+-\\d+" }
+```
+`tagPattern` is a regular expression the tag inside of `TODO(...)` has to
+match, e.g. a JIRA ticket like `TODO(LINT-123)`. It is matched
+case-insensitively and defaults to `(#|@)?\S+`.
 "#
   }
 }
 
 /// Returns `true` if the comment should be reported.
-fn check_comment(comment: &Comment) -> bool {
+fn check_comment(comment: &Comment, todo_re: &Regex) -> bool {
   if comment.kind != CommentKind::Line {
     return false;
   }
 
-  let text = comment.text.to_lowercase();
-  let text = text.trim_start();
+  let text = comment.text.trim_start();
 
-  if !text.starts_with("todo") {
+  if !text.to_lowercase().starts_with("todo") {
     return false;
   }
 
-  static TODO_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r#"todo\((#|@)?\S+\)"#).unwrap());
-
-  if TODO_RE.is_match(text) {
+  if todo_re.is_match(text) {
     return false;
   }
 
@@ -122,6 +173,8 @@ fn check_comment(comment: &Comment) -> bool {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::test_util::lint_with_options;
+  use serde_json::json;
 
   #[test]
   fn ban_ts_ignore_valid() {
@@ -178,4 +231,26 @@ const c = "c";
       "#: [{ col: 0, line: 2, message: MESSAGE, hint: HINT }],
     }
   }
+
+  #[test]
+  fn ban_untagged_todo_tag_pattern() {
+    let options = json!({ "tagPattern": "[A-Z]+-\\d+" });
+    let diagnostics = lint_with_options::<BanUntaggedTodo>(
+      options.clone(),
+      "// TODO(LINT-123) fix this\nconst a = 1;",
+    );
+    assert!(diagnostics.is_empty());
+
+    let diagnostics = lint_with_options::<BanUntaggedTodo>(
+      options,
+      "// TODO(@someusername) fix this\nconst a = 1;",
+    );
+    assert_eq!(diagnostics.len(), 1);
+
+    let err = BanUntaggedTodo::new()
+      .set_options(json!({ "tagPattern": "(" }))
+      .unwrap_err();
+    assert_eq!(err.code, CODE);
+    assert_eq!(err.field.as_deref(), Some("tagPattern"));
+  }
 }
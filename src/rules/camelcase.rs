@@ -1,9 +1,12 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use super::{parse_rule_options, RuleOptionsError};
 use crate::swc_util::StringRepr;
 use once_cell::sync::Lazy;
 use regex::{Captures, Regex};
+use serde::Deserialize;
+use serde_json::Value;
 use std::collections::{BTreeMap, BTreeSet};
 use swc_common::{Span, Spanned};
 use swc_ecmascript::ast::{
@@ -18,11 +21,21 @@ use swc_ecmascript::ast::{
 };
 use swc_ecmascript::visit::{Node, Visit, VisitWith};
 
-pub struct Camelcase;
+pub struct Camelcase {
+  /// Compiled `allow` patterns.
+  allow: Vec<Regex>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct CamelcaseOptions {
+  /// Regular expressions matching identifiers that are never reported.
+  pub allow: Vec<String>,
+}
 
 impl LintRule for Camelcase {
   fn new() -> Box<Self> {
-    Box::new(Camelcase)
+    Box::new(Camelcase { allow: vec![] })
   }
 
   fn tags(&self) -> &'static [&'static str] {
@@ -34,11 +47,22 @@ impl LintRule for Camelcase {
   }
 
   fn lint_program(&self, context: &mut Context, program: &Program) {
-    let mut visitor = CamelcaseVisitor::new(context);
+    let mut visitor = CamelcaseVisitor::new(context, &self.allow);
     visitor.visit_program(program, program);
     visitor.report_errors();
   }
 
+  fn set_options(&mut self, options: Value) -> Result<(), RuleOptionsError> {
+    let options: CamelcaseOptions = parse_rule_options(self.code(), options)?;
+    self.allow = options
+      .allow
+      .iter()
+      .map(|pattern| Regex::new(pattern))
+      .collect::<Result<_, _>>()
+      .map_err(|err| RuleOptionsError::new(self.code(), Some("allow"), err))?;
+    Ok(())
+  }
+
   fn docs(&self) -> &'static str {
     r#"Enforces the use of camelCase in variable names
 
@@ -98,6 +122,13 @@ type PascalCaseType = { someProperty: number; };
 
 interface PascalCaseInterface { someProperty: number; }
 ```
+
+### Options:
+```json
+{ "allow": ["^UNSAFE_", "^snake_case_allowed$"] }
+```
+Identifiers matching any of the regular expressions in `allow` are not
+reported.
 "#
   }
 }
@@ -393,14 +424,17 @@ struct CamelcaseVisitor<'c> {
   errors: BTreeMap<Span, IdentToCheck>,
   /// Already visited identifiers
   visited: BTreeSet<Span>,
+  /// Patterns of identifiers that are never reported
+  allow: &'c [Regex],
 }
 
 impl<'c> CamelcaseVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
+  fn new(context: &'c mut Context, allow: &'c [Regex]) -> Self {
     Self {
       context,
       errors: BTreeMap::new(),
       visited: BTreeSet::new(),
+      allow,
     }
   }
 
@@ -419,7 +453,11 @@ impl<'c> CamelcaseVisitor<'c> {
   /// Check if this ident is underscored only when it's not yet visited.
   fn check_ident<S: Spanned>(&mut self, span: &S, ident: IdentToCheck) {
     let span = span.span();
-    if self.visited.insert(span) && is_underscored(ident.get_ident_name()) {
+    let name = ident.get_ident_name();
+    if self.visited.insert(span)
+      && is_underscored(name)
+      && !self.allow.iter().any(|re| re.is_match(name))
+    {
       self.errors.insert(span, ident);
     }
   }
@@ -732,6 +770,8 @@ impl<'c> Visit for CamelcaseVisitor<'c> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::test_util::lint_with_options;
+  use serde_json::json;
 
   #[test]
   fn test_is_underscored() {
@@ -1251,4 +1291,24 @@ mod tests {
           ],
     };
   }
+
+  #[test]
+  fn camelcase_allow() {
+    let options = json!({ "allow": ["^UNSAFE_", "^snake_case$"] });
+    let diagnostics = lint_with_options::<Camelcase>(
+      options,
+      r#"
+function UNSAFE_componentWillMount() {}
+const snake_case = 1;
+const snake_case_2 = 2;
+"#,
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range.start.line, 4);
+
+    let err = Camelcase::new()
+      .set_options(json!({ "allow": ["("] }))
+      .unwrap_err();
+    assert_eq!(err.field.as_deref(), Some("allow"));
+  }
 }
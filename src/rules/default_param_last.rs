@@ -1,12 +1,9 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use swc_common::Span;
-use swc_ecmascript::ast::{ArrowExpr, Function, Pat};
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::VisitAll;
-use swc_ecmascript::visit::VisitAllWith;
+use swc_ecmascript::ast::Pat;
 
 pub struct DefaultParamLast;
 
@@ -19,13 +16,8 @@ impl LintRule for DefaultParamLast {
     "default-param-last"
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = DefaultParamLastVisitor::new(context);
-    program.visit_all_with(program, &mut visitor);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -56,54 +48,55 @@ function f(a = 2, b = 3) {}
   }
 }
 
-struct DefaultParamLastVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> DefaultParamLastVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for DefaultParamLast {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::Function, NodeKind::ArrowExpr]
   }
 
-  fn report(&mut self, span: Span) {
-    self.context.add_diagnostic_with_hint(
-      span,
-      "default-param-last",
-      "default parameters should be at last",
-      "Modify the signatures to move default parameter(s) to the end",
-    );
-  }
-
-  fn check_params<'a, 'b, I>(&'a mut self, params: I)
-  where
-    I: Iterator<Item = &'b Pat>,
-  {
-    let mut has_seen_normal_param = false;
-    for param in params {
-      match param {
-        Pat::Assign(pat) => {
-          if has_seen_normal_param {
-            self.report(pat.span);
-          }
-        }
-        Pat::Rest(_) => {}
-        _ => {
-          has_seen_normal_param = true;
-        }
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    match node {
+      NodeRef::Function(function) => {
+        check_params(context, function.params.iter().rev().map(|p| &p.pat))
+      }
+      NodeRef::ArrowExpr(arrow_expr) => {
+        check_params(context, arrow_expr.params.iter().rev())
       }
+      _ => {}
     }
   }
 }
 
-impl<'c> VisitAll for DefaultParamLastVisitor<'c> {
-  noop_visit_type!();
-
-  fn visit_function(&mut self, function: &Function, _parent: &dyn Node) {
-    self.check_params(function.params.iter().rev().map(|p| &p.pat));
-  }
+fn report(context: &mut Context, span: Span) {
+  context.add_diagnostic_with_hint(
+    span,
+    "default-param-last",
+    "default parameters should be at last",
+    "Modify the signatures to move default parameter(s) to the end",
+  );
+}
 
-  fn visit_arrow_expr(&mut self, arrow_expr: &ArrowExpr, _parent: &dyn Node) {
-    self.check_params(arrow_expr.params.iter().rev());
+fn check_params<'a, I>(context: &mut Context, params: I)
+where
+  I: Iterator<Item = &'a Pat>,
+{
+  let mut has_seen_normal_param = false;
+  for param in params {
+    match param {
+      Pat::Assign(pat) => {
+        if has_seen_normal_param {
+          report(context, pat.span);
+        }
+      }
+      Pat::Rest(_) => {}
+      _ => {
+        has_seen_normal_param = true;
+      }
+    }
   }
 }
 
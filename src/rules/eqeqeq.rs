@@ -1,11 +1,9 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use derive_more::Display;
-use swc_ecmascript::ast::{BinExpr, BinaryOp};
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
+use swc_ecmascript::ast::BinaryOp;
 
 pub struct Eqeqeq;
 
@@ -36,13 +34,8 @@ impl LintRule for Eqeqeq {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = EqeqeqVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -69,31 +62,27 @@ if ("hello world" !== input) {}
   }
 }
 
-struct EqeqeqVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> EqeqeqVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for Eqeqeq {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::BinExpr]
   }
-}
 
-impl<'c> Visit for EqeqeqVisitor<'c> {
-  noop_visit_type!();
-
-  fn visit_bin_expr(&mut self, bin_expr: &BinExpr, parent: &dyn Node) {
-    if matches!(bin_expr.op, BinaryOp::EqEq | BinaryOp::NotEq) {
-      let (message, hint) = if bin_expr.op == BinaryOp::EqEq {
-        (EqeqeqMessage::ExpectedEqual, EqeqeqHint::UseEqeqeq)
-      } else {
-        (EqeqeqMessage::ExpectedNotEqual, EqeqeqHint::UseNoteqeq)
-      };
-      self
-        .context
-        .add_diagnostic_with_hint(bin_expr.span, CODE, message, hint)
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::BinExpr(bin_expr) = node {
+      if matches!(bin_expr.op, BinaryOp::EqEq | BinaryOp::NotEq) {
+        let (message, hint) = if bin_expr.op == BinaryOp::EqEq {
+          (EqeqeqMessage::ExpectedEqual, EqeqeqHint::UseEqeqeq)
+        } else {
+          (EqeqeqMessage::ExpectedNotEqual, EqeqeqHint::UseNoteqeq)
+        };
+        context.add_diagnostic_with_hint(bin_expr.span, CODE, message, hint)
+      }
     }
-    swc_ecmascript::visit::visit_bin_expr(self, bin_expr, parent);
   }
 }
 
@@ -1,9 +1,7 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 
 pub struct ExplicitFunctionReturnType;
 
@@ -16,13 +14,8 @@ impl LintRule for ExplicitFunctionReturnType {
     "explicit-function-return-type"
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = ExplicitFunctionReturnTypeVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -47,34 +40,26 @@ function anotherCalc(): void { return; }
   }
 }
 
-struct ExplicitFunctionReturnTypeVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> ExplicitFunctionReturnTypeVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for ExplicitFunctionReturnType {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::Function]
   }
-}
-
-impl<'c> Visit for ExplicitFunctionReturnTypeVisitor<'c> {
-  noop_visit_type!();
 
-  fn visit_function(
-    &mut self,
-    function: &swc_ecmascript::ast::Function,
-    _parent: &dyn Node,
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
   ) {
-    if function.return_type.is_none() {
-      self.context.add_diagnostic_with_hint(
-        function.span,
-        "explicit-function-return-type",
-        "Missing return type on function",
-        "Add a return type to the function signature",
-      );
-    }
-    for stmt in &function.body {
-      self.visit_block_stmt(stmt, _parent);
+    if let NodeRef::Function(function) = node {
+      if function.return_type.is_none() {
+        context.add_diagnostic_with_hint(
+          function.span,
+          "explicit-function-return-type",
+          "Missing return type on function",
+          "Add a return type to the function signature",
+        );
+      }
     }
   }
 }
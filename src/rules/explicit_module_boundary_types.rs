@@ -1,6 +1,9 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use super::{parse_rule_options, RuleOptionsError};
+use serde::Deserialize;
+use serde_json::Value;
 use swc_common::Span;
 use swc_ecmascript::visit::noop_visit_type;
 use swc_ecmascript::visit::Node;
@@ -8,14 +11,27 @@ use swc_ecmascript::visit::Visit;
 
 use swc_ecmascript::ast::{
   ArrowExpr, Class, ClassMember, Decl, DefaultDecl, Expr, Function, ModuleDecl,
-  Pat, Program, TsKeywordTypeKind, TsType, TsTypeAnn, VarDecl,
+  Pat, Program, PropName, TsKeywordTypeKind, TsType, TsTypeAnn, VarDecl,
 };
 
-pub struct ExplicitModuleBoundaryTypes;
+pub struct ExplicitModuleBoundaryTypes {
+  options: ExplicitModuleBoundaryTypesOptions,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct ExplicitModuleBoundaryTypesOptions {
+  /// Allows arguments that are explicitly typed as `any`.
+  pub allow_arguments_explicitly_typed_as_any: bool,
+  /// Names of functions and methods that are not checked.
+  pub allowed_names: Vec<String>,
+}
 
 impl LintRule for ExplicitModuleBoundaryTypes {
   fn new() -> Box<Self> {
-    Box::new(ExplicitModuleBoundaryTypes)
+    Box::new(ExplicitModuleBoundaryTypes {
+      options: ExplicitModuleBoundaryTypesOptions::default(),
+    })
   }
 
   fn code(&self) -> &'static str {
@@ -23,10 +39,16 @@ impl LintRule for ExplicitModuleBoundaryTypes {
   }
 
   fn lint_program(&self, context: &mut Context, program: &Program) {
-    let mut visitor = ExplicitModuleBoundaryTypesVisitor::new(context);
+    let mut visitor =
+      ExplicitModuleBoundaryTypesVisitor::new(context, &self.options);
     visitor.visit_program(program, program);
   }
 
+  fn set_options(&mut self, options: Value) -> Result<(), RuleOptionsError> {
+    self.options = parse_rule_options(self.code(), options)?;
+    Ok(())
+  }
+
   fn docs(&self) -> &'static str {
     r#"Requires all module exports to have fully typed declarations
 
@@ -62,28 +84,56 @@ function isValid() {
   return true;
 }
 ```
+
+### Options:
+```json
+{
+  "allowArgumentsExplicitlyTypedAsAny": true,
+  "allowedNames": ["handler"]
+}
+```
+`allowArgumentsExplicitlyTypedAsAny` accepts arguments annotated with `any`,
+and functions or methods named in `allowedNames` are not checked at all.
 "#
   }
 }
 
 struct ExplicitModuleBoundaryTypesVisitor<'c> {
   context: &'c mut Context,
+  options: &'c ExplicitModuleBoundaryTypesOptions,
 }
 
 impl<'c> ExplicitModuleBoundaryTypesVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+  fn new(
+    context: &'c mut Context,
+    options: &'c ExplicitModuleBoundaryTypesOptions,
+  ) -> Self {
+    Self { context, options }
+  }
+
+  fn is_allowed_name(&self, name: Option<&str>) -> bool {
+    name.map_or(false, |name| {
+      self.options.allowed_names.iter().any(|n| n == name)
+    })
   }
 
   fn check_class(&mut self, class: &Class) {
     for member in &class.body {
       if let ClassMember::Method(method) = member {
-        self.check_fn(&method.function);
+        let name = match &method.key {
+          PropName::Ident(ident) => Some(&*ident.sym),
+          PropName::Str(s) => Some(&*s.value),
+          _ => None,
+        };
+        self.check_fn(&method.function, name);
       }
     }
   }
 
-  fn check_fn(&mut self, function: &Function) {
+  fn check_fn(&mut self, function: &Function, name: Option<&str>) {
+    if self.is_allowed_name(name) {
+      return;
+    }
     if function.return_type.is_none() {
       self.context.add_diagnostic_with_hint(
         function.span,
@@ -97,7 +147,10 @@ impl<'c> ExplicitModuleBoundaryTypesVisitor<'c> {
     }
   }
 
-  fn check_arrow(&mut self, arrow: &ArrowExpr) {
+  fn check_arrow(&mut self, arrow: &ArrowExpr, name: Option<&str>) {
+    if self.is_allowed_name(name) {
+      return;
+    }
     if arrow.return_type.is_none() {
       self.context.add_diagnostic_with_hint(
         arrow.span,
@@ -115,7 +168,9 @@ impl<'c> ExplicitModuleBoundaryTypesVisitor<'c> {
     if let Some(ann) = ann {
       let ts_type = ann.type_ann.as_ref();
       if let TsType::TsKeywordType(keyword_type) = ts_type {
-        if TsKeywordTypeKind::TsAnyKeyword == keyword_type.kind {
+        if TsKeywordTypeKind::TsAnyKeyword == keyword_type.kind
+          && !self.options.allow_arguments_explicitly_typed_as_any
+        {
           self.context.add_diagnostic_with_hint(
             span,
             "explicit-module-boundary-types",
@@ -149,7 +204,11 @@ impl<'c> ExplicitModuleBoundaryTypesVisitor<'c> {
     for declarator in &var.decls {
       if let Some(expr) = &declarator.init {
         if let Expr::Arrow(arrow) = expr.as_ref() {
-          self.check_arrow(arrow);
+          let name = match &declarator.name {
+            Pat::Ident(ident) => Some(&*ident.sym),
+            _ => None,
+          };
+          self.check_arrow(arrow, name);
         }
       }
     }
@@ -167,13 +226,16 @@ impl<'c> Visit for ExplicitModuleBoundaryTypesVisitor<'c> {
     match module_decl {
       ModuleDecl::ExportDecl(export) => match &export.decl {
         Decl::Class(decl) => self.check_class(&decl.class),
-        Decl::Fn(decl) => self.check_fn(&decl.function),
+        Decl::Fn(decl) => self.check_fn(&decl.function, Some(&decl.ident.sym)),
         Decl::Var(var) => self.check_var_decl(var),
         _ => {}
       },
       ModuleDecl::ExportDefaultDecl(export) => match &export.decl {
         DefaultDecl::Class(expr) => self.check_class(&expr.class),
-        DefaultDecl::Fn(expr) => self.check_fn(&expr.function),
+        DefaultDecl::Fn(expr) => self.check_fn(
+          &expr.function,
+          expr.ident.as_ref().map(|ident| &*ident.sym),
+        ),
         _ => {}
       },
       _ => {}
@@ -185,6 +247,7 @@ impl<'c> Visit for ExplicitModuleBoundaryTypesVisitor<'c> {
 mod tests {
   use super::*;
   use crate::test_util::*;
+  use serde_json::json;
 
   #[test]
   fn explicit_module_boundary_types_valid() {
@@ -225,4 +288,31 @@ mod tests {
       20,
     );
   }
+
+  #[test]
+  fn explicit_module_boundary_types_options() {
+    let options = json!({
+      "allowArgumentsExplicitlyTypedAsAny": true,
+      "allowedNames": ["handler", "method"],
+    });
+    for source in &[
+      "export var arrowFn = (arg: any): string => `test ${arg}`;",
+      "export function handler(req) { return; }",
+      "export var handler = (req) => {};",
+      "export default function handler() { return 1; }",
+      "export class Test { method() { return; } }",
+    ] {
+      let diagnostics = lint_with_options::<ExplicitModuleBoundaryTypes>(
+        options.clone(),
+        source,
+      );
+      assert!(diagnostics.is_empty(), "{}", source);
+    }
+
+    let diagnostics = lint_with_options::<ExplicitModuleBoundaryTypes>(
+      options,
+      "export function test(arg: any, other) { return; }",
+    );
+    assert_eq!(diagnostics.len(), 2);
+  }
 }
@@ -11,6 +11,7 @@ use swc_ecmascript::ast::PatOrExpr;
 use swc_ecmascript::ast::UnaryOp;
 use swc_ecmascript::ast::UpdateExpr;
 use swc_ecmascript::ast::UpdateOp;
+use swc_ecmascript::utils::Value;
 use swc_ecmascript::visit::noop_visit_type;
 use swc_ecmascript::visit::Node;
 use swc_ecmascript::visit::VisitAll;
@@ -36,7 +37,7 @@ impl LintRule for ForDirection {
     context: &mut Context,
     program: &swc_ecmascript::ast::Program,
   ) {
-    let mut visitor = ForDirectionVisitor::new(context);
+    let mut visitor = ForDirectionVisitor::new(context, program);
     program.visit_all_with(program, &mut visitor);
   }
 
@@ -67,11 +68,15 @@ const HINT: &str =
 
 struct ForDirectionVisitor<'c> {
   context: &'c mut Context,
+  program: &'c swc_ecmascript::ast::Program,
 }
 
 impl<'c> ForDirectionVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+  fn new(
+    context: &'c mut Context,
+    program: &'c swc_ecmascript::ast::Program,
+  ) -> Self {
+    Self { context, program }
   }
 
   fn check_update_direction(
@@ -134,6 +139,17 @@ impl<'c> ForDirectionVisitor<'c> {
     assign_expr: &AssignExpr,
     direction: i32,
   ) -> i32 {
+    let evaluator = self.context.const_evaluator(self.program);
+    if let Value::Known(step) = evaluator.eval_number(&assign_expr.right) {
+      return if step > 0.0 {
+        direction
+      } else if step < 0.0 {
+        -direction
+      } else {
+        0
+      };
+    }
+
     match &*assign_expr.right {
       Expr::Unary(unary_expr) => {
         if unary_expr.op == UnaryOp::Minus {
@@ -232,6 +248,10 @@ mod tests {
       "for(let i = 0; i === 0; i++) {}",
       "for(let i = 0; i == 0; i++) {}",
       "for(let i = 0; i < 2; ++i) { for (let j = 0; j < 2; j++) {} }",
+      "const step = 2; for(let i = 0; i < 10; i += step) {}",
+      "const step = -2; for(let i = 10; i > 0; i += step) {}",
+      "for(let i = 0; i < 10; i -= -(1 + 1)) {}",
+      "for(let i = 0; i < 10; i += step) {}",
     };
   }
 
@@ -356,6 +376,29 @@ mod tests {
         }
       ],
 
+      // constant steps
+      "const step = -1; for(let i = 0; i < 2; i += step) {}": [
+        {
+          col: 17,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "const step = 1; for(let i = 2; i >= 0; i -= -step) {}": [
+        {
+          col: 16,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "for(let i = 0; i < 2; i += 2 - 3) {}": [
+        {
+          col: 0,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+
       // nested
       r#"
 for (let i = 0; i < 2; i++) {
@@ -353,6 +353,9 @@ mod tests {
       "class Foo { bar() {} }",
       "class Foo { get bar() { if (baz) { return true; } else { return false; } } }",
       "class Foo { get() { return true; } }",
+      "class Foo { get bar() { try { return baz(); } finally { cleanup(); } } }",
+      "class Foo { get bar() { try { baz(); } finally { return true; } } }",
+      "class Foo { get bar() { try { throw e; } catch (e) { return true; } } }",
       r#"Object.defineProperty(foo, "bar", { get: function () { return true; } });"#,
       r#"Object.defineProperty(foo, "bar",
          { get: function () { ~function() { return true; }(); return true; } });"#,
@@ -540,6 +543,13 @@ const obj = {
           hint: GetterReturnHint::Return,
         }
       ],
+      "class Foo { get bar() { try { return baz(); } catch (e) {} } }": [
+        {
+          col: 12,
+          message: variant!(GetterReturnMessage, ExpectedAlways, "bar"),
+          hint: GetterReturnHint::Return,
+        }
+      ],
       "class Foo { get bar(){ ~function () { return true; }() } }": [
         {
           col: 12,
@@ -1,12 +1,9 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use swc_common::Span;
-use swc_ecmascript::ast::{CallExpr, Expr, ExprOrSpread, ExprOrSuper, NewExpr};
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::VisitAll;
-use swc_ecmascript::visit::VisitAllWith;
+use swc_ecmascript::ast::{Expr, ExprOrSpread, ExprOrSuper};
 
 pub struct NoArrayConstructor;
 
@@ -27,13 +24,8 @@ impl LintRule for NoArrayConstructor {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoArrayConstructorVisitor::new(context);
-    program.visit_all_with(program, &mut visitor);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -68,60 +60,63 @@ const c = [1,2,3];
   }
 }
 
-struct NoArrayConstructorVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoArrayConstructorVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
-  }
-
-  fn check_args(&mut self, args: Vec<ExprOrSpread>, span: Span) {
-    if args.len() != 1 {
-      self
-        .context
-        .add_diagnostic_with_hint(span, CODE, MESSAGE, HINT);
-    }
+fn check_args(context: &mut Context, args: Vec<ExprOrSpread>, span: Span) {
+  if args.len() != 1 {
+    context.add_diagnostic_with_hint(span, CODE, MESSAGE, HINT);
   }
 }
 
-impl<'c> VisitAll for NoArrayConstructorVisitor<'c> {
-  noop_visit_type!();
-
-  fn visit_new_expr(&mut self, new_expr: &NewExpr, _parent: &dyn Node) {
-    if let Expr::Ident(ident) = &*new_expr.callee {
-      let name = ident.sym.as_ref();
-      if name != "Array" {
-        return;
-      }
-      if new_expr.type_args.is_some() {
-        return;
-      }
-      match &new_expr.args {
-        Some(args) => {
-          self.check_args(args.to_vec(), new_expr.span);
-        }
-        None => self.check_args(vec![], new_expr.span),
-      };
-    }
+impl NodeRule for NoArrayConstructor {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::NewExpr, NodeKind::CallExpr]
   }
 
-  fn visit_call_expr(&mut self, call_expr: &CallExpr, _parent: &dyn Node) {
-    if let ExprOrSuper::Expr(expr) = &call_expr.callee {
-      if let Expr::Ident(ident) = expr.as_ref() {
-        let name = ident.sym.as_ref();
-        if name != "Array" {
-          return;
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    match node {
+      NodeRef::NewExpr(new_expr) => {
+        if let Expr::Ident(ident) = &*new_expr.callee {
+          let name = ident.sym.as_ref();
+          if name != "Array" {
+            return;
+          }
+          if new_expr.type_args.is_some() {
+            return;
+          }
+          match &new_expr.args {
+            Some(args) => {
+              check_args(context, args.to_vec(), new_expr.span);
+            }
+            None => check_args(context, vec![], new_expr.span),
+          };
         }
-        if call_expr.type_args.is_some() {
-          return;
+      }
+      NodeRef::CallExpr(call_expr) => {
+        if let ExprOrSuper::Expr(expr) = &call_expr.callee {
+          if let Expr::Ident(ident) = expr.as_ref() {
+            let name = ident.sym.as_ref();
+            if name != "Array" {
+              return;
+            }
+            if call_expr.type_args.is_some() {
+              return;
+            }
+
+            check_args(context, (&*call_expr.args).to_vec(), call_expr.span);
+          }
         }
-
-        self.check_args((&*call_expr.args).to_vec(), call_expr.span);
       }
+      _ => {}
     }
   }
+
+  fn checks_types(&self) -> bool {
+    true
+  }
 }
 
 #[cfg(test)]
@@ -1,11 +1,8 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::ast::{Expr, NewExpr, ParenExpr};
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::VisitAll;
-use swc_ecmascript::visit::VisitAllWith;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
+use swc_ecmascript::ast::{Expr, ParenExpr};
 
 pub struct NoAsyncPromiseExecutor;
 
@@ -27,13 +24,8 @@ impl LintRule for NoAsyncPromiseExecutor {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoAsyncPromiseExecutorVisitor::new(context);
-    program.visit_all_with(program, &mut visitor);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -67,16 +59,6 @@ new Promise((resolve, reject) => {});
   }
 }
 
-struct NoAsyncPromiseExecutorVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoAsyncPromiseExecutorVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
-  }
-}
-
 fn is_async_function(expr: &Expr) -> bool {
   match expr {
     Expr::Fn(fn_expr) => fn_expr.function.is_async,
@@ -86,30 +68,43 @@ fn is_async_function(expr: &Expr) -> bool {
   }
 }
 
-impl<'c> VisitAll for NoAsyncPromiseExecutorVisitor<'c> {
-  noop_visit_type!();
+impl NodeRule for NoAsyncPromiseExecutor {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::NewExpr]
+  }
 
-  fn visit_new_expr(&mut self, new_expr: &NewExpr, _parent: &dyn Node) {
-    if let Expr::Ident(ident) = &*new_expr.callee {
-      let name = ident.sym.as_ref();
-      if name != "Promise" {
-        return;
-      }
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::NewExpr(new_expr) = node {
+      if let Expr::Ident(ident) = &*new_expr.callee {
+        let name = ident.sym.as_ref();
+        if name != "Promise" {
+          return;
+        }
 
-      if let Some(args) = &new_expr.args {
-        if let Some(first_arg) = args.get(0) {
-          if is_async_function(&*first_arg.expr) {
-            self.context.add_diagnostic_with_hint(
-              new_expr.span,
-              CODE,
-              MESSAGE,
-              HINT,
-            );
+        if let Some(args) = &new_expr.args {
+          if let Some(first_arg) = args.get(0) {
+            if is_async_function(&*first_arg.expr) {
+              context.add_diagnostic_with_hint(
+                new_expr.span,
+                CODE,
+                MESSAGE,
+                HINT,
+              );
+            }
           }
         }
       }
     }
   }
+
+  fn checks_types(&self) -> bool {
+    true
+  }
 }
 
 #[cfg(test)]
@@ -1,14 +1,10 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use swc_ecmascript::ast::Decl;
 use swc_ecmascript::ast::Stmt;
-use swc_ecmascript::ast::SwitchCase;
 use swc_ecmascript::ast::VarDeclKind;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::VisitAll;
-use swc_ecmascript::visit::VisitAllWith;
 
 pub struct NoCaseDeclarations;
 
@@ -29,13 +25,8 @@ impl LintRule for NoCaseDeclarations {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoCaseDeclarationsVisitor::new(context);
-    program.visit_all_with(program, &mut visitor);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -91,24 +82,21 @@ switch (choice) {
   }
 }
 
-struct NoCaseDeclarationsVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoCaseDeclarationsVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoCaseDeclarations {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::SwitchCase]
   }
-}
-
-impl<'c> VisitAll for NoCaseDeclarationsVisitor<'c> {
-  noop_visit_type!();
 
-  fn visit_switch_case(
-    &mut self,
-    switch_case: &SwitchCase,
-    _parent: &dyn Node,
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
   ) {
+    let switch_case = match node {
+      NodeRef::SwitchCase(switch_case) => switch_case,
+      _ => return,
+    };
     for stmt in &switch_case.cons {
       let is_lexical_decl = match stmt {
         Stmt::Decl(decl) => match &decl {
@@ -121,12 +109,7 @@ impl<'c> VisitAll for NoCaseDeclarationsVisitor<'c> {
       };
 
       if is_lexical_decl {
-        self.context.add_diagnostic_with_hint(
-          switch_case.span,
-          CODE,
-          MESSAGE,
-          HINT,
-        );
+        context.add_diagnostic_with_hint(switch_case.span, CODE, MESSAGE, HINT);
       }
     }
   }
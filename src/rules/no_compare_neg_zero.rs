@@ -1,13 +1,11 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::{Context, LintRule};
+use crate::const_eval::ConstEvaluator;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use derive_more::Display;
 use swc_ecmascript::ast::BinaryOp::*;
-use swc_ecmascript::ast::Expr::{Lit, Unary};
-use swc_ecmascript::ast::Lit::Num;
-use swc_ecmascript::ast::UnaryExpr;
-use swc_ecmascript::ast::UnaryOp::Minus;
-use swc_ecmascript::ast::{BinExpr, BinaryOp, Expr, Program};
-use swc_ecmascript::visit::{noop_visit_type, Node, VisitAll, VisitAllWith};
+use swc_ecmascript::ast::{BinaryOp, Expr};
+use swc_ecmascript::utils::Value;
 
 pub struct NoCompareNegZero;
 
@@ -40,9 +38,8 @@ impl LintRule for NoCompareNegZero {
     CODE
   }
 
-  fn lint_program(&self, context: &mut Context, program: &Program) {
-    let mut visitor = NoCompareNegZeroVisitor::new(context);
-    program.visit_all_with(program, &mut visitor);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -64,32 +61,38 @@ if (Object.is(x, -0)) {}
   }
 }
 
-struct NoCompareNegZeroVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoCompareNegZeroVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoCompareNegZero {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::BinExpr]
   }
-}
 
-impl<'c> VisitAll for NoCompareNegZeroVisitor<'c> {
-  noop_visit_type!();
+  fn check_node(
+    &self,
+    context: &mut Context,
+    program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::BinExpr(bin_expr) = node {
+      if !bin_expr.op.is_comparator() {
+        return;
+      }
 
-  fn visit_bin_expr(&mut self, bin_expr: &BinExpr, _parent: &dyn Node) {
-    if !bin_expr.op.is_comparator() {
-      return;
+      let evaluator = context.const_evaluator(program);
+      if is_neg_zero(&evaluator, &bin_expr.left)
+        || is_neg_zero(&evaluator, &bin_expr.right)
+      {
+        context.add_diagnostic_with_hint(
+          bin_expr.span,
+          CODE,
+          NoCompareNegZeroMessage::Unexpected,
+          NoCompareNegZeroHint::ObjectIs,
+        );
+      }
     }
+  }
 
-    if bin_expr.left.is_neg_zero() || bin_expr.right.is_neg_zero() {
-      self.context.add_diagnostic_with_hint(
-        bin_expr.span,
-        CODE,
-        NoCompareNegZeroMessage::Unexpected,
-        NoCompareNegZeroHint::ObjectIs,
-      );
-    }
+  fn checks_types(&self) -> bool {
+    true
   }
 }
 
@@ -106,25 +109,10 @@ impl Comparator for BinaryOp {
   }
 }
 
-trait NegZero {
-  fn is_neg_zero(&self) -> bool;
-}
-
-impl NegZero for Expr {
-  fn is_neg_zero(&self) -> bool {
-    match self {
-      Unary(unary) => unary.is_neg_zero(),
-      _ => false,
-    }
-  }
-}
-
-impl NegZero for UnaryExpr {
-  fn is_neg_zero(&self) -> bool {
-    if let (Minus, Lit(Num(number))) = (self.op, &*self.arg) {
-      return number.value == 0.0;
-    }
-    false
+fn is_neg_zero(evaluator: &ConstEvaluator, expr: &Expr) -> bool {
+  match evaluator.eval_number(expr) {
+    Value::Known(n) => n == 0.0 && n.is_sign_negative(),
+    Value::Unknown => false,
   }
 }
 
@@ -169,6 +157,9 @@ mod tests {
       r#"x !== 0"#,
       r#"0 !== x"#,
       r#"{} == { foo: x === 0 }"#,
+      r#"const zero = 0; x === zero"#,
+      r#"let negZero = -0; x === negZero"#,
+      r#"x === -0n"#,
     };
   }
 
@@ -317,6 +308,29 @@ mod tests {
         }
       ],
 
+      // constant expressions
+      "if (x === -(0)) { }": [
+        {
+          col: 4,
+          message: NoCompareNegZeroMessage::Unexpected,
+          hint: NoCompareNegZeroHint::ObjectIs,
+        }
+      ],
+      "if (x === 0 * -1) { }": [
+        {
+          col: 4,
+          message: NoCompareNegZeroMessage::Unexpected,
+          hint: NoCompareNegZeroHint::ObjectIs,
+        }
+      ],
+      "const NEG_ZERO = -0; if (x === NEG_ZERO) { }": [
+        {
+          col: 25,
+          message: NoCompareNegZeroMessage::Unexpected,
+          hint: NoCompareNegZeroHint::ObjectIs,
+        }
+      ],
+
       // nested
       "{} == { foo: x === -0 }": [
         {
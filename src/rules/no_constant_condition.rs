@@ -42,7 +42,7 @@ impl LintRule for NoConstantCondition {
   }
 
   fn lint_program(&self, context: &mut Context, program: &Program) {
-    let mut visitor = NoConstantConditionVisitor::new(context);
+    let mut visitor = NoConstantConditionVisitor::new(context, program);
     program.visit_all_with(program, &mut visitor);
   }
 
@@ -71,11 +71,12 @@ do {} while (x === 2);
 
 struct NoConstantConditionVisitor<'c> {
   context: &'c mut Context,
+  program: &'c Program,
 }
 
 impl<'c> NoConstantConditionVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+  fn new(context: &'c mut Context, program: &'c Program) -> Self {
+    Self { context, program }
   }
 
   fn add_diagnostic(&mut self, span: Span) {
@@ -191,7 +192,13 @@ impl<'c> NoConstantConditionVisitor<'c> {
   }
 
   fn report(&mut self, condition: &Expr) {
-    if self.is_constant(condition, None, true) {
+    if self.is_constant(condition, None, true)
+      || self
+        .context
+        .const_evaluator(self.program)
+        .eval_bool(condition)
+        .is_known()
+    {
       let span = condition.span();
       self.add_diagnostic(span);
     }
@@ -292,10 +299,11 @@ mod tests {
       r#"if (void a || a);"#,
 
       // string literals
-      r#"if('str' || a){}"#,
       r#"if('str1' && a){}"#,
       r#"if(a && 'str'){}"#,
-      r#"if('str' || abc==='str'){}"#,
+      r#"let DEBUG = false; if (DEBUG) {}"#,
+      r#"const DEBUG = false; function f(DEBUG) { if (DEBUG) {} }"#,
+      r#"const mode = getMode(); if (mode === "prod") {}"#,
       r#"if ((foo || 'bar') === 'baz') {}"#,
       r#"if ((foo || 'bar') !== 'baz') {}"#,
       r#"if ((foo || 'bar') == 'baz') {}"#,
@@ -741,6 +749,57 @@ mod tests {
           message: NoConstantConditionMessage::Unexpected,
           hint: NoConstantConditionHint::Remove,
         }
+      ],
+
+      // short circuits and constant bindings
+      r#"if('str' || a){}"#: [
+        {
+          col: 3,
+          message: NoConstantConditionMessage::Unexpected,
+          hint: NoConstantConditionHint::Remove,
+        }
+      ],
+      r#"if('str' || abc==='str'){}"#: [
+        {
+          col: 3,
+          message: NoConstantConditionMessage::Unexpected,
+          hint: NoConstantConditionHint::Remove,
+        }
+      ],
+      r#"if(abc==='str' || 'str'){}"#: [
+        {
+          col: 3,
+          message: NoConstantConditionMessage::Unexpected,
+          hint: NoConstantConditionHint::Remove,
+        }
+      ],
+      r#"if(a || 'str'){}"#: [
+        {
+          col: 3,
+          message: NoConstantConditionMessage::Unexpected,
+          hint: NoConstantConditionHint::Remove,
+        }
+      ],
+      r#"if(a && 0){}"#: [
+        {
+          col: 3,
+          message: NoConstantConditionMessage::Unexpected,
+          hint: NoConstantConditionHint::Remove,
+        }
+      ],
+      r#"const DEBUG = false; if (DEBUG) {}"#: [
+        {
+          col: 25,
+          message: NoConstantConditionMessage::Unexpected,
+          hint: NoConstantConditionHint::Remove,
+        }
+      ],
+      r#"const mode = "dev"; mode === "prod" ? a : b;"#: [
+        {
+          col: 20,
+          message: NoConstantConditionMessage::Unexpected,
+          hint: NoConstantConditionHint::Remove,
+        }
       ]
     };
   }
@@ -791,20 +850,6 @@ mod tests {
 
       // TODO(humancalico) more conditions should be added to pass these cases
       // https://github.com/eslint/eslint/blob/f4d7b9e1a599346b2f21ff9de003b311b51411e6/lib/rules/no-constant-condition.js#L135-L146
-      r#"if(abc==='str' || 'str'){}"#: [
-        {
-          col: 3,
-          message: NoConstantConditionMessage::Unexpected,
-          hint: NoConstantConditionHint::Remove,
-        }
-      ],
-      r#"if(a || 'str'){}"#: [
-        {
-          col: 3,
-          message: NoConstantConditionMessage::Unexpected,
-          hint: NoConstantConditionHint::Remove,
-        }
-      ],
       r#"function* foo(){while(true){} yield 'foo';}"#: [
         {
           col: 0,
@@ -1,10 +1,10 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::js_regex::ast::{walk_pattern, Character, Visit};
+use crate::js_regex::EcmaRegexParser;
 use crate::swc_util::extract_regex;
 use derive_more::Display;
-use std::iter::Peekable;
-use std::str::Chars;
 use swc_common::Span;
 use swc_ecmascript::ast::{CallExpr, Expr, ExprOrSuper, NewExpr, Regex};
 use swc_ecmascript::visit::noop_visit_type;
@@ -100,76 +100,60 @@ impl<'c> NoControlRegexVisitor<'c> {
     );
   }
 
-  fn check_regex(&mut self, regex: &str, span: Span) {
-    let mut iter = regex.chars().peekable();
-    while let Some(ch) = iter.next() {
-      if ch != '\\' {
-        continue;
-      }
-      match iter.next() {
-        Some('x') => {
-          if let Some(cp) = read_hex_n(&mut iter, 2) {
-            if cp <= 31 {
-              self.add_diagnostic(span, cp);
-              return;
-            }
-          }
-        }
-        Some('u') => {
-          let cp = match iter.peek() {
-            Some(&'{') => read_hex_until_brace(&mut iter),
-            Some(_) => read_hex_n(&mut iter, 4),
-            _ => None,
-          };
-          if let Some(cp) = cp {
-            if cp <= 31 {
-              self.add_diagnostic(span, cp);
-              return;
-            }
-          }
-        }
-        _ => continue,
-      }
+  fn check_regex(&mut self, regex: &str, flags: &str, span: Span) {
+    let mut parser = EcmaRegexParser::new(self.context.ecma_version());
+    // Invalid regexes are reported by `no-invalid-regexp`
+    let pattern = match parser
+      .parse_flags(flags)
+      .and_then(|flags| parser.parse_pattern(regex, flags))
+    {
+      Ok(pattern) => pattern,
+      Err(_) => return,
+    };
+
+    let mut finder = ControlCharacterFinder {
+      source: regex,
+      found: None,
+    };
+    walk_pattern(&mut finder, &pattern);
+    if let Some(cp) = finder.found {
+      self.add_diagnostic(span, cp as u64);
     }
   }
 }
 
-/// Read the next n characters and try to parse it as hexadecimal.
-fn read_hex_n(iter: &mut Peekable<Chars>, n: usize) -> Option<u64> {
-  let mut s = String::new();
-  for _ in 0..n {
-    let ch = iter.next()?;
-    s.push(ch);
-  }
-  u64::from_str_radix(s.as_str(), 16).ok()
+/// Finds the first control character written as `\x..` or `\u...`.
+struct ControlCharacterFinder<'a> {
+  source: &'a str,
+  found: Option<u32>,
 }
 
-/// Read characters until `}` and try to parse it as hexadecimal.
-fn read_hex_until_brace(iter: &mut Peekable<Chars>) -> Option<u64> {
-  iter.next(); // consume `{`
-  let mut s = String::new();
-  loop {
-    let ch = iter.next()?;
-    if ch == '}' {
-      break;
+impl Visit for ControlCharacterFinder<'_> {
+  fn visit_character(&mut self, c: &Character) {
+    if self.found.is_some() || c.value > 0x1f {
+      return;
+    }
+    let raw = &self.source[c.span.start..c.span.end];
+    if raw.starts_with("\\x") || raw.starts_with("\\u") {
+      self.found = Some(c.value);
     }
-    s.push(ch);
   }
-  u64::from_str_radix(s.as_str(), 16).ok()
 }
 
 impl<'c> VisitAll for NoControlRegexVisitor<'c> {
   noop_visit_type!();
 
   fn visit_regex(&mut self, regex: &Regex, _: &dyn Node) {
-    self.check_regex(regex.exp.to_string().as_str(), regex.span);
+    self.check_regex(&regex.exp, &regex.flags, regex.span);
   }
 
   fn visit_new_expr(&mut self, new_expr: &NewExpr, _: &dyn Node) {
     if let Expr::Ident(ident) = &*new_expr.callee {
       if let Some(args) = &new_expr.args {
-        if let Some(regex) = extract_regex(&self.context.scope, ident, args) {
-          self.check_regex(regex.as_str(), new_expr.span);
+        if let Some((regex, flags)) =
+          extract_regex(&self.context.scope, ident, args)
+        {
+          self.check_regex(&regex, &flags, new_expr.span);
         }
       }
     }
@@ -178,10 +162,10 @@ impl<'c> VisitAll for NoControlRegexVisitor<'c> {
   fn visit_call_expr(&mut self, call_expr: &CallExpr, _: &dyn Node) {
     if let ExprOrSuper::Expr(expr) = &call_expr.callee {
       if let Expr::Ident(ident) = expr.as_ref() {
-        if let Some(regex) =
+        if let Some((regex, flags)) =
           extract_regex(&self.context.scope, ident, &call_expr.args)
         {
-          self.check_regex(regex.as_str(), call_expr.span);
+          self.check_regex(&regex, &flags, call_expr.span);
         }
       }
     }
@@ -192,44 +176,6 @@ impl<'c> VisitAll for NoControlRegexVisitor<'c> {
 mod tests {
   use super::*;
 
-  #[test]
-  fn test_read_hex_n() {
-    let tests = [
-      (r#"1f"#, Some(0x1f)),
-      (r#"001f"#, Some(0x1f)),
-      (r#"1g"#, None),
-      (r#"001g"#, None),
-      (r#"1ff"#, Some(0x1ff)),
-      (r#"abcd"#, Some(0xabcd)),
-    ];
-
-    for &(input, expected) in tests.iter() {
-      assert_eq!(
-        read_hex_n(&mut input.chars().peekable(), input.len()),
-        expected
-      );
-    }
-  }
-
-  #[test]
-  fn test_read_hex_until_brace() {
-    let tests = [
-      (r#"{1f}"#, Some(0x1f)),
-      (r#"{001f}"#, Some(0x1f)),
-      (r#"{1g}"#, None),
-      (r#"{001g}"#, None),
-      (r#"{1ff}"#, Some(0x1ff)),
-      (r#"{abcd}"#, Some(0xabcd)),
-    ];
-
-    for &(input, expected) in tests.iter() {
-      assert_eq!(
-        read_hex_until_brace(&mut input.chars().peekable()),
-        expected,
-      );
-    }
-  }
-
   #[test]
   fn no_control_regex_valid() {
     assert_lint_ok! {
@@ -242,6 +188,8 @@ mod tests {
       r#"/\\u{001f}/"#,
       r#"/u{0001f}/"#,
       r#"/\\u{0001f}/"#,
+      r#"/\u{001f}/"#,
+      r#"/[\x20-\x7e]/"#,
       r#"new RegExp('x1f')"#,
       r#"RegExp('x1f')"#,
       r#"new RegExp('[')"#,
@@ -268,14 +216,14 @@ mod tests {
           hint: NoControlRegexHint::DisableOrRework,
         }
       ],
-      r#"/\u{001f}/"#: [
+      r#"/\u{001f}/u"#: [
         {
           col: 0,
           message: NoControlRegexMessage::Unexpected(0x1f),
           hint: NoControlRegexHint::DisableOrRework,
         }
       ],
-      r#"/\u{0001f}/"#: [
+      r#"/\u{0001f}/u"#: [
         {
           col: 0,
           message: NoControlRegexMessage::Unexpected(0x1f),
@@ -324,6 +272,20 @@ mod tests {
           hint: NoControlRegexHint::DisableOrRework,
         }
       ],
+      r#"/[\x00-\x1f]/"#: [
+        {
+          col: 0,
+          message: NoControlRegexMessage::Unexpected(0x0),
+          hint: NoControlRegexHint::DisableOrRework,
+        }
+      ],
+      r#"new RegExp('\\u{1f}', 'u')"#: [
+        {
+          col: 0,
+          message: NoControlRegexMessage::Unexpected(0x1f),
+          hint: NoControlRegexHint::DisableOrRework,
+        }
+      ],
       r#"RegExp('\\x1f')"#: [
         {
           col: 0,
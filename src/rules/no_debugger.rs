@@ -1,11 +1,8 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use derive_more::Display;
-use swc_ecmascript::ast::DebuggerStmt;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
 
 pub struct NoDebugger;
 
@@ -36,13 +33,8 @@ impl LintRule for NoDebugger {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoDebuggerVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -70,30 +62,25 @@ function isLongString(x: string) {
 "#
   }
 }
-struct NoDebuggerVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoDebuggerVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoDebugger {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::DebuggerStmt]
   }
-}
-
-impl<'c> Visit for NoDebuggerVisitor<'c> {
-  noop_visit_type!();
 
-  fn visit_debugger_stmt(
-    &mut self,
-    debugger_stmt: &DebuggerStmt,
-    _parent: &dyn Node,
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
   ) {
-    self.context.add_diagnostic_with_hint(
-      debugger_stmt.span,
-      CODE,
-      NoDebuggerMessage::Unexpected,
-      NoDebuggerHint::Remove,
-    );
+    if let NodeRef::DebuggerStmt(debugger_stmt) = node {
+      context.add_diagnostic_with_hint(
+        debugger_stmt.span,
+        CODE,
+        NoDebuggerMessage::Unexpected,
+        NoDebuggerHint::Remove,
+      );
+    }
   }
 }
 
@@ -1,13 +1,10 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use derive_more::Display;
 use swc_ecmascript::ast::Expr;
-use swc_ecmascript::ast::UnaryExpr;
 use swc_ecmascript::ast::UnaryOp;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
 
 pub struct NoDeleteVar;
 
@@ -38,13 +35,8 @@ impl LintRule for NoDeleteVar {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoDeleteVarVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -75,31 +67,30 @@ delete obj.a; // returns true;
   }
 }
 
-struct NoDeleteVarVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoDeleteVarVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoDeleteVar {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::UnaryExpr]
   }
-}
 
-impl<'c> Visit for NoDeleteVarVisitor<'c> {
-  noop_visit_type!();
-
-  fn visit_unary_expr(&mut self, unary_expr: &UnaryExpr, _parent: &dyn Node) {
-    if unary_expr.op != UnaryOp::Delete {
-      return;
-    }
-
-    if let Expr::Ident(_) = *unary_expr.arg {
-      self.context.add_diagnostic_with_hint(
-        unary_expr.span,
-        CODE,
-        NoDeleteVarMessage::Unexpected,
-        NoDeleteVarHint::Remove,
-      );
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::UnaryExpr(unary_expr) = node {
+      if unary_expr.op != UnaryOp::Delete {
+        return;
+      }
+
+      if let Expr::Ident(_) = *unary_expr.arg {
+        context.add_diagnostic_with_hint(
+          unary_expr.span,
+          CODE,
+          NoDeleteVarMessage::Unexpected,
+          NoDeleteVarHint::Remove,
+        );
+      }
     }
   }
 }
@@ -1,13 +1,19 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::{Context, LintRule};
+use crate::const_eval::{ConstEvaluator, Constant};
 use derive_more::Display;
 use std::collections::HashSet;
-use swc_common::{Span, Spanned};
+use swc_common::{Span, Spanned, DUMMY_SP};
 use swc_ecmascript::ast::{
-  BinExpr, BinaryOp, Expr, IfStmt, ParenExpr, Program, Stmt,
+  BinExpr, BinaryOp, Bool, Expr, IfStmt, Lit, Null, Number, ParenExpr, Program,
+  Stmt, Str, StrKind, TsAsExpr, TsConstAssertion, TsNonNullExpr,
+  TsTypeAssertion, UnaryOp,
+};
+use swc_ecmascript::utils::{drop_span, Value};
+use swc_ecmascript::visit::{
+  noop_visit_mut_type, noop_visit_type, Node, VisitAll, VisitAllWith, VisitMut,
+  VisitMutWith,
 };
-use swc_ecmascript::utils::drop_span;
-use swc_ecmascript::visit::{noop_visit_type, Node, VisitAll, VisitAllWith};
 
 pub struct NoDupeElseIf;
 
@@ -43,7 +49,7 @@ impl LintRule for NoDupeElseIf {
   }
 
   fn lint_program(&self, context: &mut Context, program: &Program) {
-    let mut visitor = NoDupeElseIfVisitor::new(context);
+    let mut visitor = NoDupeElseIfVisitor::new(context, program);
     program.visit_all_with(program, &mut visitor);
   }
 
@@ -84,18 +90,90 @@ else if (a === 7) {}
 /// [eslint/no-dupe-else-if.js](https://github.com/eslint/eslint/blob/master/lib/rules/no-dupe-else-if.js).
 struct NoDupeElseIfVisitor<'c> {
   context: &'c mut Context,
+  program: &'c Program,
   checked_span: HashSet<Span>,
 }
 
 impl<'c> NoDupeElseIfVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
+  fn new(context: &'c mut Context, program: &'c Program) -> Self {
     Self {
       context,
+      program,
       checked_span: HashSet::new(),
     }
   }
 }
 
+impl<'c> NoDupeElseIfVisitor<'c> {
+  /// Replaces constant parts of the condition with literals, so that e.g.
+  /// `a === FOO` and `a === 1` are compared equal after `const FOO = 1;`.
+  /// This must happen before dropping spans, which are needed to resolve
+  /// identifiers.
+  fn fold_constants(&self, test: &Expr) -> Box<Expr> {
+    let mut test = Box::new(test.clone());
+    test.visit_mut_with(&mut ConstantFolder {
+      evaluator: self.context.const_evaluator(self.program),
+    });
+    test
+  }
+}
+
+struct ConstantFolder<'a> {
+  evaluator: ConstEvaluator<'a>,
+}
+
+impl<'a> VisitMut for ConstantFolder<'a> {
+  noop_visit_mut_type!();
+
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    if !is_side_effect_free(expr) {
+      return expr.visit_mut_children_with(self);
+    }
+    let lit = match self.evaluator.eval(expr) {
+      Value::Known(Constant::Null) => Lit::Null(Null { span: DUMMY_SP }),
+      Value::Known(Constant::Bool(value)) => Lit::Bool(Bool {
+        span: DUMMY_SP,
+        value,
+      }),
+      Value::Known(Constant::Number(value)) => Lit::Num(Number {
+        span: DUMMY_SP,
+        value,
+      }),
+      Value::Known(Constant::String(value)) => Lit::Str(Str {
+        span: DUMMY_SP,
+        value: value.into(),
+        has_escape: false,
+        kind: StrKind::Synthesized,
+      }),
+      _ => return expr.visit_mut_children_with(self),
+    };
+    *expr = Expr::Lit(lit);
+  }
+}
+
+/// Whether `expr` is built only from literals and identifiers, so that
+/// replacing it with its value doesn't drop e.g. a call or an assignment.
+fn is_side_effect_free(expr: &Expr) -> bool {
+  match expr {
+    Expr::Lit(_) | Expr::Ident(_) => true,
+    Expr::Tpl(tpl) => tpl.exprs.iter().all(|expr| is_side_effect_free(expr)),
+    Expr::Unary(unary) => {
+      unary.op != UnaryOp::Delete && is_side_effect_free(&unary.arg)
+    }
+    Expr::Bin(bin) => {
+      is_side_effect_free(&bin.left) && is_side_effect_free(&bin.right)
+    }
+    Expr::Paren(ParenExpr { expr, .. })
+    | Expr::TsAs(TsAsExpr { expr, .. })
+    | Expr::TsNonNull(TsNonNullExpr { expr, .. })
+    | Expr::TsTypeAssertion(TsTypeAssertion { expr, .. })
+    | Expr::TsConstAssertion(TsConstAssertion { expr, .. }) => {
+      is_side_effect_free(expr)
+    }
+    _ => false,
+  }
+}
+
 impl<'c> VisitAll for NoDupeElseIfVisitor<'c> {
   noop_visit_type!();
 
@@ -105,7 +183,7 @@ impl<'c> VisitAll for NoDupeElseIfVisitor<'c> {
     // This check is necessary to avoid outputting the same errors multiple times.
     if !self.checked_span.contains(&span) {
       self.checked_span.insert(span);
-      let span_dropped_test = drop_span(if_stmt.test.clone());
+      let span_dropped_test = drop_span(self.fold_constants(&if_stmt.test));
       let mut appeared_conditions: Vec<Vec<Vec<Expr>>> = Vec::new();
       append_test(&mut appeared_conditions, *span_dropped_test);
 
@@ -117,7 +195,7 @@ impl<'c> VisitAll for NoDupeElseIfVisitor<'c> {
         {
           // preserve the span before dropping
           let span = test.span();
-          let span_dropped_test = drop_span(test.clone());
+          let span_dropped_test = drop_span(self.fold_constants(test));
           let mut current_condition_to_check: Vec<Vec<Vec<Expr>>> =
             mk_condition_to_check(*span_dropped_test.clone())
               .into_iter()
@@ -284,6 +362,9 @@ mod tests {
       "if (f(a)) {} else if (f(b)) {}",
       "if (a === 1) {} else if (a === 2) {}",
       "if (a === 1) {} else if (b === 1) {}",
+      "if (x = 1) {} else if (y = 1) {}",
+      "if ((f(), 1)) {} else if ((g(), 1)) {}",
+      "if (false) {} else if (![g()]) {}",
       "if (a) {}",
       "if (a);",
       "if (a) {} else {}",
@@ -313,7 +394,6 @@ if (a) {
 "#,
       "if (a) if (b); else if (a);",
       "if (a) {} else if (!!a) {}",
-      "if (a === 1) {} else if (a === (1)) {}",
       "if (a || b) {} else if (c || d) {}",
       "if (a || b) {} else if (a || c) {}",
       "if (a) {} else if (a || b) {}",
@@ -324,6 +404,9 @@ if (a) {
       "if (a) {} else if (b && (a || c)) {}",
       "if (a) {} else if (b && (c || d && a)) {}",
       "if (a && b && c) {} else if (a && b && (c || d)) {}",
+      "const ONE = 1; if (a === ONE) {} else if (a === 2) {}",
+      "let ONE = 1; if (a === ONE) {} else if (a === 1) {}",
+      "if (a === NaN) {} else if (a === 0 / 0) {}",
     };
   }
 
@@ -909,6 +992,36 @@ if (foo) {
           message: NoDupeElseIfMessage::Unexpected,
           hint: NoDupeElseIfHint::RemoveOrRework,
         }
+      ],
+
+      // constant expressions
+      "if (a === 1) {} else if (a === (1)) {}": [
+        {
+          col: 25,
+          message: NoDupeElseIfMessage::Unexpected,
+          hint: NoDupeElseIfHint::RemoveOrRework,
+        }
+      ],
+      "const ONE = 1; if (a === ONE) {} else if (a === 1) {}": [
+        {
+          col: 42,
+          message: NoDupeElseIfMessage::Unexpected,
+          hint: NoDupeElseIfHint::RemoveOrRework,
+        }
+      ],
+      "if (a === 'foo') {} else if (a === `f${'oo'}`) {}": [
+        {
+          col: 29,
+          message: NoDupeElseIfMessage::Unexpected,
+          hint: NoDupeElseIfHint::RemoveOrRework,
+        }
+      ],
+      "if (a > 60 * 60) {} else if (b) {} else if (a > 3600 || b) {}": [
+        {
+          col: 44,
+          message: NoDupeElseIfMessage::Unexpected,
+          hint: NoDupeElseIfHint::RemoveOrRework,
+        }
       ]
     };
   }
@@ -1,16 +1,16 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use crate::swc_util::StringRepr;
 use derive_more::Display;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use swc_common::Span;
 use swc_ecmascript::ast::{
-  GetterProp, KeyValueProp, MethodProp, ObjectLit, Program, Prop, PropOrSpread,
+  GetterProp, KeyValueProp, MethodProp, ObjectLit, Prop, PropOrSpread,
   SetterProp,
 };
-use swc_ecmascript::visit::{noop_visit_type, Node, VisitAll, VisitAllWith};
 
 pub struct NoDupeKeys;
 
@@ -41,9 +41,8 @@ impl LintRule for NoDupeKeys {
     CODE
   }
 
-  fn lint_program(&self, context: &mut Context, program: &Program) {
-    let mut visitor = NoDupeKeysVisitor::new(context);
-    program.visit_all_with(program, &mut visitor);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -80,15 +79,60 @@ var foo = {
   }
 }
 
-struct NoDupeKeysVisitor<'c> {
+impl NodeRule for NoDupeKeys {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::ObjectLit]
+  }
+
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::ObjectLit(obj_lit) = node {
+      KeyChecker::new(context).check_object_lit(obj_lit);
+    }
+  }
+}
+
+struct KeyChecker<'c> {
   context: &'c mut Context,
 }
 
-impl<'c> NoDupeKeysVisitor<'c> {
+impl<'c> KeyChecker<'c> {
   fn new(context: &'c mut Context) -> Self {
     Self { context }
   }
 
+  fn check_object_lit(&mut self, obj_lit: &ObjectLit) {
+    let span = obj_lit.span;
+    let mut keys: HashMap<String, PropertyInfo> = HashMap::new();
+
+    for prop in &obj_lit.props {
+      if let PropOrSpread::Prop(prop) = prop {
+        match &**prop {
+          Prop::Shorthand(ident) => {
+            self.check_key(span, Some(ident.as_ref()), &mut keys);
+          }
+          Prop::KeyValue(KeyValueProp { key, .. }) => {
+            self.check_key(span, key.string_repr(), &mut keys);
+          }
+          Prop::Assign(_) => {}
+          Prop::Getter(GetterProp { key, .. }) => {
+            self.check_getter(span, key.string_repr(), &mut keys);
+          }
+          Prop::Setter(SetterProp { key, .. }) => {
+            self.check_setter(span, key.string_repr(), &mut keys);
+          }
+          Prop::Method(MethodProp { key, .. }) => {
+            self.check_key(span, key.string_repr(), &mut keys);
+          }
+        }
+      }
+    }
+  }
+
   fn report(&mut self, span: Span, key: impl Into<String>) {
     self.context.add_diagnostic_with_hint(
       span,
@@ -189,38 +233,6 @@ impl PropertyInfo {
   }
 }
 
-impl<'c> VisitAll for NoDupeKeysVisitor<'c> {
-  noop_visit_type!();
-
-  fn visit_object_lit(&mut self, obj_lit: &ObjectLit, _parent: &dyn Node) {
-    let span = obj_lit.span;
-    let mut keys: HashMap<String, PropertyInfo> = HashMap::new();
-
-    for prop in &obj_lit.props {
-      if let PropOrSpread::Prop(prop) = prop {
-        match &**prop {
-          Prop::Shorthand(ident) => {
-            self.check_key(span, Some(ident.as_ref()), &mut keys);
-          }
-          Prop::KeyValue(KeyValueProp { key, .. }) => {
-            self.check_key(span, key.string_repr(), &mut keys);
-          }
-          Prop::Assign(_) => {}
-          Prop::Getter(GetterProp { key, .. }) => {
-            self.check_getter(span, key.string_repr(), &mut keys);
-          }
-          Prop::Setter(SetterProp { key, .. }) => {
-            self.check_setter(span, key.string_repr(), &mut keys);
-          }
-          Prop::Method(MethodProp { key, .. }) => {
-            self.check_key(span, key.string_repr(), &mut keys);
-          }
-        }
-      }
-    }
-  }
-}
-
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -1,13 +1,11 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use derive_more::Display;
 use std::collections::HashSet;
-use swc_ecmascript::ast::{Expr, SwitchStmt};
+use swc_ecmascript::ast::Expr;
 use swc_ecmascript::utils::drop_span;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::{VisitAll, VisitAllWith};
 
 pub struct NoDuplicateCase;
 
@@ -38,13 +36,8 @@ impl LintRule for NoDuplicateCase {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoDuplicateCaseVisitor::new(context);
-    program.visit_all_with(program, &mut visitor);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -86,33 +79,32 @@ switch (someText) {
   }
 }
 
-struct NoDuplicateCaseVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoDuplicateCaseVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoDuplicateCase {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::SwitchStmt]
   }
-}
-
-impl<'c> VisitAll for NoDuplicateCaseVisitor<'c> {
-  noop_visit_type!();
 
-  fn visit_switch_stmt(&mut self, switch_stmt: &SwitchStmt, _: &dyn Node) {
-    // Check if there are duplicates by comparing span dropped expressions
-    let mut seen: HashSet<Box<Expr>> = HashSet::new();
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::SwitchStmt(switch_stmt) = node {
+      // Check if there are duplicates by comparing span dropped expressions
+      let mut seen: HashSet<Box<Expr>> = HashSet::new();
 
-    for case in &switch_stmt.cases {
-      if let Some(test) = &case.test {
-        let span_dropped_test = drop_span(test.clone());
-        if !seen.insert(span_dropped_test) {
-          self.context.add_diagnostic_with_hint(
-            case.span,
-            CODE,
-            NoDuplicateCaseMessage::Unexpected,
-            NoDuplicateCaseHint::RemoveOrRename,
-          );
+      for case in &switch_stmt.cases {
+        if let Some(test) = &case.test {
+          let span_dropped_test = drop_span(test.clone());
+          if !seen.insert(span_dropped_test) {
+            context.add_diagnostic_with_hint(
+              case.span,
+              CODE,
+              NoDuplicateCaseMessage::Unexpected,
+              NoDuplicateCaseHint::RemoveOrRename,
+            );
+          }
         }
       }
     }
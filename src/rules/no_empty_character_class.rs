@@ -1,7 +1,8 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use once_cell::sync::Lazy;
+use crate::js_regex::ast::{walk_pattern, CharacterClass, Visit as RegexVisit};
+use crate::js_regex::EcmaRegexParser;
 use swc_ecmascript::ast::Regex;
 use swc_ecmascript::visit::noop_visit_type;
 use swc_ecmascript::visit::Node;
@@ -76,30 +77,19 @@ impl<'c> Visit for NoEmptyCharacterClassVisitor<'c> {
   noop_visit_type!();
 
   fn visit_regex(&mut self, regex: &Regex, _parent: &dyn Node) {
-    let raw_regex = self
-      .context
-      .source_map
-      .span_to_snippet(regex.span)
-      .expect("error in loading snippet");
-
-    static RULE_REGEX: Lazy<regex::Regex> = Lazy::new(|| {
-      /* reference : [eslint no-empty-character-class](https://github.com/eslint/eslint/blob/master/lib/rules/no-empty-character-class.js#L13)
-       * plain-English description of the following regexp:
-       * 0. `^` fix the match at the beginning of the string
-       * 1. `\/`: the `/` that begins the regexp
-       * 2. `([^\\[]|\\.|\[([^\\\]]|\\.)+\])*`: regexp contents; 0 or more of the following
-       * 2.0. `[^\\[]`: any character that's not a `\` or a `[` (anything but escape sequences and character classes)
-       * 2.1. `\\.`: an escape sequence
-       * 2.2. `\[([^\\\]]|\\.)+\]`: a character class that isn't empty
-       * 3. `\/` the `/` that ends the regexp
-       * 4. `[gimuy]*`: optional regexp flags
-       * 5. `$`: fix the match at the end of the string
-       */
-      regex::Regex::new(r"(?u)^/([^\\\[]|\\.|\[([^\\\]]|\\.)+\])*/[gimuys]*$")
-        .unwrap()
-    });
-
-    if !RULE_REGEX.is_match(&raw_regex) {
+    let mut parser = EcmaRegexParser::new(self.context.ecma_version());
+    // Invalid regexes are reported by `no-invalid-regexp`
+    let pattern = match parser
+      .parse_flags(&regex.flags)
+      .and_then(|flags| parser.parse_pattern(&regex.exp, flags))
+    {
+      Ok(pattern) => pattern,
+      Err(_) => return,
+    };
+
+    let mut finder = EmptyCharacterClassFinder { found: false };
+    walk_pattern(&mut finder, &pattern);
+    if finder.found {
       self
         .context
         .add_diagnostic_with_hint(regex.span, CODE, MESSAGE, HINT);
@@ -107,6 +97,19 @@ impl<'c> Visit for NoEmptyCharacterClassVisitor<'c> {
   }
 }
 
+/// Finds `[]`, which doesn't match anything. `[^]` matches any character.
+struct EmptyCharacterClassFinder {
+  found: bool,
+}
+
+impl RegexVisit for EmptyCharacterClassFinder {
+  fn visit_character_class(&mut self, class: &CharacterClass) {
+    if !class.negate && class.elements.is_empty() {
+      self.found = true;
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -127,6 +130,8 @@ mod tests {
     const foo = /[\-\[\]\/\{\}\(\)\*\+\?\.\\^\$\|]/g;
     const foo = /\[/g;
     const foo = /\]/i;
+    const foo = /[^]/;
+    const foo = /\[]/;
     "#,
     };
   }
@@ -160,6 +165,11 @@ mod tests {
         message: MESSAGE,
         hint: HINT,
       }],
+      r#"const foo = /(a|[])+/u;"#: [{
+        col: 12,
+        message: MESSAGE,
+        hint: HINT,
+      }],
       r#"/^abc[]/.test("abcdefg");"#: [{
         col: 0,
         message: MESSAGE,
@@ -1,10 +1,8 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use derive_more::Display;
-use swc_ecmascript::ast::TsInterfaceDecl;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
 
 pub struct NoEmptyInterface;
 
@@ -43,13 +41,8 @@ impl LintRule for NoEmptyInterface {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoEmptyInterfaceVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -84,40 +77,42 @@ interface Baz extends Foo, Bar {}
   }
 }
 
-struct NoEmptyInterfaceVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoEmptyInterfaceVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoEmptyInterface {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::TsInterfaceDecl]
   }
-}
 
-impl<'c> Visit for NoEmptyInterfaceVisitor<'c> {
-  fn visit_ts_interface_decl(
-    &mut self,
-    interface_decl: &TsInterfaceDecl,
-    _parent: &dyn Node,
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
   ) {
-    if interface_decl.extends.len() <= 1 && interface_decl.body.body.is_empty()
-    {
-      self.context.add_diagnostic_with_hint(
-        interface_decl.span,
-        CODE,
-        if interface_decl.extends.is_empty() {
-          NoEmptyInterfaceMessage::EmptyObject
-        } else {
-          NoEmptyInterfaceMessage::Supertype
-        },
-        if interface_decl.extends.is_empty() {
-          NoEmptyInterfaceHint::RemoveOrAddMember
-        } else {
-          NoEmptyInterfaceHint::UseSuperTypeOrAddMember
-        },
-      );
+    if let NodeRef::TsInterfaceDecl(interface_decl) = node {
+      if interface_decl.extends.len() <= 1
+        && interface_decl.body.body.is_empty()
+      {
+        context.add_diagnostic_with_hint(
+          interface_decl.span,
+          CODE,
+          if interface_decl.extends.is_empty() {
+            NoEmptyInterfaceMessage::EmptyObject
+          } else {
+            NoEmptyInterfaceMessage::Supertype
+          },
+          if interface_decl.extends.is_empty() {
+            NoEmptyInterfaceHint::RemoveOrAddMember
+          } else {
+            NoEmptyInterfaceHint::UseSuperTypeOrAddMember
+          },
+        );
+      }
     }
   }
+
+  fn checks_types(&self) -> bool {
+    true
+  }
 }
 
 #[cfg(test)]
@@ -1,16 +1,12 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use crate::swc_util::StringRepr;
 use swc_common::Span;
-use swc_ecmascript::ast::CallExpr;
 use swc_ecmascript::ast::Expr;
 use swc_ecmascript::ast::ExprOrSuper;
 use swc_ecmascript::ast::ParenExpr;
-use swc_ecmascript::ast::VarDeclarator;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
 
 pub struct NoEval;
 
@@ -27,13 +23,8 @@ impl LintRule for NoEval {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoEvalVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -59,38 +50,33 @@ const value = obj[x];
   }
 }
 
-struct NoEvalVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoEvalVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoEval {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::VarDeclarator, NodeKind::CallExpr]
   }
 
-  fn maybe_add_diagnostic(&mut self, source: &dyn StringRepr, span: Span) {
-    if source.string_repr().as_deref() == Some("eval") {
-      self.add_diagnostic(span);
-    }
-  }
-
-  fn add_diagnostic(&mut self, span: Span) {
-    self
-      .context
-      .add_diagnostic_with_hint(span, CODE, MESSAGE, HINT);
-  }
-
-  fn handle_paren_callee(&mut self, p: &ParenExpr) {
-    match p.expr.as_ref() {
-      // Nested paren callee ((eval))('var foo = 0;')
-      Expr::Paren(paren) => self.handle_paren_callee(paren),
-      // Single argument callee: (eval)('var foo = 0;')
-      Expr::Ident(ident) => self.maybe_add_diagnostic(ident, ident.span),
-      // Multiple arguments callee: (0, eval)('var foo = 0;')
-      Expr::Seq(seq) => {
-        for expr in &seq.exprs {
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    match node {
+      NodeRef::VarDeclarator(v) => {
+        if let Some(expr) = &v.init {
           if let Expr::Ident(ident) = expr.as_ref() {
-            self.maybe_add_diagnostic(ident, ident.span)
+            maybe_add_diagnostic(context, ident, v.span);
+          }
+        }
+      }
+      NodeRef::CallExpr(call_expr) => {
+        if let ExprOrSuper::Expr(expr) = &call_expr.callee {
+          match expr.as_ref() {
+            Expr::Ident(ident) => {
+              maybe_add_diagnostic(context, ident, call_expr.span)
+            }
+            Expr::Paren(paren) => handle_paren_callee(context, paren),
+            _ => {}
           }
         }
       }
@@ -99,25 +85,31 @@ impl<'c> NoEvalVisitor<'c> {
   }
 }
 
-impl<'c> Visit for NoEvalVisitor<'c> {
-  noop_visit_type!();
-
-  fn visit_var_declarator(&mut self, v: &VarDeclarator, _: &dyn Node) {
-    if let Some(expr) = &v.init {
-      if let Expr::Ident(ident) = expr.as_ref() {
-        self.maybe_add_diagnostic(ident, v.span);
-      }
-    }
+fn maybe_add_diagnostic(
+  context: &mut Context,
+  source: &dyn StringRepr,
+  span: Span,
+) {
+  if source.string_repr().as_deref() == Some("eval") {
+    context.add_diagnostic_with_hint(span, CODE, MESSAGE, HINT);
   }
+}
 
-  fn visit_call_expr(&mut self, call_expr: &CallExpr, _parent: &dyn Node) {
-    if let ExprOrSuper::Expr(expr) = &call_expr.callee {
-      match expr.as_ref() {
-        Expr::Ident(ident) => self.maybe_add_diagnostic(ident, call_expr.span),
-        Expr::Paren(paren) => self.handle_paren_callee(paren),
-        _ => {}
+fn handle_paren_callee(context: &mut Context, p: &ParenExpr) {
+  match p.expr.as_ref() {
+    // Nested paren callee ((eval))('var foo = 0;')
+    Expr::Paren(paren) => handle_paren_callee(context, paren),
+    // Single argument callee: (eval)('var foo = 0;')
+    Expr::Ident(ident) => maybe_add_diagnostic(context, ident, ident.span),
+    // Multiple arguments callee: (0, eval)('var foo = 0;')
+    Expr::Seq(seq) => {
+      for expr in &seq.exprs {
+        if let Expr::Ident(ident) = expr.as_ref() {
+          maybe_add_diagnostic(context, ident, ident.span)
+        }
       }
     }
+    _ => {}
   }
 }
 
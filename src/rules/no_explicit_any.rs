@@ -1,11 +1,25 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::ast::TsKeywordType;
+use super::{parse_rule_options, RuleOptionsError};
+use serde::Deserialize;
+use serde_json::Value;
+use swc_ecmascript::ast::{
+  RestPat, TsEntityName, TsKeywordType, TsKeywordTypeKind, TsType, TsTypeAnn,
+};
 use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
+use swc_ecmascript::visit::{Visit, VisitWith};
 
-pub struct NoExplicitAny;
+pub struct NoExplicitAny {
+  options: NoExplicitAnyOptions,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct NoExplicitAnyOptions {
+  /// Allows `any` as the type of rest parameters, e.g. `...args: any[]`.
+  pub ignore_rest_args: bool,
+}
 
 const CODE: &str = "no-explicit-any";
 const MESSAGE: &str = "`any` type is not allowed";
@@ -13,7 +27,9 @@ const HINT: &str = "Use a specific type other than `any`";
 
 impl LintRule for NoExplicitAny {
   fn new() -> Box<Self> {
-    Box::new(NoExplicitAny)
+    Box::new(NoExplicitAny {
+      options: NoExplicitAnyOptions::default(),
+    })
   }
 
   fn tags(&self) -> &'static [&'static str] {
@@ -29,10 +45,15 @@ impl LintRule for NoExplicitAny {
     context: &mut Context,
     program: &swc_ecmascript::ast::Program,
   ) {
-    let mut visitor = NoExplicitAnyVisitor::new(context);
+    let mut visitor = NoExplicitAnyVisitor::new(context, &self.options);
     visitor.visit_program(program, program);
   }
 
+  fn set_options(&mut self, options: Value) -> Result<(), RuleOptionsError> {
+    self.options = parse_rule_options(self.code(), options)?;
+    Ok(())
+  }
+
   fn docs(&self) -> &'static str {
     r#"Disallows use of the `any` type 
 
@@ -54,17 +75,53 @@ function foo(): any { return undefined; }
 const someNumber: string = "two";
 function foo(): undefined { return undefined; }
 ```
+
+### Options:
+```json
+{ "ignoreRestArgs": true }
+```
+With `ignoreRestArgs` enabled, rest parameters may be typed as `any` or
+`any[]` (e.g. `function log(...args: any[]) {}`).
 "#
   }
 }
 
 struct NoExplicitAnyVisitor<'c> {
   context: &'c mut Context,
+  options: &'c NoExplicitAnyOptions,
 }
 
 impl<'c> NoExplicitAnyVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+  fn new(context: &'c mut Context, options: &'c NoExplicitAnyOptions) -> Self {
+    Self { context, options }
+  }
+}
+
+/// Checks if the type annotation is `any`, `any[]` or `Array<any>`.
+fn is_any_or_any_array(type_ann: &TsTypeAnn) -> bool {
+  fn is_any(ts_type: &TsType) -> bool {
+    matches!(
+      ts_type,
+      TsType::TsKeywordType(TsKeywordType {
+        kind: TsKeywordTypeKind::TsAnyKeyword,
+        ..
+      })
+    )
+  }
+
+  match &*type_ann.type_ann {
+    TsType::TsArrayType(array) => is_any(&array.elem_type),
+    TsType::TsTypeRef(type_ref) => {
+      match (&type_ref.type_name, &type_ref.type_params) {
+        (TsEntityName::Ident(ident), Some(params)) => {
+          ident.sym == *"Array"
+            && params.params.len() == 1
+            && is_any(&params.params[0])
+        }
+        _ => false,
+      }
+    }
+    ts_type => is_any(ts_type),
   }
 }
 
@@ -74,9 +131,7 @@ impl<'c> Visit for NoExplicitAnyVisitor<'c> {
     ts_keyword_type: &TsKeywordType,
     _parent: &dyn Node,
   ) {
-    use swc_ecmascript::ast::TsKeywordTypeKind::*;
-
-    if ts_keyword_type.kind == TsAnyKeyword {
+    if ts_keyword_type.kind == TsKeywordTypeKind::TsAnyKeyword {
       self.context.add_diagnostic_with_hint(
         ts_keyword_type.span,
         CODE,
@@ -85,11 +140,26 @@ impl<'c> Visit for NoExplicitAnyVisitor<'c> {
       );
     }
   }
+
+  fn visit_rest_pat(&mut self, rest_pat: &RestPat, _parent: &dyn Node) {
+    if self.options.ignore_rest_args
+      && rest_pat
+        .type_ann
+        .as_ref()
+        .map_or(false, is_any_or_any_array)
+    {
+      rest_pat.arg.visit_with(rest_pat, self);
+      return;
+    }
+    rest_pat.visit_children_with(self);
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::test_util::lint_with_options;
+  use serde_json::json;
 
   #[test]
   fn no_explicit_any_valid() {
@@ -138,4 +208,32 @@ type RequireWrapper = (
 ) => void;"#: [{ line: 3, col: 11, message: MESSAGE, hint: HINT }, { line: 4, col: 11, message: MESSAGE, hint: HINT }],
     }
   }
+
+  #[test]
+  fn no_explicit_any_ignore_rest_args() {
+    let options = json!({ "ignoreRestArgs": true });
+    for source in &[
+      "function foo(...args: any) {}",
+      "function foo(a: string, ...args: any[]) {}",
+      "const foo = (...args: Array<any>) => {};",
+      "type Foo = (...args: any[]) => void;",
+      "class Foo { constructor(...args: any[]) {} }",
+    ] {
+      let diagnostics =
+        lint_with_options::<NoExplicitAny>(options.clone(), source);
+      assert!(diagnostics.is_empty(), "{}", source);
+    }
+
+    for (source, col) in &[
+      ("function foo(...args: any[]): any {}", 30),
+      ("function foo(a: any, ...args: any[]) {}", 16),
+      ("function foo(...args: Foo<any>) {}", 26),
+      ("function foo(...args: any[][]) {}", 22),
+    ] {
+      let diagnostics =
+        lint_with_options::<NoExplicitAny>(options.clone(), source);
+      assert_eq!(diagnostics.len(), 1, "{}", source);
+      assert_eq!(diagnostics[0].range.start.col, *col, "{}", source);
+    }
+  }
 }
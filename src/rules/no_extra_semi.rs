@@ -1,6 +1,7 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::diagnostic::LintFix;
 use derive_more::Display;
 use swc_ecmascript::ast::{
   DoWhileStmt, EmptyStmt, ForInStmt, ForOfStmt, ForStmt, IfStmt, LabeledStmt,
@@ -83,11 +84,16 @@ impl<'c> Visit for NoExtraSemiVisitor<'c> {
   noop_visit_type!();
 
   fn visit_empty_stmt(&mut self, empty_stmt: &EmptyStmt, _parent: &dyn Node) {
-    self.context.add_diagnostic_with_hint(
+    let fix = LintFix {
+      description: NoExtraSemiHint::Remove.to_string(),
+      changes: vec![self.context.create_fix_change(empty_stmt.span, "")],
+    };
+    self.context.add_diagnostic_with_fixes(
       empty_stmt.span,
       CODE,
       NoExtraSemiMessage::Unnecessary,
-      NoExtraSemiHint::Remove,
+      Some(NoExtraSemiHint::Remove.to_string()),
+      vec![fix],
     );
   }
 
@@ -487,4 +493,12 @@ class A {
       ]
     };
   }
+  #[test]
+  fn no_extra_semi_fix() {
+    use crate::test_util::assert_lint_fix;
+    assert_lint_fix::<NoExtraSemi>("var x = 5;;", "var x = 5;");
+    assert_lint_fix::<NoExtraSemi>("function foo(){};", "function foo(){}");
+    assert_lint_fix::<NoExtraSemi>("class A { ; }", "class A {  }");
+    assert_lint_fix::<NoExtraSemi>("for(;;);", "for(;;);");
+  }
 }
@@ -0,0 +1,547 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use super::Context;
+use super::LintRule;
+use super::{parse_rule_options, RuleOptionsError};
+use crate::scopes::{BindingKind, Scope};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use swc_common::Spanned;
+use swc_ecmascript::ast::{
+  BinaryOp, CallExpr, Expr, ExprOrSuper, ExprStmt, FnDecl, Lit, NewExpr, Pat,
+  Program, VarDecl, VarDeclKind,
+};
+use swc_ecmascript::utils::{ident::IdentLike, Id};
+use swc_ecmascript::visit::{noop_visit_type, Node, VisitAll, VisitAllWith};
+
+pub struct NoFloatingPromises {
+  /// Paths of global functions which return a promise, e.g. `Deno.open`.
+  async_apis: HashSet<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct NoFloatingPromisesOptions {
+  /// Paths of global functions which return a promise. Replaces the default
+  /// list of async Deno APIs.
+  pub async_apis: Vec<String>,
+}
+
+impl Default for NoFloatingPromisesOptions {
+  fn default() -> Self {
+    Self {
+      async_apis: DEFAULT_ASYNC_APIS.iter().map(|s| s.to_string()).collect(),
+    }
+  }
+}
+
+const DEFAULT_ASYNC_APIS: &[&str] = &[
+  "Deno.chmod",
+  "Deno.chown",
+  "Deno.connect",
+  "Deno.connectTls",
+  "Deno.copy",
+  "Deno.copyFile",
+  "Deno.create",
+  "Deno.fdatasync",
+  "Deno.fstat",
+  "Deno.fsync",
+  "Deno.ftruncate",
+  "Deno.link",
+  "Deno.lstat",
+  "Deno.makeTempDir",
+  "Deno.makeTempFile",
+  "Deno.mkdir",
+  "Deno.open",
+  "Deno.readAll",
+  "Deno.readFile",
+  "Deno.readLink",
+  "Deno.readTextFile",
+  "Deno.realPath",
+  "Deno.remove",
+  "Deno.rename",
+  "Deno.seek",
+  "Deno.stat",
+  "Deno.symlink",
+  "Deno.truncate",
+  "Deno.utime",
+  "Deno.writeAll",
+  "Deno.writeFile",
+  "Deno.writeTextFile",
+  "fetch",
+];
+
+/// Static methods of `Promise` which return a promise.
+const PROMISE_METHODS: &[&str] =
+  &["all", "allSettled", "any", "race", "reject", "resolve"];
+
+const CODE: &str = "no-floating-promises";
+const MESSAGE: &str = "Promise is neither awaited nor handled";
+const HINT: &str = "Add `await`, return the promise, handle rejections with `.catch()`, or discard it explicitly with `void`";
+
+impl LintRule for NoFloatingPromises {
+  fn new() -> Box<Self> {
+    let options = NoFloatingPromisesOptions::default();
+    Box::new(NoFloatingPromises {
+      async_apis: options.async_apis.into_iter().collect(),
+    })
+  }
+
+  fn code(&self) -> &'static str {
+    CODE
+  }
+
+  fn lint_program(&self, context: &mut Context, program: &Program) {
+    let mut collector = AsyncFunctionCollector {
+      functions: HashMap::new(),
+    };
+    program.visit_all_with(program, &mut collector);
+
+    let mut visitor = NoFloatingPromisesVisitor {
+      context,
+      async_apis: &self.async_apis,
+      functions: collector.functions,
+    };
+    program.visit_all_with(program, &mut visitor);
+  }
+
+  fn set_options(&mut self, options: Value) -> Result<(), RuleOptionsError> {
+    let options: NoFloatingPromisesOptions =
+      parse_rule_options(self.code(), options)?;
+    self.async_apis = options.async_apis.into_iter().collect();
+    Ok(())
+  }
+
+  fn docs(&self) -> &'static str {
+    r#"Requires promises to be awaited or to have their rejections handled
+
+A promise which is neither awaited nor handled runs detached from the code
+calling it: its errors are reported as unhandled rejections, and the code after
+it can run before it has settled, e.g. before a file has been written.
+
+Without type information, this rule only knows that a call returns a promise
+when it calls an `async` function declared in the same file, a `Promise`
+method like `Promise.all`, `new Promise`, or one of the known async APIs like
+`Deno.writeTextFile`. A statement made of such a call is reported unless the
+promise is awaited, returned, discarded with `void`, or has a rejection handler
+attached with `.catch(onRejected)` or `.then(onFulfilled, onRejected)`.
+
+### Invalid:
+```typescript
+async function save() {}
+save();
+
+Deno.writeTextFile("hello.txt", "Hello");
+Promise.all([save(), save()]).then(() => console.log("saved"));
+```
+
+### Valid:
+```typescript
+async function save() {}
+await save();
+void save();
+save().catch((err) => console.error(err));
+
+function write() {
+  return Deno.writeTextFile("hello.txt", "Hello");
+}
+```
+
+### Options:
+```json
+{ "asyncApis": ["Deno.writeTextFile", "fetch", "myGlobal.load"] }
+```
+`asyncApis` lists global functions, as dotted paths, which return a promise.
+It replaces the default list of async Deno APIs.
+"#
+  }
+}
+
+/// Collects functions which are bound to a name that can't be reassigned,
+/// i.e. function declarations and `const` bindings.
+struct AsyncFunctionCollector {
+  /// Whether all functions bound to an id are `async`. swc's resolver gives
+  /// function declarations in blocks the id of the enclosing function's
+  /// bindings, so one id can be bound to several functions.
+  functions: HashMap<Id, bool>,
+}
+
+impl AsyncFunctionCollector {
+  fn bind(&mut self, id: Id, is_async: bool) {
+    *self.functions.entry(id).or_insert(true) &= is_async;
+  }
+}
+
+impl VisitAll for AsyncFunctionCollector {
+  noop_visit_type!();
+
+  fn visit_fn_decl(&mut self, fn_decl: &FnDecl, _: &dyn Node) {
+    self.bind(fn_decl.ident.to_id(), fn_decl.function.is_async);
+  }
+
+  fn visit_var_decl(&mut self, var_decl: &VarDecl, _: &dyn Node) {
+    if var_decl.kind != VarDeclKind::Const {
+      return;
+    }
+    for decl in &var_decl.decls {
+      if let (Pat::Ident(ident), Some(init)) = (&decl.name, &decl.init) {
+        self.bind(ident.to_id(), is_async_function(init));
+      }
+    }
+  }
+}
+
+fn is_async_function(expr: &Expr) -> bool {
+  match expr {
+    Expr::Paren(paren) => is_async_function(&paren.expr),
+    Expr::Fn(fn_expr) => fn_expr.function.is_async,
+    Expr::Arrow(arrow) => arrow.is_async,
+    _ => false,
+  }
+}
+
+/// Path of a global like `Deno.writeTextFile`, if `expr` refers to one.
+fn global_path(scope: &Scope, expr: &Expr) -> Option<String> {
+  match expr {
+    Expr::Paren(paren) => global_path(scope, &paren.expr),
+    Expr::OptChain(opt_chain) => global_path(scope, &opt_chain.expr),
+    Expr::Ident(ident) if scope.var(&ident.to_id()).is_none() => {
+      Some(ident.sym.to_string())
+    }
+    Expr::Member(member) => {
+      let obj = match &member.obj {
+        ExprOrSuper::Expr(obj) => global_path(scope, obj)?,
+        ExprOrSuper::Super(_) => return None,
+      };
+      let prop = match (&*member.prop, member.computed) {
+        (Expr::Ident(ident), false) => ident.sym.to_string(),
+        (Expr::Lit(Lit::Str(s)), true) => s.value.to_string(),
+        _ => return None,
+      };
+      Some(format!("{}.{}", obj, prop))
+    }
+    _ => None,
+  }
+}
+
+struct NoFloatingPromisesVisitor<'c, 'a> {
+  context: &'c mut Context,
+  async_apis: &'a HashSet<String>,
+  functions: HashMap<Id, bool>,
+}
+
+impl<'c, 'a> NoFloatingPromisesVisitor<'c, 'a> {
+  /// Whether `expr` evaluates to a promise whose rejection isn't handled.
+  fn is_floating_promise(&self, expr: &Expr) -> bool {
+    match expr {
+      Expr::Paren(paren) => self.is_floating_promise(&paren.expr),
+      // `f?.()` still returns a promise if `f` is defined.
+      Expr::OptChain(opt_chain) => self.is_floating_promise(&opt_chain.expr),
+      Expr::Call(call) => self.is_floating_call(call),
+      Expr::New(new) => self.is_promise_constructor(new),
+      Expr::Cond(cond) => {
+        self.is_floating_promise(&cond.cons)
+          || self.is_floating_promise(&cond.alt)
+      }
+      Expr::Bin(bin)
+        if matches!(
+          bin.op,
+          BinaryOp::LogicalAnd
+            | BinaryOp::LogicalOr
+            | BinaryOp::NullishCoalescing
+        ) =>
+      {
+        self.is_floating_promise(&bin.left)
+          || self.is_floating_promise(&bin.right)
+      }
+      Expr::Seq(seq) => seq.exprs.iter().any(|e| self.is_floating_promise(e)),
+      _ => false,
+    }
+  }
+
+  fn is_floating_call(&self, call: &CallExpr) -> bool {
+    let callee = match &call.callee {
+      ExprOrSuper::Expr(callee) => &**callee,
+      ExprOrSuper::Super(_) => return false,
+    };
+    // `p?.catch(h)` is handled like `p.catch(h)`.
+    let callee = match callee {
+      Expr::OptChain(opt_chain) => &*opt_chain.expr,
+      _ => callee,
+    };
+
+    // Methods of the promise itself, which return a new promise.
+    if let Expr::Member(member) = callee {
+      if let (ExprOrSuper::Expr(obj), Expr::Ident(prop), false) =
+        (&member.obj, &*member.prop, member.computed)
+      {
+        let is_handled = match &*prop.sym {
+          "catch" => !call.args.is_empty(),
+          "then" => call.args.len() >= 2,
+          "finally" => false,
+          _ => return self.is_async_callee(callee),
+        };
+        return !is_handled && self.is_floating_promise(obj);
+      }
+    }
+    self.is_async_callee(callee)
+  }
+
+  /// Whether calling `callee` returns a promise.
+  fn is_async_callee(&self, callee: &Expr) -> bool {
+    if let Expr::Ident(ident) = callee {
+      let id = ident.to_id();
+      let is_bound_to_async_function =
+        match self.context.scope().var(&id).map(|var| var.kind()) {
+          Some(BindingKind::Function) | Some(BindingKind::Const) => {
+            self.functions.get(&id) == Some(&true)
+          }
+          _ => false,
+        };
+      if is_bound_to_async_function {
+        return true;
+      }
+    }
+    if is_async_function(callee) {
+      return true;
+    }
+
+    match global_path(self.context.scope(), callee) {
+      Some(path) => {
+        self.async_apis.contains(&path)
+          || path
+            .strip_prefix("Promise.")
+            .map_or(false, |method| PROMISE_METHODS.contains(&method))
+      }
+      None => false,
+    }
+  }
+
+  fn is_promise_constructor(&self, new: &NewExpr) -> bool {
+    global_path(self.context.scope(), &new.callee).as_deref() == Some("Promise")
+  }
+}
+
+impl<'c, 'a> VisitAll for NoFloatingPromisesVisitor<'c, 'a> {
+  noop_visit_type!();
+
+  fn visit_expr_stmt(&mut self, expr_stmt: &ExprStmt, _: &dyn Node) {
+    if self.is_floating_promise(&expr_stmt.expr) {
+      self.context.add_diagnostic_with_hint(
+        expr_stmt.expr.span(),
+        CODE,
+        MESSAGE,
+        HINT,
+      );
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::lint_with_options;
+  use serde_json::json;
+
+  #[test]
+  fn no_floating_promises_valid() {
+    assert_lint_ok! {
+      NoFloatingPromises,
+      "async function f() {} await f();",
+      "async function f() {} void f();",
+      "async function f() {} const p = f();",
+      "async function f() {} function g() { return f(); }",
+      "async function f() {}\n{ function f() {} f(); }",
+      "async function f() {} const g = () => f();",
+      "async function f() {} f().catch(console.error);",
+      "async function f() {} f().then(() => {}, console.error);",
+      "async function f() {} f().then(g).catch(console.error);",
+      "async function f() {} f().finally(g).catch(console.error);",
+      "function f() {} f();",
+      "let f = async () => {}; f = () => {}; f();",
+      "async function f() {} function g(f) { f(); }",
+      "const f = async () => {}; { const f = () => {}; f(); }",
+      "await Deno.writeTextFile('a.txt', 'a');",
+      "Deno.writeTextFileSync('a.txt', 'a');",
+      "const Deno = {}; Deno.writeTextFile('a.txt', 'a');",
+      "await Promise.all([]);",
+      "Promise.resolve().catch(() => {});",
+      "new Promise((resolve) => resolve()).then(f, g);",
+      "class A { async f() {} g() { this.f(); } }",
+      "foo.then(() => {});",
+      "foo();",
+      "async function f() {} f?.().catch(console.error);",
+      "async function f() {} f()?.catch(console.error);",
+      "Deno?.writeTextFile('a.txt', 'a').catch(console.error);",
+    };
+  }
+
+  #[test]
+  fn no_floating_promises_invalid() {
+    assert_lint_err! {
+      NoFloatingPromises,
+      "async function f() {} f();": [
+        {
+          col: 22,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "function f() {}\nfunction g() { async function f() {} f(); }": [
+        {
+          line: 2,
+          col: 37,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "const f = async () => {}; f(1, 2);": [
+        {
+          col: 26,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "const f = async function () {}; function g() { f(); }": [
+        {
+          col: 47,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "g(); async function g() {}": [
+        {
+          col: 0,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "(async () => {})();": [
+        {
+          col: 0,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "Deno.writeTextFile('a.txt', 'a');": [
+        {
+          col: 0,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "Deno['remove']('a.txt');": [
+        {
+          col: 0,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "fetch('https://deno.land').then((res) => res.text());": [
+        {
+          col: 0,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "Promise.all([a, b]);": [
+        {
+          col: 0,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "new Promise((resolve) => setTimeout(resolve, 10));": [
+        {
+          col: 0,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "Promise.reject(new Error()).finally(() => {});": [
+        {
+          col: 0,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "async function f() {} f().catch();": [
+        {
+          col: 22,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "async function f() {} cond ? f() : null;": [
+        {
+          col: 22,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "async function f() {} ready && f();": [
+        {
+          col: 22,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "async function f() {} (f(), g());": [
+        {
+          col: 22,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "async function f() {} f?.();": [
+        {
+          col: 22,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "Deno.writeTextFile?.('a.txt', 'a');": [
+        {
+          col: 0,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "Deno?.remove('a.txt');": [
+        {
+          col: 0,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+      "async function f() {} f?.().finally(g);": [
+        {
+          col: 22,
+          message: MESSAGE,
+          hint: HINT,
+        }
+      ],
+    };
+  }
+
+  #[test]
+  fn no_floating_promises_async_apis() {
+    let options = json!({ "asyncApis": ["db.query"] });
+    let diagnostics = lint_with_options::<NoFloatingPromises>(
+      options.clone(),
+      "db.query('SELECT 1');",
+    );
+    assert_eq!(diagnostics.len(), 1);
+
+    let diagnostics = lint_with_options::<NoFloatingPromises>(
+      options,
+      "Deno.writeTextFile('a.txt', 'a');",
+    );
+    assert!(diagnostics.is_empty());
+
+    let err = NoFloatingPromises::new()
+      .set_options(json!({ "asyncApis": "db.query" }))
+      .unwrap_err();
+    assert_eq!(err.field.as_deref(), Some("asyncApis"));
+  }
+}
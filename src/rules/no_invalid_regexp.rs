@@ -74,10 +74,8 @@ struct NoInvalidRegexpVisitor<'c> {
 
 impl<'c> NoInvalidRegexpVisitor<'c> {
   fn new(context: &'c mut Context) -> Self {
-    Self {
-      context,
-      validator: EcmaRegexValidator::new(EcmaVersion::ES2018),
-    }
+    let validator = EcmaRegexValidator::new(context.ecma_version());
+    Self { context, validator }
   }
 
   fn handle_call_or_new_expr(
@@ -104,10 +102,10 @@ impl<'c> NoInvalidRegexpVisitor<'c> {
 
   fn check_regex(&mut self, pattern: &str, flags: &str, span: Span) {
     if self.check_for_invalid_flags(flags)
-      || (!flags.is_empty()
-        && self.check_for_invalid_pattern(pattern, flags.contains('u')))
-      || (self.check_for_invalid_pattern(pattern, true)
-        && self.check_for_invalid_pattern(pattern, false))
+      || (!flags.is_empty() && self.check_for_invalid_pattern(pattern, flags))
+      || (flags.is_empty()
+        && self.check_for_invalid_pattern(pattern, "u")
+        && self.check_for_invalid_pattern(pattern, ""))
     {
       self
         .context
@@ -119,8 +117,11 @@ impl<'c> NoInvalidRegexpVisitor<'c> {
     self.validator.validate_flags(flags).is_err()
   }
 
-  fn check_for_invalid_pattern(&mut self, source: &str, u_flag: bool) -> bool {
-    self.validator.validate_pattern(source, u_flag).is_err()
+  fn check_for_invalid_pattern(&mut self, source: &str, flags: &str) -> bool {
+    self
+      .validator
+      .validate_pattern_with_flags(source, flags)
+      .is_err()
   }
 }
 
@@ -163,6 +164,7 @@ impl<'c> Visit for NoInvalidRegexpVisitor<'c> {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::linter::LinterBuilder;
 
   #[test]
   fn no_invalid_regexp_valid() {
@@ -195,11 +197,16 @@ new RegExp('(?<!a)b');
 new RegExp('(?<a>b)\\k<a>');
 new RegExp('(?<a>b)\\k<a>', 'u');
 new RegExp('\\p{Letter}', 'u');
+new RegExp('.', 'd');
+new RegExp('[\\p{L}--\\p{Ll}]', 'v');
+new RegExp('[\\q{abc}&&\\w]', 'v');
 
 var foo = new RegExp('(a)bc[de]', '');
 var foo = new RegExp('a', '');
 /(a)bc[de]/.test('abcd');
 /(a)bc[de]/u;
+/(?<y>\d{4})/d;
+/[\p{L}--\p{Ll}]/v;
 let x = new FooBar('\\');
 let re = new RegExp('foo', x);"#,
     };
@@ -217,6 +224,24 @@ let re = new RegExp('foo', x);"#,
       r#"/(?<a>a)\k</"#: [{ col: 0, message: MESSAGE, hint: HINT }],
       r#"/(?<!a){1}/"#: [{ col: 0, message: MESSAGE, hint: HINT }],
       r#"/(a)(a)(a)(a)(a)(a)(a)(a)(a)(a)\11/u"#: [{ col: 0, message: MESSAGE, hint: HINT }],
+      r#"/[a&&&b]/v"#: [{ col: 0, message: MESSAGE, hint: HINT }],
+      r#"/[^\p{RGI_Emoji}]/v"#: [{ col: 0, message: MESSAGE, hint: HINT }],
+      r#"new RegExp('.', 'uv');"#: [{ col: 0, message: MESSAGE, hint: HINT }],
     }
   }
+
+  #[test]
+  fn no_invalid_regexp_ecma_version() {
+    let mut linter = LinterBuilder::default()
+      .ecma_version(EcmaVersion::ES2018)
+      .rules(vec![NoInvalidRegexp::new()])
+      .build();
+    let (_, diagnostics) = linter.lint(
+      "lint_test.ts".to_string(),
+      "/(?<y>\\d{4})/d;\n/[\\p{L}--\\p{Ll}]/v;".to_string(),
+    );
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].range.start.line, 1);
+    assert_eq!(diagnostics[1].range.start.line, 2);
+  }
 }
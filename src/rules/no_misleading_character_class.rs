@@ -0,0 +1,337 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use super::Context;
+use super::LintRule;
+use crate::js_regex::ast::{
+  walk_character_class, walk_pattern, CharacterClass, ClassElement, Span, Visit,
+};
+use crate::swc_util::collect_regexes;
+use derive_more::Display;
+use swc_ecmascript::ast::Program;
+
+pub struct NoMisleadingCharacterClass;
+
+const CODE: &str = "no-misleading-character-class";
+const SEQUENCE_HINT: &str = "Match the sequence with an alternation, e.g. `(?:ab|c)`, instead of a character class";
+
+#[derive(Clone, Copy, Display, PartialEq)]
+enum MisleadingSequence {
+  #[display(fmt = "Unexpected surrogate pair in character class")]
+  SurrogatePair,
+  #[display(fmt = "Unexpected combined character in character class")]
+  CombiningMark,
+  #[display(fmt = "Unexpected modified emoji in character class")]
+  EmojiModifier,
+  #[display(fmt = "Unexpected national flag in character class")]
+  RegionalIndicatorPair,
+  #[display(fmt = "Unexpected joined character sequence in character class")]
+  ZeroWidthJoiner,
+}
+
+impl MisleadingSequence {
+  fn hint(self) -> &'static str {
+    match self {
+      MisleadingSequence::SurrogatePair => {
+        "Add the `u` flag to match the pair as a single character"
+      }
+      _ => SEQUENCE_HINT,
+    }
+  }
+}
+
+/// Ranges of common combining marks, i.e. characters of the `M` general
+/// category, which are displayed together with the preceding character.
+const COMBINING_MARKS: &[(u32, u32)] = &[
+  (0x0300, 0x036F),
+  (0x0483, 0x0489),
+  (0x0591, 0x05BD),
+  (0x0610, 0x061A),
+  (0x064B, 0x065F),
+  (0x0670, 0x0670),
+  (0x06D6, 0x06DC),
+  (0x06DF, 0x06E4),
+  (0x0900, 0x0903),
+  (0x093A, 0x093C),
+  (0x093E, 0x094F),
+  (0x0951, 0x0957),
+  (0x0962, 0x0963),
+  (0x0E31, 0x0E31),
+  (0x0E34, 0x0E3A),
+  (0x0E47, 0x0E4E),
+  (0x1AB0, 0x1AFF),
+  (0x1DC0, 0x1DFF),
+  (0x20D0, 0x20FF),
+  (0x302A, 0x302F),
+  (0x3099, 0x309A),
+  (0xFE00, 0xFE0F),
+  (0xFE20, 0xFE2F),
+  (0xE0100, 0xE01EF),
+];
+
+const ZERO_WIDTH_JOINER: u32 = 0x200D;
+
+fn is_combining_mark(c: u32) -> bool {
+  COMBINING_MARKS
+    .iter()
+    .any(|(min, max)| *min <= c && c <= *max)
+}
+
+fn is_emoji_modifier(c: u32) -> bool {
+  (0x1F3FB..=0x1F3FF).contains(&c)
+}
+
+fn is_regional_indicator(c: u32) -> bool {
+  (0x1F1E6..=0x1F1FF).contains(&c)
+}
+
+impl LintRule for NoMisleadingCharacterClass {
+  fn new() -> Box<Self> {
+    Box::new(NoMisleadingCharacterClass)
+  }
+
+  fn code(&self) -> &'static str {
+    CODE
+  }
+
+  fn lint_program(&self, context: &mut Context, program: &Program) {
+    for regex in collect_regexes(program, &context.scope) {
+      let (pattern, flags) = match regex.parse(context.ecma_version()) {
+        Some(parsed) => parsed,
+        None => continue,
+      };
+      let mut finder = SequenceFinder {
+        unicode: flags.unicode || flags.unicode_sets,
+        sequences: vec![],
+      };
+      walk_pattern(&mut finder, &pattern);
+
+      for (span, sequence) in finder.sequences {
+        context.add_diagnostic_with_hint(
+          regex.span_of(span),
+          CODE,
+          sequence,
+          sequence.hint(),
+        );
+      }
+    }
+  }
+
+  fn docs(&self) -> &'static str {
+    r#"Disallows characters made of several code points in character classes
+
+A character class matches a single code point, or a single UTF-16 code unit
+without the `u` flag. Characters which are displayed as one but are made of
+several code points or code units, e.g. emojis, accented letters and flags,
+are split into their parts, each of which is matched on its own.
+
+### Invalid:
+```typescript
+/^[👍]$/;
+/^[Á]$/u;
+/^[❇️]$/u;
+/^[👶🏻]$/u;
+/^[🇯🇵]$/u;
+/^[👨‍👩‍👦]$/u;
+```
+
+### Valid:
+```typescript
+/^[abc]$/;
+/^[👍]$/u;
+/^(?:Á|❇️|👶🏻|🇯🇵)$/u;
+```
+"#
+  }
+}
+
+struct SequenceFinder {
+  /// Whether characters are code points rather than UTF-16 code units.
+  unicode: bool,
+  sequences: Vec<(Span, MisleadingSequence)>,
+}
+
+impl SequenceFinder {
+  /// Checks a run of characters which follow each other in a class. Each
+  /// kind of sequence is reported once per run.
+  fn check_characters(&mut self, characters: &[(u32, Span)]) {
+    let mut code_points: Vec<(u32, Span)> = vec![];
+    let mut found = vec![];
+    for &(value, span) in characters {
+      if let Some((lead, lead_span)) = code_points.last_mut() {
+        let is_pair = !self.unicode
+          && (0xD800..=0xDBFF).contains(lead)
+          && (0xDC00..=0xDFFF).contains(&value);
+        if is_pair {
+          *lead = 0x10000 + ((*lead - 0xD800) << 10) + (value - 0xDC00);
+          *lead_span = Span::new(lead_span.start, span.end);
+          found.push((*lead_span, MisleadingSequence::SurrogatePair));
+          continue;
+        }
+      }
+      code_points.push((value, span));
+    }
+
+    for (i, window) in code_points.windows(2).enumerate() {
+      let ((previous, start), (current, end)) = (window[0], window[1]);
+      let span = Span::new(start.start, end.end);
+      if is_combining_mark(current) && !is_combining_mark(previous) {
+        found.push((span, MisleadingSequence::CombiningMark));
+      }
+      if is_emoji_modifier(current) && !is_emoji_modifier(previous) {
+        found.push((span, MisleadingSequence::EmojiModifier));
+      }
+      if is_regional_indicator(current) && is_regional_indicator(previous) {
+        found.push((span, MisleadingSequence::RegionalIndicatorPair));
+      }
+      if current == ZERO_WIDTH_JOINER {
+        if let Some((_, next)) = code_points.get(i + 2) {
+          let span = Span::new(start.start, next.end);
+          found.push((span, MisleadingSequence::ZeroWidthJoiner));
+        }
+      }
+    }
+
+    found.sort_by_key(|(span, _)| span.start);
+    let mut reported = vec![];
+    for (span, sequence) in found {
+      if !reported.contains(&sequence) {
+        reported.push(sequence);
+        self.sequences.push((span, sequence));
+      }
+    }
+  }
+}
+
+impl Visit for SequenceFinder {
+  fn visit_character_class(&mut self, n: &CharacterClass) {
+    let mut characters = vec![];
+    for element in &n.elements {
+      match element {
+        ClassElement::Character(c) => characters.push((c.value, c.span)),
+        _ => {
+          if !characters.is_empty() {
+            self.check_characters(&characters);
+            characters.clear();
+          }
+        }
+      }
+    }
+    if !characters.is_empty() {
+      self.check_characters(&characters);
+    }
+    walk_character_class(self, n);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn no_misleading_character_class_valid() {
+    assert_lint_ok! {
+      NoMisleadingCharacterClass,
+      "/[abc]/;",
+      "/[\u{1F44D}]/u;",
+      "/[\u{1F44D}]/v;",
+      r#"/[\uD83D\uDC4D]/u;"#,
+      r#"/[\uD83D]/;"#,
+      r#"/\uD83D\uDC4D/;"#,
+      "/(?:A\u{301}|\u{2747}\u{FE0F})/u;",
+      "/[\u{301}]/u;",
+      "/[a-z\u{301}]/u;",
+      "/[\u{1F1EF}]/u;",
+      "/[\u{200D}]/u;",
+      "/[\\q{\u{1F476}\u{1F3FB}|\u{1F1EF}\u{1F1F5}}]/v;",
+      "new RegExp('[\u{1F44D}]', 'u');",
+    };
+  }
+
+  #[test]
+  fn no_misleading_character_class_invalid() {
+    assert_lint_err! {
+      NoMisleadingCharacterClass,
+      r#"/[\uD83D\uDC4D]/;"#: [
+        {
+          col: 2,
+          message: MisleadingSequence::SurrogatePair,
+          hint: MisleadingSequence::SurrogatePair.hint(),
+        }
+      ],
+      "/^[\u{1F44D}]$/;": [
+        {
+          col: 3,
+          message: MisleadingSequence::SurrogatePair,
+          hint: MisleadingSequence::SurrogatePair.hint(),
+        }
+      ],
+      "new RegExp('[\u{1F44D}]');": [
+        {
+          col: 13,
+          message: MisleadingSequence::SurrogatePair,
+          hint: MisleadingSequence::SurrogatePair.hint(),
+        }
+      ],
+      "/[A\u{301}]/u;": [
+        {
+          col: 2,
+          message: MisleadingSequence::CombiningMark,
+          hint: SEQUENCE_HINT,
+        }
+      ],
+      "/[\u{2747}\u{FE0F}]/u;": [
+        {
+          col: 2,
+          message: MisleadingSequence::CombiningMark,
+          hint: SEQUENCE_HINT,
+        }
+      ],
+      "/[\u{1F476}\u{1F3FB}]/u;": [
+        {
+          col: 2,
+          message: MisleadingSequence::EmojiModifier,
+          hint: SEQUENCE_HINT,
+        }
+      ],
+      "/[\u{1F1EF}\u{1F1F5}]/u;": [
+        {
+          col: 2,
+          message: MisleadingSequence::RegionalIndicatorPair,
+          hint: SEQUENCE_HINT,
+        }
+      ],
+      "/[\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F466}]/u;": [
+        {
+          col: 2,
+          message: MisleadingSequence::ZeroWidthJoiner,
+          hint: SEQUENCE_HINT,
+        }
+      ],
+      "/[a-z]|[xA\u{301}]/u;": [
+        {
+          col: 9,
+          message: MisleadingSequence::CombiningMark,
+          hint: SEQUENCE_HINT,
+        }
+      ],
+      r#"/[\uD83D\uDC76\uD83C\uDFFB]/;"#: [
+        {
+          col: 2,
+          message: MisleadingSequence::SurrogatePair,
+          hint: MisleadingSequence::SurrogatePair.hint(),
+        },
+        {
+          col: 2,
+          message: MisleadingSequence::EmojiModifier,
+          hint: SEQUENCE_HINT,
+        }
+      ],
+      r#"new RegExp("[\\uD83D\\uDC4D]");"#: [
+        {
+          col: 0,
+          message: MisleadingSequence::SurrogatePair,
+          hint: MisleadingSequence::SurrogatePair.hint(),
+        }
+      ],
+    };
+  }
+}
@@ -1,9 +1,8 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::ast::{TsModuleDecl, TsModuleName};
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::{VisitAll, VisitAllWith};
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
+use swc_ecmascript::ast::TsModuleName;
 
 pub struct NoNamespace;
 
@@ -23,34 +22,34 @@ impl LintRule for NoNamespace {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoNamespaceVisitor::new(context);
-    program.visit_all_with(program, &mut visitor);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct NoNamespaceVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoNamespaceVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoNamespace {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::TsModuleDecl]
   }
-}
 
-impl<'c> VisitAll for NoNamespaceVisitor<'c> {
-  fn visit_ts_module_decl(&mut self, mod_decl: &TsModuleDecl, _: &dyn Node) {
-    if !mod_decl.global && !mod_decl.declare {
-      if let TsModuleName::Ident(_) = mod_decl.id {
-        self.context.add_diagnostic(mod_decl.span, CODE, MESSAGE);
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::TsModuleDecl(mod_decl) = node {
+      if !mod_decl.global && !mod_decl.declare {
+        if let TsModuleName::Ident(_) = mod_decl.id {
+          context.add_diagnostic(mod_decl.span, CODE, MESSAGE);
+        }
       }
     }
   }
+
+  fn checks_types(&self) -> bool {
+    true
+  }
 }
 
 #[cfg(test)]
@@ -1,10 +1,8 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::ast::{Expr, NewExpr};
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::{VisitAll, VisitAllWith};
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
+use swc_ecmascript::ast::Expr;
 
 pub struct NoNewSymbol;
 
@@ -24,36 +22,34 @@ impl LintRule for NoNewSymbol {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoNewSymbolVisitor::new(context);
-    program.visit_all_with(program, &mut visitor);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct NoNewSymbolVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoNewSymbolVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoNewSymbol {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::NewExpr]
   }
-}
 
-impl<'c> VisitAll for NoNewSymbolVisitor<'c> {
-  noop_visit_type!();
-
-  fn visit_new_expr(&mut self, new_expr: &NewExpr, _parent: &dyn Node) {
-    if let Expr::Ident(ident) = &*new_expr.callee {
-      if ident.sym == *"Symbol" {
-        self.context.add_diagnostic(new_expr.span, CODE, MESSAGE);
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::NewExpr(new_expr) = node {
+      if let Expr::Ident(ident) = &*new_expr.callee {
+        if ident.sym == *"Symbol" {
+          context.add_diagnostic(new_expr.span, CODE, MESSAGE);
+        }
       }
     }
   }
+
+  fn checks_types(&self) -> bool {
+    true
+  }
 }
 
 #[cfg(test)]
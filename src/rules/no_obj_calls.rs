@@ -1,14 +1,10 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use swc_common::Span;
-use swc_ecmascript::ast::CallExpr;
 use swc_ecmascript::ast::Expr;
 use swc_ecmascript::ast::ExprOrSuper;
-use swc_ecmascript::ast::NewExpr;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
 
 pub struct NoObjCalls;
 
@@ -31,55 +27,46 @@ impl LintRule for NoObjCalls {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoObjCallsVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct NoObjCallsVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoObjCallsVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoObjCalls {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::CallExpr, NodeKind::NewExpr]
   }
 
-  fn check_callee(&mut self, callee_name: impl AsRef<str>, span: Span) {
-    let callee_name = callee_name.as_ref();
-    match callee_name {
-      "Math" | "JSON" | "Reflect" | "Atomics" => {
-        self.context.add_diagnostic(
-          span,
-          "no-obj-calls",
-          get_message(callee_name),
-        );
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    match node {
+      NodeRef::CallExpr(call_expr) => {
+        if let ExprOrSuper::Expr(expr) = &call_expr.callee {
+          if let Expr::Ident(ident) = expr.as_ref() {
+            check_callee(context, &ident.sym, call_expr.span);
+          }
+        }
+      }
+      NodeRef::NewExpr(new_expr) => {
+        if let Expr::Ident(ident) = &*new_expr.callee {
+          check_callee(context, &ident.sym, new_expr.span);
+        }
       }
       _ => {}
     }
   }
 }
 
-impl<'c> Visit for NoObjCallsVisitor<'c> {
-  noop_visit_type!();
-
-  fn visit_call_expr(&mut self, call_expr: &CallExpr, _parent: &dyn Node) {
-    if let ExprOrSuper::Expr(expr) = &call_expr.callee {
-      if let Expr::Ident(ident) = expr.as_ref() {
-        self.check_callee(&ident.sym, call_expr.span);
-      }
-    }
-  }
-
-  fn visit_new_expr(&mut self, new_expr: &NewExpr, _parent: &dyn Node) {
-    if let Expr::Ident(ident) = &*new_expr.callee {
-      self.check_callee(&ident.sym, new_expr.span);
+fn check_callee(context: &mut Context, callee_name: &str, span: Span) {
+  match callee_name {
+    "Math" | "JSON" | "Reflect" | "Atomics" => {
+      context.add_diagnostic(span, "no-obj-calls", get_message(callee_name));
     }
+    _ => {}
   }
 }
 
@@ -1,11 +1,9 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use once_cell::sync::Lazy;
 use regex::Regex;
-use swc_ecmascript::ast::Number;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
 
 pub struct NoOctal;
 
@@ -25,40 +23,39 @@ impl LintRule for NoOctal {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoOctalVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct NoOctalVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoOctalVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoOctal {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::Number]
   }
-}
 
-impl<'c> Visit for NoOctalVisitor<'c> {
-  fn visit_number(&mut self, literal_num: &Number, _parent: &dyn Node) {
-    static OCTAL: Lazy<Regex> = Lazy::new(|| Regex::new(r"^0[0-9]").unwrap());
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::Number(literal_num) = node {
+      static OCTAL: Lazy<Regex> = Lazy::new(|| Regex::new(r"^0[0-9]").unwrap());
 
-    let raw_number = self
-      .context
-      .source_map
-      .span_to_snippet(literal_num.span)
-      .expect("error in loading snippet");
+      let raw_number = context
+        .source_map
+        .span_to_snippet(literal_num.span)
+        .expect("error in loading snippet");
 
-    if OCTAL.is_match(&raw_number) {
-      self.context.add_diagnostic(literal_num.span, CODE, MESSAGE);
+      if OCTAL.is_match(&raw_number) {
+        context.add_diagnostic(literal_num.span, CODE, MESSAGE);
+      }
     }
   }
+
+  fn checks_types(&self) -> bool {
+    true
+  }
 }
 
 #[cfg(test)]
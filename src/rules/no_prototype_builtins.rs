@@ -1,13 +1,9 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-
-use swc_ecmascript::ast::CallExpr;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use swc_ecmascript::ast::Expr;
 use swc_ecmascript::ast::ExprOrSuper;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
 
 const BANNED_PROPERTIES: &[&str] =
   &["hasOwnProperty", "isPrototypeOf", "propertyIsEnumberable"];
@@ -36,30 +32,26 @@ impl LintRule for NoPrototypeBuiltins {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoPrototypeBuiltinsVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct NoPrototypeBuiltinsVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoPrototypeBuiltinsVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoPrototypeBuiltins {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::CallExpr]
   }
-}
-
-impl<'c> Visit for NoPrototypeBuiltinsVisitor<'c> {
-  noop_visit_type!();
 
-  fn visit_call_expr(&mut self, call_expr: &CallExpr, _parent: &dyn Node) {
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    let call_expr = match node {
+      NodeRef::CallExpr(call_expr) => call_expr,
+      _ => return,
+    };
     let member_expr = match &call_expr.callee {
       ExprOrSuper::Expr(boxed_expr) => match &**boxed_expr {
         Expr::Member(member_expr) => {
@@ -76,11 +68,7 @@ impl<'c> Visit for NoPrototypeBuiltinsVisitor<'c> {
     if let Expr::Ident(ident) = &*member_expr.prop {
       let prop_name = ident.sym.as_ref();
       if BANNED_PROPERTIES.contains(&prop_name) {
-        self.context.add_diagnostic(
-          call_expr.span,
-          CODE,
-          get_message(prop_name),
-        );
+        context.add_diagnostic(call_expr.span, CODE, get_message(prop_name));
       }
     }
   }
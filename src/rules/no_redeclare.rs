@@ -7,7 +7,7 @@ use swc_ecmascript::{
   visit::Visit, visit::VisitWith,
 };
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 pub struct NoRedeclare;
 
@@ -31,6 +31,7 @@ impl LintRule for NoRedeclare {
     let mut visitor = NoRedeclareVisitor {
       context,
       bindings: Default::default(),
+      type_bindings: Default::default(),
     };
     program.visit_with(program, &mut visitor);
   }
@@ -40,6 +41,10 @@ struct NoRedeclareVisitor<'c> {
   context: &'c mut Context,
   /// TODO(kdy1): Change this to HashMap<Id, Vec<Span>> and use those spans to point previous bindings/
   bindings: HashSet<Id>,
+  /// Types live in a separate namespace, so they never conflict with values.
+  /// The value is `true` for type aliases, which can't be merged with
+  /// other types unlike interfaces.
+  type_bindings: HashMap<Id, bool>,
 }
 
 impl<'c> NoRedeclareVisitor<'c> {
@@ -50,11 +55,31 @@ impl<'c> NoRedeclareVisitor<'c> {
       self.context.add_diagnostic(i.span, CODE, MESSAGE);
     }
   }
+
+  fn declare_type(&mut self, i: &Ident, is_alias: bool) {
+    let id = i.to_id();
+
+    if let Some(prev_is_alias) = self.type_bindings.insert(id, is_alias) {
+      if prev_is_alias || is_alias {
+        self.context.add_diagnostic(i.span, CODE, MESSAGE);
+      }
+    }
+  }
 }
 
 impl<'c> Visit for NoRedeclareVisitor<'c> {
   noop_visit_type!();
 
+  fn visit_decl(&mut self, d: &Decl, _: &dyn Node) {
+    match d {
+      Decl::TsInterface(i) => self.declare_type(&i.id, false),
+      Decl::TsTypeAlias(a) => self.declare_type(&a.id, true),
+      _ => {}
+    }
+
+    d.visit_children_with(self);
+  }
+
   fn visit_fn_decl(&mut self, f: &FnDecl, _: &dyn Node) {
     if f.function.body.is_none() {
       return;
@@ -107,6 +132,10 @@ mod tests {
       class D {
         constructor(a: string) {}
       }",
+      "interface A {} interface A {}",
+      "interface A {} const A = 1;",
+      "type A = string; const A = 1;",
+      "type A = string; function f() { type A = number; }",
     };
   }
 
@@ -141,6 +170,9 @@ mod tests {
       "var a = 3; var a = 10; var a = 15;": [{col: 15, message: MESSAGE}, {col: 27, message: MESSAGE}],
       "var a; var {a = 0, b: Object = 0} = {};": [{line: 1, col: 12, message: MESSAGE}],
       "var a; var {a = 0, b: globalThis = 0} = {};": [{line: 1, col: 12, message: MESSAGE}],
+      "type A = string; type A = number;": [{col: 22, message: MESSAGE}],
+      "interface A {} type A = number;": [{col: 20, message: MESSAGE}],
+      "type A = string; interface A {}": [{col: 27, message: MESSAGE}],
     }
   }
 }
@@ -1,6 +1,10 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::js_regex::ast::{
+  walk_pattern, CharacterClass, Span as RegexSpan, Visit,
+};
+use crate::js_regex::EcmaRegexParser;
 use crate::swc_util::extract_regex;
 use once_cell::sync::Lazy;
 use swc_common::Span;
@@ -45,11 +49,9 @@ impl<'c> NoRegexSpacesVisitor<'c> {
     Self { context }
   }
 
-  fn check_regex(&mut self, regex: &str, span: Span) {
+  fn check_regex(&mut self, regex: &str, flags: &str, span: Span) {
     static DOUBLE_SPACE: Lazy<regex::Regex> =
       Lazy::new(|| regex::Regex::new(r"(?u) {2}").unwrap());
-    static BRACKETS: Lazy<regex::Regex> =
-      Lazy::new(|| regex::Regex::new(r"\[.*?[^\\]\]").unwrap());
     static SPACES: Lazy<regex::Regex> = Lazy::new(|| {
       regex::Regex::new(r#"(?u)( {2,})(?: [+*{?]|[^+*{?]|$)"#).unwrap()
     });
@@ -58,16 +60,24 @@ impl<'c> NoRegexSpacesVisitor<'c> {
       return;
     }
 
-    let mut character_classes = vec![];
-    for mtch in BRACKETS.find_iter(regex) {
-      character_classes.push((mtch.start(), mtch.end()));
-    }
+    let mut parser = EcmaRegexParser::new(self.context.ecma_version());
+    // Invalid regexes are reported by `no-invalid-regexp`
+    let pattern = match parser
+      .parse_flags(flags)
+      .and_then(|flags| parser.parse_pattern(regex, flags))
+    {
+      Ok(pattern) => pattern,
+      Err(_) => return,
+    };
+    let mut collector = CharacterClassCollector { classes: vec![] };
+    walk_pattern(&mut collector, &pattern);
 
     for mtch in SPACES.find_iter(regex) {
-      let not_in_classes = &character_classes
+      let not_in_classes = collector
+        .classes
         .iter()
-        .all(|ref v| mtch.start() < v.0 || v.1 <= mtch.start());
-      if *not_in_classes {
+        .all(|class| mtch.start() < class.start || class.end <= mtch.start());
+      if not_in_classes {
         self.context.add_diagnostic(span, CODE, MESSAGE);
         return;
       }
@@ -75,18 +85,30 @@ impl<'c> NoRegexSpacesVisitor<'c> {
   }
 }
 
+struct CharacterClassCollector {
+  classes: Vec<RegexSpan>,
+}
+
+impl Visit for CharacterClassCollector {
+  fn visit_character_class(&mut self, class: &CharacterClass) {
+    self.classes.push(class.span);
+  }
+}
+
 impl<'c> VisitAll for NoRegexSpacesVisitor<'c> {
   noop_visit_type!();
 
   fn visit_regex(&mut self, regex: &Regex, _: &dyn Node) {
-    self.check_regex(regex.exp.to_string().as_str(), regex.span);
+    self.check_regex(&regex.exp, &regex.flags, regex.span);
   }
 
   fn visit_new_expr(&mut self, new_expr: &NewExpr, _: &dyn Node) {
     if let Expr::Ident(ident) = &*new_expr.callee {
       if let Some(args) = &new_expr.args {
-        if let Some(regex) = extract_regex(&self.context.scope, ident, args) {
-          self.check_regex(regex.as_str(), new_expr.span);
+        if let Some((regex, flags)) =
+          extract_regex(&self.context.scope, ident, args)
+        {
+          self.check_regex(&regex, &flags, new_expr.span);
         }
       }
     }
@@ -95,10 +117,10 @@ impl<'c> VisitAll for NoRegexSpacesVisitor<'c> {
   fn visit_call_expr(&mut self, call_expr: &CallExpr, _: &dyn Node) {
     if let ExprOrSuper::Expr(expr) = &call_expr.callee {
       if let Expr::Ident(ident) = expr.as_ref() {
-        if let Some(regex) =
+        if let Some((regex, flags)) =
           extract_regex(&self.context.scope, ident, &call_expr.args)
         {
-          self.check_regex(regex.as_str(), call_expr.span);
+          self.check_regex(&regex, &flags, call_expr.span);
         }
       }
     }
@@ -156,10 +178,10 @@ mod tests {
       "var foo = RegExp(' [  ] [  ] ');",
       "var foo = new RegExp(' \\[   \\] ');",
 
-      // TODO(@disizali) invalid regexes must handled on separated rule called `no-invalid-regexp`.
-      // "var foo = new RegExp('[  ');",
-      // "var foo = new RegExp('{  ', 'u');",
-      // "var foo = new RegExp(' \\[   ');",
+      // invalid regexes are handled by `no-invalid-regexp`
+      "var foo = new RegExp('[  ');",
+      "var foo = new RegExp('{  ', 'u');",
+      "var foo = new RegExp(' \\[   ');",
     };
   }
 
@@ -1,15 +1,11 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use swc_ecmascript::ast::BlockStmt;
-use swc_ecmascript::ast::Class;
 use swc_ecmascript::ast::ClassMember;
 use swc_ecmascript::ast::MethodKind;
-use swc_ecmascript::ast::SetterProp;
 use swc_ecmascript::ast::Stmt;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
 
 pub struct NoSetterReturn;
 
@@ -26,72 +22,64 @@ impl LintRule for NoSetterReturn {
     "no-setter-return"
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoSetterReturnVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct NoSetterReturnVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoSetterReturnVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoSetterReturn {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::Class, NodeKind::SetterProp]
   }
 
-  fn check_block_stmt(&mut self, block_stmt: &BlockStmt) {
-    for stmt in &block_stmt.stmts {
-      if let Stmt::Return(return_stmt) = stmt {
-        if return_stmt.arg.is_some() {
-          self.context.add_diagnostic(
-            return_stmt.span,
-            "no-setter-return",
-            "Setter cannot return a value",
-          );
-        }
-      }
-    }
-  }
-}
-
-impl<'c> Visit for NoSetterReturnVisitor<'c> {
-  noop_visit_type!();
-
-  fn visit_class(&mut self, class: &Class, _parent: &dyn Node) {
-    for member in &class.body {
-      match member {
-        ClassMember::Method(class_method) => {
-          if class_method.kind == MethodKind::Setter {
-            if let Some(block_stmt) = &class_method.function.body {
-              self.check_block_stmt(block_stmt);
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    match node {
+      NodeRef::Class(class) => {
+        for member in &class.body {
+          match member {
+            ClassMember::Method(class_method) => {
+              if class_method.kind == MethodKind::Setter {
+                if let Some(block_stmt) = &class_method.function.body {
+                  check_block_stmt(context, block_stmt);
+                }
+              }
             }
-          }
-        }
-        ClassMember::PrivateMethod(private_method) => {
-          if private_method.kind == MethodKind::Setter {
-            if let Some(block_stmt) = &private_method.function.body {
-              self.check_block_stmt(block_stmt);
+            ClassMember::PrivateMethod(private_method) => {
+              if private_method.kind == MethodKind::Setter {
+                if let Some(block_stmt) = &private_method.function.body {
+                  check_block_stmt(context, block_stmt);
+                }
+              }
             }
+            _ => {}
           }
         }
-        _ => {}
       }
+      NodeRef::SetterProp(setter_prop) => {
+        if let Some(block_stmt) = &setter_prop.body {
+          check_block_stmt(context, block_stmt);
+        }
+      }
+      _ => {}
     }
   }
+}
 
-  fn visit_setter_prop(
-    &mut self,
-    setter_prop: &SetterProp,
-    _parent: &dyn Node,
-  ) {
-    if let Some(block_stmt) = &setter_prop.body {
-      self.check_block_stmt(block_stmt);
+fn check_block_stmt(context: &mut Context, block_stmt: &BlockStmt) {
+  for stmt in &block_stmt.stmts {
+    if let Stmt::Return(return_stmt) = stmt {
+      if return_stmt.arg.is_some() {
+        context.add_diagnostic(
+          return_stmt.span,
+          "no-setter-return",
+          "Setter cannot return a value",
+        );
+      }
     }
   }
 }
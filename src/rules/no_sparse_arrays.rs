@@ -1,9 +1,7 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 
 pub struct NoSparseArrays;
 
@@ -16,40 +14,30 @@ impl LintRule for NoSparseArrays {
     "no-sparse-arrays"
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoSparseArraysVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct NoSparseArraysVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoSparseArraysVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoSparseArrays {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::ArrayLit]
   }
-}
-
-impl<'c> Visit for NoSparseArraysVisitor<'c> {
-  noop_visit_type!();
 
-  fn visit_array_lit(
-    &mut self,
-    array_lit: &swc_ecmascript::ast::ArrayLit,
-    _parent: &dyn Node,
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
   ) {
-    if array_lit.elems.iter().any(|e| e.is_none()) {
-      self.context.add_diagnostic(
-        array_lit.span,
-        "no-sparse-arrays",
-        "Sparse arrays are not allowed",
-      );
+    if let NodeRef::ArrayLit(array_lit) = node {
+      if array_lit.elems.iter().any(|e| e.is_none()) {
+        context.add_diagnostic(
+          array_lit.span,
+          "no-sparse-arrays",
+          "Sparse arrays are not allowed",
+        );
+      }
     }
   }
 }
@@ -0,0 +1,582 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use super::Context;
+use super::LintRule;
+use crate::js_regex::ast::{
+  Alternative, CharacterClass, CharacterSetKind, ClassElement, Flags,
+  Quantifier, SetOperationKind, Span as RegexSpan, Term,
+};
+use crate::swc_util::collect_regexes;
+
+pub struct NoSuperLinearRegex;
+
+const CODE: &str = "no-super-linear-regex";
+const EXPONENTIAL_HINT: &str = "Rework the repeated part so that each input can be matched in only one way, e.g. without nested quantifiers or overlapping alternatives";
+const POLYNOMIAL_HINT: &str =
+  "Rework the adjacent quantifiers so that they can't match the same characters";
+
+impl LintRule for NoSuperLinearRegex {
+  fn new() -> Box<Self> {
+    Box::new(NoSuperLinearRegex)
+  }
+
+  fn code(&self) -> &'static str {
+    CODE
+  }
+
+  fn lint_program(
+    &self,
+    context: &mut Context,
+    program: &swc_ecmascript::ast::Program,
+  ) {
+    for regex in collect_regexes(program, &context.scope) {
+      let (pattern, flags) = match regex.parse(context.ecma_version()) {
+        Some(parsed) => parsed,
+        None => continue,
+      };
+
+      let mut analyzer = Analyzer {
+        flags,
+        backtracking: vec![],
+      };
+      analyzer.check_alternatives(&pattern.alternatives);
+
+      for (sub_pattern, kind) in analyzer.backtracking {
+        let span = regex.span_of(sub_pattern);
+        let sub_pattern = &regex.pattern[sub_pattern.start..sub_pattern.end];
+        let (message, hint) = match kind {
+          Backtracking::Exponential => (
+            format!("`{}` can cause exponential backtracking", sub_pattern),
+            EXPONENTIAL_HINT,
+          ),
+          Backtracking::Polynomial => (
+            format!("`{}` can cause polynomial backtracking", sub_pattern),
+            POLYNOMIAL_HINT,
+          ),
+        };
+        context.add_diagnostic_with_hint(span, CODE, message, hint);
+      }
+    }
+  }
+
+  fn docs(&self) -> &'static str {
+    r#"Disallows regular expressions which can take super-linear time to match
+
+When a match fails, the regex engine backtracks and tries every other way the
+pattern could match the input. If a part of the pattern can match the same
+input in many ways, the time taken grows polynomially or even exponentially
+with the length of the input, which a malicious user can exploit to block the
+process (ReDoS).
+
+This rule reports repeated groups whose repetitions can match the same input in
+different ways, e.g. nested quantifiers and ambiguous alternatives, and
+adjacent quantifiers which can match the same characters.
+
+### Invalid:
+```typescript
+/(a+)+$/;
+/(\w|\d)*!/;
+/\d+\.?\d+$/;
+new RegExp("(?:x*y?)*z");
+```
+
+### Valid:
+```typescript
+/a+$/;
+/(\w|-)*!/;
+/\d+(\.\d+)?$/;
+new RegExp("(?:x+y)*z");
+```
+"#
+  }
+}
+
+enum Backtracking {
+  Exponential,
+  Polynomial,
+}
+
+struct Analyzer {
+  flags: Flags,
+  backtracking: Vec<(RegexSpan, Backtracking)>,
+}
+
+impl Analyzer {
+  fn check_alternatives(&mut self, alternatives: &[Alternative]) {
+    for alternative in alternatives {
+      self.check_alternative(alternative);
+    }
+  }
+
+  fn check_alternative(&mut self, alternative: &Alternative) {
+    let terms = &alternative.terms;
+    // Two unbounded quantifiers which can match the same characters, with
+    // nothing but optional terms between them, can split the input between
+    // them in O(n) ways at every position.
+    for (i, term) in terms.iter().enumerate() {
+      if !is_unbounded(term) {
+        continue;
+      }
+      let chars = self.chars(term);
+      for next in &terms[i + 1..] {
+        if is_unbounded(next) && chars.intersects(&self.chars(next)) {
+          let span = RegexSpan::new(term.span().start, next.span().end);
+          self.backtracking.push((span, Backtracking::Polynomial));
+          break;
+        }
+        if !is_nullable(next) {
+          break;
+        }
+      }
+    }
+
+    for term in terms {
+      self.check_term(term);
+    }
+  }
+
+  fn check_term(&mut self, term: &Term) {
+    match term {
+      Term::Quantifier(quantifier) => {
+        if quantifier.max.is_none() && self.is_ambiguous(&quantifier.term) {
+          self
+            .backtracking
+            .push((quantifier.span, Backtracking::Exponential));
+        } else {
+          self.check_term(&quantifier.term);
+        }
+      }
+      Term::Group(group) => self.check_alternatives(&group.alternatives),
+      Term::CapturingGroup(group) => {
+        self.check_alternatives(&group.alternatives)
+      }
+      Term::Lookaround(lookaround) => {
+        self.check_alternatives(&lookaround.alternatives)
+      }
+      _ => {}
+    }
+  }
+
+  /// Whether one repetition of `term` can match an input in more than one
+  /// way, so that repeating it takes exponential time to fail.
+  fn is_ambiguous(&self, term: &Term) -> bool {
+    if find_repeatable(term).is_some() {
+      return true;
+    }
+
+    let alternatives = match term {
+      Term::Group(group) => &group.alternatives,
+      Term::CapturingGroup(group) => &group.alternatives,
+      _ => return false,
+    };
+    // Alternatives which can match the same input, e.g. `(a|\w)`.
+    let sequences: Vec<_> = alternatives
+      .iter()
+      .filter_map(|alternative| self.fixed_sequence(alternative))
+      .collect();
+    sequences.iter().enumerate().any(|(i, a)| {
+      sequences[i + 1..].iter().any(|b| {
+        a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.intersects(b))
+      })
+    })
+  }
+
+  /// Characters matched by each term of the alternative, if every term
+  /// matches exactly one character.
+  fn fixed_sequence(&self, alternative: &Alternative) -> Option<Vec<CharSet>> {
+    alternative
+      .terms
+      .iter()
+      .map(|term| match term {
+        Term::Character(..)
+        | Term::CharacterSet(..)
+        | Term::CharacterClass(..) => Some(self.chars(term)),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// Characters `term` can consume anywhere in its match.
+  fn chars(&self, term: &Term) -> CharSet {
+    let chars = match term {
+      Term::Character(c) => CharSet::single(c.value),
+      Term::CharacterSet(set) => self.set_chars(&set.kind, set.negate),
+      Term::CharacterClass(class) => self.class_chars(class),
+      Term::Quantifier(quantifier) => self.chars(&quantifier.term),
+      Term::Group(group) => self.alternatives_chars(&group.alternatives),
+      Term::CapturingGroup(group) => {
+        self.alternatives_chars(&group.alternatives)
+      }
+      // A backreference can match anything its group did.
+      Term::Backreference(..) => CharSet::all(),
+      Term::Assertion(..) | Term::Lookaround(..) => CharSet::default(),
+    };
+    if self.flags.ignore_case {
+      chars.fold_case()
+    } else {
+      chars
+    }
+  }
+
+  fn alternatives_chars(&self, alternatives: &[Alternative]) -> CharSet {
+    let mut chars = CharSet::default();
+    for term in alternatives.iter().flat_map(|a| &a.terms) {
+      chars = chars.union(&self.chars(term));
+    }
+    chars
+  }
+
+  fn class_chars(&self, class: &CharacterClass) -> CharSet {
+    let mut chars = CharSet::default();
+    for element in &class.elements {
+      chars = chars.union(&self.element_chars(element));
+    }
+    if class.negate {
+      chars.negate()
+    } else {
+      chars
+    }
+  }
+
+  fn element_chars(&self, element: &ClassElement) -> CharSet {
+    match element {
+      ClassElement::Character(c) => CharSet::single(c.value),
+      ClassElement::CharacterSet(set) => self.set_chars(&set.kind, set.negate),
+      ClassElement::Range(range) => {
+        CharSet::range(range.min.value, range.max.value)
+      }
+      ClassElement::Class(class) => self.class_chars(class),
+      ClassElement::StringDisjunction(disjunction) => {
+        let mut chars = CharSet::default();
+        for c in disjunction.alternatives.iter().flat_map(|s| &s.characters) {
+          chars = chars.union(&CharSet::single(c.value));
+        }
+        chars
+      }
+      ClassElement::SetOperation(operation) => {
+        let left = self.element_chars(&operation.left);
+        match operation.kind {
+          SetOperationKind::Intersection => {
+            left.intersection(&self.element_chars(&operation.right))
+          }
+          // Strings of the right operand only remove whole strings.
+          SetOperationKind::Subtraction => left,
+        }
+      }
+    }
+  }
+
+  fn set_chars(&self, kind: &CharacterSetKind, negate: bool) -> CharSet {
+    let chars = match kind {
+      CharacterSetKind::Any if self.flags.dot_all => CharSet::all(),
+      CharacterSetKind::Any => CharSet::from_ranges(LINE_TERMINATORS).negate(),
+      CharacterSetKind::Digit => CharSet::range('0' as u32, '9' as u32),
+      CharacterSetKind::Space => CharSet::from_ranges(WHITE_SPACES),
+      CharacterSetKind::Word => CharSet::from_ranges(WORD_CHARACTERS),
+      // Assume a property can match anything, its negation as well.
+      CharacterSetKind::Property { .. } => return CharSet::all(),
+    };
+    if negate {
+      chars.negate()
+    } else {
+      chars
+    }
+  }
+}
+
+fn is_unbounded(term: &Term) -> bool {
+  matches!(term, Term::Quantifier(Quantifier { max: None, .. }))
+}
+
+/// Whether `term` can match the empty string.
+fn is_nullable(term: &Term) -> bool {
+  match term {
+    Term::Assertion(..) | Term::Lookaround(..) | Term::Backreference(..) => {
+      true
+    }
+    Term::Quantifier(quantifier) => {
+      quantifier.min == 0 || is_nullable(&quantifier.term)
+    }
+    Term::Group(group) => {
+      group.alternatives.iter().any(is_nullable_alternative)
+    }
+    Term::CapturingGroup(group) => {
+      group.alternatives.iter().any(is_nullable_alternative)
+    }
+    Term::CharacterClass(..) | Term::CharacterSet(..) | Term::Character(..) => {
+      false
+    }
+  }
+}
+
+fn is_nullable_alternative(alternative: &Alternative) -> bool {
+  alternative.terms.iter().all(is_nullable)
+}
+
+/// Finds a quantifier which can make up a whole match of `term` on its own,
+/// with a varying number of repetitions, e.g. `a+` in `(a+b?)`. Repeating
+/// `term` then splits the input between the repetitions in exponentially
+/// many ways.
+fn find_repeatable(term: &Term) -> Option<&Quantifier> {
+  let alternatives = match term {
+    Term::Quantifier(quantifier)
+      if quantifier
+        .max
+        .map_or(true, |max| max > 1 && max > quantifier.min) =>
+    {
+      return Some(quantifier)
+    }
+    Term::Quantifier(quantifier) => return find_repeatable(&quantifier.term),
+    Term::Group(group) => &group.alternatives,
+    Term::CapturingGroup(group) => &group.alternatives,
+    _ => return None,
+  };
+  alternatives.iter().find_map(|alternative| {
+    alternative.terms.iter().enumerate().find_map(|(i, term)| {
+      let others_nullable = alternative
+        .terms
+        .iter()
+        .enumerate()
+        .all(|(j, other)| i == j || is_nullable(other));
+      if others_nullable {
+        find_repeatable(term)
+      } else {
+        None
+      }
+    })
+  })
+}
+
+const MAX_CHAR: u32 = 0x10ffff;
+
+const LINE_TERMINATORS: &[(u32, u32)] =
+  &[(0x0a, 0x0a), (0x0d, 0x0d), (0x2028, 0x2029)];
+
+const WHITE_SPACES: &[(u32, u32)] = &[
+  (0x09, 0x0d),
+  (0x20, 0x20),
+  (0xa0, 0xa0),
+  (0x1680, 0x1680),
+  (0x2000, 0x200a),
+  (0x2028, 0x2029),
+  (0x202f, 0x202f),
+  (0x205f, 0x205f),
+  (0x3000, 0x3000),
+  (0xfeff, 0xfeff),
+];
+
+const WORD_CHARACTERS: &[(u32, u32)] = &[
+  (0x30, 0x39), // 0-9
+  (0x41, 0x5a), // A-Z
+  (0x5f, 0x5f), // _
+  (0x61, 0x7a), // a-z
+];
+
+/// Set of characters as sorted, disjoint and non-adjacent ranges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct CharSet(Vec<(u32, u32)>);
+
+impl CharSet {
+  fn all() -> Self {
+    CharSet(vec![(0, MAX_CHAR)])
+  }
+
+  fn single(c: u32) -> Self {
+    CharSet(vec![(c, c)])
+  }
+
+  fn range(min: u32, max: u32) -> Self {
+    CharSet(vec![(min, max)])
+  }
+
+  fn from_ranges(ranges: &[(u32, u32)]) -> Self {
+    let mut ranges = ranges.to_vec();
+    ranges.sort_unstable();
+    let mut merged: Vec<(u32, u32)> = vec![];
+    for (min, max) in ranges {
+      match merged.last_mut() {
+        Some(last) if min <= last.1.saturating_add(1) => {
+          last.1 = last.1.max(max)
+        }
+        _ => merged.push((min, max)),
+      }
+    }
+    CharSet(merged)
+  }
+
+  fn union(&self, other: &CharSet) -> CharSet {
+    let ranges: Vec<_> = self.0.iter().chain(&other.0).copied().collect();
+    CharSet::from_ranges(&ranges)
+  }
+
+  fn intersection(&self, other: &CharSet) -> CharSet {
+    let mut ranges = vec![];
+    for &(a_min, a_max) in &self.0 {
+      for &(b_min, b_max) in &other.0 {
+        let (min, max) = (a_min.max(b_min), a_max.min(b_max));
+        if min <= max {
+          ranges.push((min, max));
+        }
+      }
+    }
+    CharSet::from_ranges(&ranges)
+  }
+
+  fn intersects(&self, other: &CharSet) -> bool {
+    !self.intersection(other).0.is_empty()
+  }
+
+  fn negate(&self) -> CharSet {
+    let mut ranges = vec![];
+    let mut next = 0;
+    for &(min, max) in &self.0 {
+      if min > next {
+        ranges.push((next, min - 1));
+      }
+      next = max + 1;
+    }
+    if next <= MAX_CHAR {
+      ranges.push((next, MAX_CHAR));
+    }
+    CharSet(ranges)
+  }
+
+  /// Adds the other case of ASCII letters.
+  fn fold_case(&self) -> CharSet {
+    let lower = CharSet::range('a' as u32, 'z' as u32);
+    let upper = CharSet::range('A' as u32, 'Z' as u32);
+    let shift = |set: CharSet, up: bool| {
+      let ranges: Vec<_> = set
+        .0
+        .iter()
+        .map(|&(min, max)| {
+          if up {
+            (min - 32, max - 32)
+          } else {
+            (min + 32, max + 32)
+          }
+        })
+        .collect();
+      CharSet(ranges)
+    };
+    self
+      .union(&shift(self.intersection(&lower), true))
+      .union(&shift(self.intersection(&upper), false))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn no_super_linear_regex_valid() {
+    assert_lint_ok! {
+      NoSuperLinearRegex,
+      r#"/a+$/"#,
+      r#"/(ab+c)+$/"#,
+      r#"/(a|b)*c/"#,
+      r#"/(\w|-)*!/"#,
+      r#"/(ab|ac)*/"#,
+      r#"/\d+(\.\d+)?$/"#,
+      r#"/\w+@\w+\.\w+/"#,
+      r#"/[a-z]+[0-9]*$/"#,
+      r#"/^(a{2})+$/"#,
+      r#"/^(a?)+$/"#,
+      r#"/a*b*/"#,
+      r#"/a*B*/"#,
+      r#"new RegExp("(?:x+y)*z")"#,
+      r#"new RegExp("(a+)+", 'z')"#,
+      r#"new RegExp(pattern)"#,
+      r#"/[\w&&\d]+[a-z]+/v"#,
+    };
+  }
+
+  #[test]
+  fn no_super_linear_regex_invalid() {
+    assert_lint_err! {
+      NoSuperLinearRegex,
+      r#"/(a+)+$/"#: [
+        {
+          col: 1,
+          message: "`(a+)+` can cause exponential backtracking",
+          hint: EXPONENTIAL_HINT,
+        }
+      ],
+      r#"/^(?:\w+\s?)*$/"#: [
+        {
+          col: 2,
+          message: r"`(?:\w+\s?)*` can cause exponential backtracking",
+          hint: EXPONENTIAL_HINT,
+        }
+      ],
+      r#"/(\w|\d)*!/"#: [
+        {
+          col: 1,
+          message: r"`(\w|\d)*` can cause exponential backtracking",
+          hint: EXPONENTIAL_HINT,
+        }
+      ],
+      r#"/(a|A)+!/i"#: [
+        {
+          col: 1,
+          message: "`(a|A)+` can cause exponential backtracking",
+          hint: EXPONENTIAL_HINT,
+        }
+      ],
+      r#"/x((a*)*)+/"#: [
+        {
+          col: 2,
+          message: "`((a*)*)+` can cause exponential backtracking",
+          hint: EXPONENTIAL_HINT,
+        }
+      ],
+      r#"/^(a{1,3})+$/"#: [
+        {
+          col: 2,
+          message: "`(a{1,3})+` can cause exponential backtracking",
+          hint: EXPONENTIAL_HINT,
+        }
+      ],
+      r#"/\d+\.?\d+$/"#: [
+        {
+          col: 1,
+          message: r"`\d+\.?\d+` can cause polynomial backtracking",
+          hint: POLYNOMIAL_HINT,
+        }
+      ],
+      r#"/=.*[^;]*;/"#: [
+        {
+          col: 2,
+          message: "`.*[^;]*` can cause polynomial backtracking",
+          hint: POLYNOMIAL_HINT,
+        }
+      ],
+      r#"new RegExp("(?:x*y?)*z")"#: [
+        {
+          col: 12,
+          message: "`(?:x*y?)*` can cause exponential backtracking",
+          hint: EXPONENTIAL_HINT,
+        }
+      ],
+      r#"RegExp("\\s*\\s*$", "m")"#: [
+        {
+          col: 0,
+          message: r"`\s*\s*` can cause polynomial backtracking",
+          hint: POLYNOMIAL_HINT,
+        }
+      ],
+      r#"/(a+)+b(c*)*/"#: [
+        {
+          col: 1,
+          message: "`(a+)+` can cause exponential backtracking",
+          hint: EXPONENTIAL_HINT,
+        },
+        {
+          col: 7,
+          message: "`(c*)*` can cause exponential backtracking",
+          hint: EXPONENTIAL_HINT,
+        }
+      ],
+    };
+  }
+}
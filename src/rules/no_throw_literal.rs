@@ -1,10 +1,8 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::ast::{Expr, ThrowStmt};
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
+use swc_ecmascript::ast::Expr;
 
 pub struct NoThrowLiteral;
 
@@ -17,44 +15,37 @@ impl LintRule for NoThrowLiteral {
     "no-throw-literal"
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoThrowLiteralVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct NoThrowLiteralVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoThrowLiteralVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoThrowLiteral {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::ThrowStmt]
   }
-}
-
-impl<'c> Visit for NoThrowLiteralVisitor<'c> {
-  noop_visit_type!();
 
-  fn visit_throw_stmt(&mut self, throw_stmt: &ThrowStmt, _parent: &dyn Node) {
-    match &*throw_stmt.arg {
-      Expr::Lit(_) => self.context.add_diagnostic(
-        throw_stmt.span,
-        "no-throw-literal",
-        "expected an error object to be thrown",
-      ),
-      Expr::Ident(ident) if ident.sym == *"undefined" => {
-        self.context.add_diagnostic(
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::ThrowStmt(throw_stmt) = node {
+      match &*throw_stmt.arg {
+        Expr::Lit(_) => context.add_diagnostic(
           throw_stmt.span,
           "no-throw-literal",
-          "do not throw undefined",
-        )
+          "expected an error object to be thrown",
+        ),
+        Expr::Ident(ident) if ident.sym == *"undefined" => context
+          .add_diagnostic(
+            throw_stmt.span,
+            "no-throw-literal",
+            "do not throw undefined",
+          ),
+        _ => {}
       }
-      _ => {}
     }
   }
 }
@@ -0,0 +1,295 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use super::{Context, LintRule};
+use crate::control_flow::FunctionId;
+use crate::dataflow::{Access, AccessKind, Analysis, DataFlow};
+use crate::scopes::BindingKind;
+use std::collections::{HashMap, HashSet};
+use swc_ecmascript::ast::{Program, VarDecl};
+use swc_ecmascript::utils::{find_ids, Id};
+use swc_ecmascript::visit::{noop_visit_type, Node, Visit, VisitWith};
+
+pub struct NoUninitializedRead;
+
+const CODE: &str = "no-uninitialized-read";
+
+impl LintRule for NoUninitializedRead {
+  fn new() -> Box<Self> {
+    Box::new(NoUninitializedRead)
+  }
+
+  fn code(&self) -> &'static str {
+    CODE
+  }
+
+  fn lint_program(&self, context: &mut Context, program: &Program) {
+    let scope = context.scope();
+    let cfg = context.cfg(program);
+    let data_flow = DataFlow::new(program, cfg, scope);
+    let mut assigned_elsewhere = AssignedElsewhere::default();
+    program.visit_with(program, &mut assigned_elsewhere);
+
+    // Variables which can be read without a value, by the function declaring
+    // them: `let` declared without a value and every hoisted `var`.
+    let mut declared = HashMap::new();
+    for (block, _) in cfg.blocks() {
+      for access in data_flow.accesses(block) {
+        if let Some(var) = scope.var(&access.id) {
+          let tracked = matches!(
+            (access.kind, var.kind()),
+            (AccessKind::Declare, BindingKind::Let)
+              | (AccessKind::Declare, BindingKind::Var)
+              | (AccessKind::Init, BindingKind::Var)
+          );
+          if tracked && !assigned_elsewhere.0.contains(&access.id) {
+            let function = data_flow.function_at(var.span().lo);
+            declared.insert(access.id.clone(), (function, var.kind()));
+          }
+        }
+      }
+    }
+    // A nested function may assign the variable at any time.
+    declared.retain(|id, (function, _)| {
+      scope.references_to(id).all(|r| {
+        !r.is_write() || data_flow.function_at(r.span().lo) == *function
+      })
+    });
+
+    let analysis = Unassigned { declared };
+    let solution = data_flow.solve(&analysis);
+    let mut reads = vec![];
+    data_flow.walk(&analysis, &solution, |unassigned, access| {
+      if access.kind == AccessKind::Read && unassigned.contains(&access.id) {
+        reads.push((access.span, access.id.0.clone()));
+      }
+    });
+
+    reads.sort_by_key(|(span, _)| span.lo);
+    for (span, sym) in reads {
+      context.add_diagnostic_with_hint(
+        span,
+        CODE,
+        format!("`{}` is read before it is assigned a value", sym),
+        format!("Assign a value to `{}` before reading it", sym),
+      );
+    }
+  }
+
+  fn docs(&self) -> &'static str {
+    r#"Disallows reading variables which may not have been assigned a value yet.
+
+A variable declared without a value is `undefined` until it's assigned. This
+rule follows the control flow of the function declaring the variable, and
+reports reads which are reachable from the declaration without passing an
+assignment. Variables assigned in nested functions are not checked.
+
+Assignments in short-circuiting expressions like `a && (b = 1)` are assumed
+to always happen, so reads after them are not reported.
+
+### Invalid:
+```typescript
+let a;
+console.log(a);
+
+let b;
+if (cond) {
+  b = 1;
+}
+console.log(b);
+
+function f() {
+  return c;
+  var c = 1;
+}
+```
+
+### Valid:
+```typescript
+let a;
+a = 1;
+console.log(a);
+
+let b;
+if (cond) {
+  b = 1;
+} else {
+  b = 2;
+}
+console.log(b);
+
+let c;
+setup(() => {
+  c = 1;
+});
+console.log(c);
+```
+"#
+  }
+}
+
+/// Variables whose values are assigned by code the rule can't see, i.e.
+/// ambient declarations like `declare let a: number;` and declarations with a
+/// definite assignment assertion like `let a!: number;`.
+#[derive(Default)]
+struct AssignedElsewhere(HashSet<Id>);
+
+impl Visit for AssignedElsewhere {
+  noop_visit_type!();
+
+  fn visit_var_decl(&mut self, n: &VarDecl, _: &dyn Node) {
+    for decl in &n.decls {
+      if n.declare || decl.definite {
+        self.0.extend(find_ids::<_, Id>(&decl.name));
+      }
+    }
+    n.visit_children_with(self);
+  }
+}
+
+/// Set of variables declared without a value which may not have been
+/// assigned yet.
+struct Unassigned {
+  declared: HashMap<Id, (Option<FunctionId>, BindingKind)>,
+}
+
+impl Analysis for Unassigned {
+  type State = HashSet<Id>;
+
+  fn initial(&self, function: FunctionId) -> Self::State {
+    // `var` declarations are hoisted to the start of the function.
+    self
+      .declared
+      .iter()
+      .filter(|(_, (f, kind))| {
+        *f == Some(function) && *kind == BindingKind::Var
+      })
+      .map(|(id, _)| id.clone())
+      .collect()
+  }
+
+  fn join(&self, state: &mut Self::State, other: &Self::State) {
+    state.extend(other.iter().cloned());
+  }
+
+  fn transfer(&self, state: &mut Self::State, access: &Access) {
+    match access.kind {
+      // `let a;` assigns `undefined` again on every evaluation.
+      AccessKind::Declare => {
+        if let Some((_, BindingKind::Let)) = self.declared.get(&access.id) {
+          state.insert(access.id.clone());
+        }
+      }
+      AccessKind::Init | AccessKind::Write => {
+        state.remove(&access.id);
+      }
+      AccessKind::Read => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn no_uninitialized_read_valid() {
+    assert_lint_ok! {
+      NoUninitializedRead,
+      "let a = 1; a;",
+      "let a; a = 1; a;",
+      "var a; a = 1; a;",
+      "let a; for (a of b) { a; }",
+      "let a; [a] = b; a;",
+      "let a; setup(() => { a = 1; }); a;",
+      "let a; function f() { return a; }",
+      "function f(a) { var a; return a; }",
+      "let a; try { a = f(); } catch (e) { throw e; } a;",
+      "declare var a: number; a;",
+      "export declare let a: number; a;",
+      "let a!: number; a;",
+      // Short-circuiting expressions don't split the control flow.
+      "let a; b && (a = 1); a;",
+      r#"
+let a;
+if (b) {
+  a = 1;
+} else {
+  a = 2;
+}
+a;
+      "#,
+      r#"
+let a;
+while (true) {
+  a = f();
+  if (a) break;
+}
+a;
+      "#,
+    };
+  }
+
+  #[test]
+  fn no_uninitialized_read_invalid() {
+    assert_lint_err! {
+      NoUninitializedRead,
+      "let a; a;": [
+        {
+          col: 7,
+          message: "`a` is read before it is assigned a value",
+          hint: "Assign a value to `a` before reading it",
+        }
+      ],
+      "var a; f(a);": [
+        {
+          col: 9,
+          message: "`a` is read before it is assigned a value",
+          hint: "Assign a value to `a` before reading it",
+        }
+      ],
+      "let a; a += 1;": [
+        {
+          col: 7,
+          message: "`a` is read before it is assigned a value",
+          hint: "Assign a value to `a` before reading it",
+        }
+      ],
+      "let a; if (b) { a = 1; } a;": [
+        {
+          col: 25,
+          message: "`a` is read before it is assigned a value",
+          hint: "Assign a value to `a` before reading it",
+        }
+      ],
+      "function f() { return a; var a = 1; }": [
+        {
+          col: 22,
+          message: "`a` is read before it is assigned a value",
+          hint: "Assign a value to `a` before reading it",
+        }
+      ],
+      "let a; try { a = f(); } catch (e) {} a;": [
+        {
+          col: 37,
+          message: "`a` is read before it is assigned a value",
+          hint: "Assign a value to `a` before reading it",
+        }
+      ],
+      r#"
+for (const b of c) {
+  let a;
+  if (b) {
+    a = 1;
+  }
+  a;
+}
+      "#: [
+        {
+          line: 7,
+          col: 2,
+          message: "`a` is read before it is assigned a value",
+          hint: "Assign a value to `a` before reading it",
+        }
+      ],
+    };
+  }
+}
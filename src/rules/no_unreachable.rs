@@ -161,6 +161,10 @@ function foo() {
       "function* foo() { try { yield 1; return; } catch (err) { return err; } }",
       "function foo() { try { bar(); return; } catch (err) { return err; } }",
       "function foo() { try { a.b.c = 1; return; } catch (err) { return err; } }",
+      "function foo() { try { throw e; } catch (err) { return err; } }",
+      "function foo() { try { a(); } finally { return; } }",
+      "function foo() { try { const { a } = b; return; } catch (err) { return err; } }",
+      "function foo() { try { if (a) {} b(); return; } catch (err) { return err; } }",
 
       r#"
 function normalize(type: string): string | undefined {
@@ -392,6 +396,9 @@ try {
         "function foo() { var x = 1; if (x) return; else throw -1; x = 2; }": [{ col: 58, message: MESSAGE }],
         "function foo() { var x = 1; try { return; } finally {} x = 2; }": [{ col: 55, message: MESSAGE }],
         "function foo() { var x = 1; try { } finally { return; } x = 2; }": [{ col: 56, message: MESSAGE }],
+        "function foo() { try { return; } catch (err) { x = 2; } }": [{ col: 47, message: MESSAGE }],
+        "function foo() { try { throw e; } catch (err) { return; } x = 2; }": [{ col: 58, message: MESSAGE }],
+        "function foo() { try { throw e; } finally { return; } x = 2; }": [{ col: 54, message: MESSAGE }],
         "function foo() { var x = 1; do { return; } while (x); x = 2; }": [{ col: 54, message: MESSAGE }],
         "function foo() { var x = 1; while (x) { if (x) break; else continue; x = 2; } }": [{ col: 69, message: MESSAGE }],
         "function foo() { var x = 1; for (;;) { if (x) continue; } x = 2; }": [{ col: 58, message: MESSAGE }],
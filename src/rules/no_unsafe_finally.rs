@@ -1,10 +1,15 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::{Context, LintRule};
+use swc_atoms::JsWord;
 use swc_common::Span;
-use swc_ecmascript::ast::Program;
-use swc_ecmascript::ast::Stmt::{Break, Continue, Return, Throw};
-use swc_ecmascript::ast::TryStmt;
-use swc_ecmascript::visit::{noop_visit_type, Node, VisitAll, VisitAllWith};
+use swc_ecmascript::ast::{
+  ArrowExpr, BreakStmt, Class, ContinueStmt, DoWhileStmt, ForInStmt, ForOfStmt,
+  ForStmt, Function, LabeledStmt, Program, ReturnStmt, SwitchStmt, ThrowStmt,
+  TryStmt, WhileStmt,
+};
+use swc_ecmascript::visit::{
+  noop_visit_type, Node, Visit, VisitAll, VisitAllWith, VisitWith,
+};
 
 pub struct NoUnsafeFinally;
 
@@ -92,19 +97,112 @@ impl<'c> VisitAll for NoUnsafeFinallyVisitor<'c> {
 
   fn visit_try_stmt(&mut self, try_stmt: &TryStmt, _parent: &dyn Node) {
     if let Some(finally_block) = &try_stmt.finalizer {
-      for stmt in &finally_block.stmts {
-        match stmt {
-          Break(_) => self.add_diagnostic(finally_block.span, "Break"),
-          Continue(_) => self.add_diagnostic(finally_block.span, "Continue"),
-          Return(_) => self.add_diagnostic(finally_block.span, "Return"),
-          Throw(_) => self.add_diagnostic(finally_block.span, "Throw"),
-          _ => {}
-        }
+      let mut collector = UnsafeStmtCollector::default();
+      finally_block.visit_children_with(&mut collector);
+      for stmt_type in collector.stmts {
+        self.add_diagnostic(finally_block.span, stmt_type);
       }
     }
   }
 }
 
+/// Collects statements which jump out of a `finally` block, including nested
+/// ones like `if (a) return;`.
+#[derive(Default)]
+struct UnsafeStmtCollector {
+  stmts: Vec<&'static str>,
+  /// Labels declared in the `finally` block
+  labels: Vec<JsWord>,
+  /// Number of enclosing loops in the `finally` block
+  loops: usize,
+  /// Number of enclosing switch statements in the `finally` block
+  switches: usize,
+}
+
+impl UnsafeStmtCollector {
+  fn visit_loop<N: VisitWith<Self>>(&mut self, n: &N) {
+    self.loops += 1;
+    n.visit_children_with(self);
+    self.loops -= 1;
+  }
+}
+
+impl Visit for UnsafeStmtCollector {
+  noop_visit_type!();
+
+  // Control flow statements in nested functions and classes don't affect
+  // the `finally` block.
+  fn visit_function(&mut self, _: &Function, _: &dyn Node) {}
+  fn visit_arrow_expr(&mut self, _: &ArrowExpr, _: &dyn Node) {}
+  fn visit_class(&mut self, _: &Class, _: &dyn Node) {}
+
+  fn visit_try_stmt(&mut self, n: &TryStmt, _: &dyn Node) {
+    // Nested `finally` is checked on its own.
+    n.block.visit_with(n, self);
+    n.handler.visit_with(n, self);
+  }
+
+  fn visit_return_stmt(&mut self, _: &ReturnStmt, _: &dyn Node) {
+    self.stmts.push("Return");
+  }
+
+  fn visit_throw_stmt(&mut self, _: &ThrowStmt, _: &dyn Node) {
+    self.stmts.push("Throw");
+  }
+
+  fn visit_break_stmt(&mut self, n: &BreakStmt, _: &dyn Node) {
+    let inside = match &n.label {
+      Some(label) => self.labels.contains(&label.sym),
+      None => self.loops + self.switches > 0,
+    };
+    if !inside {
+      self.stmts.push("Break");
+    }
+  }
+
+  fn visit_continue_stmt(&mut self, n: &ContinueStmt, _: &dyn Node) {
+    let inside = match &n.label {
+      Some(label) => self.labels.contains(&label.sym),
+      None => self.loops > 0,
+    };
+    if !inside {
+      self.stmts.push("Continue");
+    }
+  }
+
+  fn visit_labeled_stmt(&mut self, n: &LabeledStmt, _: &dyn Node) {
+    self.labels.push(n.label.sym.clone());
+    n.body.visit_with(n, self);
+    self.labels.pop();
+  }
+
+  fn visit_switch_stmt(&mut self, n: &SwitchStmt, _: &dyn Node) {
+    self.switches += 1;
+    n.visit_children_with(self);
+    self.switches -= 1;
+  }
+
+  fn visit_for_stmt(&mut self, n: &ForStmt, _: &dyn Node) {
+    self.visit_loop(n);
+  }
+
+  fn visit_for_in_stmt(&mut self, n: &ForInStmt, _: &dyn Node) {
+    self.visit_loop(n);
+  }
+
+  fn visit_for_of_stmt(&mut self, n: &ForOfStmt, _: &dyn Node) {
+    self.visit_loop(n);
+  }
+
+  fn visit_while_stmt(&mut self, n: &WhileStmt, _: &dyn Node) {
+    self.visit_loop(n);
+  }
+
+  fn visit_do_while_stmt(&mut self, n: &DoWhileStmt, _: &dyn Node) {
+    self.visit_loop(n);
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -139,6 +237,21 @@ let foo = function() {
 };
      "#,
       r#"
+let foo = function(a) {
+  try {
+    return 1;
+  } finally {
+    for (const b of a) {
+      if (b) continue;
+      break;
+    }
+    outer: {
+      break outer;
+    }
+  }
+};
+      "#,
+      r#"
 let foo = function(a) {
   try {
     return 1;
@@ -161,6 +274,41 @@ let foo = function(a) {
   fn no_unsafe_finally_invalid() {
     assert_lint_err_on_line::<NoUnsafeFinally>(
       r#"
+let foo = function(a) {
+  try {
+    return 1;
+  } finally {
+    if (a) {
+      return 3;
+    }
+  }
+};
+     "#,
+      5,
+      12,
+    );
+    assert_lint_err_on_line::<NoUnsafeFinally>(
+      r#"
+while (a) {
+  try {
+    foo();
+  } finally {
+    for (const b of c) {
+      if (b) break;
+      continue;
+    }
+    switch (a) {
+      case 1:
+        continue;
+    }
+  }
+}
+     "#,
+      5,
+      12,
+    );
+    assert_lint_err_on_line::<NoUnsafeFinally>(
+      r#"
 let foo = function() {
   try {
     return 1;
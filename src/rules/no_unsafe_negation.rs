@@ -1,13 +1,10 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::ast::BinExpr;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use swc_ecmascript::ast::BinaryOp;
 use swc_ecmascript::ast::Expr;
 use swc_ecmascript::ast::UnaryOp;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
 
 pub struct NoUnsafeNegation;
 
@@ -24,38 +21,32 @@ impl LintRule for NoUnsafeNegation {
     "no-unsafe-negation"
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoUnsafeNegationVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct NoUnsafeNegationVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoUnsafeNegationVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoUnsafeNegation {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::BinExpr]
   }
-}
-
-impl<'c> Visit for NoUnsafeNegationVisitor<'c> {
-  noop_visit_type!();
 
-  fn visit_bin_expr(&mut self, bin_expr: &BinExpr, _parent: &dyn Node) {
-    if bin_expr.op == BinaryOp::In || bin_expr.op == BinaryOp::InstanceOf {
-      if let Expr::Unary(unary_expr) = &*bin_expr.left {
-        if unary_expr.op == UnaryOp::Bang {
-          self.context.add_diagnostic(
-            bin_expr.span,
-            "no-unsafe-negation",
-            "Unexpected negation of left operand",
-          );
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::BinExpr(bin_expr) = node {
+      if bin_expr.op == BinaryOp::In || bin_expr.op == BinaryOp::InstanceOf {
+        if let Expr::Unary(unary_expr) = &*bin_expr.left {
+          if unary_expr.op == UnaryOp::Bang {
+            context.add_diagnostic(
+              bin_expr.span,
+              "no-unsafe-negation",
+              "Unexpected negation of left operand",
+            );
+          }
         }
       }
     }
@@ -12,9 +12,8 @@ use swc_ecmascript::{
     Decl, ExportDecl, ExportNamedSpecifier, Expr, FnDecl, FnExpr, Ident,
     ImportDefaultSpecifier, ImportNamedSpecifier, ImportStarAsSpecifier,
     KeyValueProp, MemberExpr, MethodKind, NamedExport, Param, Pat, Program,
-    Prop, SetterProp, TsEntityName, TsEnumDecl, TsExprWithTypeArgs,
-    TsModuleDecl, TsNamespaceDecl, TsPropertySignature, TsTypeRef, VarDecl,
-    VarDeclOrPat, VarDeclarator,
+    Prop, SetterProp, TsEnumDecl, TsModuleDecl, TsNamespaceDecl,
+    TsPropertySignature, VarDecl, VarDeclOrPat, VarDeclarator,
   },
   visit::VisitWith,
 };
@@ -36,15 +35,10 @@ impl LintRule for NoUnusedVars {
     let mut collector = Collector {
       used_vars: Default::default(),
       cur_defining: Default::default(),
-      used_types: Default::default(),
     };
     program.visit_with(program, &mut collector);
 
-    let mut visitor = NoUnusedVarVisitor::new(
-      context,
-      collector.used_vars,
-      collector.used_types,
-    );
+    let mut visitor = NoUnusedVarVisitor::new(context, collector.used_vars);
     program.visit_with(program, &mut visitor);
   }
 }
@@ -52,7 +46,6 @@ impl LintRule for NoUnusedVars {
 /// Collects information about variable usages.
 struct Collector {
   used_vars: HashSet<Id>,
-  used_types: HashSet<Id>,
   /// Currently defining functions or variables.
   ///
   ///
@@ -108,23 +101,6 @@ impl Visit for Collector {
     n.init.visit_with(n, self);
   }
 
-  fn visit_ts_type_ref(&mut self, ty: &TsTypeRef, _: &dyn Node) {
-    ty.type_params.visit_with(ty, self);
-
-    let id = get_id(&ty.type_name);
-    self.used_types.insert(id);
-  }
-
-  fn visit_ts_expr_with_type_args(
-    &mut self,
-    n: &TsExprWithTypeArgs,
-    _: &dyn Node,
-  ) {
-    let id = get_id(&n.expr);
-    self.used_vars.insert(id);
-    n.type_args.visit_with(n, self);
-  }
-
   /// Ignore key
   fn visit_key_value_prop(&mut self, n: &KeyValueProp, _: &dyn Node) {
     n.value.visit_with(n, self);
@@ -231,30 +207,23 @@ impl Visit for Collector {
   }
 }
 
-fn get_id(r: &TsEntityName) -> Id {
-  match r {
-    TsEntityName::TsQualifiedName(q) => get_id(&q.left),
-    TsEntityName::Ident(i) => i.to_id(),
-  }
-}
-
 struct NoUnusedVarVisitor<'c> {
   context: &'c mut Context,
   used_vars: HashSet<Id>,
-  used_types: HashSet<Id>,
 }
 
 impl<'c> NoUnusedVarVisitor<'c> {
-  fn new(
-    context: &'c mut Context,
-    used_vars: HashSet<Id>,
-    used_types: HashSet<Id>,
-  ) -> Self {
-    Self {
-      context,
-      used_vars,
-      used_types,
-    }
+  fn new(context: &'c mut Context, used_vars: HashSet<Id>) -> Self {
+    Self { context, used_vars }
+  }
+
+  /// Imports and enums are also used when they're referred from types.
+  fn is_used_as_type(&self, ident: &Ident) -> bool {
+    self
+      .context
+      .scope()
+      .references_to(&ident.to_id())
+      .any(|r| r.is_type())
   }
 }
 
@@ -361,7 +330,7 @@ impl<'c> Visit for NoUnusedVarVisitor<'c> {
     import: &ImportNamedSpecifier,
     _: &dyn Node,
   ) {
-    if self.used_types.contains(&import.local.to_id()) {
+    if self.is_used_as_type(&import.local) {
       return;
     }
     self.handle_id(&import.local);
@@ -372,7 +341,7 @@ impl<'c> Visit for NoUnusedVarVisitor<'c> {
     import: &ImportDefaultSpecifier,
     _: &dyn Node,
   ) {
-    if self.used_types.contains(&import.local.to_id()) {
+    if self.is_used_as_type(&import.local) {
       return;
     }
     self.handle_id(&import.local);
@@ -383,7 +352,7 @@ impl<'c> Visit for NoUnusedVarVisitor<'c> {
     import: &ImportStarAsSpecifier,
     _: &dyn Node,
   ) {
-    if self.used_types.contains(&import.local.to_id()) {
+    if self.is_used_as_type(&import.local) {
       return;
     }
     self.handle_id(&import.local);
@@ -427,7 +396,7 @@ impl<'c> Visit for NoUnusedVarVisitor<'c> {
       return;
     }
 
-    if self.used_types.contains(&n.id.to_id()) {
+    if self.is_used_as_type(&n.id) {
       return;
     }
     self.handle_id(&n.id);
@@ -1074,6 +1043,10 @@ export default class Foo {
   }
 }
       ",
+      "
+import type { Foo } from './foo.ts';
+export let foo: Foo;
+      ",
     };
   }
 
@@ -1443,6 +1416,17 @@ export interface Bar extends baz.test {}
       2,
       7,
     );
+
+    // Types don't use the value with the same name
+    assert_lint_err_on_line::<NoUnusedVars>(
+      "
+const Base = 1;
+interface Base {}
+export class Thing implements Base {}
+        ",
+      2,
+      6,
+    );
   }
 
   // TODO(magurotuna): deals with this using ControlFlow
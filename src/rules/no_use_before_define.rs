@@ -0,0 +1,420 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use super::{parse_rule_options, Context, LintRule, RuleOptionsError};
+use crate::control_flow::FunctionId;
+use crate::dataflow::{Access, AccessKind, Analysis, DataFlow};
+use crate::scopes::{BindingKind, Scope};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use swc_atoms::JsWord;
+use swc_common::Span;
+use swc_ecmascript::ast::{
+  ArrowExpr, Class, Expr, Function, Ident, Pat, Program,
+};
+use swc_ecmascript::utils::{find_ids, Id};
+use swc_ecmascript::visit::{noop_visit_type, Node, Visit, VisitWith};
+
+pub struct NoUseBeforeDefine {
+  options: NoUseBeforeDefineOptions,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct NoUseBeforeDefineOptions {
+  /// Checks function declarations, even though they are hoisted.
+  pub functions: bool,
+  /// Checks classes used in nested functions before their declarations.
+  pub classes: bool,
+  /// Checks variables used in nested functions before their declarations.
+  pub variables: bool,
+  /// Checks types, e.g. interfaces and type aliases, used before their
+  /// declarations.
+  pub types: bool,
+}
+
+impl Default for NoUseBeforeDefineOptions {
+  fn default() -> Self {
+    Self {
+      functions: true,
+      classes: true,
+      variables: true,
+      types: false,
+    }
+  }
+}
+
+const CODE: &str = "no-use-before-define";
+
+impl LintRule for NoUseBeforeDefine {
+  fn new() -> Box<Self> {
+    Box::new(NoUseBeforeDefine {
+      options: NoUseBeforeDefineOptions::default(),
+    })
+  }
+
+  fn code(&self) -> &'static str {
+    CODE
+  }
+
+  fn lint_program(&self, context: &mut Context, program: &Program) {
+    let scope = context.scope();
+    let data_flow = DataFlow::new(program, context.cfg(program), scope);
+    let mut spans = vec![];
+
+    // `let`, `const` and classes used in their temporal dead zone, which
+    // ends when the declaration is evaluated.
+    let analysis = Initialized { scope };
+    let solution = data_flow.solve(&analysis);
+    data_flow.walk(&analysis, &solution, |initialized, access| {
+      if matches!(access.kind, AccessKind::Read | AccessKind::Write)
+        && analysis.tracks(&access.id)
+        && !initialized.contains(&access.id)
+      {
+        let declared_in = scope
+          .var(&access.id)
+          .and_then(|var| data_flow.function_at(var.span().lo));
+        if declared_in == data_flow.function_at(access.span.lo) {
+          spans.push((access.span, access.id.0.clone()));
+        }
+      }
+    });
+
+    for reference in scope.references() {
+      let var = match scope.resolve(reference) {
+        Some(var) => var,
+        None => continue,
+      };
+      if var.span().lo <= reference.span().lo {
+        continue;
+      }
+
+      let check = if reference.is_type() {
+        self.options.types
+      } else {
+        let nested = data_flow.function_at(var.span().lo)
+          != data_flow.function_at(reference.span().lo);
+        match var.kind() {
+          BindingKind::Function => self.options.functions,
+          BindingKind::Var | BindingKind::Enum | BindingKind::Namespace => {
+            !nested || self.options.variables
+          }
+          // Uses in the same function are checked by the data-flow analysis.
+          BindingKind::Let | BindingKind::Const => {
+            nested && self.options.variables
+          }
+          BindingKind::Class => nested && self.options.classes,
+          _ => false,
+        }
+      };
+      if check {
+        spans.push((reference.span(), reference.id().0.clone()));
+      }
+    }
+
+    let mut param_defaults = ParamDefaults::default();
+    program.visit_with(program, &mut param_defaults);
+    spans.extend(param_defaults.spans);
+
+    spans.sort_by_key(|(span, _)| span.lo);
+    spans.dedup_by_key(|(span, _)| span.lo);
+    for (span, sym) in spans {
+      context.add_diagnostic_with_hint(
+        span,
+        CODE,
+        format!("`{}` is used before it is defined", sym),
+        format!("Move the declaration of `{}` before its first use", sym),
+      );
+    }
+  }
+
+  fn set_options(&mut self, options: Value) -> Result<(), RuleOptionsError> {
+    self.options = parse_rule_options(self.code(), options)?;
+    Ok(())
+  }
+
+  fn docs(&self) -> &'static str {
+    r#"Disallows the use of variables, functions and classes before they are defined.
+
+`let`, `const` and `class` bindings, as well as parameters in the defaults of
+the parameters before them, can't be used before their declarations are
+evaluated, which throws a `ReferenceError`. Other bindings are hoisted,
+but using them before their declarations makes code harder to follow.
+
+Uses of a binding in the function which declares it are checked following
+the control flow, so a use after the declaration in the source can still be
+reported, e.g. in another case of a `switch` statement.
+
+### Invalid:
+```typescript
+alert(a);
+const a = 1;
+
+f();
+function f() {}
+
+function g(a = b, b = 1) {}
+
+switch (b) {
+  case 0:
+    let c = 1;
+    break;
+  case 1:
+    c = 2;
+}
+```
+
+### Valid:
+```typescript
+const a = 1;
+alert(a);
+
+function f() {}
+f();
+
+function g() {
+  return b;
+}
+const b = 1;
+```
+(with `{ "variables": false }`)
+
+### Options:
+```json
+{ "functions": true, "classes": true, "variables": true, "types": false }
+```
+- `functions`: checks function declarations, even though they are hoisted.
+- `classes`: checks classes used in nested functions before their declarations.
+- `variables`: checks variables used in nested functions before their
+  declarations.
+- `types`: checks types, e.g. interfaces and type aliases, used before their
+  declarations.
+"#
+  }
+}
+
+/// Set of `let`, `const` and class bindings whose declarations have been
+/// evaluated on every path.
+struct Initialized<'a> {
+  scope: &'a Scope,
+}
+
+impl Initialized<'_> {
+  fn tracks(&self, id: &Id) -> bool {
+    self.scope.var(id).map_or(false, |var| {
+      matches!(
+        var.kind(),
+        BindingKind::Let | BindingKind::Const | BindingKind::Class
+      )
+    })
+  }
+}
+
+impl Analysis for Initialized<'_> {
+  type State = HashSet<Id>;
+
+  fn initial(&self, _: FunctionId) -> Self::State {
+    HashSet::new()
+  }
+
+  fn join(&self, state: &mut Self::State, other: &Self::State) {
+    state.retain(|id| other.contains(id));
+  }
+
+  fn transfer(&self, state: &mut Self::State, access: &Access) {
+    if matches!(access.kind, AccessKind::Declare | AccessKind::Init) {
+      state.insert(access.id.clone());
+    }
+  }
+}
+
+/// Uses of parameters in the defaults of the parameters before them, like
+/// `b` in `function f(a = b, b = 1) {}`. These are not resolved to the
+/// parameters by the scope analysis, because they are not declared yet when
+/// the default is evaluated.
+#[derive(Default)]
+struct ParamDefaults {
+  spans: Vec<(Span, JsWord)>,
+}
+
+impl ParamDefaults {
+  fn check_params(&mut self, params: &[&Pat]) {
+    for (i, param) in params.iter().enumerate() {
+      let mut later: Vec<Ident> = params[i + 1..]
+        .iter()
+        .flat_map(|p| find_ids::<_, Ident>(*p))
+        .collect();
+      if let Pat::Assign(assign) = param {
+        later.extend(find_ids::<_, Ident>(&assign.left));
+      }
+      let mut uses = DefaultUses::default();
+      param.visit_with(*param, &mut uses);
+      for ident in uses.0 {
+        if later.iter().any(|l| l.sym == ident.sym) {
+          self.spans.push((ident.span, ident.sym));
+        }
+      }
+    }
+  }
+}
+
+impl Visit for ParamDefaults {
+  noop_visit_type!();
+
+  fn visit_function(&mut self, n: &Function, _: &dyn Node) {
+    let params: Vec<_> = n.params.iter().map(|p| &p.pat).collect();
+    self.check_params(&params);
+    n.visit_children_with(self);
+  }
+
+  fn visit_arrow_expr(&mut self, n: &ArrowExpr, _: &dyn Node) {
+    let params: Vec<_> = n.params.iter().collect();
+    self.check_params(&params);
+    n.visit_children_with(self);
+  }
+}
+
+/// Identifiers used in the defaults of a parameter, outside of nested
+/// functions and classes which may be called after all parameters are
+/// initialized.
+#[derive(Default)]
+struct DefaultUses(Vec<Ident>);
+
+impl Visit for DefaultUses {
+  noop_visit_type!();
+
+  fn visit_expr(&mut self, n: &Expr, _: &dyn Node) {
+    match n {
+      Expr::Ident(ident) => self.0.push(ident.clone()),
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  fn visit_function(&mut self, _: &Function, _: &dyn Node) {}
+  fn visit_arrow_expr(&mut self, _: &ArrowExpr, _: &dyn Node) {}
+  fn visit_class(&mut self, _: &Class, _: &dyn Node) {}
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_util::lint_with_options;
+  use serde_json::json;
+
+  #[test]
+  fn no_use_before_define_valid() {
+    assert_lint_ok! {
+      NoUseBeforeDefine,
+      "const a = 1; a;",
+      "let a; a = 1;",
+      "function f() {} f();",
+      "class A {} new A();",
+      "var a = 1; a;",
+      "class A { static create() { return new A(); } }",
+      "class A { static instance = new A(); }",
+      "for (const a of b) { a; }",
+      "try {} catch (e) { e; }",
+      "function f(a = 1, b = a) {}",
+      "function f(a = () => b, b = 1) {}",
+      "function f({ a, b = a }) {}",
+      "const b = 1; function f(a = b) {}",
+      "import { a } from './a.ts'; a;",
+      "let a: Foo; interface Foo {}",
+      "function f(): Foo { return {}; } type Foo = {};",
+      r#"
+let a;
+if (b) {
+  a = 1;
+} else {
+  a = 2;
+}
+a;
+      "#,
+    };
+  }
+
+  #[test]
+  fn no_use_before_define_invalid() {
+    assert_lint_err! {
+      NoUseBeforeDefine,
+      "function g(a = b, b = 1) {}": [
+        {
+          col: 15,
+          message: "`b` is used before it is defined",
+          hint: "Move the declaration of `b` before its first use",
+        }
+      ],
+      "function f(a = a) {}": [
+        {
+          col: 15,
+          message: "`a` is used before it is defined",
+          hint: "Move the declaration of `a` before its first use",
+        }
+      ],
+      "a; const a = 1;": [
+        {
+          col: 0,
+          message: "`a` is used before it is defined",
+          hint: "Move the declaration of `a` before its first use",
+        }
+      ],
+      "a = 1; let a;": [{ col: 0, message: "`a` is used before it is defined", hint: "Move the declaration of `a` before its first use" }],
+      "a; var a = 1;": [{ col: 0, message: "`a` is used before it is defined", hint: "Move the declaration of `a` before its first use" }],
+      "f(); function f() {}": [{ col: 0, message: "`f` is used before it is defined", hint: "Move the declaration of `f` before its first use" }],
+      "new A(); class A {}": [{ col: 4, message: "`A` is used before it is defined", hint: "Move the declaration of `A` before its first use" }],
+      "const a = a + 1;": [{ col: 10, message: "`a` is used before it is defined", hint: "Move the declaration of `a` before its first use" }],
+      "class A extends B {} class B {}": [{ col: 16, message: "`B` is used before it is defined", hint: "Move the declaration of `B` before its first use" }],
+      "function f() { return a; } const a = 1;": [{ col: 22, message: "`a` is used before it is defined", hint: "Move the declaration of `a` before its first use" }],
+      "function f() { return new A(); } class A {}": [{ col: 26, message: "`A` is used before it is defined", hint: "Move the declaration of `A` before its first use" }],
+      "E.A; enum E { A }": [{ col: 0, message: "`E` is used before it is defined", hint: "Move the declaration of `E` before its first use" }],
+      r#"
+switch (a) {
+  case 0:
+    let b = 1;
+    break;
+  case 1:
+    b = 2;
+}
+      "#: [{ line: 7, col: 4, message: "`b` is used before it is defined", hint: "Move the declaration of `b` before its first use" }],
+      r#"
+for (;;) {
+  if (a) {
+    b;
+  }
+  const b = 1;
+}
+      "#: [{ line: 4, col: 4, message: "`b` is used before it is defined", hint: "Move the declaration of `b` before its first use" }],
+    };
+  }
+
+  #[test]
+  fn no_use_before_define_options() {
+    let options = json!({
+      "functions": false,
+      "classes": false,
+      "variables": false,
+      "types": true,
+    });
+    for source in &[
+      "f(); function f() {}",
+      "function f() { return a; } const a = 1;",
+      "function f() { return new A(); } class A {}",
+    ] {
+      let diagnostics =
+        lint_with_options::<NoUseBeforeDefine>(options.clone(), source);
+      assert!(diagnostics.is_empty(), "{}", source);
+    }
+
+    for (source, col) in &[
+      ("a; const a = 1;", 0),
+      ("a; var a = 1;", 0),
+      ("new A(); class A {}", 4),
+      ("let a: Foo; interface Foo {}", 7),
+      ("function f(): Foo { return {}; } type Foo = {};", 14),
+    ] {
+      let diagnostics =
+        lint_with_options::<NoUseBeforeDefine>(options.clone(), source);
+      assert_eq!(diagnostics.len(), 1, "{}", source);
+      assert_eq!(diagnostics[0].range.start.col, *col, "{}", source);
+    }
+  }
+}
@@ -0,0 +1,318 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use super::Context;
+use super::LintRule;
+use crate::js_regex::ast::{
+  Alternative, BackreferenceKind, LookaroundKind, Span, Term,
+};
+use crate::swc_util::collect_regexes;
+use std::collections::HashMap;
+use swc_ecmascript::ast::Program;
+
+pub struct NoUselessBackreference;
+
+const CODE: &str = "no-useless-backreference";
+const HINT: &str =
+  "Remove the backreference, or move it where the group has been matched";
+
+impl LintRule for NoUselessBackreference {
+  fn new() -> Box<Self> {
+    Box::new(NoUselessBackreference)
+  }
+
+  fn code(&self) -> &'static str {
+    CODE
+  }
+
+  fn lint_program(&self, context: &mut Context, program: &Program) {
+    for regex in collect_regexes(program, &context.scope) {
+      let pattern = match regex.parse(context.ecma_version()) {
+        Some((pattern, _)) => pattern,
+        None => continue,
+      };
+      let mut collector = GroupCollector::default();
+      collector.visit_alternatives(0, &pattern.alternatives);
+
+      for reference in &collector.references {
+        let problems: Vec<_> = collector
+          .groups
+          .iter()
+          .filter(|group| match &reference.kind {
+            BackreferenceKind::Number(number) => group.number == *number,
+            BackreferenceKind::Name(name) => group.name.as_ref() == Some(name),
+          })
+          .map(|group| (group, collector.problem(reference, group)))
+          .collect();
+        // With duplicate names, a reference is fine if any of the groups it
+        // refers to can have been matched.
+        let (group, problem) = match problems.as_slice() {
+          [] => continue,
+          [(group, Some(problem)), ..]
+            if problems.iter().all(|(_, problem)| problem.is_some()) =>
+          {
+            (group, problem)
+          }
+          _ => continue,
+        };
+
+        let source = |span: Span| &regex.pattern[span.start..span.end];
+        context.add_diagnostic_with_hint(
+          regex.span_of(reference.span),
+          CODE,
+          format!(
+            "Backreference `{}` will be ignored, as it references group `{}` {}",
+            source(reference.span),
+            source(group.span),
+            problem
+          ),
+          HINT,
+        );
+      }
+    }
+  }
+
+  fn docs(&self) -> &'static str {
+    r#"Disallows backreferences which always match the empty string
+
+A backreference matches what its group matched, or the empty string if the
+group hasn't matched anything yet. This is always the case for a backreference
+from within the group, before the group, in another alternative than the group,
+or outside of a negative lookaround containing the group. Lookbehinds are
+matched backwards, so a backreference before a group in the same lookbehind is
+fine, but one after it is not.
+
+### Invalid:
+```typescript
+/(a\1)/;
+/\1(a)/;
+/(a)|\1/;
+/(?!(a))\1/;
+/(?<=(a)\1)/;
+/(?<foo>a)|\k<foo>/;
+```
+
+### Valid:
+```typescript
+/(a)\1/;
+/(a)(?:b|\1)/;
+/(?=(a))\1/;
+/(?<=\1(a))/;
+/(?<foo>a)\k<foo>/;
+```
+"#
+  }
+}
+
+/// Position of a node in the pattern, as the list of the groups, lookarounds
+/// and the pattern itself containing it, each with the index of the
+/// alternative containing the node. The pattern has the id 0.
+type Path = Vec<(usize, usize)>;
+
+struct CapturingGroup {
+  id: usize,
+  number: u32,
+  name: Option<String>,
+  span: Span,
+  path: Path,
+}
+
+struct Reference {
+  kind: BackreferenceKind,
+  span: Span,
+  path: Path,
+}
+
+#[derive(Default)]
+struct GroupCollector {
+  path: Path,
+  next_id: usize,
+  /// Kind and negation of lookarounds, by id.
+  lookarounds: HashMap<usize, (LookaroundKind, bool)>,
+  groups: Vec<CapturingGroup>,
+  references: Vec<Reference>,
+}
+
+impl GroupCollector {
+  fn visit_alternatives(&mut self, id: usize, alternatives: &[Alternative]) {
+    for (i, alternative) in alternatives.iter().enumerate() {
+      self.path.push((id, i));
+      for term in &alternative.terms {
+        self.visit_term(term);
+      }
+      self.path.pop();
+    }
+  }
+
+  fn visit_term(&mut self, term: &Term) {
+    match term {
+      Term::CapturingGroup(group) => {
+        let id = self.new_id();
+        self.groups.push(CapturingGroup {
+          id,
+          number: self.groups.len() as u32 + 1,
+          name: group.name.clone(),
+          span: group.span,
+          path: self.path.clone(),
+        });
+        self.visit_alternatives(id, &group.alternatives);
+      }
+      Term::Group(group) => {
+        let id = self.new_id();
+        self.visit_alternatives(id, &group.alternatives);
+      }
+      Term::Lookaround(lookaround) => {
+        let id = self.new_id();
+        self
+          .lookarounds
+          .insert(id, (lookaround.kind, lookaround.negate));
+        self.visit_alternatives(id, &lookaround.alternatives);
+      }
+      Term::Quantifier(quantifier) => self.visit_term(&quantifier.term),
+      Term::Backreference(reference) => self.references.push(Reference {
+        kind: reference.reference.clone(),
+        span: reference.span,
+        path: self.path.clone(),
+      }),
+      _ => {}
+    }
+  }
+
+  fn new_id(&mut self) -> usize {
+    self.next_id += 1;
+    self.next_id
+  }
+
+  /// Explains why the reference to the group always matches the empty
+  /// string, if it does.
+  fn problem(
+    &self,
+    reference: &Reference,
+    group: &CapturingGroup,
+  ) -> Option<&'static str> {
+    if reference.path.iter().any(|(id, _)| *id == group.id) {
+      return Some("from within that group");
+    }
+
+    let common = reference
+      .path
+      .iter()
+      .zip(&group.path)
+      .take_while(|(a, b)| a == b)
+      .count();
+    if let (Some(a), Some(b)) =
+      (reference.path.get(common), group.path.get(common))
+    {
+      if a.0 == b.0 {
+        return Some("which is in another alternative");
+      }
+    }
+    let in_negative_lookaround = group.path[common..]
+      .iter()
+      .any(|(id, _)| matches!(self.lookarounds.get(id), Some((_, true))));
+    if in_negative_lookaround {
+      return Some("which is in a negative lookaround");
+    }
+
+    // Lookbehinds are matched from right to left.
+    let backwards = matches!(
+      reference.path[..common]
+        .iter()
+        .rev()
+        .find_map(|(id, _)| self.lookarounds.get(id)),
+      Some((LookaroundKind::Lookbehind, _))
+    );
+    if backwards {
+      if group.span.end <= reference.span.start {
+        return Some("which appears before in the same lookbehind");
+      }
+    } else if group.span.start > reference.span.start {
+      return Some("which appears later in the pattern");
+    }
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn no_useless_backreference_valid() {
+    assert_lint_ok! {
+      NoUselessBackreference,
+      r#"/(a)\1/;"#,
+      r#"/(a)(?:b|\1)/;"#,
+      r#"/(a)b\1+/;"#,
+      r#"/((a)|b)\2/;"#,
+      r#"/(?=(a))\1/;"#,
+      r#"/(?:(a)|b)(?:c|\1)/;"#,
+      r#"/(?!(a)\1)/;"#,
+      r#"/(?<=\1(a))/;"#,
+      r#"/(?<foo>a)\k<foo>/;"#,
+      r#"/\1/;"#,
+      r#"new RegExp("(a)\\1");"#,
+    };
+  }
+
+  #[test]
+  fn no_useless_backreference_invalid() {
+    assert_lint_err! {
+      NoUselessBackreference,
+      r#"/(a\1)/;"#: [
+        {
+          col: 3,
+          message: "Backreference `\\1` will be ignored, as it references group `(a\\1)` from within that group",
+          hint: HINT,
+        }
+      ],
+      r#"/\1(a)/;"#: [
+        {
+          col: 1,
+          message: "Backreference `\\1` will be ignored, as it references group `(a)` which appears later in the pattern",
+          hint: HINT,
+        }
+      ],
+      r#"/(a)|b\1/;"#: [
+        {
+          col: 6,
+          message: "Backreference `\\1` will be ignored, as it references group `(a)` which is in another alternative",
+          hint: HINT,
+        }
+      ],
+      r#"/(?!(a))\1/;"#: [
+        {
+          col: 8,
+          message: "Backreference `\\1` will be ignored, as it references group `(a)` which is in a negative lookaround",
+          hint: HINT,
+        }
+      ],
+      r#"/(?<=(a)\1)/;"#: [
+        {
+          col: 8,
+          message: "Backreference `\\1` will be ignored, as it references group `(a)` which appears before in the same lookbehind",
+          hint: HINT,
+        }
+      ],
+      r#"/(?<foo>a)|\k<foo>/;"#: [
+        {
+          col: 11,
+          message: "Backreference `\\k<foo>` will be ignored, as it references group `(?<foo>a)` which is in another alternative",
+          hint: HINT,
+        }
+      ],
+      r#"new RegExp("\\1(a)");"#: [
+        {
+          col: 0,
+          message: "Backreference `\\1` will be ignored, as it references group `(a)` which appears later in the pattern",
+          hint: HINT,
+        }
+      ],
+      r#"RegExp("(a\\1)")"#: [
+        {
+          col: 0,
+          message: "Backreference `\\1` will be ignored, as it references group `(a\\1)` from within that group",
+          hint: HINT,
+        }
+      ],
+    };
+  }
+}
@@ -0,0 +1,427 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use super::Context;
+use super::LintRule;
+use crate::diagnostic::LintFix;
+use crate::js_regex::ast::{
+  walk_character_class, walk_pattern, Character, CharacterClass, Flags,
+  Span as RegexSpan, Visit as RegexVisit,
+};
+use crate::swc_util::{collect_regexes, RegexSource};
+use swc_common::{BytePos, Span};
+use swc_ecmascript::ast::{JSXAttrValue, Program, Str, TaggedTpl, TplElement};
+use swc_ecmascript::visit::{noop_visit_type, Node, Visit, VisitWith};
+
+pub struct NoUselessEscape;
+
+const CODE: &str = "no-useless-escape";
+const HINT: &str =
+  "Remove the backslash, or escape it if it's meant to be kept";
+
+/// Characters which have a meaning when escaped in a string.
+const STRING_ESCAPES: &str = "\\nrvtbfux\r\n\u{2028}\u{2029}";
+/// Characters which have a meaning when escaped in a regex, inside and outside
+/// character classes.
+const REGEX_ESCAPES: &str = "\\bcdDfnpPrsStvwWxu0123456789]/";
+/// Characters which have a meaning when escaped outside character classes.
+const REGEX_NON_CLASS_ESCAPES: &str = "^.$*+?[{}|()Bk";
+
+impl LintRule for NoUselessEscape {
+  fn new() -> Box<Self> {
+    Box::new(NoUselessEscape)
+  }
+
+  fn code(&self) -> &'static str {
+    CODE
+  }
+
+  fn lint_program(&self, context: &mut Context, program: &Program) {
+    let mut escapes = vec![];
+    for regex in collect_regexes(program, &context.scope) {
+      let (pattern, flags) = match regex.parse(context.ecma_version()) {
+        Some(parsed) => parsed,
+        None => continue,
+      };
+      let mut finder = RegexEscapeFinder {
+        regex: &regex,
+        flags,
+        class: None,
+        escapes: &mut escapes,
+      };
+      walk_pattern(&mut finder, &pattern);
+    }
+
+    let mut visitor = StringEscapeFinder {
+      context,
+      escapes: &mut escapes,
+    };
+    program.visit_with(program, &mut visitor);
+
+    escapes.sort_by_key(|escape| escape.span.lo);
+    for escape in escapes {
+      let fixes = match escape.backslash {
+        Some(backslash) => vec![LintFix {
+          description: HINT.to_string(),
+          changes: vec![context.create_fix_change(backslash, "")],
+        }],
+        None => vec![],
+      };
+      context.add_diagnostic_with_fixes(
+        escape.span,
+        CODE,
+        format!("Unnecessary escape character: \\{}", escape.character),
+        Some(HINT.to_string()),
+        fixes,
+      );
+    }
+  }
+
+  fn docs(&self) -> &'static str {
+    r#"Disallows escaping characters which don't need to be escaped
+
+Escaping a character which has no special meaning in a string, template or
+regular expression doesn't change its value, but makes the code harder to read
+and may hide a mistake, e.g. a backslash which was meant to be part of the
+string.
+
+### Invalid:
+```typescript
+"\a";
+'\"';
+`\"${foo}\"`;
+/\!/;
+/[\.]/;
+new RegExp("[a\\-]");
+```
+
+### Valid:
+```typescript
+"\n";
+"\"";
+`\${foo}`;
+/\./;
+/[\]\\]/;
+/[a\-z]/;
+String.raw`\a`;
+```
+"#
+  }
+}
+
+/// An unnecessary escape, e.g. `\a`.
+struct UselessEscape {
+  span: Span,
+  character: char,
+  /// Span of the backslash, if known.
+  backslash: Option<Span>,
+}
+
+struct RegexEscapeFinder<'a> {
+  regex: &'a RegexSource,
+  flags: Flags,
+  /// The innermost character class containing the visited node.
+  class: Option<(RegexSpan, bool)>,
+  escapes: &'a mut Vec<UselessEscape>,
+}
+
+impl RegexEscapeFinder<'_> {
+  /// Returns the escaped character if `c` is an identity escape, e.g. `\a`.
+  fn identity_escape(&self, c: &Character) -> Option<char> {
+    let raw = &self.regex.pattern[c.span.start..c.span.end];
+    let mut chars = raw.strip_prefix('\\')?.chars();
+    match (chars.next(), chars.next()) {
+      (Some(escaped), None) if escaped as u32 == c.value => Some(escaped),
+      _ => None,
+    }
+  }
+
+  fn is_useful(&self, c: &Character, escaped: char) -> bool {
+    if REGEX_ESCAPES.contains(escaped) {
+      return true;
+    }
+    let (class, negate) = match self.class {
+      Some(class) => class,
+      None => return REGEX_NON_CLASS_ESCAPES.contains(escaped),
+    };
+    // Every punctuator in a class with the `v` flag is either syntax or
+    // reserved for future syntax.
+    if self.flags.unicode_sets {
+      return escaped.is_ascii_punctuation();
+    }
+    let first = class.start + if negate { 2 } else { 1 };
+    match escaped {
+      '^' => c.span.start == class.start + 1,
+      '-' => c.span.start != first && c.span.end != class.end - 1,
+      _ => false,
+    }
+  }
+}
+
+impl RegexVisit for RegexEscapeFinder<'_> {
+  fn visit_character_class(&mut self, n: &CharacterClass) {
+    let outer = self.class.replace((n.span, n.negate));
+    walk_character_class(self, n);
+    self.class = outer;
+  }
+
+  fn visit_character(&mut self, n: &Character) {
+    if let Some(escaped) = self.identity_escape(n) {
+      if !self.is_useful(n, escaped) {
+        let backslash = RegexSpan::new(n.span.start, n.span.start + 1);
+        self.escapes.push(UselessEscape {
+          span: self.regex.span_of(n.span),
+          character: escaped,
+          backslash: self
+            .regex
+            .pattern_start
+            .map(|_| self.regex.span_of(backslash)),
+        });
+      }
+    }
+  }
+}
+
+struct StringEscapeFinder<'c, 'a> {
+  context: &'c Context,
+  escapes: &'a mut Vec<UselessEscape>,
+}
+
+impl StringEscapeFinder<'_, '_> {
+  /// Checks the raw text of a string or template, starting at `start` in
+  /// the source.
+  fn check_raw(&mut self, raw: &str, start: BytePos, quote: char) {
+    let mut chars = raw.char_indices();
+    while let Some((i, c)) = chars.next() {
+      if c != '\\' {
+        continue;
+      }
+      let escaped = match chars.next() {
+        Some((_, escaped)) => escaped,
+        None => break,
+      };
+      let useful = STRING_ESCAPES.contains(escaped)
+        || escaped == quote
+        || escaped.is_ascii_digit()
+        // `\$` and `\{` only matter when they would start a substitution.
+        || (quote == '`'
+          && (escaped == '$' && raw[i + 2..].starts_with('{')
+            || escaped == '{' && raw[..i].ends_with('$')));
+      if !useful {
+        let lo = start + BytePos(i as u32);
+        let backslash = Span::new(lo, lo + BytePos(1), Default::default());
+        self.escapes.push(UselessEscape {
+          span: backslash
+            .with_hi(lo + BytePos((1 + escaped.len_utf8()) as u32)),
+          character: escaped,
+          backslash: Some(backslash),
+        });
+      }
+    }
+  }
+}
+
+impl Visit for StringEscapeFinder<'_, '_> {
+  noop_visit_type!();
+
+  fn visit_str(&mut self, str_lit: &Str, _: &dyn Node) {
+    if !str_lit.has_escape {
+      return;
+    }
+    if let Ok(snippet) = self.context.source_map.span_to_snippet(str_lit.span) {
+      if let Some(quote) = snippet.chars().next() {
+        let raw = &snippet[1..snippet.len() - 1];
+        self.check_raw(raw, str_lit.span.lo + BytePos(1), quote);
+      }
+    }
+  }
+
+  fn visit_tpl_element(&mut self, element: &TplElement, _: &dyn Node) {
+    self.check_raw(&element.raw.value, element.span.lo, '`');
+  }
+
+  // Tags get the raw text of templates, e.g. `String.raw`.
+  fn visit_tagged_tpl(&mut self, tagged_tpl: &TaggedTpl, _: &dyn Node) {
+    tagged_tpl.tag.visit_with(tagged_tpl, self);
+    for expr in &tagged_tpl.exprs {
+      expr.visit_with(tagged_tpl, self);
+    }
+  }
+
+  // Backslashes aren't escapes in JSX attributes.
+  fn visit_jsx_attr_value(&mut self, value: &JSXAttrValue, _: &dyn Node) {
+    if !matches!(value, JSXAttrValue::Lit(_)) {
+      value.visit_children_with(self);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::ast_parser::get_syntax_for_file;
+  use crate::linter::LinterBuilder;
+
+  #[test]
+  fn no_useless_escape_valid() {
+    assert_lint_ok! {
+      NoUselessEscape,
+      r#""\n\r\t\v\b\f\\";"#,
+      r#""\x41\u0041\u{41}\0\12";"#,
+      r#""\"";"#,
+      r#"'\'';"#,
+      "\"a\\\nb\";",
+      r#"`\``;"#,
+      r#"`\${a}`;"#,
+      r#"`$\{a}`;"#,
+      r#"`${a}\n`;"#,
+      r#"String.raw`\a`;"#,
+      r#"tag`\.${a}\.`;"#,
+      r#"/\./;"#,
+      r#"/\\/;"#,
+      r#"/\//;"#,
+      r#"/[\/]/;"#,
+      r#"/\(\)\[\]\{\}\|\^\$\*\+\?/;"#,
+      r#"/[\]\\]/;"#,
+      r#"/[\^]/;"#,
+      r#"/[a\-z]/;"#,
+      r#"/\d\w\s\b\B\cA\x41\u0041\1(a)/;"#,
+      r#"/(?<a>.)\k<a>/;"#,
+      r#"/\p{L}/u;"#,
+      r#"/[\&\!]/v;"#,
+      r#"new RegExp("\\.");"#,
+      r#"new RegExp("[\\^a]");"#,
+    };
+  }
+
+  #[test]
+  fn no_useless_escape_invalid() {
+    assert_lint_err! {
+      NoUselessEscape,
+      r#""\a";"#: [
+        {
+          col: 1,
+          message: "Unnecessary escape character: \\a",
+          hint: HINT,
+        }
+      ],
+      r#"'\"';"#: [
+        {
+          col: 1,
+          message: "Unnecessary escape character: \\\"",
+          hint: HINT,
+        }
+      ],
+      r#""a\.b\-";"#: [
+        {
+          col: 2,
+          message: "Unnecessary escape character: \\.",
+          hint: HINT,
+        },
+        {
+          col: 5,
+          message: "Unnecessary escape character: \\-",
+          hint: HINT,
+        }
+      ],
+      r#"`\"${a}\$`;"#: [
+        {
+          col: 1,
+          message: "Unnecessary escape character: \\\"",
+          hint: HINT,
+        },
+        {
+          col: 7,
+          message: "Unnecessary escape character: \\$",
+          hint: HINT,
+        }
+      ],
+      r#"/\!/;"#: [
+        {
+          col: 1,
+          message: "Unnecessary escape character: \\!",
+          hint: HINT,
+        }
+      ],
+      r#"/a\-/;"#: [
+        {
+          col: 2,
+          message: "Unnecessary escape character: \\-",
+          hint: HINT,
+        }
+      ],
+      r#"/[\.\-a]/;"#: [
+        {
+          col: 2,
+          message: "Unnecessary escape character: \\.",
+          hint: HINT,
+        }
+      ],
+      r#"/[a\-]/;"#: [
+        {
+          col: 3,
+          message: "Unnecessary escape character: \\-",
+          hint: HINT,
+        }
+      ],
+      r#"/[^\^]/;"#: [
+        {
+          col: 3,
+          message: "Unnecessary escape character: \\^",
+          hint: HINT,
+        }
+      ],
+      r#"/[\$]/;"#: [
+        {
+          col: 2,
+          message: "Unnecessary escape character: \\$",
+          hint: HINT,
+        }
+      ],
+      r#"/\a/;"#: [
+        {
+          col: 1,
+          message: "Unnecessary escape character: \\a",
+          hint: HINT,
+        }
+      ],
+      r#"new RegExp("\\#");"#: [
+        {
+          col: 0,
+          message: "Unnecessary escape character: \\#",
+          hint: HINT,
+        }
+      ],
+      r#"new RegExp("\#");"#: [
+        {
+          col: 12,
+          message: "Unnecessary escape character: \\#",
+          hint: HINT,
+        }
+      ],
+    };
+  }
+
+  #[test]
+  fn no_useless_escape_jsx() {
+    let mut linter = LinterBuilder::default()
+      .syntax(get_syntax_for_file("lint_test.tsx").unwrap())
+      .rules(vec![NoUselessEscape::new()])
+      .build();
+    let (_, diagnostics) = linter.lint(
+      "lint_test.tsx".to_string(),
+      r#"<div className="\a">{"\a"}</div>;"#.to_string(),
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range.start.col, 22);
+  }
+
+  #[test]
+  fn no_useless_escape_fix() {
+    use crate::test_util::assert_lint_fix;
+    assert_lint_fix::<NoUselessEscape>(r#""\a\n";"#, r#""a\n";"#);
+    assert_lint_fix::<NoUselessEscape>(r#"/[\.]\./;"#, r#"/[.]\./;"#);
+    assert_lint_fix::<NoUselessEscape>(
+      r#"new RegExp("\\#");"#,
+      r#"new RegExp("\\#");"#,
+    );
+  }
+}
@@ -1,11 +1,18 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::ast::VarDecl;
-use swc_ecmascript::ast::VarDeclKind;
+use crate::diagnostic::LintFix;
+use crate::scopes::ScopeKind;
+use std::collections::{HashMap, HashSet};
+use swc_common::{BytePos, Span};
+use swc_ecmascript::ast::{
+  Decl, Ident, ModuleItem, Stmt, VarDecl, VarDeclKind,
+};
+use swc_ecmascript::utils::{find_ids, ident::IdentLike, Id};
 use swc_ecmascript::visit::noop_visit_type;
 use swc_ecmascript::visit::Node;
 use swc_ecmascript::visit::Visit;
+use swc_ecmascript::visit::VisitWith;
 
 pub struct NoVar;
 
@@ -23,31 +30,133 @@ impl LintRule for NoVar {
     context: &mut Context,
     program: &swc_ecmascript::ast::Program,
   ) {
-    let mut visitor = NoVarVisitor::new(context);
+    let mut collector = IdentCollector::default();
+    program.visit_with(program, &mut collector);
+
+    let mut visitor = NoVarVisitor::new(context, collector.idents);
     visitor.visit_program(program, program);
   }
 }
 
+/// Collects positions of all identifiers, which is used to find out
+/// whether a variable is referenced before its declaration.
+#[derive(Default)]
+struct IdentCollector {
+  idents: HashMap<Id, Vec<BytePos>>,
+}
+
+impl Visit for IdentCollector {
+  noop_visit_type!();
+
+  fn visit_ident(&mut self, ident: &Ident, _parent: &dyn Node) {
+    self
+      .idents
+      .entry(ident.to_id())
+      .or_default()
+      .push(ident.span.lo());
+  }
+}
+
 struct NoVarVisitor<'c> {
   context: &'c mut Context,
+  idents: HashMap<Id, Vec<BytePos>>,
+  /// Declarations that are direct children of a statement list.
+  listed_decls: HashSet<Span>,
 }
 
 impl<'c> NoVarVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+  fn new(context: &'c mut Context, idents: HashMap<Id, Vec<BytePos>>) -> Self {
+    Self {
+      context,
+      idents,
+      listed_decls: HashSet::new(),
+    }
+  }
+
+  /// Replacing `var` with `let` is only safe when it doesn't change which
+  /// code can see the variable, that is the declaration is placed directly
+  /// in a function or the program, it declares each variable once and no
+  /// variable is referenced before being declared.
+  fn create_fix(&self, var_decl: &VarDecl) -> Option<LintFix> {
+    if !self.listed_decls.contains(&var_decl.span) {
+      return None;
+    }
+
+    for decl in &var_decl.decls {
+      let ids: Vec<Id> = find_ids(&decl.name);
+      for id in ids {
+        let var = self.context.scope.var(&id)?;
+        if !matches!(
+          var.path().last(),
+          None | Some(ScopeKind::Function) | Some(ScopeKind::Arrow)
+        ) {
+          return None;
+        }
+
+        let declared_count = self
+          .context
+          .scope
+          .ids_with_symbol(&id.0)
+          .map_or(0, |ids| ids.iter().filter(|i| **i == id).count());
+        if declared_count != 1 {
+          return None;
+        }
+
+        // The binding itself is always found before the end of the
+        // declarator, so any other occurrence means a reference in TDZ.
+        let used_before_declared = self.idents.get(&id).map_or(false, |v| {
+          v.iter().filter(|&&lo| lo < decl.span.hi()).count() > 1
+        });
+        if used_before_declared {
+          return None;
+        }
+      }
+    }
+
+    let keyword_span = var_decl.span.with_hi(var_decl.span.lo() + BytePos(3));
+    Some(LintFix {
+      description: "Replace `var` with `let`".to_string(),
+      changes: vec![self.context.create_fix_change(keyword_span, "let")],
+    })
   }
 }
 
 impl<'c> Visit for NoVarVisitor<'c> {
   noop_visit_type!();
 
+  fn visit_module_items(&mut self, items: &[ModuleItem], parent: &dyn Node) {
+    for item in items {
+      if let ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) = item {
+        self.listed_decls.insert(var_decl.span);
+      }
+      item.visit_with(parent, self);
+    }
+  }
+
+  fn visit_stmts(&mut self, stmts: &[Stmt], parent: &dyn Node) {
+    for stmt in stmts {
+      if let Stmt::Decl(Decl::Var(var_decl)) = stmt {
+        self.listed_decls.insert(var_decl.span);
+      }
+      stmt.visit_with(parent, self);
+    }
+  }
+
   fn visit_var_decl(&mut self, var_decl: &VarDecl, _parent: &dyn Node) {
     if var_decl.kind == VarDeclKind::Var {
-      self.context.add_diagnostic(
-        var_decl.span,
-        "no-var",
-        "`var` keyword is not allowed",
-      );
+      let message = "`var` keyword is not allowed";
+      if let Some(fix) = self.create_fix(var_decl) {
+        self.context.add_diagnostic_with_fix(
+          var_decl.span,
+          "no-var",
+          message,
+          fix,
+        );
+      } else {
+        self
+          .context
+          .add_diagnostic(var_decl.span, "no-var", message);
+      }
     }
   }
 }
@@ -64,4 +173,32 @@ mod tests {
       0,
     );
   }
+
+  #[test]
+  fn no_var_fix() {
+    assert_lint_fix::<NoVar>("var a = 1;", "let a = 1;");
+    assert_lint_fix::<NoVar>(
+      "function foo() { var a = 1, b; return a; }",
+      "function foo() { let a = 1, b; return a; }",
+    );
+    assert_lint_fix::<NoVar>(
+      "foo(() => { var { a, b } = obj; });",
+      "foo(() => { let { a, b } = obj; });",
+    );
+
+    // These are not fixed since `let` would change the semantics.
+    assert_lint_fix::<NoVar>("var a = 1; var a = 2;", "var a = 1; var a = 2;");
+    assert_lint_fix::<NoVar>("a = 0; var a = 1;", "a = 0; var a = 1;");
+    assert_lint_fix::<NoVar>("var a = a + 1;", "var a = a + 1;");
+    assert_lint_fix::<NoVar>("if (a) { var b = 1; }", "if (a) { var b = 1; }");
+    assert_lint_fix::<NoVar>("if (a) var b = 1;", "if (a) var b = 1;");
+    assert_lint_fix::<NoVar>(
+      "for (var i = 0; i < 3; i++) {}",
+      "for (var i = 0; i < 3; i++) {}",
+    );
+    assert_lint_fix::<NoVar>(
+      "function foo() { return a; var a = 1; }",
+      "function foo() { return a; var a = 1; }",
+    );
+  }
 }
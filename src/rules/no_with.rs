@@ -1,10 +1,7 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::ast::WithStmt;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 
 pub struct NoWith;
 
@@ -24,31 +21,25 @@ impl LintRule for NoWith {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = NoWithVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct NoWithVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> NoWithVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for NoWith {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::WithStmt]
   }
-}
 
-impl<'c> Visit for NoWithVisitor<'c> {
-  noop_visit_type!();
-
-  fn visit_with_stmt(&mut self, with_stmt: &WithStmt, _parent: &dyn Node) {
-    self.context.add_diagnostic(with_stmt.span, CODE, MESSAGE);
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::WithStmt(with_stmt) = node {
+      context.add_diagnostic(with_stmt.span, CODE, MESSAGE);
+    }
   }
 }
 
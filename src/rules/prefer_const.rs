@@ -1,22 +1,20 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::diagnostic::LintFix;
+use crate::scopes::BindingKind;
 use derive_more::Display;
-use std::cell::RefCell;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::iter;
 use std::mem;
-use std::rc::Rc;
-use swc_atoms::JsWord;
-use swc_common::{Span, Spanned};
+use swc_common::{BytePos, Span};
 use swc_ecmascript::ast::{
-  ArrowExpr, AssignExpr, BlockStmt, BlockStmtOrExpr, CatchClause, Class,
-  Constructor, DoWhileStmt, Expr, ExprStmt, ForInStmt, ForOfStmt, ForStmt,
-  Function, Ident, IfStmt, ObjectPatProp, ParamOrTsParamProp, Pat, PatOrExpr,
-  Program, Stmt, TsParamPropParam, UpdateExpr, VarDecl, VarDeclKind,
-  VarDeclOrExpr, VarDeclOrPat, WhileStmt, WithStmt,
+  AssignExpr, Expr, ExprStmt, ForInStmt, ForOfStmt, ForStmt, Ident, ModuleItem,
+  ObjectPatProp, Pat, PatOrExpr, Program, Stmt, UpdateExpr, VarDecl,
+  VarDeclKind, VarDeclOrExpr, VarDeclOrPat,
 };
 use swc_ecmascript::utils::find_ids;
+use swc_ecmascript::utils::ident::IdentLike;
 use swc_ecmascript::visit::noop_visit_type;
 use swc_ecmascript::visit::{Node, Visit, VisitWith};
 
@@ -59,30 +57,13 @@ impl LintRule for PreferConst {
 
     let mut visitor = PreferConstVisitor::new(
       context,
-      mem::take(&mut collector.scopes),
       mem::take(&mut collector.var_groups),
+      mem::take(&mut collector.let_decls),
     );
     visitor.visit_program(program, program);
   }
 }
 
-type Scope = Rc<RefCell<RawScope>>;
-
-#[derive(Debug)]
-struct RawScope {
-  parent: Option<Scope>,
-  variables: BTreeMap<JsWord, Span>,
-}
-
-impl RawScope {
-  fn new(parent: Option<Scope>) -> Self {
-    Self {
-      parent,
-      variables: BTreeMap::new(),
-    }
-  }
-}
-
 struct DeclInfo {
   /// the span of its declaration
   span: Span,
@@ -90,23 +71,13 @@ struct DeclInfo {
   in_other_scope: bool,
 }
 
-/// Looks for the declaration span of the given variable by traversing from the given scope to the parents.
-/// Returns `None` if no matching span is found. Most likely it means the variable is not declared
-/// with `let`.
-fn get_decl_by_ident(scope: Scope, ident: &Ident) -> Option<DeclInfo> {
-  let mut cur_scope = Some(scope);
-  let mut is_current_scope = true;
-  while let Some(cur) = cur_scope {
-    if let Some(&span) = cur.borrow().variables.get(&ident.sym) {
-      return Some(DeclInfo {
-        span,
-        in_other_scope: !is_current_scope,
-      });
-    }
-    cur_scope = cur.borrow().parent.as_ref().map(Rc::clone);
-    is_current_scope = false;
-  }
-  None
+/// The binding an assigned identifier refers to.
+enum AssignTarget {
+  Let(DeclInfo),
+  /// Any other binding, e.g. a parameter, which can't be `const`.
+  Other,
+  /// Identifiers not declared in the program, e.g. globals.
+  Undeclared,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -161,6 +132,10 @@ impl DisjointSet {
     }
   }
 
+  fn contains(&self, span: Span) -> bool {
+    self.parents.contains_key(&span)
+  }
+
   fn add_root(&mut self, span: Span, status: VarStatus) {
     if self.parents.contains_key(&span) {
       return;
@@ -234,46 +209,55 @@ impl DisjointSet {
   }
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
-enum ScopeRange {
-  Global,
-  Block(Span),
+/// A `let` declaration, used to decide whether it can be fixed as a whole.
+#[derive(Debug)]
+struct LetDecl {
+  span: Span,
+  idents: Vec<Span>,
+  /// `const` requires all declarators to be initialized.
+  initialized: bool,
 }
 
+/// Collects the variables declared with `let`, which are the only ones that
+/// can be turned into `const`.
 #[derive(Debug)]
 struct VariableCollector {
-  scopes: BTreeMap<ScopeRange, Scope>,
-  cur_scope: ScopeRange,
   var_groups: DisjointSet,
+  let_decls: Vec<LetDecl>,
 }
 
 impl VariableCollector {
   fn new() -> Self {
     Self {
-      scopes: BTreeMap::new(),
-      cur_scope: ScopeRange::Global,
       var_groups: DisjointSet::new(),
+      let_decls: Vec::new(),
     }
   }
 
-  fn insert_var(&mut self, ident: &Ident, status: VarStatus) {
-    self.var_groups.add_root(ident.span, status);
-    let mut scope = self.scopes.get(&self.cur_scope).unwrap().borrow_mut();
-    scope.variables.insert(ident.sym.clone(), ident.span);
+  fn insert_let_decl(&mut self, var_decl: &VarDecl, initialized: bool) {
+    let mut idents = Vec::new();
+    for decl in &var_decl.decls {
+      extract_idents_from_pat(&mut idents, &decl.name);
+    }
+    self.let_decls.push(LetDecl {
+      span: var_decl.span,
+      idents: idents.into_iter().map(|i| i.span).collect(),
+      initialized,
+    });
   }
 
   fn insert_vars(&mut self, idents: &[&Ident], status: VarStatus) {
     match idents {
       [] => {}
       [ident] => {
-        self.insert_var(ident, status);
+        self.var_groups.add_root(ident.span, status);
       }
       [first, others @ ..] => {
-        self.insert_var(first, status);
+        self.var_groups.add_root(first.span, status);
 
         // If there are more than one idents, they need to be grouped
         for i in others {
-          self.insert_var(i, status);
+          self.var_groups.add_root(i.span, status);
           self.var_groups.unite(first.span, i.span);
         }
       }
@@ -292,272 +276,65 @@ impl VariableCollector {
     self.insert_vars(&idents, status);
   }
 
-  fn with_child_scope<F, S>(&mut self, node: S, op: F)
-  where
-    S: Spanned,
-    F: FnOnce(&mut VariableCollector),
-  {
-    let parent_scope_range = self.cur_scope;
-    let parent_scope = self.scopes.get(&parent_scope_range).map(Rc::clone);
-    let child_scope = RawScope::new(parent_scope);
-    self.scopes.insert(
-      ScopeRange::Block(node.span()),
-      Rc::new(RefCell::new(child_scope)),
-    );
-    self.cur_scope = ScopeRange::Block(node.span());
-    op(self);
-    self.cur_scope = parent_scope_range;
+  /// Variables declared in `for (let ... of xs)` and `for (let ... in xs)`
+  /// are initialized on each iteration.
+  fn insert_loop_left(&mut self, left: &VarDeclOrPat) {
+    if let VarDeclOrPat::VarDecl(var_decl) = left {
+      if var_decl.kind == VarDeclKind::Let {
+        for decl in &var_decl.decls {
+          self.extract_decl_idents(&decl.name, true);
+        }
+        self.insert_let_decl(var_decl, true);
+      }
+    }
   }
 }
 
 impl Visit for VariableCollector {
   noop_visit_type!();
 
-  fn visit_program(&mut self, program: &Program, _: &dyn Node) {
-    let scope = RawScope::new(None);
-    self
-      .scopes
-      .insert(ScopeRange::Global, Rc::new(RefCell::new(scope)));
-    program.visit_children_with(self);
-  }
-
-  fn visit_function(&mut self, function: &Function, _: &dyn Node) {
-    self.with_child_scope(function, |a| {
-      for param in &function.params {
-        param.visit_children_with(a);
-        let idents: Vec<Ident> = find_ids(&param.pat);
-        for ident in idents {
-          a.insert_var(&ident, VarStatus::Reassigned);
-        }
-      }
-      if let Some(body) = &function.body {
-        body.visit_children_with(a);
-      }
-    });
-  }
-
-  fn visit_arrow_expr(&mut self, arrow_expr: &ArrowExpr, _: &dyn Node) {
-    self.with_child_scope(arrow_expr, |a| {
-      for param in &arrow_expr.params {
-        param.visit_children_with(a);
-        let idents: Vec<Ident> = find_ids(param);
-        for ident in idents {
-          a.insert_var(&ident, VarStatus::Reassigned);
-        }
-      }
-      match &arrow_expr.body {
-        BlockStmtOrExpr::BlockStmt(block_stmt) => {
-          block_stmt.visit_children_with(a);
-        }
-        BlockStmtOrExpr::Expr(expr) => {
-          expr.visit_children_with(a);
-        }
-      }
-    });
-  }
-
-  fn visit_block_stmt(&mut self, block_stmt: &BlockStmt, _: &dyn Node) {
-    self.with_child_scope(block_stmt, |a| {
-      block_stmt.visit_children_with(a);
-    });
-  }
-
   fn visit_for_stmt(&mut self, for_stmt: &ForStmt, _: &dyn Node) {
-    self.with_child_scope(for_stmt, |a| {
-      match &for_stmt.init {
-        Some(VarDeclOrExpr::VarDecl(var_decl)) => {
-          var_decl.visit_children_with(a);
-          if var_decl.kind == VarDeclKind::Let {
-            let mut idents = Vec::new();
-            let mut has_init = false;
-            for decl in &var_decl.decls {
-              extract_idents_from_pat(&mut idents, &decl.name);
-              has_init |= decl.init.is_some();
-            }
-            let status = if has_init {
-              VarStatus::Initialized
-            } else {
-              VarStatus::Declared
-            };
-            a.insert_vars(&idents, status);
-          }
-        }
-        Some(VarDeclOrExpr::Expr(expr)) => {
-          expr.visit_children_with(a);
-        }
-        None => {}
-      }
-
-      if let Some(test_expr) = &for_stmt.test {
-        test_expr.visit_children_with(a);
-      }
-      if let Some(update_expr) = &for_stmt.update {
-        update_expr.visit_children_with(a);
-      }
-
-      if let Stmt::Block(block_stmt) = &*for_stmt.body {
-        block_stmt.visit_children_with(a);
-      } else {
-        for_stmt.body.visit_children_with(a);
-      }
-    });
-  }
-
-  fn visit_for_of_stmt(&mut self, for_of_stmt: &ForOfStmt, _: &dyn Node) {
-    self.with_child_scope(for_of_stmt, |a| {
-      if let VarDeclOrPat::VarDecl(var_decl) = &for_of_stmt.left {
+    match &for_stmt.init {
+      Some(VarDeclOrExpr::VarDecl(var_decl)) => {
+        var_decl.visit_children_with(self);
         if var_decl.kind == VarDeclKind::Let {
+          let mut idents = Vec::new();
+          let mut has_init = false;
           for decl in &var_decl.decls {
-            a.extract_decl_idents(&decl.name, true);
+            extract_idents_from_pat(&mut idents, &decl.name);
+            has_init |= decl.init.is_some();
           }
+          let status = if has_init {
+            VarStatus::Initialized
+          } else {
+            VarStatus::Declared
+          };
+          self.insert_vars(&idents, status);
+          let initialized = var_decl.decls.iter().all(|d| d.init.is_some());
+          self.insert_let_decl(var_decl, initialized);
         }
       }
-
-      for_of_stmt.right.visit_children_with(a);
-
-      if let Stmt::Block(block_stmt) = &*for_of_stmt.body {
-        block_stmt.visit_children_with(a);
-      } else {
-        for_of_stmt.body.visit_children_with(a);
+      Some(VarDeclOrExpr::Expr(expr)) => {
+        expr.visit_with(for_stmt, self);
       }
-    });
-  }
-
-  fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt, _: &dyn Node) {
-    self.with_child_scope(for_in_stmt, |a| {
-      if let VarDeclOrPat::VarDecl(var_decl) = &for_in_stmt.left {
-        if var_decl.kind == VarDeclKind::Let {
-          for decl in &var_decl.decls {
-            a.extract_decl_idents(&decl.name, true);
-          }
-        }
-      }
-
-      for_in_stmt.right.visit_children_with(a);
-
-      if let Stmt::Block(block_stmt) = &*for_in_stmt.body {
-        block_stmt.visit_children_with(a);
-      } else {
-        for_in_stmt.body.visit_children_with(a);
-      }
-    });
-  }
-
-  fn visit_if_stmt(&mut self, if_stmt: &IfStmt, _: &dyn Node) {
-    self.with_child_scope(if_stmt, |a| {
-      if_stmt.test.visit_children_with(a);
-      // BlockStmt needs special handling to avoid creating a duplicate scope
-      if let Stmt::Block(body) = &*if_stmt.cons {
-        body.visit_children_with(a);
-      } else {
-        if_stmt.cons.visit_children_with(a);
-      }
-    });
-
-    if let Some(alt) = &if_stmt.alt {
-      self.with_child_scope(alt, |a| {
-        alt.visit_children_with(a);
-      });
+      None => {}
     }
-  }
 
-  fn visit_while_stmt(&mut self, while_stmt: &WhileStmt, _: &dyn Node) {
-    self.with_child_scope(while_stmt, |a| {
-      while_stmt.test.visit_children_with(a);
-      // BlockStmt needs special handling to avoid creating a duplicate scope
-      if let Stmt::Block(body) = &*while_stmt.body {
-        body.visit_children_with(a);
-      } else {
-        while_stmt.body.visit_children_with(a);
-      }
-    });
-  }
-
-  fn visit_do_while_stmt(&mut self, do_while_stmt: &DoWhileStmt, _: &dyn Node) {
-    self.with_child_scope(do_while_stmt, |a| {
-      // BlockStmt needs special handling to avoid creating a duplicate scope
-      if let Stmt::Block(body) = &*do_while_stmt.body {
-        body.visit_children_with(a);
-      } else {
-        do_while_stmt.body.visit_children_with(a);
-      }
-      do_while_stmt.test.visit_children_with(a);
-    });
+    for_stmt.test.visit_with(for_stmt, self);
+    for_stmt.update.visit_with(for_stmt, self);
+    for_stmt.body.visit_with(for_stmt, self);
   }
 
-  fn visit_with_stmt(&mut self, with_stmt: &WithStmt, _: &dyn Node) {
-    self.with_child_scope(with_stmt, |a| {
-      with_stmt.obj.visit_children_with(a);
-      // BlockStmt needs special handling to avoid creating a duplicate scope
-      if let Stmt::Block(body) = &*with_stmt.body {
-        body.visit_children_with(a);
-      } else {
-        with_stmt.body.visit_children_with(a);
-      }
-    });
-  }
-
-  fn visit_catch_clause(&mut self, catch_clause: &CatchClause, _: &dyn Node) {
-    self.with_child_scope(catch_clause, |a| {
-      if let Some(param) = &catch_clause.param {
-        let idents: Vec<Ident> = find_ids(param);
-        for ident in idents {
-          a.insert_var(&ident, VarStatus::Reassigned);
-        }
-      }
-      catch_clause.body.visit_children_with(a);
-    });
-  }
-
-  fn visit_class(&mut self, class: &Class, _: &dyn Node) {
-    for decorator in &class.decorators {
-      decorator.visit_children_with(self);
-    }
-    if let Some(super_class) = &class.super_class {
-      super_class.visit_children_with(self);
-    }
-    self.with_child_scope(class, |a| {
-      for member in &class.body {
-        member.visit_children_with(a);
-      }
-    });
+  fn visit_for_of_stmt(&mut self, for_of_stmt: &ForOfStmt, _: &dyn Node) {
+    self.insert_loop_left(&for_of_stmt.left);
+    for_of_stmt.right.visit_with(for_of_stmt, self);
+    for_of_stmt.body.visit_with(for_of_stmt, self);
   }
 
-  fn visit_constructor(&mut self, constructor: &Constructor, _: &dyn Node) {
-    self.with_child_scope(constructor, |a| {
-      for param in &constructor.params {
-        match param {
-          ParamOrTsParamProp::TsParamProp(ts_param_prop) => {
-            for decorator in &ts_param_prop.decorators {
-              decorator.visit_children_with(a);
-            }
-            match &ts_param_prop.param {
-              TsParamPropParam::Ident(ident) => {
-                a.insert_var(ident, VarStatus::Reassigned);
-              }
-              TsParamPropParam::Assign(assign_pat) => {
-                assign_pat.visit_children_with(a);
-                let idents: Vec<Ident> = find_ids(&assign_pat.left);
-                for ident in idents {
-                  a.insert_var(&ident, VarStatus::Reassigned);
-                }
-              }
-            }
-          }
-          ParamOrTsParamProp::Param(param) => {
-            param.visit_children_with(a);
-            let idents: Vec<Ident> = find_ids(&param.pat);
-            for ident in idents {
-              a.insert_var(&ident, VarStatus::Reassigned);
-            }
-          }
-        }
-      }
-
-      if let Some(body) = &constructor.body {
-        body.visit_children_with(a);
-      }
-    });
+  fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt, _: &dyn Node) {
+    self.insert_loop_left(&for_in_stmt.left);
+    for_in_stmt.right.visit_with(for_in_stmt, self);
+    for_in_stmt.body.visit_with(for_in_stmt, self);
   }
 
   fn visit_var_decl(&mut self, var_decl: &VarDecl, _: &dyn Node) {
@@ -566,14 +343,18 @@ impl Visit for VariableCollector {
       for decl in &var_decl.decls {
         self.extract_decl_idents(&decl.name, decl.init.is_some());
       }
+      let initialized = var_decl.decls.iter().all(|d| d.init.is_some());
+      self.insert_let_decl(var_decl, initialized);
     }
   }
 }
 
 struct PreferConstVisitor<'c> {
-  scopes: BTreeMap<ScopeRange, Scope>,
-  cur_scope: ScopeRange,
   var_groups: DisjointSet,
+  /// Whether the statement being visited is an expression statement placed
+  /// directly in a statement list, e.g. not the body of `if` without braces.
+  listed: bool,
+  let_decls: Vec<LetDecl>,
   context: &'c mut Context,
 }
 
@@ -634,41 +415,83 @@ where
 impl<'c> PreferConstVisitor<'c> {
   fn new(
     context: &'c mut Context,
-    scopes: BTreeMap<ScopeRange, Scope>,
     var_groups: DisjointSet,
+    let_decls: Vec<LetDecl>,
   ) -> Self {
     Self {
       context,
-      scopes,
       var_groups,
-      cur_scope: ScopeRange::Global,
+      let_decls,
+      listed: false,
     }
   }
 
-  fn report(&mut self, span: Span) {
+  fn report(&mut self, span: Span, maybe_fix: Option<LintFix>) {
     if let Ok(s) = self.context.source_map.span_to_snippet(span) {
-      self.context.add_diagnostic_with_hint(
+      self.context.add_diagnostic_with_fixes(
         span,
         CODE,
         PreferConstMessage::NeverReassigned(s),
-        PreferConstHint::UseConst,
+        Some(PreferConstHint::UseConst.to_string()),
+        maybe_fix.into_iter().collect(),
       );
     }
   }
 
-  fn with_child_scope<F, S>(&mut self, node: &S, op: F)
-  where
-    S: Spanned,
-    F: FnOnce(&mut Self),
-  {
-    let parent_scope_range = self.cur_scope;
-    self.cur_scope = ScopeRange::Block(node.span());
-    op(self);
-    self.cur_scope = parent_scope_range;
+  /// Creates fixes for `let` declarations whose variables are all reported,
+  /// keyed by the span of the first variable in the declaration.
+  fn create_fixes(&self, reported: &[Span]) -> HashMap<Span, LintFix> {
+    let reported: HashSet<&Span> = reported.iter().collect();
+    let mut fixes = HashMap::new();
+
+    for let_decl in &self.let_decls {
+      if !let_decl.initialized
+        || let_decl.idents.is_empty()
+        || !let_decl.idents.iter().all(|i| reported.contains(i))
+      {
+        continue;
+      }
+
+      let keyword_span = let_decl.span.with_hi(let_decl.span.lo() + BytePos(3));
+      if self
+        .context
+        .source_map
+        .span_to_snippet(keyword_span)
+        .as_deref()
+        != Ok("let")
+      {
+        continue;
+      }
+
+      fixes.insert(
+        let_decl.idents[0],
+        LintFix {
+          description: PreferConstHint::UseConst.to_string(),
+          changes: vec![self.context.create_fix_change(keyword_span, "const")],
+        },
+      );
+    }
+
+    fixes
   }
 
-  fn get_scope(&self) -> Scope {
-    Rc::clone(self.scopes.get(&self.cur_scope).unwrap())
+  /// Looks up the declaration `ident` refers to.
+  /// Assignments which aren't placed directly in the scope of the
+  /// declaration, like `if (x) a = 1;`, count as being in another scope.
+  fn get_decl(&self, ident: &Ident) -> AssignTarget {
+    let scope = &self.context.scope;
+    let var = match scope.var(&ident.to_id()) {
+      Some(var) => var,
+      None => return AssignTarget::Undeclared,
+    };
+    if var.kind() != BindingKind::Let || !self.var_groups.contains(var.span()) {
+      return AssignTarget::Other;
+    }
+    AssignTarget::Let(DeclInfo {
+      span: var.span(),
+      in_other_scope: !self.listed
+        || scope.scope_at(ident.span.lo) != var.scope(),
+    })
   }
 
   fn extract_assign_idents<'a>(&mut self, pat: &'a Pat) {
@@ -691,10 +514,17 @@ impl<'c> PreferConstVisitor<'c> {
     idents: impl Iterator<Item = &'a Ident>,
     force_reassigned: bool,
   ) {
-    let scope = self.get_scope();
-    let decls: Vec<DeclInfo> = idents
-      .filter_map(|i| get_decl_by_ident(Rc::clone(&scope), &i))
-      .collect();
+    let mut decls = Vec::new();
+    let mut force_reassigned = force_reassigned;
+    for ident in idents {
+      match self.get_decl(ident) {
+        AssignTarget::Let(decl) => decls.push(decl),
+        // Variables assigned together with one which can't be `const` can't
+        // be declared with `const` either.
+        AssignTarget::Other => force_reassigned = true,
+        AssignTarget::Undeclared => {}
+      }
+    }
 
     match decls.as_slice() {
       [] => {}
@@ -724,11 +554,39 @@ impl<'c> Visit for PreferConstVisitor<'c> {
   fn visit_program(&mut self, program: &Program, _: &dyn Node) {
     program.visit_children_with(self);
     // After visiting all nodes, reports errors.
-    for span in self.var_groups.dump() {
-      self.report(span);
+    let reported = self.var_groups.dump();
+    let mut fixes = self.create_fixes(&reported);
+    for span in reported {
+      let maybe_fix = fixes.remove(&span);
+      self.report(span, maybe_fix);
     }
   }
 
+  fn visit_module_items(&mut self, items: &[ModuleItem], _: &dyn Node) {
+    let prev = self.listed;
+    for item in items {
+      self.listed = matches!(item, ModuleItem::Stmt(Stmt::Expr(_)));
+      item.visit_children_with(self);
+    }
+    self.listed = prev;
+  }
+
+  fn visit_stmts(&mut self, stmts: &[Stmt], _: &dyn Node) {
+    let prev = self.listed;
+    for stmt in stmts {
+      self.listed = matches!(stmt, Stmt::Expr(_));
+      stmt.visit_children_with(self);
+    }
+    self.listed = prev;
+  }
+
+  /// Statements which aren't in a statement list, e.g. loop bodies.
+  fn visit_stmt(&mut self, stmt: &Stmt, _: &dyn Node) {
+    let prev = mem::replace(&mut self.listed, false);
+    stmt.visit_children_with(self);
+    self.listed = prev;
+  }
+
   fn visit_assign_expr(&mut self, assign_expr: &AssignExpr, _: &dyn Node) {
     // This only handles _nested_ `AssignmentExpression` since not nested `AssignExpression` (i.e. the direct child of
     // `ExpressionStatement`) is already handled by `visit_expr_stmt`. The variables within nested
@@ -785,172 +643,30 @@ impl<'c> Visit for PreferConstVisitor<'c> {
     }
   }
 
-  fn visit_function(&mut self, function: &Function, _: &dyn Node) {
-    self.with_child_scope(function, |a| {
-      if let Some(body) = &function.body {
-        body.visit_children_with(a);
-      }
-    });
-  }
-
-  fn visit_arrow_expr(&mut self, arrow_expr: &ArrowExpr, _: &dyn Node) {
-    self.with_child_scope(arrow_expr, |a| match &arrow_expr.body {
-      BlockStmtOrExpr::BlockStmt(block_stmt) => {
-        block_stmt.visit_children_with(a);
-      }
-      BlockStmtOrExpr::Expr(expr) => {
-        expr.visit_children_with(a);
-      }
-    });
-  }
-
-  fn visit_block_stmt(&mut self, block_stmt: &BlockStmt, _: &dyn Node) {
-    self.with_child_scope(block_stmt, |a| block_stmt.visit_children_with(a));
-  }
-
-  fn visit_for_stmt(&mut self, for_stmt: &ForStmt, _: &dyn Node) {
-    self.with_child_scope(for_stmt, |a| {
-      for_stmt.init.visit_children_with(a);
-      for_stmt.test.visit_children_with(a);
-      for_stmt.update.visit_children_with(a);
-
-      if let Stmt::Block(block_stmt) = &*for_stmt.body {
-        block_stmt.visit_children_with(a);
-      } else {
-        for_stmt.body.visit_children_with(a);
-      }
-    });
-  }
-
   fn visit_for_of_stmt(&mut self, for_of_stmt: &ForOfStmt, _: &dyn Node) {
-    self.with_child_scope(for_of_stmt, |a| {
-      match &for_of_stmt.left {
-        VarDeclOrPat::VarDecl(var_decl) => {
-          var_decl.visit_with(&for_of_stmt.left, a);
-        }
-        VarDeclOrPat::Pat(pat) => {
-          a.extract_assign_idents(pat);
-        }
-      }
-
-      for_of_stmt.right.visit_children_with(a);
-
-      if let Stmt::Block(block_stmt) = &*for_of_stmt.body {
-        block_stmt.visit_children_with(a);
-      } else {
-        for_of_stmt.body.visit_children_with(a);
-      }
-    });
-  }
-
-  fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt, _: &dyn Node) {
-    self.with_child_scope(for_in_stmt, |a| {
-      match &for_in_stmt.left {
-        VarDeclOrPat::VarDecl(var_decl) => {
-          var_decl.visit_with(&for_in_stmt.left, a);
-        }
-        VarDeclOrPat::Pat(pat) => {
-          a.extract_assign_idents(pat);
-        }
+    match &for_of_stmt.left {
+      VarDeclOrPat::VarDecl(var_decl) => {
+        var_decl.visit_with(&for_of_stmt.left, self);
       }
-
-      for_in_stmt.right.visit_children_with(a);
-
-      if let Stmt::Block(block_stmt) = &*for_in_stmt.body {
-        block_stmt.visit_children_with(a);
-      } else {
-        for_in_stmt.body.visit_children_with(a);
-      }
-    });
-  }
-
-  fn visit_if_stmt(&mut self, if_stmt: &IfStmt, _: &dyn Node) {
-    self.with_child_scope(if_stmt, |a| {
-      if_stmt.test.visit_children_with(a);
-      // BlockStmt needs special handling to avoid creating a duplicate scope
-      if let Stmt::Block(body) = &*if_stmt.cons {
-        body.visit_children_with(a);
-      } else {
-        if_stmt.cons.visit_children_with(a);
+      VarDeclOrPat::Pat(pat) => {
+        self.extract_assign_idents(pat);
       }
-    });
-
-    if let Some(alt) = &if_stmt.alt {
-      self.with_child_scope(alt, |a| {
-        alt.visit_children_with(a);
-      });
     }
+    for_of_stmt.right.visit_with(for_of_stmt, self);
+    for_of_stmt.body.visit_with(for_of_stmt, self);
   }
 
-  fn visit_while_stmt(&mut self, while_stmt: &WhileStmt, _: &dyn Node) {
-    self.with_child_scope(while_stmt, |a| {
-      while_stmt.test.visit_children_with(a);
-      // BlockStmt needs special handling to avoid creating a duplicate scope
-      if let Stmt::Block(body) = &*while_stmt.body {
-        body.visit_children_with(a);
-      } else {
-        while_stmt.body.visit_children_with(a);
-      }
-    });
-  }
-
-  fn visit_do_while_stmt(&mut self, do_while_stmt: &DoWhileStmt, _: &dyn Node) {
-    self.with_child_scope(do_while_stmt, |a| {
-      // BlockStmt needs special handling to avoid creating a duplicate scope
-      if let Stmt::Block(body) = &*do_while_stmt.body {
-        body.visit_children_with(a);
-      } else {
-        do_while_stmt.body.visit_children_with(a);
+  fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt, _: &dyn Node) {
+    match &for_in_stmt.left {
+      VarDeclOrPat::VarDecl(var_decl) => {
+        var_decl.visit_with(&for_in_stmt.left, self);
       }
-      do_while_stmt.test.visit_children_with(a);
-    });
-  }
-
-  fn visit_with_stmt(&mut self, with_stmt: &WithStmt, _: &dyn Node) {
-    self.with_child_scope(with_stmt, |a| {
-      with_stmt.obj.visit_children_with(a);
-      // BlockStmt needs special handling to avoid creating a duplicate scope
-      if let Stmt::Block(body) = &*with_stmt.body {
-        body.visit_children_with(a);
-      } else {
-        with_stmt.body.visit_children_with(a);
+      VarDeclOrPat::Pat(pat) => {
+        self.extract_assign_idents(pat);
       }
-    });
-  }
-
-  fn visit_catch_clause(&mut self, catch_clause: &CatchClause, _: &dyn Node) {
-    self.with_child_scope(catch_clause, |a| {
-      if let Some(param) = &catch_clause.param {
-        param.visit_children_with(a);
-      }
-      catch_clause.body.visit_children_with(a);
-    });
-  }
-
-  fn visit_class(&mut self, class: &Class, _: &dyn Node) {
-    for decorator in &class.decorators {
-      decorator.visit_children_with(self);
     }
-    if let Some(super_class) = &class.super_class {
-      super_class.visit_children_with(self);
-    }
-    self.with_child_scope(class, |a| {
-      for member in &class.body {
-        member.visit_children_with(a);
-      }
-    });
-  }
-
-  fn visit_constructor(&mut self, constructor: &Constructor, _: &dyn Node) {
-    self.with_child_scope(constructor, |a| {
-      for param in &constructor.params {
-        param.visit_children_with(a);
-      }
-
-      if let Some(body) = &constructor.body {
-        body.visit_children_with(a);
-      }
-    });
+    for_in_stmt.right.visit_with(for_in_stmt, self);
+    for_in_stmt.body.visit_with(for_in_stmt, self);
   }
 }
 
@@ -966,595 +682,6 @@ mod variable_collector_tests {
     v
   }
 
-  fn variables(scope: &Scope) -> Vec<String> {
-    scope
-      .borrow()
-      .variables
-      .keys()
-      .map(|k| k.to_string())
-      .collect()
-  }
-
-  #[test]
-  fn collector_works_function() {
-    let src = r#"
-let global1;
-function foo({ param1, key: param2 }) {
-  let inner1 = 2;
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let foo_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["inner1", "param1", "param2"], foo_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_arrow_function() {
-    let src = r#"
-let global1;
-const arrow = (param1, ...param2) => {
-  let inner1;
-};
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let foo_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["inner1", "param1", "param2"], foo_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_block() {
-    let src = r#"
-let global1;
-{
-  let inner1 = 1;
-  let inner2;
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let inner_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["inner1", "inner2"], inner_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_if_1() {
-    let src = r#"
-let global1;
-if (true) {
-  let inner1 = 1;
-  let inner2;
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let inner_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["inner1", "inner2"], inner_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_if_2() {
-    let src = r#"
-let global1;
-if (true) {
-  let cons = 1;
-} else {
-  let alt = 3;
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let cons_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["cons"], cons_vars);
-
-    let alt_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["alt"], alt_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_if_3() {
-    let src = r#"
-let global1;
-if (true) {
-  let cons1 = 1;
-} else if (false) {
-  let cons2 = 2;
-} else {
-  let alt;
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let cons1_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["cons1"], cons1_vars);
-
-    let cons2_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["cons2"], cons2_vars);
-
-    let alt_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["alt"], alt_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_if_4() {
-    let src = r#"
-let global1;
-if (true) foo();
-else if (false) bar();
-else baz();
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let cons1_vars = variables(scope_iter.next().unwrap());
-    assert!(cons1_vars.is_empty());
-
-    let cons2_vars = variables(scope_iter.next().unwrap());
-    assert!(cons2_vars.is_empty());
-
-    let alt_vars = variables(scope_iter.next().unwrap());
-    assert!(alt_vars.is_empty());
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_for_1() {
-    let src = r#"
-let global1;
-for (let i = 0, j = 10; i < 10; i++) {
-  let inner;
-  j--;
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let for_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["i", "inner", "j"], for_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_for_2() {
-    let src = r#"
-let global1;
-for (let i = 0, j = 10; i < 10; i++) i += 2;
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let for_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["i", "j"], for_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_for_3() {
-    let src = r#"
-let global1 = 0;
-for (global1 = 0; global1 < 10; ++global1) foo();
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let for_vars = variables(scope_iter.next().unwrap());
-    assert!(for_vars.is_empty());
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_for_of_1() {
-    let src = r#"
-let global1;
-for (let i of [1, 2, 3]) {
-  let inner;
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let for_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["i", "inner"], for_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_for_of_2() {
-    let src = r#"
-let global1;
-for (let { i, j } of [{ i: 1, j: 2 }, { i : 3, j: 4 }]) i += 2;
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let for_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["i", "j"], for_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_for_of_3() {
-    let src = r#"
-let global1 = 0;
-for (global1 of [1, 2, 3]) foo();
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let for_vars = variables(scope_iter.next().unwrap());
-    assert!(for_vars.is_empty());
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_for_in_1() {
-    let src = r#"
-let global1;
-for (let i in [1, 2, 3]) {
-  let inner;
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let for_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["i", "inner"], for_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_for_in_2() {
-    let src = r#"
-let global1;
-for (let i in [1, 2, 3]) i += 2;
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let for_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["i"], for_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_for_in_3() {
-    let src = r#"
-let global1 = 0;
-for (global1 in [1, 2, 3]) foo();
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let for_vars = variables(scope_iter.next().unwrap());
-    assert!(for_vars.is_empty());
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_while_1() {
-    let src = r#"
-let global1;
-while (true) {
-  let inner;
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let while_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["inner"], while_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_while_2() {
-    let src = r#"
-let global1;
-while (true) foo();
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let while_vars = variables(scope_iter.next().unwrap());
-    assert!(while_vars.is_empty());
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_do_while_1() {
-    let src = r#"
-let global1;
-do {
-  let inner;
-} while (true)
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let while_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["inner"], while_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_do_while_2() {
-    let src = r#"
-let global1;
-do foo(); while (true)
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let while_vars = variables(scope_iter.next().unwrap());
-    assert!(while_vars.is_empty());
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_with_1() {
-    let src = r#"
-let global1;
-with (foo) {
-  let inner = 1;
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let with_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["inner"], with_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_with_2() {
-    let src = r#"
-let global1;
-with (foo) bar();
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let with_vars = variables(scope_iter.next().unwrap());
-    assert!(with_vars.is_empty());
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_try_catch() {
-    let src = r#"
-let global1;
-try {
-  let tryVar;
-  foo();
-} catch(e) {
-  let catchVar;
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let try_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["tryVar"], try_vars);
-
-    let catch_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["catchVar", "e"], catch_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_class() {
-    let src = r#"
-let global1;
-class Foo {
-  field;
-  #privateField;
-  constructor(consParam) {
-    let cons;
-  }
-  get getter() {
-    let g;
-  }
-  set setter() {
-    let s;
-  }
-  static staticMethod(staticMethodParam) {
-    let sm;
-  }
-  method(methodParam) {
-    let m;
-  }
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let class_vars = variables(scope_iter.next().unwrap());
-    assert!(class_vars.is_empty()); // collector doesn't collect class fields
-
-    let constructor_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["cons", "consParam"], constructor_vars);
-
-    let getter_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["g"], getter_vars);
-
-    let setter_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["s"], setter_vars);
-
-    let static_method_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["sm", "staticMethodParam"], static_method_vars);
-
-    let method_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["m", "methodParam"], method_vars);
-
-    assert!(scope_iter.next().is_none());
-  }
-
-  #[test]
-  fn collector_works_complex_1() {
-    let src = r#"
-let global1;
-function foo({ p1 = 0 }) {
-  while (cond) {
-    let while1 = true;
-    if (while1) {
-      break;
-    }
-    let [while2, { while3, key: while4 = 4 }] = bar();
-  }
-}
-let global2 = 42;
-    "#;
-    let v = collect(src);
-    let mut scope_iter = v.scopes.values();
-
-    let global_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["global1", "global2"], global_vars);
-
-    let function_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["p1"], function_vars);
-
-    let while_vars = variables(scope_iter.next().unwrap());
-    assert_eq!(vec!["while1", "while2", "while3", "while4"], while_vars);
-
-    let if_vars = variables(scope_iter.next().unwrap());
-    assert!(if_vars.is_empty());
-
-    assert!(scope_iter.next().is_none());
-  }
-
   #[test]
   fn var_groups_1() {
     let src = r#"
@@ -1585,11 +712,9 @@ let { foo, bar: { bar, baz: x = 42 } } = obj;
     let src = r#"
 function f(x: number, y: string = 42) {}
 "#;
+    // Parameters can't be `const`, so they aren't collected
     let mut v = collect(src);
-    assert_eq!(v.var_groups.roots.len(), 2);
-    for &(s, _) in v.var_groups.roots.values() {
-      assert_eq!(s, VarStatus::Reassigned);
-    }
+    assert!(v.var_groups.roots.is_empty());
     assert_eq!(v.var_groups.dump().len(), 0);
   }
 
@@ -1599,11 +724,7 @@ function f(x: number, y: string = 42) {}
 try {} catch (e) {}
 "#;
     let mut v = collect(src);
-    assert_eq!(v.var_groups.roots.len(), 1);
-    assert_eq!(
-      v.var_groups.roots.values().next().unwrap().0,
-      VarStatus::Reassigned
-    );
+    assert!(v.var_groups.roots.is_empty());
     assert_eq!(v.var_groups.dump().len(), 0);
   }
 
@@ -1643,6 +764,8 @@ mod prefer_const_tests {
       r#"let x = 0; x--;"#,
       r#"let x = 0; --x;"#,
       r#"let x; { x = 0; } foo(x);"#,
+      r#"class A { m() { let x = 0; x = 1; } }"#,
+      r#"const o = { set s(v) { let x; if (v) x = v; } };"#,
       r#"let x = 0; x = 1;"#,
       r#"const x = 0;"#,
       r#"for (let i = 0, end = 10; i < end; ++i) {}"#,
@@ -1782,6 +905,20 @@ mod prefer_const_tests {
           hint: PreferConstHint::UseConst,
         }
       ],
+      r#"class A { m() { let x = 0; } }"#: [
+        {
+          col: 20,
+          message: variant!(PreferConstMessage, NeverReassigned, "x"),
+          hint: PreferConstHint::UseConst,
+        }
+      ],
+      r#"const o = { get g() { let x; x = 1; return x; } };"#: [
+        {
+          col: 26,
+          message: variant!(PreferConstMessage, NeverReassigned, "x"),
+          hint: PreferConstHint::UseConst,
+        }
+      ],
       r#"let x = 1; foo(x);"#: [
         {
           col: 4,
@@ -2185,4 +1322,26 @@ e = 2;
       ]
     };
   }
+  #[test]
+  fn prefer_const_fix() {
+    use crate::test_util::assert_lint_fix;
+    assert_lint_fix::<PreferConst>("let a = 1;", "const a = 1;");
+    assert_lint_fix::<PreferConst>("let a = 1, b = 2;", "const a = 1, b = 2;");
+    assert_lint_fix::<PreferConst>(
+      "for (let a of [1, 2]) { console.log(a); }",
+      "for (const a of [1, 2]) { console.log(a); }",
+    );
+    assert_lint_fix::<PreferConst>(
+      "let { a, b } = obj;",
+      "const { a, b } = obj;",
+    );
+
+    // Only some of the variables are never reassigned.
+    assert_lint_fix::<PreferConst>(
+      "let a = 1, b = 2; b = 3;",
+      "let a = 1, b = 2; b = 3;",
+    );
+    // `const` needs an initializer.
+    assert_lint_fix::<PreferConst>("let a; a = 0;", "let a; a = 0;");
+  }
 }
@@ -0,0 +1,165 @@
+// Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use super::Context;
+use super::LintRule;
+use crate::diagnostic::Severity;
+use crate::js_regex::ast::{walk_pattern, walk_term, Span, Term, Visit};
+use crate::js_regex::EcmaVersion;
+use crate::swc_util::collect_regexes;
+use swc_ecmascript::ast::Program;
+
+pub struct PreferNamedCaptureGroup;
+
+const CODE: &str = "prefer-named-capture-group";
+const HINT: &str =
+  "Name the group with `(?<name>...)`, or make it non-capturing with `(?:...)`";
+
+impl LintRule for PreferNamedCaptureGroup {
+  fn new() -> Box<Self> {
+    Box::new(PreferNamedCaptureGroup)
+  }
+
+  fn code(&self) -> &'static str {
+    CODE
+  }
+
+  fn default_severity(&self) -> Severity {
+    Severity::Warning
+  }
+
+  fn lint_program(&self, context: &mut Context, program: &Program) {
+    // Named groups were added in ES2018.
+    if context.ecma_version() < EcmaVersion::ES2018 {
+      return;
+    }
+
+    for regex in collect_regexes(program, &context.scope) {
+      let pattern = match regex.parse(context.ecma_version()) {
+        Some((pattern, _)) => pattern,
+        None => continue,
+      };
+      let mut finder = UnnamedGroupFinder { groups: vec![] };
+      walk_pattern(&mut finder, &pattern);
+
+      for group in finder.groups {
+        context.add_diagnostic_with_hint(
+          regex.span_of(group),
+          CODE,
+          format!(
+            "Capture group `{}` should be named or non-capturing",
+            &regex.pattern[group.start..group.end]
+          ),
+          HINT,
+        );
+      }
+    }
+  }
+
+  fn docs(&self) -> &'static str {
+    r#"Enforces naming the capturing groups of regular expressions
+
+Groups referred to by their number are hard to keep track of, and adding a
+group shifts the numbers of the following groups. Named groups make it clear
+what is captured, and groups which aren't referred to don't need to capture.
+
+### Invalid:
+```typescript
+/(\d{4})-(\d{2})/;
+new RegExp("(ba[rz])");
+```
+
+### Valid:
+```typescript
+/(?<year>\d{4})-(?<month>\d{2})/;
+/(?:ba[rz])/;
+new RegExp("(?<name>ba[rz])");
+```
+"#
+  }
+}
+
+struct UnnamedGroupFinder {
+  groups: Vec<Span>,
+}
+
+impl Visit for UnnamedGroupFinder {
+  fn visit_term(&mut self, n: &Term) {
+    if let Term::CapturingGroup(group) = n {
+      if group.name.is_none() {
+        self.groups.push(group.span);
+      }
+    }
+    walk_term(self, n);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::linter::LinterBuilder;
+
+  #[test]
+  fn prefer_named_capture_group_valid() {
+    assert_lint_ok! {
+      PreferNamedCaptureGroup,
+      "/foo/;",
+      "/(?<a>foo)/;",
+      "/(?:foo)(?=bar)(?<!baz)/;",
+      r#"/\(foo\)[(]/;"#,
+      r#"new RegExp("(?<a>foo)");"#,
+      r#"new RegExp(foo);"#,
+      r#"const RegExp = f; new RegExp("(foo)");"#,
+      "/(/;",
+    };
+  }
+
+  #[test]
+  fn prefer_named_capture_group_invalid() {
+    assert_lint_err! {
+      PreferNamedCaptureGroup,
+      "/(foo)/;": [
+        {
+          col: 1,
+          message: "Capture group `(foo)` should be named or non-capturing",
+          hint: HINT,
+        }
+      ],
+      "/(?<a>(b))+(c)/;": [
+        {
+          col: 6,
+          message: "Capture group `(b)` should be named or non-capturing",
+          hint: HINT,
+        },
+        {
+          col: 11,
+          message: "Capture group `(c)` should be named or non-capturing",
+          hint: HINT,
+        }
+      ],
+      r#"RegExp("a(b)");"#: [
+        {
+          col: 9,
+          message: "Capture group `(b)` should be named or non-capturing",
+          hint: HINT,
+        }
+      ],
+      r#"new RegExp("\ta(b)");"#: [
+        {
+          col: 0,
+          message: "Capture group `(b)` should be named or non-capturing",
+          hint: HINT,
+        }
+      ],
+    };
+  }
+
+  #[test]
+  fn prefer_named_capture_group_ecma_version() {
+    let mut linter = LinterBuilder::default()
+      .ecma_version(EcmaVersion::ES2017)
+      .rules(vec![PreferNamedCaptureGroup::new()])
+      .build();
+    let (_, diagnostics) =
+      linter.lint("lint_test.ts".to_string(), "/(foo)/;".to_string());
+    assert!(diagnostics.is_empty());
+  }
+}
@@ -1,11 +1,10 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use once_cell::sync::Lazy;
 use regex::Regex;
-use swc_ecmascript::ast::{TsModuleDecl, TsModuleName};
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
+use swc_ecmascript::ast::TsModuleName;
 
 pub struct PreferNamespaceKeyword;
 
@@ -25,40 +24,33 @@ impl LintRule for PreferNamespaceKeyword {
     CODE
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = PreferNamespaceKeywordVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct PreferNamespaceKeywordVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> PreferNamespaceKeywordVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for PreferNamespaceKeyword {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::TsModuleDecl]
   }
-}
 
-impl<'c> Visit for PreferNamespaceKeywordVisitor<'c> {
-  fn visit_ts_module_decl(
-    &mut self,
-    mod_decl: &TsModuleDecl,
-    parent: &dyn Node,
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
   ) {
+    let mod_decl = match node {
+      NodeRef::TsModuleDecl(mod_decl) => mod_decl,
+      _ => return,
+    };
     if let TsModuleName::Str(_) = &mod_decl.id {
       return;
     }
     static KEYWORD: Lazy<Regex> =
       Lazy::new(|| Regex::new(r"(declare\s)?(?P<keyword>\w+)").unwrap());
 
-    let snippet = self
-      .context
+    let snippet = context
       .source_map
       .span_to_snippet(mod_decl.span)
       .expect("error in load snippet");
@@ -66,12 +58,13 @@ impl<'c> Visit for PreferNamespaceKeywordVisitor<'c> {
     if let Some(capt) = KEYWORD.captures(&snippet) {
       let keyword = capt.name("keyword").unwrap().as_str();
       if keyword == "module" && !mod_decl.global {
-        self.context.add_diagnostic(mod_decl.span, CODE, MESSAGE)
+        context.add_diagnostic(mod_decl.span, CODE, MESSAGE)
       }
     }
-    for stmt in &mod_decl.body {
-      self.visit_ts_namespace_body(stmt, parent)
-    }
+  }
+
+  fn checks_types(&self) -> bool {
+    true
   }
 }
 
@@ -1,10 +1,7 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::ast::VarDecl;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 
 pub struct SingleVarDeclarator;
 
@@ -17,36 +14,30 @@ impl LintRule for SingleVarDeclarator {
     "single-var-declarator"
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = SingleVarDeclaratorVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct SingleVarDeclaratorVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> SingleVarDeclaratorVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for SingleVarDeclarator {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::VarDecl]
   }
-}
-
-impl<'c> Visit for SingleVarDeclaratorVisitor<'c> {
-  noop_visit_type!();
 
-  fn visit_var_decl(&mut self, var_decl: &VarDecl, _parent: &dyn Node) {
-    if var_decl.decls.len() > 1 {
-      self.context.add_diagnostic(
-        var_decl.span,
-        "single-var-declarator",
-        "Multiple variable declarators are not allowed",
-      );
+  fn check_node(
+    &self,
+    context: &mut Context,
+    _program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    if let NodeRef::VarDecl(var_decl) = node {
+      if var_decl.decls.len() > 1 {
+        context.add_diagnostic(
+          var_decl.span,
+          "single-var-declarator",
+          "Multiple variable declarators are not allowed",
+        );
+      }
     }
   }
 }
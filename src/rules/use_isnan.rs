@@ -1,9 +1,9 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::Context;
 use super::LintRule;
-use swc_ecmascript::visit::noop_visit_type;
-use swc_ecmascript::visit::Node;
-use swc_ecmascript::visit::Visit;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
+use swc_ecmascript::ast::{BinaryOp, Expr};
+use swc_ecmascript::utils::Value;
 
 pub struct UseIsNaN;
 
@@ -20,96 +20,87 @@ impl LintRule for UseIsNaN {
     "use-isnan"
   }
 
-  fn lint_program(
-    &self,
-    context: &mut Context,
-    program: &swc_ecmascript::ast::Program,
-  ) {
-    let mut visitor = UseIsNaNVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 }
 
-struct UseIsNaNVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> UseIsNaNVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+fn is_nan(
+  context: &Context,
+  program: &swc_ecmascript::ast::Program,
+  expr: &Expr,
+) -> bool {
+  if let Expr::Ident(ident) = expr {
+    if ident.sym == *"NaN" {
+      return true;
+    }
+  }
+  match context.const_evaluator(program).eval_number(expr) {
+    Value::Known(n) => n.is_nan(),
+    Value::Unknown => false,
   }
 }
 
-fn is_nan_identifier(ident: &swc_ecmascript::ast::Ident) -> bool {
-  ident.sym == *"NaN"
-}
-
-impl<'c> Visit for UseIsNaNVisitor<'c> {
-  noop_visit_type!();
+impl NodeRule for UseIsNaN {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::BinExpr, NodeKind::SwitchStmt]
+  }
 
-  fn visit_bin_expr(
-    &mut self,
-    bin_expr: &swc_ecmascript::ast::BinExpr,
-    _parent: &dyn Node,
+  fn check_node(
+    &self,
+    context: &mut Context,
+    program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
   ) {
-    if bin_expr.op == swc_ecmascript::ast::BinaryOp::EqEq
-      || bin_expr.op == swc_ecmascript::ast::BinaryOp::NotEq
-      || bin_expr.op == swc_ecmascript::ast::BinaryOp::EqEqEq
-      || bin_expr.op == swc_ecmascript::ast::BinaryOp::NotEqEq
-      || bin_expr.op == swc_ecmascript::ast::BinaryOp::Lt
-      || bin_expr.op == swc_ecmascript::ast::BinaryOp::LtEq
-      || bin_expr.op == swc_ecmascript::ast::BinaryOp::Gt
-      || bin_expr.op == swc_ecmascript::ast::BinaryOp::GtEq
-    {
-      if let swc_ecmascript::ast::Expr::Ident(ident) = &*bin_expr.left {
-        if is_nan_identifier(&ident) {
-          self.context.add_diagnostic(
-            bin_expr.span,
-            "use-isnan",
-            "Use the isNaN function to compare with NaN",
-          );
+    match node {
+      NodeRef::BinExpr(bin_expr) => {
+        if bin_expr.op == BinaryOp::EqEq
+          || bin_expr.op == BinaryOp::NotEq
+          || bin_expr.op == BinaryOp::EqEqEq
+          || bin_expr.op == BinaryOp::NotEqEq
+          || bin_expr.op == BinaryOp::Lt
+          || bin_expr.op == BinaryOp::LtEq
+          || bin_expr.op == BinaryOp::Gt
+          || bin_expr.op == BinaryOp::GtEq
+        {
+          if is_nan(context, program, &bin_expr.left) {
+            context.add_diagnostic(
+              bin_expr.span,
+              "use-isnan",
+              "Use the isNaN function to compare with NaN",
+            );
+          }
+          if is_nan(context, program, &bin_expr.right) {
+            context.add_diagnostic(
+              bin_expr.span,
+              "use-isnan",
+              "Use the isNaN function to compare with NaN",
+            );
+          }
         }
       }
-      if let swc_ecmascript::ast::Expr::Ident(ident) = &*bin_expr.right {
-        if is_nan_identifier(&ident) {
-          self.context.add_diagnostic(
-            bin_expr.span,
+      NodeRef::SwitchStmt(switch_stmt) => {
+        if is_nan(context, program, &switch_stmt.discriminant) {
+          context.add_diagnostic(
+            switch_stmt.span,
             "use-isnan",
-            "Use the isNaN function to compare with NaN",
+            "'switch(NaN)' can never match a case clause. Use Number.isNaN instead of the switch",
           );
         }
-      }
-    }
-  }
 
-  fn visit_switch_stmt(
-    &mut self,
-    switch_stmt: &swc_ecmascript::ast::SwitchStmt,
-    _parent: &dyn Node,
-  ) {
-    if let swc_ecmascript::ast::Expr::Ident(ident) = &*switch_stmt.discriminant
-    {
-      if is_nan_identifier(&ident) {
-        self.context.add_diagnostic(
-          switch_stmt.span,
-          "use-isnan",
-          "'switch(NaN)' can never match a case clause. Use Number.isNaN instead of the switch",
-        );
-      }
-    }
-
-    for case in &switch_stmt.cases {
-      if let Some(expr) = &case.test {
-        if let swc_ecmascript::ast::Expr::Ident(ident) = &**expr {
-          if is_nan_identifier(ident) {
-            self.context.add_diagnostic(
-              case.span,
-              "use-isnan",
-              "'case NaN' can never match. Use Number.isNaN before the switch",
-            );
+        for case in &switch_stmt.cases {
+          if let Some(expr) = &case.test {
+            if is_nan(context, program, expr) {
+              context.add_diagnostic(
+                case.span,
+                "use-isnan",
+                "'case NaN' can never match. Use Number.isNaN before the switch",
+              );
+            }
           }
         }
       }
+      _ => {}
     }
   }
 }
@@ -122,6 +113,9 @@ mod tests {
   #[test]
   fn use_isnan_invalid() {
     assert_lint_err::<UseIsNaN>("42 === NaN", 0);
+    assert_lint_err::<UseIsNaN>("x !== 0 / 0", 0);
+    assert_lint_err::<UseIsNaN>("const nan = NaN; x < nan", 17);
+    assert_lint_err::<UseIsNaN>("x == +'foo'", 0);
     assert_lint_err_on_line_n::<UseIsNaN>(
       r#"
 switch (NaN) {
@@ -133,5 +127,23 @@ switch (NaN) {
       "#,
       vec![(2, 0), (3, 2)],
     );
+    assert_lint_err_on_line_n::<UseIsNaN>(
+      r#"
+const NOT_A_NUMBER = Infinity - Infinity;
+switch (x) {
+  case NOT_A_NUMBER:
+    break;
+}
+      "#,
+      vec![(4, 2)],
+    );
+  }
+
+  #[test]
+  fn use_isnan_valid() {
+    assert_lint_ok::<UseIsNaN>("isNaN(x)");
+    assert_lint_ok::<UseIsNaN>("x === 0 / 1");
+    assert_lint_ok::<UseIsNaN>("let nan = NaN; nan = 1; x === nan");
+    assert_lint_ok::<UseIsNaN>("x === '' + NaN");
   }
 }
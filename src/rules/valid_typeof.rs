@@ -1,12 +1,13 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 use super::{Context, LintRule};
+use crate::const_eval::Constant;
+use crate::dispatcher::{NodeKind, NodeRef, NodeRule};
 use swc_common::Spanned;
+use swc_ecmascript::ast::BinExpr;
 use swc_ecmascript::ast::BinaryOp::{EqEq, EqEqEq, NotEq, NotEqEq};
-use swc_ecmascript::ast::Expr::{Lit, Unary};
-use swc_ecmascript::ast::Lit::Str;
+use swc_ecmascript::ast::Expr::Unary;
 use swc_ecmascript::ast::UnaryOp::TypeOf;
-use swc_ecmascript::ast::{BinExpr, Program};
-use swc_ecmascript::visit::{noop_visit_type, Node, Visit};
+use swc_ecmascript::utils::Value;
 
 pub struct ValidTypeof;
 
@@ -26,9 +27,8 @@ impl LintRule for ValidTypeof {
     CODE
   }
 
-  fn lint_program(&self, context: &mut Context, program: &Program) {
-    let mut visitor = ValidTypeofVisitor::new(context);
-    visitor.visit_program(program, program);
+  fn as_node_rule(&self) -> Option<&dyn NodeRule> {
+    Some(self)
   }
 
   fn docs(&self) -> &'static str {
@@ -44,7 +44,7 @@ When used with a value the `typeof` operator returns one of the following string
 - `"symbol"`
 - `"bigint"`
 
-This rule disallows comparison with anything other than one of these string literals when using the `typeof` operator, as this likely represents a typing mistake in the string. The rule also disallows comparing the result of a `typeof` operation with any non-string literal value, such as `undefined`, which can represent an inadvertent use of a keyword instead of a string. This includes comparing against string variables even if they contain one of the above values as this cannot be guaranteed. An exception to this is comparing the results of two `typeof` operations as these are both guaranteed to return on of the above strings.
+This rule disallows comparison with anything other than one of these string literals when using the `typeof` operator, as this likely represents a typing mistake in the string. The rule also disallows comparing the result of a `typeof` operation with any non-string literal value, such as `undefined`, which can represent an inadvertent use of a keyword instead of a string. This includes comparing against string variables even if they contain one of the above values as this cannot be guaranteed, unless they're `const` bindings of a constant string. An exception to this is comparing the results of two `typeof` operations as these are both guaranteed to return on of the above strings.
 
 ### Invalid:
 ```typescript
@@ -84,24 +84,29 @@ typeof baz === "string"
 ```
 ```typescript
 typeof bar === typeof qux
+```
+```typescript
+const STRING = "string";
+typeof baz === STRING
 ```"#
   }
 }
 
-struct ValidTypeofVisitor<'c> {
-  context: &'c mut Context,
-}
-
-impl<'c> ValidTypeofVisitor<'c> {
-  fn new(context: &'c mut Context) -> Self {
-    Self { context }
+impl NodeRule for ValidTypeof {
+  fn node_kinds(&self) -> &'static [NodeKind] {
+    &[NodeKind::BinExpr]
   }
-}
 
-impl<'c> Visit for ValidTypeofVisitor<'c> {
-  noop_visit_type!();
-
-  fn visit_bin_expr(&mut self, bin_expr: &BinExpr, _parent: &dyn Node) {
+  fn check_node(
+    &self,
+    context: &mut Context,
+    program: &swc_ecmascript::ast::Program,
+    node: NodeRef,
+  ) {
+    let bin_expr = match node {
+      NodeRef::BinExpr(bin_expr) => bin_expr,
+      _ => return,
+    };
     if !bin_expr.is_eq_expr() {
       return;
     }
@@ -110,17 +115,21 @@ impl<'c> Visit for ValidTypeofVisitor<'c> {
       (Unary(unary), operand) | (operand, Unary(unary))
         if unary.op == TypeOf =>
       {
-        match operand {
-          Unary(unary) if unary.op == TypeOf => {}
-          Lit(Str(str)) => {
-            if !is_valid_typeof_string(&str.value) {
-              self.context.add_diagnostic(str.span, CODE, MESSAGE);
-            }
-          }
-          _ => {
-            self.context.add_diagnostic(operand.span(), CODE, MESSAGE);
+        if let Unary(unary) = operand {
+          if unary.op == TypeOf {
+            return;
           }
         }
+        // Besides string literals, this accepts e.g. template literals and
+        // `const` bindings whose value is known to be a valid string.
+        let evaluator = context.const_evaluator(program);
+        let is_valid = match evaluator.eval(operand) {
+          Value::Known(Constant::String(s)) => is_valid_typeof_string(&s),
+          _ => false,
+        };
+        if !is_valid {
+          context.add_diagnostic(operand.span(), CODE, MESSAGE);
+        }
       }
       _ => {}
     }
@@ -164,6 +173,10 @@ typeof foo === "string"
 typeof bar == "undefined"
       "#,
       r#"typeof bar === typeof qux"#,
+      r#"typeof foo === `string`"#,
+      r#"typeof foo === ("big" + "int")"#,
+      r#"const NUMBER = "number"; typeof foo === NUMBER"#,
+      r#"const T = "func"; typeof foo !== `${T}tion`"#,
     };
   }
 
@@ -199,6 +212,22 @@ typeof bar == "undefined"
         col: 15,
         message: MESSAGE
       }],
+      r#"typeof foo == 5"#: [{
+        col: 14,
+        message: MESSAGE
+      }],
+      r#"typeof foo === `strnig`"#: [{
+        col: 15,
+        message: MESSAGE
+      }],
+      r#"const T = "nunber"; typeof foo === T"#: [{
+        col: 35,
+        message: MESSAGE
+      }],
+      r#"let T = "number"; typeof foo === T"#: [{
+        col: 33,
+        message: MESSAGE
+      }],
     }
   }
 }
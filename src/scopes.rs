@@ -1,12 +1,21 @@
 use std::collections::HashMap;
 use swc_atoms::JsWord;
-use swc_common::DUMMY_SP;
+use swc_common::{BytePos, Span, Spanned, SyntaxContext, DUMMY_SP};
 use swc_ecmascript::ast::{
-  ArrowExpr, BlockStmt, BlockStmtOrExpr, CatchClause, ClassDecl, ClassExpr,
-  DoWhileStmt, Expr, FnDecl, FnExpr, ForInStmt, ForOfStmt, ForStmt, Function,
-  Ident, ImportDefaultSpecifier, ImportNamedSpecifier, ImportStarAsSpecifier,
-  Invalid, Param, Pat, Program, SwitchStmt, VarDecl, VarDeclKind, WhileStmt,
-  WithStmt,
+  ArrowExpr, AssignExpr, AssignOp, AssignPatProp, BlockStmt, BlockStmtOrExpr,
+  CatchClause, ClassDecl, ClassExpr, ClassMethod, ClassProp, Constructor,
+  DoWhileStmt, ExportSpecifier, Expr, FnDecl, FnExpr, ForInStmt, ForOfStmt,
+  ForStmt, Function, GetterProp, Ident, ImportDecl, ImportSpecifier, Invalid,
+  JSXClosingElement, JSXElementName, JSXObject, MemberExpr, MethodProp,
+  NamedExport, Param, Pat, PatOrExpr, PrivateMethod, PrivateProp, Program,
+  Prop, PropName, SetterProp, SwitchStmt, TsCallSignatureDecl,
+  TsConditionalType, TsConstructSignatureDecl, TsConstructorType, TsEntityName,
+  TsEnumDecl, TsExprWithTypeArgs, TsFnType, TsImportEqualsDecl,
+  TsInterfaceDecl, TsMappedType, TsMethodSignature, TsModuleDecl, TsModuleName,
+  TsModuleRef, TsNamespaceDecl, TsParamProp, TsParamPropParam,
+  TsPropertySignature, TsTypeAliasDecl, TsTypeParam, TsTypeQuery,
+  TsTypeQueryExpr, TsTypeRef, UpdateExpr, VarDecl, VarDeclKind, VarDeclOrPat,
+  WhileStmt, WithStmt,
 };
 use swc_ecmascript::utils::find_ids;
 use swc_ecmascript::utils::ident::IdentLike;
@@ -15,17 +24,48 @@ use swc_ecmascript::visit::Node;
 use swc_ecmascript::visit::Visit;
 use swc_ecmascript::visit::VisitWith;
 
+/// Result of the scope analysis of a program.
+///
+/// It holds the tree of nested scopes, every binding declared in them and
+/// every reference to an identifier, resolved to its binding when possible.
+///
+/// TypeScript has separate namespaces for values and types, so `interface Foo`
+/// and `const Foo` are two different bindings. Some declarations like classes,
+/// enums and namespaces live in both of them.
 #[derive(Debug)]
 pub struct Scope {
-  vars: HashMap<Id, Var>,
+  bindings: Vec<Var>,
+  /// Indices of `bindings` in the value namespace
+  values: HashMap<Id, usize>,
+  /// Indices of `bindings` in the type namespace
+  types: HashMap<Id, usize>,
   symbols: HashMap<JsWord, Vec<Id>>,
+  scopes: Vec<ScopeNode>,
+  references: Vec<Reference>,
+  unresolved: Vec<usize>,
 }
 
 impl Scope {
   pub fn analyze(program: &Program) -> Self {
+    let (root_kind, root_span) = match program {
+      Program::Module(m) => (ScopeKind::Module, m.span),
+      Program::Script(s) => (ScopeKind::Script, s.span),
+    };
     let mut scope = Self {
-      vars: Default::default(),
+      bindings: Default::default(),
+      values: Default::default(),
+      types: Default::default(),
       symbols: Default::default(),
+      scopes: vec![ScopeNode {
+        kind: root_kind,
+        span: root_span,
+        parent: None,
+        children: vec![],
+        vars: vec![],
+        types: vec![],
+      }],
+      references: Default::default(),
+      unresolved: Default::default(),
     };
     let mut path = vec![];
 
@@ -34,20 +74,213 @@ impl Scope {
       &mut Analyzer {
         scope: &mut scope,
         path: &mut path,
+        current: ScopeId(0),
+        target: None,
       },
     );
 
+    scope.resolve_references();
     scope
   }
 
-  // Get all declarations with a symbol.
-  #[allow(dead_code)]
+  // Get all value declarations with a symbol.
   pub fn ids_with_symbol(&self, sym: &JsWord) -> Option<&Vec<Id>> {
     self.symbols.get(sym)
   }
 
+  /// Binding of `id` in the value namespace.
   pub fn var(&self, id: &Id) -> Option<&Var> {
-    self.vars.get(id)
+    self.values.get(id).map(|i| &self.bindings[*i])
+  }
+
+  /// Binding of `id` in the type namespace.
+  pub fn type_var(&self, id: &Id) -> Option<&Var> {
+    self.types.get(id).map(|i| &self.bindings[*i])
+  }
+
+  /// The scope of the whole program.
+  pub fn root(&self) -> ScopeId {
+    ScopeId(0)
+  }
+
+  pub fn scope(&self, id: ScopeId) -> &ScopeNode {
+    &self.scopes[id.0]
+  }
+
+  /// Innermost scope that contains `pos`.
+  pub fn scope_at(&self, pos: BytePos) -> ScopeId {
+    let mut current = self.root();
+    while let Some(child) =
+      self.scope(current).children.iter().find(|c| {
+        self.scope(**c).span.lo <= pos && pos < self.scope(**c).span.hi
+      })
+    {
+      current = *child;
+    }
+    current
+  }
+
+  /// Finds the value named `sym` visible from `scope`, looking through
+  /// the scope and its ancestors.
+  pub fn lookup(&self, scope: ScopeId, sym: &JsWord) -> Option<&Id> {
+    self.lookup_in(scope, sym, |node| &node.vars)
+  }
+
+  /// Same as `lookup`, but for the type namespace.
+  pub fn lookup_type(&self, scope: ScopeId, sym: &JsWord) -> Option<&Id> {
+    self.lookup_in(scope, sym, |node| &node.types)
+  }
+
+  fn lookup_in<F>(&self, scope: ScopeId, sym: &JsWord, ids: F) -> Option<&Id>
+  where
+    F: Fn(&ScopeNode) -> &Vec<Id>,
+  {
+    let mut current = Some(scope);
+    while let Some(id) = current {
+      let node = self.scope(id);
+      if let Some(found) = ids(node).iter().find(|i| &i.0 == sym) {
+        return Some(found);
+      }
+      current = node.parent;
+    }
+    None
+  }
+
+  /// All references found in the program, in source order.
+  pub fn references(&self) -> &[Reference] {
+    &self.references
+  }
+
+  /// References to the bindings of `id`, value references first and then
+  /// type references if it's declared in both namespaces separately.
+  pub fn references_to<'a>(
+    &'a self,
+    id: &Id,
+  ) -> impl Iterator<Item = &'a Reference> + 'a {
+    let value = self.values.get(id).copied();
+    let ty = self.types.get(id).copied().filter(|t| Some(*t) != value);
+    value.into_iter().chain(ty).flat_map(move |b| {
+      self.bindings[b]
+        .references
+        .iter()
+        .map(move |i| &self.references[*i])
+    })
+  }
+
+  /// References which don't point at any binding declared in the program,
+  /// e.g. globals.
+  pub fn unresolved_references(
+    &self,
+  ) -> impl Iterator<Item = &'_ Reference> + '_ {
+    self.unresolved.iter().map(move |i| &self.references[*i])
+  }
+
+  /// Binding which `reference` points at.
+  pub fn resolve(&self, reference: &Reference) -> Option<&Var> {
+    self.binding_index(reference).map(|i| &self.bindings[i])
+  }
+
+  fn binding_index(&self, reference: &Reference) -> Option<usize> {
+    if reference.kind == ReferenceKind::Type {
+      self.types.get(&reference.id).copied()
+    } else {
+      self.values.get(&reference.id).copied()
+    }
+  }
+
+  /// swc's resolver doesn't mark the names of namespaces, so a reference to
+  /// a namespace is resolved by its symbol to a namespace declared in one of
+  /// the scopes enclosing the reference.
+  fn namespace_index(&self, reference: &Reference) -> Option<usize> {
+    let id = (reference.id.0.clone(), SyntaxContext::empty());
+    let index = if reference.kind == ReferenceKind::Type {
+      *self.types.get(&id)?
+    } else {
+      *self.values.get(&id)?
+    };
+    let binding = &self.bindings[index];
+    if binding.kind != BindingKind::Namespace {
+      return None;
+    }
+    let mut scope = Some(reference.scope);
+    while let Some(current) = scope {
+      if current == binding.scope {
+        return Some(index);
+      }
+      scope = self.scopes[current.0].parent;
+    }
+    None
+  }
+
+  /// Resolves the `i`th reference if it refers to a namespace, making the
+  /// namespace known by the id it's referenced with.
+  fn resolve_namespace(&mut self, i: usize) -> Option<usize> {
+    let reference = &self.references[i];
+    let index = self.namespace_index(reference)?;
+    let id = reference.id.clone();
+    if reference.kind == ReferenceKind::Type {
+      self.types.insert(id, index);
+    } else {
+      self.values.insert(id, index);
+    }
+    Some(index)
+  }
+
+  fn resolve_references(&mut self) {
+    for i in 0..self.references.len() {
+      let index = match self.binding_index(&self.references[i]) {
+        Some(index) => Some(index),
+        None => self.resolve_namespace(i),
+      };
+      match index {
+        Some(b) => self.bindings[b].references.push(i),
+        None => self.unresolved.push(i),
+      }
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ScopeId(usize);
+
+/// A single scope in the scope tree.
+#[derive(Debug)]
+pub struct ScopeNode {
+  kind: ScopeKind,
+  span: Span,
+  parent: Option<ScopeId>,
+  children: Vec<ScopeId>,
+  vars: Vec<Id>,
+  types: Vec<Id>,
+}
+
+impl ScopeNode {
+  pub fn kind(&self) -> ScopeKind {
+    self.kind
+  }
+
+  pub fn span(&self) -> Span {
+    self.span
+  }
+
+  /// `None` for the root scope.
+  pub fn parent(&self) -> Option<ScopeId> {
+    self.parent
+  }
+
+  pub fn children(&self) -> &[ScopeId] {
+    &self.children
+  }
+
+  /// Values declared in this scope. `var` declarations belong to the
+  /// nearest function or root scope.
+  pub fn vars(&self) -> &[Id] {
+    &self.vars
+  }
+
+  /// Types declared in this scope.
+  pub fn types(&self) -> &[Id] {
+    &self.types
   }
 }
 
@@ -55,11 +288,14 @@ impl Scope {
 pub struct Var {
   path: Vec<ScopeKind>,
   kind: BindingKind,
+  scope: ScopeId,
+  span: Span,
+  references: Vec<usize>,
 }
 
 impl Var {
+  /// Kinds of the scopes the declaration is syntactically nested in.
   /// Empty path means root scope.
-  #[allow(dead_code)]
   pub fn path(&self) -> &[ScopeKind] {
     &self.path
   }
@@ -67,6 +303,16 @@ impl Var {
   pub fn kind(&self) -> BindingKind {
     self.kind
   }
+
+  /// Scope which owns the binding.
+  pub fn scope(&self) -> ScopeId {
+    self.scope
+  }
+
+  /// Span of the identifier that declares the binding.
+  pub fn span(&self) -> Span {
+    self.span
+  }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
@@ -79,11 +325,47 @@ pub enum BindingKind {
   Class,
   CatchClause,
   Import,
+  /// `import type { Foo } from "./foo.ts"`
+  TypeImport,
+  Enum,
+  Namespace,
+  Interface,
+  TypeAlias,
+  TypeParam,
+}
+
+impl BindingKind {
+  /// Returns true if the binding exists at runtime.
+  pub fn is_value(self) -> bool {
+    !matches!(
+      self,
+      BindingKind::TypeImport
+        | BindingKind::Interface
+        | BindingKind::TypeAlias
+        | BindingKind::TypeParam
+    )
+  }
+
+  /// Returns true if the binding can be used as a type.
+  pub fn is_type(self) -> bool {
+    matches!(
+      self,
+      BindingKind::Class
+        | BindingKind::Import
+        | BindingKind::TypeImport
+        | BindingKind::Enum
+        | BindingKind::Namespace
+        | BindingKind::Interface
+        | BindingKind::TypeAlias
+        | BindingKind::TypeParam
+    )
+  }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub enum ScopeKind {
-  // Module,
+  Module,
+  Script,
   Arrow,
   Function,
   Block,
@@ -92,59 +374,204 @@ pub enum ScopeKind {
   Switch,
   With,
   Catch,
+  Namespace,
+  /// Declaration with type parameters, e.g. an interface or a function type
+  Type,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ReferenceKind {
+  Read,
+  Write,
+  /// e.g. `a += 1` or `a++`
+  ReadWrite,
+  /// Reference in the type namespace, e.g. `Foo` in `let a: Foo`
+  Type,
+  /// Value referenced from a type, e.g. `a` in `let b: typeof a`
+  TypeQuery,
+}
+
+#[derive(Clone, Debug)]
+pub struct Reference {
+  id: Id,
+  span: Span,
+  kind: ReferenceKind,
+  scope: ScopeId,
+}
+
+impl Reference {
+  pub fn id(&self) -> &Id {
+    &self.id
+  }
+
+  pub fn span(&self) -> Span {
+    self.span
+  }
+
+  pub fn kind(&self) -> ReferenceKind {
+    self.kind
+  }
+
+  /// Scope the reference appears in.
+  pub fn scope(&self) -> ScopeId {
+    self.scope
+  }
+
+  /// Returns true if the value is read at runtime.
+  pub fn is_read(&self) -> bool {
+    matches!(self.kind, ReferenceKind::Read | ReferenceKind::ReadWrite)
+  }
+
+  pub fn is_write(&self) -> bool {
+    matches!(self.kind, ReferenceKind::Write | ReferenceKind::ReadWrite)
+  }
+
+  /// Returns true if the reference is only a part of a type.
+  pub fn is_type(&self) -> bool {
+    matches!(self.kind, ReferenceKind::Type | ReferenceKind::TypeQuery)
+  }
 }
 
 struct Analyzer<'a> {
   scope: &'a mut Scope,
   path: &'a mut Vec<ScopeKind>,
+  current: ScopeId,
+  /// Set while visiting the target of an assignment.
+  target: Option<ReferenceKind>,
 }
 
 impl Analyzer<'_> {
-  fn declare_id(&mut self, kind: BindingKind, i: Id) {
-    self.scope.vars.insert(
-      i.clone(),
-      Var {
-        kind,
-        path: self.path.clone(),
-      },
-    );
-    self.scope.symbols.entry(i.0.clone()).or_default().push(i);
+  fn declare_id(&mut self, kind: BindingKind, i: Id, span: Span) {
+    let scope = if kind == BindingKind::Var {
+      self.function_scope()
+    } else {
+      self.current
+    };
+    let index = self.scope.bindings.len();
+    self.scope.bindings.push(Var {
+      kind,
+      path: self.path.clone(),
+      scope,
+      span,
+      references: vec![],
+    });
+
+    let node = &mut self.scope.scopes[scope.0];
+    if kind.is_type() {
+      node.types.push(i.clone());
+      self.scope.types.insert(i.clone(), index);
+    }
+    if kind.is_value() {
+      node.vars.push(i.clone());
+      self.scope.values.insert(i.clone(), index);
+      self.scope.symbols.entry(i.0.clone()).or_default().push(i);
+    }
   }
 
   fn declare(&mut self, kind: BindingKind, i: &Ident) {
-    self.declare_id(kind, i.to_id());
+    self.declare_id(kind, i.to_id(), i.span);
   }
 
   fn declare_pat(&mut self, kind: BindingKind, pat: &Pat) {
-    let ids: Vec<Id> = find_ids(pat);
+    let idents: Vec<Ident> = find_ids(pat);
 
-    for id in ids {
-      self.declare_id(kind, id);
+    for ident in idents {
+      self.declare(kind, &ident);
     }
   }
 
-  fn visit_with_path<T>(&mut self, kind: ScopeKind, node: &T)
+  /// Nearest scope which `var` declarations are hoisted to.
+  fn function_scope(&self) -> ScopeId {
+    let mut id = self.current;
+    loop {
+      let node = &self.scope.scopes[id.0];
+      match (node.kind, node.parent) {
+        (ScopeKind::Function, _) | (ScopeKind::Arrow, _) | (_, None) => {
+          return id
+        }
+        (_, Some(parent)) => id = parent,
+      }
+    }
+  }
+
+  fn reference(&mut self, kind: ReferenceKind, i: &Ident) {
+    self.scope.references.push(Reference {
+      id: i.to_id(),
+      span: i.span,
+      kind,
+      scope: self.current,
+    });
+  }
+
+  fn visit_with_path<T>(&mut self, kind: ScopeKind, span: Span, node: &T)
   where
     T: 'static + for<'any> VisitWith<Analyzer<'any>>,
   {
-    self.path.push(kind);
-    node.visit_with(node, self);
-    self.path.pop();
+    self.with(kind, span, |a| node.visit_with(node, a));
   }
 
-  fn with<F>(&mut self, kind: ScopeKind, op: F)
+  fn with<F>(&mut self, kind: ScopeKind, span: Span, op: F)
   where
     F: FnOnce(&mut Analyzer),
   {
+    let id = ScopeId(self.scope.scopes.len());
+    self.scope.scopes.push(ScopeNode {
+      kind,
+      span,
+      parent: Some(self.current),
+      children: vec![],
+      vars: vec![],
+      types: vec![],
+    });
+    self.scope.scopes[self.current.0].children.push(id);
+
+    let parent = self.current;
+    self.current = id;
     self.path.push(kind);
     op(self);
     self.path.pop();
+    self.current = parent;
+  }
+
+  fn visit_target(&mut self, kind: ReferenceKind, target: &PatOrExpr) {
+    match target {
+      PatOrExpr::Pat(pat) => self.visit_target_pat(kind, pat),
+      PatOrExpr::Expr(expr) => match &**expr {
+        Expr::Ident(i) => self.reference(kind, i),
+        _ => expr.visit_with(target, self),
+      },
+    }
+  }
+
+  /// Adds a reference to the leftmost identifier of `Foo.Bar.Baz`.
+  fn reference_entity(&mut self, kind: ReferenceKind, name: &TsEntityName) {
+    let mut name = name;
+    while let TsEntityName::TsQualifiedName(q) = name {
+      name = &q.left;
+    }
+    if let TsEntityName::Ident(i) = name {
+      self.reference(kind, i);
+    }
+  }
+
+  fn visit_target_pat(&mut self, kind: ReferenceKind, pat: &Pat) {
+    let prev = self.target.replace(kind);
+    pat.visit_with(pat, self);
+    self.target = prev;
   }
 }
 
 impl Visit for Analyzer<'_> {
   fn visit_arrow_expr(&mut self, n: &ArrowExpr, _: &dyn Node) {
-    self.with(ScopeKind::Arrow, |a| n.visit_children_with(a))
+    self.with(ScopeKind::Arrow, n.span, |a| {
+      for param in &n.params {
+        a.declare_pat(BindingKind::Param, param);
+      }
+      n.type_params.visit_with(n, a);
+      n.params.visit_with(n, a);
+      n.return_type.visit_with(n, a);
+      n.body.visit_with(n, a);
+    })
   }
 
   /// Overriden not to add ScopeKind::Block
@@ -157,6 +584,8 @@ impl Visit for Analyzer<'_> {
 
   fn visit_var_decl(&mut self, n: &VarDecl, _: &dyn Node) {
     n.decls.iter().for_each(|v| {
+      // Default values, computed keys and type annotations of the pattern
+      v.name.visit_with(n, self);
       v.init.visit_with(n, self);
 
       // If the class name and the variable name are the same like `let Foo = class Foo {}`,
@@ -190,7 +619,9 @@ impl Visit for Analyzer<'_> {
   /// Overriden not to add ScopeKind::Block
   fn visit_function(&mut self, n: &Function, _: &dyn Node) {
     n.decorators.visit_with(n, self);
+    n.type_params.visit_with(n, self);
     n.params.visit_with(n, self);
+    n.return_type.visit_with(n, self);
 
     // Don't add ScopeKind::Block
     match &n.body {
@@ -202,7 +633,7 @@ impl Visit for Analyzer<'_> {
   fn visit_fn_decl(&mut self, n: &FnDecl, _: &dyn Node) {
     self.declare(BindingKind::Function, &n.ident);
 
-    self.visit_with_path(ScopeKind::Function, &n.function);
+    self.visit_with_path(ScopeKind::Function, n.function.span, &n.function);
   }
 
   fn visit_fn_expr(&mut self, n: &FnExpr, _: &dyn Node) {
@@ -210,57 +641,138 @@ impl Visit for Analyzer<'_> {
       self.declare(BindingKind::Function, ident);
     }
 
-    self.visit_with_path(ScopeKind::Function, &n.function);
+    self.visit_with_path(ScopeKind::Function, n.function.span, &n.function);
+  }
+
+  fn visit_class_method(&mut self, n: &ClassMethod, _: &dyn Node) {
+    n.key.visit_with(n, self);
+    self.visit_with_path(ScopeKind::Function, n.function.span, &n.function);
+  }
+
+  fn visit_private_method(&mut self, n: &PrivateMethod, _: &dyn Node) {
+    self.visit_with_path(ScopeKind::Function, n.function.span, &n.function);
+  }
+
+  fn visit_constructor(&mut self, n: &Constructor, _: &dyn Node) {
+    n.key.visit_with(n, self);
+    self.with(ScopeKind::Function, n.span, |a| {
+      n.params.visit_with(n, a);
+      if let Some(body) = &n.body {
+        body.stmts.visit_with(n, a);
+      }
+    })
+  }
+
+  fn visit_method_prop(&mut self, n: &MethodProp, _: &dyn Node) {
+    n.key.visit_with(n, self);
+    self.visit_with_path(ScopeKind::Function, n.function.span, &n.function);
+  }
+
+  fn visit_getter_prop(&mut self, n: &GetterProp, _: &dyn Node) {
+    n.key.visit_with(n, self);
+    self.with(ScopeKind::Function, n.span, |a| {
+      n.type_ann.visit_with(n, a);
+      if let Some(body) = &n.body {
+        body.stmts.visit_with(n, a);
+      }
+    })
+  }
+
+  fn visit_setter_prop(&mut self, n: &SetterProp, _: &dyn Node) {
+    n.key.visit_with(n, self);
+    self.with(ScopeKind::Function, n.span, |a| {
+      a.declare_pat(BindingKind::Param, &n.param);
+      n.param.visit_with(n, a);
+      if let Some(body) = &n.body {
+        body.stmts.visit_with(n, a);
+      }
+    })
   }
 
   fn visit_class_decl(&mut self, n: &ClassDecl, _: &dyn Node) {
     self.declare(BindingKind::Class, &n.ident);
 
-    self.visit_with_path(ScopeKind::Class, &n.class);
+    self.visit_with_path(ScopeKind::Class, n.class.span, &n.class);
+  }
+
+  fn visit_class_expr(&mut self, n: &ClassExpr, _: &dyn Node) {
+    self.with(ScopeKind::Class, n.class.span, |a| {
+      if let Some(ident) = &n.ident {
+        // Already declared by `let Foo = class Foo {}`
+        if a.scope.var(&ident.to_id()).is_none() {
+          a.declare(BindingKind::Class, ident);
+        }
+      }
+      n.class.visit_with(n, a);
+    })
+  }
+
+  fn visit_class_prop(&mut self, n: &ClassProp, _: &dyn Node) {
+    n.decorators.visit_with(n, self);
+    if n.computed {
+      n.key.visit_with(n, self);
+    }
+    n.value.visit_with(n, self);
+    n.type_ann.visit_with(n, self);
+  }
+
+  fn visit_private_prop(&mut self, n: &PrivateProp, _: &dyn Node) {
+    n.decorators.visit_with(n, self);
+    n.value.visit_with(n, self);
+    n.type_ann.visit_with(n, self);
   }
 
   fn visit_block_stmt(&mut self, n: &BlockStmt, _: &dyn Node) {
-    self.visit_with_path(ScopeKind::Block, &n.stmts)
+    self.visit_with_path(ScopeKind::Block, n.span, &n.stmts)
   }
 
   fn visit_catch_clause(&mut self, n: &CatchClause, _: &dyn Node) {
-    if let Some(pat) = &n.param {
-      self.declare_pat(BindingKind::CatchClause, pat);
-    }
-    self.visit_with_path(ScopeKind::Catch, &n.body)
+    self.with(ScopeKind::Catch, n.span, |a| {
+      if let Some(pat) = &n.param {
+        a.declare_pat(BindingKind::CatchClause, pat);
+      }
+      n.param.visit_with(n, a);
+      n.body.visit_with(n, a);
+    })
   }
 
   fn visit_param(&mut self, n: &Param, _: &dyn Node) {
     self.declare_pat(BindingKind::Param, &n.pat);
+    n.visit_children_with(self);
   }
 
-  fn visit_import_named_specifier(
-    &mut self,
-    n: &ImportNamedSpecifier,
-    _: &dyn Node,
-  ) {
-    self.declare(BindingKind::Import, &n.local);
-  }
-
-  fn visit_import_default_specifier(
-    &mut self,
-    n: &ImportDefaultSpecifier,
-    _: &dyn Node,
-  ) {
-    self.declare(BindingKind::Import, &n.local);
+  fn visit_ts_param_prop(&mut self, n: &TsParamProp, _: &dyn Node) {
+    n.decorators.visit_with(n, self);
+    match &n.param {
+      TsParamPropParam::Ident(i) => {
+        self.declare(BindingKind::Param, i);
+        i.type_ann.visit_with(n, self);
+      }
+      TsParamPropParam::Assign(assign) => {
+        self.declare_pat(BindingKind::Param, &assign.left);
+        assign.visit_children_with(self);
+      }
+    }
   }
 
-  fn visit_import_star_as_specifier(
-    &mut self,
-    n: &ImportStarAsSpecifier,
-    _: &dyn Node,
-  ) {
-    self.declare(BindingKind::Import, &n.local);
+  fn visit_import_decl(&mut self, n: &ImportDecl, _: &dyn Node) {
+    let kind = if n.type_only {
+      BindingKind::TypeImport
+    } else {
+      BindingKind::Import
+    };
+    for specifier in &n.specifiers {
+      match specifier {
+        ImportSpecifier::Named(s) => self.declare(kind, &s.local),
+        ImportSpecifier::Default(s) => self.declare(kind, &s.local),
+        ImportSpecifier::Namespace(s) => self.declare(kind, &s.local),
+      }
+    }
   }
 
   fn visit_with_stmt(&mut self, n: &WithStmt, _: &dyn Node) {
     n.obj.visit_with(n, self);
-    self.with(ScopeKind::With, |a| n.body.visit_children_with(a))
+    self.with(ScopeKind::With, n.span, |a| n.body.visit_children_with(a))
   }
 
   fn visit_for_stmt(&mut self, n: &ForStmt, _: &dyn Node) {
@@ -268,45 +780,294 @@ impl Visit for Analyzer<'_> {
     n.update.visit_with(n, self);
     n.test.visit_with(n, self);
 
-    self.visit_with_path(ScopeKind::Loop, &n.body);
+    self.visit_with_path(ScopeKind::Loop, n.body.span(), &n.body);
   }
 
   fn visit_for_of_stmt(&mut self, n: &ForOfStmt, _: &dyn Node) {
-    n.left.visit_with(n, self);
+    match &n.left {
+      VarDeclOrPat::VarDecl(decl) => decl.visit_with(n, self),
+      VarDeclOrPat::Pat(pat) => {
+        self.visit_target_pat(ReferenceKind::Write, pat)
+      }
+    }
     n.right.visit_with(n, self);
 
-    self.visit_with_path(ScopeKind::Loop, &n.body);
+    self.visit_with_path(ScopeKind::Loop, n.body.span(), &n.body);
   }
 
   fn visit_for_in_stmt(&mut self, n: &ForInStmt, _: &dyn Node) {
-    n.left.visit_with(n, self);
+    match &n.left {
+      VarDeclOrPat::VarDecl(decl) => decl.visit_with(n, self),
+      VarDeclOrPat::Pat(pat) => {
+        self.visit_target_pat(ReferenceKind::Write, pat)
+      }
+    }
     n.right.visit_with(n, self);
 
-    self.visit_with_path(ScopeKind::Loop, &n.body);
+    self.visit_with_path(ScopeKind::Loop, n.body.span(), &n.body);
   }
 
   fn visit_do_while_stmt(&mut self, n: &DoWhileStmt, _: &dyn Node) {
     n.test.visit_with(n, self);
 
-    self.visit_with_path(ScopeKind::Loop, &n.body);
+    self.visit_with_path(ScopeKind::Loop, n.body.span(), &n.body);
   }
 
   fn visit_while_stmt(&mut self, n: &WhileStmt, _: &dyn Node) {
     n.test.visit_with(n, self);
 
-    self.visit_with_path(ScopeKind::Loop, &n.body);
+    self.visit_with_path(ScopeKind::Loop, n.body.span(), &n.body);
   }
 
   fn visit_switch_stmt(&mut self, n: &SwitchStmt, _: &dyn Node) {
     n.discriminant.visit_with(n, self);
 
-    self.visit_with_path(ScopeKind::Switch, &n.cases);
+    self.visit_with_path(ScopeKind::Switch, n.span, &n.cases);
+  }
+
+  fn visit_ts_enum_decl(&mut self, n: &TsEnumDecl, _: &dyn Node) {
+    self.declare(BindingKind::Enum, &n.id);
+    n.members.visit_with(n, self);
+  }
+
+  fn visit_ts_module_decl(&mut self, n: &TsModuleDecl, _: &dyn Node) {
+    if let TsModuleName::Ident(i) = &n.id {
+      self.declare(BindingKind::Namespace, i);
+    }
+    self.with(ScopeKind::Namespace, n.span, |a| n.body.visit_with(n, a))
+  }
+
+  fn visit_ts_namespace_decl(&mut self, n: &TsNamespaceDecl, _: &dyn Node) {
+    self.declare(BindingKind::Namespace, &n.id);
+    self.with(ScopeKind::Namespace, n.span, |a| n.body.visit_with(n, a))
+  }
+
+  fn visit_ts_import_equals_decl(
+    &mut self,
+    n: &TsImportEqualsDecl,
+    _: &dyn Node,
+  ) {
+    self.declare(BindingKind::Import, &n.id);
+    if let TsModuleRef::TsEntityName(name) = &n.module_ref {
+      self.reference_entity(ReferenceKind::Read, name);
+    }
+  }
+
+  fn visit_expr(&mut self, n: &Expr, _: &dyn Node) {
+    let prev = self.target.take();
+    match n {
+      Expr::Ident(i) => self.reference(ReferenceKind::Read, i),
+      _ => n.visit_children_with(self),
+    }
+    self.target = prev;
+  }
+
+  fn visit_pat(&mut self, n: &Pat, _: &dyn Node) {
+    match (n, self.target) {
+      (Pat::Ident(i), Some(kind)) => {
+        self.reference(kind, i);
+        i.type_ann.visit_with(n, self);
+      }
+      (Pat::Ident(i), None) => i.type_ann.visit_with(n, self),
+      (Pat::Expr(e), Some(kind)) => match &**e {
+        Expr::Ident(i) => self.reference(kind, i),
+        _ => e.visit_with(n, self),
+      },
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  fn visit_assign_pat_prop(&mut self, n: &AssignPatProp, _: &dyn Node) {
+    if let Some(kind) = self.target {
+      self.reference(kind, &n.key);
+    }
+    n.value.visit_with(n, self);
+  }
+
+  fn visit_assign_expr(&mut self, n: &AssignExpr, _: &dyn Node) {
+    let kind = if n.op == AssignOp::Assign {
+      ReferenceKind::Write
+    } else {
+      ReferenceKind::ReadWrite
+    };
+    self.visit_target(kind, &n.left);
+    n.right.visit_with(n, self);
+  }
+
+  fn visit_update_expr(&mut self, n: &UpdateExpr, _: &dyn Node) {
+    match &*n.arg {
+      Expr::Ident(i) => self.reference(ReferenceKind::ReadWrite, i),
+      _ => n.arg.visit_with(n, self),
+    }
+  }
+
+  fn visit_member_expr(&mut self, n: &MemberExpr, _: &dyn Node) {
+    n.obj.visit_with(n, self);
+    if n.computed {
+      n.prop.visit_with(n, self);
+    }
+  }
+
+  fn visit_prop_name(&mut self, n: &PropName, _: &dyn Node) {
+    if let PropName::Computed(computed) = n {
+      computed.visit_with(n, self);
+    }
+  }
+
+  fn visit_prop(&mut self, n: &Prop, _: &dyn Node) {
+    match n {
+      Prop::Shorthand(i) => self.reference(ReferenceKind::Read, i),
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  fn visit_named_export(&mut self, n: &NamedExport, _: &dyn Node) {
+    // Re-exports don't refer to local bindings
+    if n.src.is_some() {
+      return;
+    }
+    let kind = if n.type_only {
+      ReferenceKind::Type
+    } else {
+      ReferenceKind::Read
+    };
+    for specifier in &n.specifiers {
+      if let ExportSpecifier::Named(named) = specifier {
+        self.reference(kind, &named.orig);
+      }
+    }
+  }
+
+  fn visit_jsx_element_name(&mut self, n: &JSXElementName, _: &dyn Node) {
+    match n {
+      // Lower case names are intrinsic elements like `<div>`
+      JSXElementName::Ident(i) => {
+        if !i.sym.starts_with(|c: char| c.is_ascii_lowercase()) {
+          self.reference(ReferenceKind::Read, i);
+        }
+      }
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  fn visit_jsx_object(&mut self, n: &JSXObject, _: &dyn Node) {
+    match n {
+      JSXObject::Ident(i) => self.reference(ReferenceKind::Read, i),
+      _ => n.visit_children_with(self),
+    }
+  }
+
+  /// The name of the closing element is the same reference as the opening one
+  fn visit_jsx_closing_element(&mut self, _: &JSXClosingElement, _: &dyn Node) {
+  }
+
+  fn visit_ts_type_ref(&mut self, n: &TsTypeRef, _: &dyn Node) {
+    self.reference_entity(ReferenceKind::Type, &n.type_name);
+    n.type_params.visit_with(n, self);
+  }
+
+  fn visit_ts_type_query(&mut self, n: &TsTypeQuery, _: &dyn Node) {
+    if let TsTypeQueryExpr::TsEntityName(name) = &n.expr_name {
+      self.reference_entity(ReferenceKind::TypeQuery, name);
+    }
+  }
+
+  fn visit_ts_expr_with_type_args(
+    &mut self,
+    n: &TsExprWithTypeArgs,
+    _: &dyn Node,
+  ) {
+    self.reference_entity(ReferenceKind::Type, &n.expr);
+    n.type_args.visit_with(n, self);
+  }
+
+  fn visit_ts_type_param(&mut self, n: &TsTypeParam, _: &dyn Node) {
+    self.declare(BindingKind::TypeParam, &n.name);
+    n.constraint.visit_with(n, self);
+    n.default.visit_with(n, self);
+  }
+
+  fn visit_ts_interface_decl(&mut self, n: &TsInterfaceDecl, _: &dyn Node) {
+    self.declare(BindingKind::Interface, &n.id);
+    self.with(ScopeKind::Type, n.span, |a| {
+      n.type_params.visit_with(n, a);
+      n.extends.visit_with(n, a);
+      n.body.visit_with(n, a);
+    })
+  }
+
+  fn visit_ts_type_alias_decl(&mut self, n: &TsTypeAliasDecl, _: &dyn Node) {
+    self.declare(BindingKind::TypeAlias, &n.id);
+    self.with(ScopeKind::Type, n.span, |a| {
+      n.type_params.visit_with(n, a);
+      n.type_ann.visit_with(n, a);
+    })
+  }
+
+  fn visit_ts_property_signature(
+    &mut self,
+    n: &TsPropertySignature,
+    _: &dyn Node,
+  ) {
+    if n.computed {
+      n.key.visit_with(n, self);
+    }
+    self.with(ScopeKind::Type, n.span, |a| {
+      n.type_params.visit_with(n, a);
+      n.params.visit_with(n, a);
+      n.type_ann.visit_with(n, a);
+    });
+    n.init.visit_with(n, self);
+  }
+
+  fn visit_ts_method_signature(&mut self, n: &TsMethodSignature, _: &dyn Node) {
+    if n.computed {
+      n.key.visit_with(n, self);
+    }
+    self.with(ScopeKind::Type, n.span, |a| {
+      n.type_params.visit_with(n, a);
+      n.params.visit_with(n, a);
+      n.type_ann.visit_with(n, a);
+    })
+  }
+
+  fn visit_ts_fn_type(&mut self, n: &TsFnType, _: &dyn Node) {
+    self.with(ScopeKind::Type, n.span, |a| n.visit_children_with(a))
+  }
+
+  fn visit_ts_constructor_type(&mut self, n: &TsConstructorType, _: &dyn Node) {
+    self.with(ScopeKind::Type, n.span, |a| n.visit_children_with(a))
+  }
+
+  fn visit_ts_call_signature_decl(
+    &mut self,
+    n: &TsCallSignatureDecl,
+    _: &dyn Node,
+  ) {
+    self.with(ScopeKind::Type, n.span, |a| n.visit_children_with(a))
+  }
+
+  fn visit_ts_construct_signature_decl(
+    &mut self,
+    n: &TsConstructSignatureDecl,
+    _: &dyn Node,
+  ) {
+    self.with(ScopeKind::Type, n.span, |a| n.visit_children_with(a))
+  }
+
+  fn visit_ts_mapped_type(&mut self, n: &TsMappedType, _: &dyn Node) {
+    self.with(ScopeKind::Type, n.span, |a| n.visit_children_with(a))
+  }
+
+  /// Creates a scope for `infer` type parameters
+  fn visit_ts_conditional_type(&mut self, n: &TsConditionalType, _: &dyn Node) {
+    self.with(ScopeKind::Type, n.span, |a| n.visit_children_with(a))
   }
 }
 
 #[cfg(test)]
 mod tests {
-  use super::{BindingKind, Scope, ScopeKind, Var};
+  use super::{BindingKind, ReferenceKind, Scope, ScopeKind, Var};
   use crate::ast_parser;
   use crate::ast_parser::AstParser;
   use swc_ecmascript::utils::Id;
@@ -315,7 +1076,8 @@ mod tests {
     let ast_parser = AstParser::new();
     let syntax = ast_parser::get_default_ts_config();
     let (program, _comments) = ast_parser
-      .parse_program("file_name.ts", syntax, source_code)
+      .parse_program("file_name.ts", syntax, source_code.to_string())
+      .program
       .unwrap();
 
     Scope::analyze(&program)
@@ -338,6 +1100,13 @@ mod tests {
     scope.var(&id(scope, symbol)).unwrap()
   }
 
+  fn reference_kinds(scope: &Scope, symbol: &str) -> Vec<ReferenceKind> {
+    scope
+      .references_to(&id(scope, symbol))
+      .map(|r| r.kind())
+      .collect()
+  }
+
   #[test]
   fn scopes() {
     let source_code = r#"
@@ -378,6 +1147,243 @@ try {
     assert_eq!(var(&scope, "Foo").path(), &[]);
 
     assert_eq!(var(&scope, "e").kind(), BindingKind::CatchClause);
-    assert_eq!(var(&scope, "e").path(), &[]);
+    assert_eq!(var(&scope, "e").path(), &[ScopeKind::Catch]);
+  }
+
+  #[test]
+  fn scope_tree() {
+    let source_code = r#"
+export function f() {
+  if (x) {
+    var v = 1;
+    let l = 2;
+  }
+  const g = () => v;
+}
+"#;
+    let scope = test_scope(source_code);
+    let root = scope.scope(scope.root());
+    assert_eq!(root.kind(), ScopeKind::Module);
+    assert_eq!(root.children().len(), 1);
+
+    let function = root.children()[0];
+    assert_eq!(scope.scope(function).kind(), ScopeKind::Function);
+    assert_eq!(scope.scope(function).parent(), Some(scope.root()));
+    assert_eq!(
+      scope
+        .scope(function)
+        .children()
+        .iter()
+        .map(|c| scope.scope(*c).kind())
+        .collect::<Vec<_>>(),
+      vec![ScopeKind::Block, ScopeKind::Arrow]
+    );
+
+    // `var` is hoisted to the function scope, `let` is not
+    assert_eq!(var(&scope, "v").scope(), function);
+    assert_ne!(var(&scope, "l").scope(), function);
+
+    let arrow = scope.scope(function).children()[1];
+    assert_eq!(scope.lookup(arrow, &"v".into()), Some(&id(&scope, "v")));
+    assert_eq!(scope.lookup(scope.root(), &"v".into()), None);
+
+    let pos = var(&scope, "l").span().lo;
+    assert_eq!(scope.scope(scope.scope_at(pos)).kind(), ScopeKind::Block);
+  }
+
+  #[test]
+  fn method_scopes() {
+    let source_code = r#"
+class A {
+  constructor(c) { var cv; }
+  m(p) { var v; let l; }
+  #pm(pp) { var pv; }
+}
+export const o = {
+  om(op) { var ov; },
+  get g() { var gv; return gv; },
+  set s(sp) { var sv; },
+};
+"#;
+    let scope = test_scope(source_code);
+    let cases = [
+      (Some("c"), "cv", ScopeKind::Class),
+      (Some("p"), "v", ScopeKind::Class),
+      (Some("pp"), "pv", ScopeKind::Class),
+      (Some("op"), "ov", ScopeKind::Module),
+      (None, "gv", ScopeKind::Module),
+      (Some("sp"), "sv", ScopeKind::Module),
+    ];
+    for (param, local, parent) in cases.iter() {
+      let function = var(&scope, local).scope();
+      let node = scope.scope(function);
+      assert_eq!(node.kind(), ScopeKind::Function, "{}", local);
+      assert_eq!(scope.scope(node.parent().unwrap()).kind(), *parent);
+      assert!(node.vars().contains(&id(&scope, local)));
+      assert_eq!(scope.lookup(scope.root(), &(*local).into()), None);
+
+      let pos = var(&scope, local).span().lo;
+      assert_eq!(scope.scope_at(pos), function);
+      if let Some(param) = param {
+        assert_eq!(var(&scope, param).kind(), BindingKind::Param);
+        assert!(node.vars().contains(&id(&scope, param)));
+        assert_eq!(
+          scope.lookup(function, &(*param).into()),
+          Some(&id(&scope, param))
+        );
+      }
+    }
+
+    // `let` in a method belongs to the method's scope too
+    assert_eq!(var(&scope, "l").scope(), var(&scope, "v").scope());
+    assert_eq!(
+      var(&scope, "v").path(),
+      &[ScopeKind::Class, ScopeKind::Function]
+    );
+    assert_eq!(scope.unresolved_references().count(), 0);
+  }
+
+  #[test]
+  fn references() {
+    let source_code = r#"
+let a = 1;
+a = 2;
+a += 3;
+a++;
+[a] = [4];
+({ a } = { a });
+for (a of []) {}
+console.log(a, b.c, obj[a]);
+const f = (p = a) => p;
+export { a };
+"#;
+    let scope = test_scope(source_code);
+    assert_eq!(
+      reference_kinds(&scope, "a"),
+      vec![
+        ReferenceKind::Write,
+        ReferenceKind::ReadWrite,
+        ReferenceKind::ReadWrite,
+        ReferenceKind::Write,
+        ReferenceKind::Write,
+        ReferenceKind::Read,
+        ReferenceKind::Write,
+        ReferenceKind::Read,
+        ReferenceKind::Read,
+        ReferenceKind::Read,
+        ReferenceKind::Read,
+      ]
+    );
+    assert_eq!(reference_kinds(&scope, "p"), vec![ReferenceKind::Read]);
+    assert_eq!(var(&scope, "p").kind(), BindingKind::Param);
+
+    let mut unresolved: Vec<String> = scope
+      .unresolved_references()
+      .map(|r| r.id().0.to_string())
+      .collect();
+    unresolved.sort();
+    assert_eq!(unresolved, vec!["b", "console", "obj"]);
+
+    for reference in scope.references() {
+      match scope.resolve(reference) {
+        Some(v) => assert!(
+          v.kind() == BindingKind::Let || v.kind() == BindingKind::Param
+        ),
+        None => assert!(reference.is_read()),
+      }
+    }
+  }
+
+  #[test]
+  fn typescript_declarations() {
+    let source_code = r#"
+interface Foo { a: Baz }
+type Baz = Foo;
+enum E { A }
+namespace N { export const x = 1; }
+class C implements Foo {
+  constructor(private p: Baz) {}
+  m(): E { return E.A; }
+}
+let v: Foo = E.A as Baz;
+"#;
+    let scope = test_scope(source_code);
+    assert_eq!(scope.unresolved_references().count(), 0);
+    assert_eq!(var(&scope, "E").kind(), BindingKind::Enum);
+    assert_eq!(var(&scope, "N").kind(), BindingKind::Namespace);
+    assert_eq!(var(&scope, "p").kind(), BindingKind::Param);
+    assert_eq!(
+      reference_kinds(&scope, "E"),
+      vec![
+        ReferenceKind::Type,
+        ReferenceKind::Read,
+        ReferenceKind::Read
+      ]
+    );
+  }
+
+  #[test]
+  fn type_and_value_namespaces() {
+    let source_code = r#"
+import type { T } from "./t.ts";
+import { U } from "./u.ts";
+interface Foo { a: T }
+const Foo = 1;
+type Bar<P> = P | U;
+class C<Q> implements Foo { b: Q = Foo as any; }
+let a: Foo = { a: 1 };
+let b: typeof a = a;
+export type { Bar };
+"#;
+    let scope = test_scope(source_code);
+    let foo = id(&scope, "Foo");
+    assert_eq!(scope.var(&foo).unwrap().kind(), BindingKind::Const);
+    assert_eq!(scope.type_var(&foo).unwrap().kind(), BindingKind::Interface);
+    assert_eq!(
+      reference_kinds(&scope, "Foo"),
+      vec![
+        ReferenceKind::Read,
+        ReferenceKind::Type,
+        ReferenceKind::Type
+      ]
+    );
+
+    let t = scope
+      .lookup_type(scope.root(), &"T".into())
+      .unwrap()
+      .clone();
+    assert_eq!(scope.type_var(&t).unwrap().kind(), BindingKind::TypeImport);
+    assert!(scope.var(&t).is_none());
+    assert_eq!(scope.lookup(scope.root(), &"T".into()), None);
+
+    let u = scope
+      .lookup_type(scope.root(), &"U".into())
+      .unwrap()
+      .clone();
+    assert_eq!(scope.type_var(&u).unwrap().kind(), BindingKind::Import);
+    assert!(scope.var(&u).is_some());
+
+    let bar = scope
+      .lookup_type(scope.root(), &"Bar".into())
+      .unwrap()
+      .clone();
+    assert_eq!(
+      scope
+        .references_to(&bar)
+        .map(|r| r.kind())
+        .collect::<Vec<_>>(),
+      vec![ReferenceKind::Type]
+    );
+    assert!(scope.references_to(&bar).all(|r| r.is_type()));
+
+    assert_eq!(
+      reference_kinds(&scope, "a"),
+      vec![ReferenceKind::TypeQuery, ReferenceKind::Read]
+    );
+
+    // Type parameters are not visible outside of their declarations
+    assert_eq!(scope.lookup_type(scope.root(), &"P".into()), None);
+    assert_eq!(scope.lookup_type(scope.root(), &"Q".into()), None);
+    assert_eq!(scope.unresolved_references().count(), 0);
   }
 }
@@ -1,18 +1,24 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
+use crate::js_regex::ast::{Flags, Pattern, Span as RegexSpan};
+use crate::js_regex::{EcmaRegexParser, EcmaVersion};
 use crate::scopes::Scope;
+use swc_common::{BytePos, Span};
 use swc_ecmascript::ast::{
-  ComputedPropName, Expr, ExprOrSpread, Ident, Lit, MemberExpr, PatOrExpr,
-  PrivateName, Prop, PropName, PropOrSpread, Str, Tpl,
+  CallExpr, ComputedPropName, Expr, ExprOrSpread, ExprOrSuper, Ident, Lit,
+  MemberExpr, NewExpr, PatOrExpr, PrivateName, Program, Prop, PropName,
+  PropOrSpread, Regex, Str, Tpl,
 };
 use swc_ecmascript::utils::{find_ids, ident::IdentLike};
+use swc_ecmascript::visit::{noop_visit_type, Node, VisitAll, VisitAllWith};
 
-/// Extracts regex string from an expression, using ScopeManager.
+/// Extracts regex string and flags from an expression, using ScopeManager.
 /// If the passed expression is not regular expression, this will return `None`.
+/// Flags which aren't a string literal are returned as empty.
 pub(crate) fn extract_regex(
   scope: &Scope,
   expr_ident: &Ident,
   expr_args: &[ExprOrSpread],
-) -> Option<String> {
+) -> Option<(String, String)> {
   if expr_ident.sym != *"RegExp" {
     return None;
   }
@@ -21,16 +27,131 @@ pub(crate) fn extract_regex(
     return None;
   }
 
+  let flags = match expr_args.get(1).map(|arg| &*arg.expr) {
+    Some(Expr::Lit(Lit::Str(literal))) => Some(literal.value.to_string()),
+    _ => None,
+  };
   match expr_args.get(0) {
     Some(first_arg) => match &*first_arg.expr {
-      Expr::Lit(Lit::Str(literal)) => Some(literal.value.to_string()),
-      Expr::Lit(Lit::Regex(regex)) => Some(regex.exp.to_string()),
+      Expr::Lit(Lit::Str(literal)) => {
+        Some((literal.value.to_string(), flags.unwrap_or_default()))
+      }
+      Expr::Lit(Lit::Regex(regex)) => Some((
+        regex.exp.to_string(),
+        flags.unwrap_or_else(|| regex.flags.to_string()),
+      )),
       _ => None,
     },
     None => None,
   }
 }
 
+/// Position of the pattern passed to `RegExp` in the source, if it's written
+/// as is, i.e. in a regex literal or in a string literal without escapes.
+fn regex_pattern_start(expr_args: &[ExprOrSpread]) -> Option<BytePos> {
+  match &*expr_args.first()?.expr {
+    Expr::Lit(Lit::Str(literal)) if !literal.has_escape => {
+      Some(literal.span.lo + BytePos(1))
+    }
+    Expr::Lit(Lit::Regex(regex)) => Some(regex.span.lo + BytePos(1)),
+    _ => None,
+  }
+}
+
+/// A regex in the source, either a regex literal or a pattern passed to
+/// `RegExp`.
+pub(crate) struct RegexSource {
+  pub pattern: String,
+  pub flags: String,
+  /// Span of the regex literal or of the `RegExp` call.
+  pub span: Span,
+  /// Position of the pattern in the source, if it's written as is.
+  pub pattern_start: Option<BytePos>,
+}
+
+impl RegexSource {
+  /// Parses the regex, returning `None` if it's invalid. Invalid regexes are
+  /// reported by `no-invalid-regexp`.
+  pub fn parse(&self, ecma_version: EcmaVersion) -> Option<(Pattern, Flags)> {
+    let mut parser = EcmaRegexParser::new(ecma_version);
+    let flags = parser.parse_flags(&self.flags).ok()?;
+    let pattern = parser.parse_pattern(&self.pattern, flags).ok()?;
+    Some((pattern, flags))
+  }
+
+  /// Converts a span relative to the pattern to a span in the source. Falls
+  /// back to the span of the whole regex if the pattern isn't written as is.
+  pub fn span_of(&self, span: RegexSpan) -> Span {
+    match self.pattern_start {
+      Some(start) => Span::new(
+        start + BytePos(span.start as u32),
+        start + BytePos(span.end as u32),
+        Default::default(),
+      ),
+      None => self.span,
+    }
+  }
+}
+
+/// Collects the regex literals and `RegExp` calls of a program, in source
+/// order.
+pub(crate) fn collect_regexes(
+  program: &Program,
+  scope: &Scope,
+) -> Vec<RegexSource> {
+  let mut collector = RegexCollector {
+    scope,
+    regexes: vec![],
+  };
+  program.visit_all_with(program, &mut collector);
+  collector.regexes
+}
+
+struct RegexCollector<'a> {
+  scope: &'a Scope,
+  regexes: Vec<RegexSource>,
+}
+
+impl RegexCollector<'_> {
+  fn check_call(&mut self, callee: &Expr, args: &[ExprOrSpread], span: Span) {
+    if let Expr::Ident(ident) = callee {
+      if let Some((pattern, flags)) = extract_regex(self.scope, ident, args) {
+        self.regexes.push(RegexSource {
+          pattern,
+          flags,
+          span,
+          pattern_start: regex_pattern_start(args),
+        });
+      }
+    }
+  }
+}
+
+impl VisitAll for RegexCollector<'_> {
+  noop_visit_type!();
+
+  fn visit_regex(&mut self, regex: &Regex, _: &dyn Node) {
+    self.regexes.push(RegexSource {
+      pattern: regex.exp.to_string(),
+      flags: regex.flags.to_string(),
+      span: regex.span,
+      pattern_start: Some(regex.span.lo + BytePos(1)),
+    });
+  }
+
+  fn visit_new_expr(&mut self, new_expr: &NewExpr, _: &dyn Node) {
+    if let Some(args) = &new_expr.args {
+      self.check_call(&new_expr.callee, args, new_expr.span);
+    }
+  }
+
+  fn visit_call_expr(&mut self, call_expr: &CallExpr, _: &dyn Node) {
+    if let ExprOrSuper::Expr(callee) = &call_expr.callee {
+      self.check_call(callee, &call_expr.args, call_expr.span);
+    }
+  }
+}
+
 pub(crate) trait StringRepr {
   fn string_repr(&self) -> Option<String>;
 }
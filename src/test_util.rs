@@ -1,6 +1,8 @@
 // Copyright 2020 the Deno authors. All rights reserved. MIT license.
 
 use crate::ast_parser;
+use crate::ast_parser::PARSE_ERROR_CODE;
+use crate::autofix::apply_lint_fixes;
 use crate::diagnostic::LintDiagnostic;
 use crate::linter::LinterBuilder;
 use crate::rules::LintRule;
@@ -166,9 +168,11 @@ fn lint(rule: Box<dyn LintRule>, source: &str) -> Vec<LintDiagnostic> {
     .rules(vec![rule])
     .build();
 
-  let (_, diagnostics) = linter
-    .lint("deno_lint_test.tsx".to_string(), source.to_string())
-    .unwrap_or_else(|_| panic!("Failed to lint.\n[source code]\n{}", source));
+  let (_, diagnostics) =
+    linter.lint("deno_lint_test.tsx".to_string(), source.to_string());
+  if let Some(err) = diagnostics.iter().find(|d| d.code == PARSE_ERROR_CODE) {
+    panic!("Failed to lint: {}\n[source code]\n{}", err.message, source);
+  }
   diagnostics
 }
 
@@ -247,6 +251,32 @@ pub fn assert_lint_ok<T: LintRule + 'static>(source: &str) {
   }
 }
 
+/// Asserts that applying the preferred fixes of all diagnostics reported by
+/// the rule turns `source` into `expected`.
+pub fn assert_lint_fix<T: LintRule + 'static>(source: &str, expected: &str) {
+  let rule = T::new();
+  let diagnostics = lint(rule, source);
+  let fixed = apply_lint_fixes(source, &diagnostics)
+    .map_or_else(|| source.to_string(), |(fixed, _)| fixed);
+  assert_eq!(
+    expected, fixed,
+    "Fixed source is not as expected.\n\nsource:\n{}\n",
+    source
+  );
+}
+
+/// Lints `source` with the rule configured with `options`.
+pub fn lint_with_options<T: LintRule + 'static>(
+  options: serde_json::Value,
+  source: &str,
+) -> Vec<LintDiagnostic> {
+  let mut rule = T::new();
+  if let Err(err) = rule.set_options(options) {
+    panic!("{}", err);
+  }
+  lint(rule, source)
+}
+
 pub fn assert_lint_err<T: LintRule + 'static>(source: &str, col: usize) {
   assert_lint_err_on_line::<T>(source, 1, col)
 }
@@ -305,7 +335,8 @@ pub fn parse(source_code: &str) -> Program {
   let ast_parser = ast_parser::AstParser::new();
   let syntax = ast_parser::get_default_ts_config();
   let (program, _comments) = ast_parser
-    .parse_program("file_name.ts", syntax, source_code)
+    .parse_program("file_name.ts", syntax, source_code.to_string())
+    .program
     .unwrap();
   program
 }